@@ -0,0 +1,84 @@
+import { grid, pointsToRect } from '../../grid/controller/Grid';
+import { JsCodeResult } from '../../quadratic-core/quadratic_core';
+import { JavascriptMessage } from './javascriptTypes';
+
+class JavascriptWebWorker {
+  private worker?: Worker;
+
+  init() {
+    this.worker = new Worker(new URL('./javascript.worker.ts', import.meta.url));
+
+    this.worker.onmessage = async (e: MessageEvent<JavascriptMessage>) => {
+      const event = e.data;
+
+      if (event.type === 'results') {
+        const javascriptResult = event.results;
+        if (!javascriptResult) throw new Error('Expected results to be defined in javascript.ts');
+
+        // ensure that the 2d array has equally sized rows
+        const arrayOutput = javascriptResult.array_output;
+        if (arrayOutput && arrayOutput.length) {
+          const size = Math.max(...arrayOutput.map((row) => row.length));
+          arrayOutput.forEach((row) => {
+            while (row.length < size) row.push('');
+          });
+        }
+
+        // console.error and console.warn output is reported as std_err
+        const stdErr = [javascriptResult.std_err, javascriptResult.error_msg].filter((s) => s).join('\n');
+        const result = new JsCodeResult(
+          javascriptResult.success,
+          undefined,
+          stdErr || undefined,
+          javascriptResult.std_out || undefined,
+          javascriptResult.output_value ?? undefined,
+          arrayOutput && arrayOutput.length ? JSON.stringify(arrayOutput) : undefined,
+          javascriptResult.line_number ?? undefined,
+          undefined
+        );
+        grid.calculationComplete(result);
+      } else if (event.type === 'get-cells') {
+        const range = event.range;
+        if (!range) {
+          throw new Error('Expected range to be defined in get-cells');
+        }
+        const cells = grid.calculationGetCells(
+          pointsToRect(range.x0, range.y0, range.x1 - range.x0, range.y1 - range.y0),
+          range.sheet,
+          range.lineNumber
+        );
+
+        // cells will be undefined if the sheet name is invalid
+        if (this.worker) {
+          if (cells) {
+            this.worker.postMessage({ type: 'get-cells', cells } as JavascriptMessage);
+          } else {
+            const error = range.sheet ? `Sheet '${range.sheet}' not found` : 'Unable to get cells';
+            this.worker.postMessage({ type: 'get-cells', error } as JavascriptMessage);
+          }
+        }
+      } else {
+        throw new Error(`Unhandled javascriptWebWorker.type ${event.type}`);
+      }
+    };
+  }
+
+  start(javascript: string): boolean {
+    if (!this.worker) {
+      return false;
+    }
+    this.worker.postMessage({ type: 'execute', javascript } as JavascriptMessage);
+    return true;
+  }
+}
+
+export const javascriptWebWorker = new JavascriptWebWorker();
+
+declare global {
+  interface Window {
+    startJavascript: any;
+  }
+}
+
+// need to bind to window because rustWorker.ts cannot include any TS imports; see https://rustwasm.github.io/wasm-bindgen/reference/js-snippets.html#caveats
+window.startJavascript = javascriptWebWorker.start.bind(javascriptWebWorker);
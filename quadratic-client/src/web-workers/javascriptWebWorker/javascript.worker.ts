@@ -0,0 +1,96 @@
+/* eslint-disable no-restricted-globals */
+
+import { JavascriptMessage, JavascriptReturnType } from './javascriptTypes';
+
+let getCellsMessages:
+  | { resolve: (cells: { x: number; y: number; value: string }[]) => void; reject: (error: Error) => void }
+  | undefined;
+
+const getCells = async (
+  x0: number,
+  y0: number,
+  x1: number,
+  y1: number,
+  sheet?: string,
+  lineNumber?: number
+): Promise<{ x: number; y: number; value: string }[]> => {
+  return new Promise((resolve, reject) => {
+    getCellsMessages = { resolve, reject };
+    self.postMessage({ type: 'get-cells', range: { x0, y0, x1, y1, sheet, lineNumber } } as JavascriptMessage);
+  });
+};
+
+const toOutputString = (value: any): string => {
+  if (value === undefined || value === null) return '';
+  if (typeof value === 'object') return JSON.stringify(value);
+  return value.toString();
+};
+
+const toArrayOutput = (value: any[]): string[][] => {
+  return value.map((row: any) => (Array.isArray(row) ? row.map(toOutputString) : [toOutputString(row)]));
+};
+
+// finds the line of the user's code from an error's stack trace (the code is wrapped in one extra line)
+const lineNumberFromError = (e: any): number | null => {
+  const match = /<anonymous>:(\d+):\d+/.exec(e?.stack ?? '');
+  return match ? Math.max(parseInt(match[1]) - 2, 1) : null;
+};
+
+const runJavascript = async (javascript: string): Promise<JavascriptReturnType> => {
+  const stdOut: string[] = [];
+  const stdErr: string[] = [];
+  const format = (args: any[]) => args.map(toOutputString).join(' ');
+  const console = {
+    log: (...args: any[]) => stdOut.push(format(args)),
+    info: (...args: any[]) => stdOut.push(format(args)),
+    warn: (...args: any[]) => stdErr.push(format(args)),
+    error: (...args: any[]) => stdErr.push(format(args)),
+  };
+
+  try {
+    const AsyncFunction = Object.getPrototypeOf(async function () {}).constructor;
+    const fn = new AsyncFunction('getCells', 'console', javascript);
+    const result = await fn(getCells, console);
+
+    // an empty array has no cells to output, so it is returned as a blank value
+    const hasArrayOutput = Array.isArray(result) && result.length > 0;
+    return {
+      success: true,
+      std_out: stdOut.join('\n'),
+      std_err: stdErr.join('\n'),
+      error_msg: null,
+      line_number: null,
+      output_value: hasArrayOutput ? null : toOutputString(Array.isArray(result) ? undefined : result),
+      array_output: hasArrayOutput ? toArrayOutput(result) : null,
+    };
+  } catch (e: any) {
+    return {
+      success: false,
+      std_out: stdOut.join('\n'),
+      std_err: stdErr.join('\n'),
+      error_msg: e?.toString() ?? 'Unknown JavaScript Error',
+      line_number: lineNumberFromError(e),
+      output_value: null,
+      array_output: null,
+    };
+  }
+};
+
+self.onmessage = async (e: MessageEvent<JavascriptMessage>) => {
+  const event = e.data;
+
+  if (event.type === 'get-cells') {
+    if (getCellsMessages) {
+      const { resolve, reject } = getCellsMessages;
+      getCellsMessages = undefined;
+      if (event.cells) {
+        resolve(event.cells);
+      } else {
+        reject(new Error(event.error ?? 'Unable to get cells'));
+      }
+    }
+  } else if (event.type === 'execute' && event.javascript !== undefined) {
+    const results = await runJavascript(event.javascript);
+    self.postMessage({ type: 'results', results } as JavascriptMessage);
+  }
+};
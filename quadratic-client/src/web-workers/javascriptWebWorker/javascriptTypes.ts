@@ -0,0 +1,19 @@
+export interface JavascriptReturnType {
+  success: boolean;
+  std_out: string;
+  std_err: string;
+  error_msg: string | null;
+  line_number: number | null;
+
+  output_value: string | null;
+  array_output: string[][] | null;
+}
+
+export interface JavascriptMessage {
+  type: 'results' | 'execute' | 'get-cells';
+  javascript?: string;
+  results?: JavascriptReturnType;
+  range?: { sheet?: string; x0: number; y0: number; x1: number; y1: number; lineNumber?: number };
+  cells?: { x: number; y: number; value: string }[];
+  error?: string;
+}
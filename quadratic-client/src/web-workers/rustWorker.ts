@@ -6,3 +6,7 @@ export const runPython = (
 ): boolean => {
   return window.startPython(python_code, getCells);
 };
+
+export const runJavascript = (javascript_code: string): boolean => {
+  return window.startJavascript(javascript_code);
+};
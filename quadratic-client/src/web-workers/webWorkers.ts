@@ -1,7 +1,9 @@
 //@ts-ignore
 
+import { javascriptWebWorker } from './javascriptWebWorker/javascript';
 import { pythonWebWorker } from './pythonWebWorker/python';
 
 export const initializeWebWorkers = (): void => {
   pythonWebWorker.init();
+  javascriptWebWorker.init();
 };
@@ -0,0 +1,178 @@
+use std::collections::{HashMap, HashSet};
+
+use indexmap::IndexSet;
+
+use crate::controller::GridController;
+use crate::grid::CellRef;
+
+use super::TransactionInProgress;
+
+impl TransactionInProgress {
+    /// Moves the cells in `cells_to_compute` into `compute_order`, along with
+    /// every code cell downstream of them. The code cells are sorted so that
+    /// each one runs only after all of its inputs are final.
+    ///
+    /// Returns the cycles found among the code cells. These are left out of
+    /// `compute_order`.
+    pub(super) fn order_cells_to_compute(
+        &mut self,
+        grid_controller: &GridController,
+    ) -> Vec<HashSet<CellRef>> {
+        // find the code cells downstream of the changed cells that are not
+        // already scheduled (anything scheduled already has its dependents
+        // scheduled after it)
+        let mut dependents: HashMap<CellRef, HashSet<CellRef>> = HashMap::new();
+        let mut stack: Vec<CellRef> = self.cells_to_compute.drain(..).rev().collect();
+        let mut roots = vec![];
+        while let Some(cell_ref) = stack.pop() {
+            if dependents.contains_key(&cell_ref)
+                || self.compute_order.contains(&cell_ref)
+                || self.cells_in_cycles.contains(&cell_ref)
+            {
+                continue;
+            }
+            let cell_dependents = grid_controller.get_code_cell_dependents(cell_ref);
+            stack.extend(cell_dependents.iter().copied());
+            dependents.insert(cell_ref, cell_dependents);
+            roots.push(cell_ref);
+        }
+        let has_new_code_cells = roots
+            .iter()
+            .any(|cell_ref| is_code_cell(grid_controller, *cell_ref));
+        if !has_new_code_cells {
+            return vec![];
+        }
+
+        // sort the already scheduled and newly found cells together
+        for cell_ref in self.compute_order.iter() {
+            dependents.insert(
+                *cell_ref,
+                grid_controller.get_code_cell_dependents(*cell_ref),
+            );
+        }
+        let roots = self.compute_order.iter().chain(roots.iter()).copied();
+        let mut order = IndexSet::new();
+        let mut cycles = vec![];
+        for component in strongly_connected_components(roots, &dependents) {
+            let is_cycle = component.len() > 1
+                || dependents
+                    .get(&component[0])
+                    .is_some_and(|cells| cells.contains(&component[0]));
+            if is_cycle {
+                cycles.push(
+                    component
+                        .into_iter()
+                        .filter(|cell_ref| is_code_cell(grid_controller, *cell_ref))
+                        .collect(),
+                );
+            } else {
+                order.extend(component);
+            }
+        }
+        order.retain(|cell_ref| is_code_cell(grid_controller, *cell_ref));
+        self.compute_order = order;
+        cycles
+    }
+
+    /// Queues a code cell to run again if its latest run read a cell that is
+    /// still waiting to be computed. Reordering then runs the cell after its
+    /// new inputs, or finds the cycle that the run closed.
+    pub(super) fn reorder_if_inputs_pending(
+        &mut self,
+        grid_controller: &GridController,
+        cell_ref: CellRef,
+    ) {
+        let is_pending = |cell: &CellRef| {
+            self.compute_order.contains(cell)
+                || grid_controller
+                    .sheet(cell.sheet)
+                    .get_spill(*cell)
+                    .is_some_and(|code_cell| self.compute_order.contains(&code_cell))
+        };
+        if grid_controller
+            .get_cells_accessed(cell_ref)
+            .iter()
+            .any(is_pending)
+        {
+            self.cells_to_compute.insert(cell_ref);
+        }
+    }
+}
+
+fn is_code_cell(grid_controller: &GridController, cell_ref: CellRef) -> bool {
+    grid_controller
+        .sheet(cell_ref.sheet)
+        .get_code_cell_from_ref(cell_ref)
+        .is_some()
+}
+
+/// Returns the strongly connected components of the cells reachable from
+/// `roots`, using Tarjan's algorithm. Every component comes before the
+/// components that depend on it. A component with more than one cell is a
+/// cycle.
+fn strongly_connected_components(
+    roots: impl Iterator<Item = CellRef>,
+    dependents: &HashMap<CellRef, HashSet<CellRef>>,
+) -> Vec<Vec<CellRef>> {
+    let no_dependents = HashSet::new();
+    let dependents_of = |cell_ref: CellRef| dependents.get(&cell_ref).unwrap_or(&no_dependents);
+
+    let mut index: HashMap<CellRef, usize> = HashMap::new();
+    let mut low_link: HashMap<CellRef, usize> = HashMap::new();
+    let mut component_stack = vec![];
+    let mut on_component_stack = HashSet::new();
+    let mut components = vec![];
+    for root in roots {
+        if index.contains_key(&root) {
+            continue;
+        }
+        // depth-first search, keeping the unvisited dependents of each cell
+        let mut stack = vec![(root, dependents_of(root).iter())];
+        let root_index = index.len();
+        index.insert(root, root_index);
+        low_link.insert(root, root_index);
+        component_stack.push(root);
+        on_component_stack.insert(root);
+        while let Some((cell_ref, cell_dependents)) = stack.last_mut() {
+            let cell_ref = *cell_ref;
+            if let Some(&dependent) = cell_dependents.next() {
+                if let Some(&dependent_index) = index.get(&dependent) {
+                    if on_component_stack.contains(&dependent) {
+                        let low = low_link[&cell_ref].min(dependent_index);
+                        low_link.insert(cell_ref, low);
+                    }
+                } else {
+                    let dependent_index = index.len();
+                    index.insert(dependent, dependent_index);
+                    low_link.insert(dependent, dependent_index);
+                    component_stack.push(dependent);
+                    on_component_stack.insert(dependent);
+                    stack.push((dependent, dependents_of(dependent).iter()));
+                }
+                continue;
+            }
+
+            // all dependents are visited
+            stack.pop();
+            let low = low_link[&cell_ref];
+            if let Some((parent, _)) = stack.last() {
+                let parent_low = low_link[parent].min(low);
+                low_link.insert(*parent, parent_low);
+            }
+            if low == index[&cell_ref] {
+                let mut component = vec![];
+                while let Some(member) = component_stack.pop() {
+                    on_component_stack.remove(&member);
+                    component.push(member);
+                    if member == cell_ref {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    // components are found after everything that depends on them
+    components.reverse();
+    components
+}
@@ -19,6 +19,7 @@ impl TransactionInProgress {
         cell_ref: CellRef,
         sheet_id: SheetId,
     ) {
+        let rng = grid_controller.formula_rng();
         let mut ctx = Ctx::new(
             grid_controller.grid(),
             SheetPos {
@@ -27,6 +28,7 @@ impl TransactionInProgress {
                 y: pos.y,
             },
         );
+        ctx.rng = rng;
         match parse_formula(&code_string, pos) {
             Ok(parsed) => {
                 match parsed.eval(&mut ctx) {
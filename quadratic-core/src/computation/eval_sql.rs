@@ -0,0 +1,81 @@
+use crate::{
+    controller::{update_code_cell_value::update_code_cell_value, GridController},
+    formulas::Ctx,
+    grid::{
+        CellRef, CodeCellLanguage, CodeCellRunOutput, CodeCellRunResult, CodeCellValue, SheetId,
+    },
+    sql::parse_sql,
+    util::date_string,
+    Pos, SheetPos,
+};
+
+use super::TransactionInProgress;
+
+impl TransactionInProgress {
+    pub(super) fn eval_sql(
+        &mut self,
+        grid_controller: &mut GridController,
+        code_string: String,
+        pos: Pos,
+        cell_ref: CellRef,
+        sheet_id: SheetId,
+    ) {
+        let mut ctx = Ctx::new(
+            grid_controller.grid(),
+            SheetPos {
+                sheet_id,
+                x: pos.x,
+                y: pos.y,
+            },
+        );
+        let result = parse_sql(&code_string).and_then(|query| query.eval(&mut ctx));
+
+        // every cell of the queried range is a dependency of the query
+        self.cells_accessed = ctx
+            .cells_accessed
+            .iter()
+            .map(|sheet_pos| {
+                let sheet = grid_controller
+                    .grid_mut()
+                    .sheet_mut_from_id(sheet_pos.sheet_id);
+                sheet.get_or_create_cell_ref((*sheet_pos).into())
+            })
+            .collect();
+
+        let output = match result {
+            Ok(value) => CodeCellRunOutput {
+                std_out: None,
+                std_err: None,
+                result: CodeCellRunResult::Ok {
+                    output_value: value,
+                    cells_accessed: self.cells_accessed.clone(),
+                },
+                spill: false,
+            },
+            Err(error) => CodeCellRunOutput {
+                std_out: None,
+                std_err: Some(error.to_string()),
+                result: CodeCellRunResult::Err { error },
+                spill: false,
+            },
+        };
+        let updated_code_cell_value = CodeCellValue {
+            language: CodeCellLanguage::Sql,
+            code_string,
+            formatted_code_string: None,
+            output: Some(output),
+            last_modified: date_string(),
+        };
+        if update_code_cell_value(
+            grid_controller,
+            cell_ref,
+            Some(updated_code_cell_value),
+            &mut self.cells_to_compute,
+            &mut self.reverse_operations,
+            &mut self.summary,
+        ) {
+            // clears cells_accessed
+            self.cells_accessed.clear();
+        }
+    }
+}
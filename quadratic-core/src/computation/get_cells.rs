@@ -10,6 +10,16 @@ use crate::grid::CellRef;
 
 use super::TransactionInProgress;
 
+/// Reasons that cells could not be read for a code cell.
+pub(super) enum GetCellsError {
+    /// An internal logic error, which is only logged.
+    Internal,
+    /// The code cell referenced its own position.
+    SelfReference(String),
+    /// The requested sheet does not exist.
+    SheetNotFound(String),
+}
+
 impl TransactionInProgress {
     /// gets cells for use in async calculations
     pub fn get_cells(
@@ -17,6 +27,28 @@ impl TransactionInProgress {
         grid_controller: &mut GridController,
         get_cells: JsComputeGetCells,
     ) -> Option<CellsForArray> {
+        let line_number = get_cells.line_number();
+        match self.read_cells(grid_controller, get_cells) {
+            Ok(array) => Some(array),
+            Err(GetCellsError::Internal) => Some(CellsForArray::new(vec![], true)),
+            Err(GetCellsError::SelfReference(msg)) => {
+                self.code_cell_sheet_error(grid_controller, msg, line_number);
+                self.loop_compute(grid_controller);
+                Some(CellsForArray::new(vec![], true))
+            }
+            Err(GetCellsError::SheetNotFound(msg)) => {
+                self.code_cell_sheet_error(grid_controller, msg, line_number);
+                Some(CellsForArray::new(vec![], true))
+            }
+        }
+    }
+
+    /// reads cells for the current code cell and records them as accessed
+    pub(super) fn read_cells(
+        &mut self,
+        grid_controller: &mut GridController,
+        get_cells: JsComputeGetCells,
+    ) -> Result<CellsForArray, GetCellsError> {
         // ensure that the get_cells is not requesting a reference to itself
         let (current_sheet, pos) = if let Some(current_cell_ref) = self.current_cell_ref {
             let sheet = grid_controller.sheet(current_cell_ref.sheet);
@@ -27,7 +59,7 @@ impl TransactionInProgress {
                 crate::util::dbgjs(
                     "Expected current_cell_ref's sheet to be defined in transaction::get_cells",
                 );
-                return Some(CellsForArray::new(vec![], true));
+                return Err(GetCellsError::Internal);
             };
             (sheet, pos)
         } else {
@@ -35,7 +67,7 @@ impl TransactionInProgress {
             crate::util::dbgjs(
                 "Expected current_sheet_pos to be defined in transaction::get_cells",
             );
-            return Some(CellsForArray::new(vec![], true));
+            return Err(GetCellsError::Internal);
         };
 
         let sheet_name = get_cells.sheet_name();
@@ -55,9 +87,7 @@ impl TransactionInProgress {
                 } else {
                     "Sheet not found".to_string()
                 };
-                self.code_cell_sheet_error(grid_controller, msg, get_cells.line_number());
-                self.loop_compute(grid_controller);
-                return Some(CellsForArray::new(vec![], true));
+                return Err(GetCellsError::SelfReference(msg));
             }
 
             let rect = get_cells.rect();
@@ -76,7 +106,7 @@ impl TransactionInProgress {
                 }
             }
             self.cells_accessed = cells_accessed.into_iter().collect();
-            Some(array)
+            Ok(array)
         } else {
             // unable to find sheet by name, generate error
             let msg = if let (Some(sheet_name), Some(line_number)) =
@@ -86,8 +116,7 @@ impl TransactionInProgress {
             } else {
                 "Sheet not found".to_string()
             };
-            self.code_cell_sheet_error(grid_controller, msg, get_cells.line_number());
-            Some(CellsForArray::new(vec![], true))
+            Err(GetCellsError::SheetNotFound(msg))
         }
     }
 }
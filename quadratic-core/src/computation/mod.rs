@@ -1,4 +1,6 @@
+pub mod compute_order;
 pub mod eval_formula;
+pub mod eval_sql;
 pub mod get_cells;
 pub mod transaction_in_progress;
 use std::collections::HashSet;
@@ -18,6 +20,10 @@ pub struct TransactionInProgress {
     reverse_operations: Vec<Operation>,
     cells_updated: IndexSet<RegionRef>,
     cells_to_compute: IndexSet<CellRef>,
+    // code cells waiting to run, sorted so each runs after its inputs
+    compute_order: IndexSet<CellRef>,
+    // code cells found in a cycle; these are not computed again during this transaction
+    cells_in_cycles: HashSet<CellRef>,
     pub cursor: Option<String>,
     cells_accessed: Vec<CellRef>,
     pub summary: TransactionSummary,
@@ -1,16 +1,16 @@
-use std::collections::HashSet;
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
 
 use indexmap::IndexSet;
-use wasm_bindgen::JsValue;
 
 use crate::{
     controller::update_code_cell_value::update_code_cell_value,
-    grid::{CodeCellLanguage, CodeCellRunOutput, CodeCellRunResult},
+    grid::{CellRef, CodeCellLanguage, CodeCellRunOutput, CodeCellRunResult},
     util::date_string,
     Error, ErrorMsg, Span,
 };
 
 use crate::controller::{
+    code_runner::{CodeRunner, CodeRunnerResult},
     operation::Operation,
     transaction_summary::TransactionSummary,
     transaction_types::JsCodeResult,
@@ -18,7 +18,7 @@ use crate::controller::{
     GridController,
 };
 
-use super::TransactionInProgress;
+use super::{get_cells::GetCellsError, TransactionInProgress};
 
 impl TransactionInProgress {
     /// Creates and runs a new Transaction
@@ -39,6 +39,8 @@ impl TransactionInProgress {
             transaction_type,
             cells_updated: IndexSet::new(),
             cells_to_compute: IndexSet::new(),
+            compute_order: IndexSet::new(),
+            cells_in_cycles: HashSet::new(),
             cells_accessed: vec![],
             sheets_with_changed_bounds: HashSet::new(),
 
@@ -54,6 +56,14 @@ impl TransactionInProgress {
         // apply operations
         transaction.transact(grid_controller, operations, compute);
 
+        // volatile formulas, such as those calling `RAND`, are recomputed on
+        // every recalculation
+        if compute {
+            transaction
+                .cells_to_compute
+                .extend(grid_controller.get_volatile_cells());
+        }
+
         // crate::util::dbgjs(&format!(
         //     "[CellsToCompute len] {}",
         //     transaction.cells_to_compute.len()
@@ -76,7 +86,7 @@ impl TransactionInProgress {
             if self.waiting_for_async.is_some() {
                 break;
             }
-            if self.cells_to_compute.is_empty() {
+            if self.cells_to_compute.is_empty() && self.compute_order.is_empty() {
                 self.complete = true;
                 self.summary.save = true;
                 if self.has_async {
@@ -87,9 +97,10 @@ impl TransactionInProgress {
         }
     }
 
-    /// Clear the `cells_to_compute` attribute
+    /// Clear the `cells_to_compute` and `compute_order` attributes
     pub fn clear_cells_to_compute(&mut self) {
         self.cells_to_compute.clear();
+        self.compute_order.clear();
     }
 
     /// recalculate bounds for changed sheets
@@ -157,7 +168,10 @@ impl TransactionInProgress {
                 return;
             };
         updated_code_cell_value.last_modified = date_string();
-        let msg = ErrorMsg::PythonError(error_msg.clone().into());
+        let msg = match updated_code_cell_value.language {
+            CodeCellLanguage::JavaScript => ErrorMsg::JavaScriptError(error_msg.clone().into()),
+            _ => ErrorMsg::PythonError(error_msg.clone().into()),
+        };
         let span = line_number.map(|line_number| Span {
             start: line_number as u32,
             end: line_number as u32,
@@ -182,6 +196,50 @@ impl TransactionInProgress {
         self.waiting_for_async = None;
     }
 
+    /// sets a circular reference error on every code cell in a cycle and
+    /// queues the cells that depend on the cycle
+    fn circular_reference_error(
+        &mut self,
+        grid_controller: &mut GridController,
+        cycle: HashSet<CellRef>,
+    ) {
+        for cell_ref in cycle.iter() {
+            let sheet = grid_controller.grid().sheet_from_id(cell_ref.sheet);
+            let Some(mut code_cell_value) = sheet.get_code_cell_from_ref(*cell_ref).cloned() else {
+                continue;
+            };
+            let error = ErrorMsg::CircularReference.without_span();
+            code_cell_value.last_modified = date_string();
+            code_cell_value.output = Some(CodeCellRunOutput {
+                std_out: None,
+                std_err: Some(error.msg.to_string()),
+                result: CodeCellRunResult::Err { error },
+                spill: false,
+            });
+            update_code_cell_value(
+                grid_controller,
+                *cell_ref,
+                Some(code_cell_value),
+                &mut self.cells_to_compute,
+                &mut self.reverse_operations,
+                &mut self.summary,
+            );
+        }
+
+        for cell_ref in cycle.iter() {
+            self.cells_to_compute.shift_remove(cell_ref);
+            self.compute_order.shift_remove(cell_ref);
+            if let Some(dependent_cells) = grid_controller.get_dependent_cells(*cell_ref) {
+                self.cells_to_compute.extend(
+                    dependent_cells
+                        .into_iter()
+                        .filter(|dependent| !cycle.contains(dependent)),
+                );
+            }
+        }
+        self.cells_in_cycles.extend(cycle);
+    }
+
     /// continues the calculate cycle after an async call
     pub fn calculation_complete(
         &mut self,
@@ -191,6 +249,18 @@ impl TransactionInProgress {
         if self.complete {
             panic!("Transaction is already complete");
         }
+        if self.waiting_for_async.is_none() {
+            // this should only occur after an internal logic error
+            crate::util::dbgjs("Expected transaction to be waiting_for_async to be defined in transaction::complete");
+            return;
+        }
+        self.complete_code_cell(grid_controller, result);
+        // continue the compute loop after a successful async call
+        self.loop_compute(grid_controller);
+    }
+
+    /// updates the current code cell with the result of running its code
+    fn complete_code_cell(&mut self, grid_controller: &mut GridController, result: JsCodeResult) {
         let (language, code_string) =
             if let Some(old_code_cell_value) = self.current_code_cell.clone() {
                 (
@@ -200,56 +270,81 @@ impl TransactionInProgress {
             } else {
                 panic!("Expected current_code_cell to be defined in transaction::complete");
             };
-        match self.waiting_for_async {
-            None => {
-                // this should only occur after an internal logic error
-                crate::util::dbgjs("Expected transaction to be waiting_for_async to be defined in transaction::complete");
-                return;
-            }
-            Some(waiting_for_async) => {
-                match waiting_for_async {
-                    CodeCellLanguage::Python => {
-                        let cell_ref = if let Some(sheet_pos) = self.current_cell_ref {
-                            sheet_pos
-                        } else {
-                            panic!(
-                                "Expected current_sheet_pos to be defined in transaction::complete"
-                            );
-                        };
-                        let sheet = grid_controller.grid_mut().sheet_mut_from_id(cell_ref.sheet);
-                        let updated_code_cell_value = result.into_code_cell_value(
-                            sheet,
-                            cell_ref,
-                            language,
-                            code_string,
-                            &self.cells_accessed,
-                            &mut self.reverse_operations,
-                        );
-                        if update_code_cell_value(
-                            grid_controller,
-                            cell_ref,
-                            Some(updated_code_cell_value),
-                            &mut self.cells_to_compute,
-                            &mut self.reverse_operations,
-                            &mut self.summary,
-                        ) {
-                            // clear cells_accessed
-                            self.cells_accessed.clear();
-                        }
-                        self.waiting_for_async = None;
-                    }
-                    _ => {
-                        crate::util::dbgjs("Transaction.complete called for an unhandled language");
+        let cell_ref = if let Some(sheet_pos) = self.current_cell_ref {
+            sheet_pos
+        } else {
+            panic!("Expected current_sheet_pos to be defined in transaction::complete");
+        };
+        let sheet = grid_controller.grid_mut().sheet_mut_from_id(cell_ref.sheet);
+        let updated_code_cell_value = result.into_code_cell_value(
+            sheet,
+            cell_ref,
+            language,
+            code_string,
+            &self.cells_accessed,
+            &mut self.reverse_operations,
+        );
+        if update_code_cell_value(
+            grid_controller,
+            cell_ref,
+            Some(updated_code_cell_value),
+            &mut self.cells_to_compute,
+            &mut self.reverse_operations,
+            &mut self.summary,
+        ) {
+            // clear cells_accessed
+            self.cells_accessed.clear();
+        }
+        self.waiting_for_async = None;
+        self.reorder_if_inputs_pending(grid_controller, cell_ref);
+    }
+
+    /// runs the current code cell using a registered code runner
+    fn run_code(
+        &mut self,
+        grid_controller: &mut GridController,
+        runner: Rc<RefCell<dyn CodeRunner>>,
+        language: CodeCellLanguage,
+        code_string: String,
+    ) {
+        let mut get_cells_error = None;
+        let result = runner
+            .borrow_mut()
+            .run(language, &code_string, &mut |get_cells| {
+                let line_number = get_cells.line_number();
+                match self.read_cells(grid_controller, get_cells) {
+                    Ok(array) => Some(array),
+                    Err(error) => {
+                        get_cells_error.get_or_insert((error, line_number));
+                        None
                     }
                 }
-            }
+            });
+
+        // an error reading cells takes precedence over the runner's result
+        match get_cells_error {
+            Some((GetCellsError::Internal, _)) => {}
+            Some((
+                GetCellsError::SelfReference(msg) | GetCellsError::SheetNotFound(msg),
+                line_number,
+            )) => self.code_cell_sheet_error(grid_controller, msg, line_number),
+            None => match result {
+                CodeRunnerResult::Complete(result) => {
+                    self.complete_code_cell(grid_controller, result);
+                }
+                CodeRunnerResult::Pending => {
+                    // exit the compute cycle and wait for the host to continue the transaction
+                    self.waiting_for_async = Some(language);
+                    self.has_async = true;
+                }
+                CodeRunnerResult::Failed(msg) => {
+                    self.code_cell_sheet_error(grid_controller, msg, None);
+                }
+            },
         }
-        // continue the compute loop after a successful async call
-        self.loop_compute(grid_controller);
     }
 
-    /// checks the next cell in the cells_to_compute and computes it
-    /// returns true if an async call is made or the compute cycle is completed
+    /// computes the next code cell in the compute_order
     fn compute(&mut self, grid_controller: &mut GridController) {
         while let Some(region) = self.cells_updated.shift_remove_index(0) {
             if let Some(dependent_cells) = grid_controller.get_dependent_cells_for_region(region) {
@@ -257,19 +352,16 @@ impl TransactionInProgress {
             }
         }
 
-        if let Some(cell_ref) = self.cells_to_compute.shift_remove_index(0) {
-            // todo: this would be a good place to check for cycles
-            // add all dependent cells to the cells_to_compute
-            if let Some(dependent_cells) = grid_controller.get_dependent_cells(cell_ref) {
-                #[cfg(feature = "show-operations")]
-                dependent_cells.iter().for_each(|cell_ref| {
-                    let sheet = grid_controller.sheet(cell_ref.sheet);
-                    if let Some(pos) = sheet.cell_ref_to_pos(*cell_ref) {
-                        crate::util::dbgjs(format!("[Adding Dependent Cell] {:?}", pos));
-                    }
-                });
+        // schedule any newly changed cells and their dependents
+        if !self.cells_to_compute.is_empty() {
+            for cycle in self.order_cells_to_compute(grid_controller) {
+                self.circular_reference_error(grid_controller, cycle);
+            }
+        }
 
-                self.cells_to_compute.extend(dependent_cells);
+        if let Some(cell_ref) = self.compute_order.shift_remove_index(0) {
+            if self.cells_in_cycles.contains(&cell_ref) {
+                return;
             }
 
             let sheet = grid_controller.grid().sheet_from_id(cell_ref.sheet);
@@ -282,33 +374,18 @@ impl TransactionInProgress {
                         crate::util::dbgjs(format!(
                             "[Compute] {:?} ({} remaining)",
                             pos,
-                            self.cells_to_compute.len()
+                            self.compute_order.len()
                         ));
                     }
                     self.current_cell_ref = Some(cell_ref);
                     self.current_code_cell = Some(code_cell.clone());
                     let code_string = code_cell.code_string.clone();
                     let language = code_cell.language;
+                    if let Some(runner) = grid_controller.code_runner(language) {
+                        self.run_code(grid_controller, runner, language, code_string);
+                        return;
+                    }
                     match language {
-                        CodeCellLanguage::Python => {
-                            // python is run async so we exit the compute cycle and wait for TS to restart the transaction
-                            if !cfg!(test) {
-                                let result = crate::wasm_bindings::js::runPython(code_string);
-
-                                // run python will return false if python is not loaded (this can be generalized if we need to return a different error)
-                                if result == JsValue::FALSE {
-                                    self.code_cell_sheet_error(
-                                        grid_controller,
-                                        "Python interpreter not yet loaded (please run again)"
-                                            .to_string(),
-                                        None,
-                                    );
-                                    return;
-                                }
-                            }
-                            self.waiting_for_async = Some(language);
-                            self.has_async = true;
-                        }
                         CodeCellLanguage::Formula => {
                             self.eval_formula(
                                 grid_controller,
@@ -318,12 +395,18 @@ impl TransactionInProgress {
                                 cell_ref,
                                 sheet.id,
                             );
+                            self.reorder_if_inputs_pending(grid_controller, cell_ref);
                         }
-                        _ => {
-                            crate::util::dbgjs(format!(
-                                "Compute language {} not supported in compute.rs",
-                                language
-                            ));
+                        CodeCellLanguage::Sql => {
+                            self.eval_sql(grid_controller, code_string, pos, cell_ref, sheet.id);
+                            self.reorder_if_inputs_pending(grid_controller, cell_ref);
+                        }
+                        CodeCellLanguage::Python | CodeCellLanguage::JavaScript => {
+                            self.code_cell_sheet_error(
+                                grid_controller,
+                                format!("No code runner registered for {language}"),
+                                None,
+                            );
                         }
                     }
                 }
@@ -358,13 +441,14 @@ mod test {
 
     use crate::{
         controller::{
+            code_runner::PendingCodeRunner,
             operation::Operation,
             transaction_types::{CellForArray, JsCodeResult, JsComputeGetCells},
             transactions::TransactionType,
             GridController,
         },
-        grid::{CodeCellLanguage, CodeCellValue},
-        CellValue, Pos,
+        grid::{CodeCellLanguage, CodeCellRunResult, CodeCellValue},
+        CellValue, Error, ErrorMsg, Pos, Span,
     };
 
     fn setup_python(
@@ -373,6 +457,7 @@ mod test {
         cell_value: CellValue,
     ) -> GridController {
         let mut gc = gc.unwrap_or_default();
+        gc.set_code_runner(CodeCellLanguage::Python, PendingCodeRunner);
         let sheet_ids = gc.sheet_ids();
         let sheet = gc.grid_mut().sheet_mut_from_id(sheet_ids[0]);
         let cell_value_pos = Pos { x: 0, y: 0 };
@@ -599,6 +684,107 @@ mod test {
         assert!(code_cell_value.get_output_value(1, 0).is_none());
     }
 
+    #[test]
+    fn test_javascript_with_cell_reference() {
+        let mut gc = GridController::new();
+        gc.set_code_runner(CodeCellLanguage::JavaScript, PendingCodeRunner);
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "10".into(), None);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::JavaScript,
+            "return (await getCells(0, 0, 0, 0))[0].value * 2;".into(),
+            None,
+        );
+        assert!(gc.is_transaction_busy());
+
+        let cells_for_array = gc.calculation_get_cells(JsComputeGetCells::new(
+            crate::Rect::single_pos(Pos { x: 0, y: 0 }),
+            None,
+            None,
+        ));
+        assert_eq!(
+            *cells_for_array.unwrap().get_cells(),
+            vec![CellForArray::new(0, 0, Some("10".into()))]
+        );
+
+        let result = JsCodeResult::new(
+            true,
+            None,
+            None,
+            Some("doubling".into()),
+            Some("20".into()),
+            None,
+            None,
+            None,
+        );
+        let summary = gc.calculation_complete(result);
+        assert!(summary.save);
+        assert_eq!(summary.code_cells_modified, HashSet::from([sheet_id]));
+
+        let code_cell_value = gc
+            .sheet(sheet_id)
+            .get_code_cell(Pos { x: 1, y: 0 })
+            .unwrap()
+            .to_owned();
+        assert_eq!(
+            code_cell_value.get_output_value(0, 0),
+            Some(CellValue::Number(BigDecimal::from(20)))
+        );
+        let output = code_cell_value.output.unwrap();
+        assert_eq!(output.std_out, Some("doubling".into()));
+
+        // the javascript cell reruns when its input changes
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "11".into(), None);
+        assert!(gc.is_transaction_busy());
+    }
+
+    #[test]
+    fn test_javascript_error() {
+        let mut gc = GridController::new();
+        gc.set_code_runner(CodeCellLanguage::JavaScript, PendingCodeRunner);
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 0 },
+            CodeCellLanguage::JavaScript,
+            "asdf".into(),
+            None,
+        );
+
+        let error = "ReferenceError: asdf is not defined".to_string();
+        let result = JsCodeResult::new(
+            false,
+            None,
+            Some(error.clone()),
+            None,
+            None,
+            None,
+            Some(1),
+            None,
+        );
+        gc.calculation_complete(result);
+
+        let output = gc
+            .sheet(sheet_id)
+            .get_code_cell(Pos { x: 0, y: 0 })
+            .unwrap()
+            .output
+            .clone()
+            .unwrap();
+        assert_eq!(output.std_err, Some(error.clone()));
+        assert_eq!(
+            output.result,
+            CodeCellRunResult::Err {
+                error: Error {
+                    span: Some(Span { start: 1, end: 1 }),
+                    msg: ErrorMsg::JavaScriptError(error.into()),
+                }
+            }
+        );
+    }
+
     #[test]
     fn test_execute_operation_set_cell_values_formula() {
         let mut gc = GridController::new();
@@ -745,6 +931,70 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_sql_cell() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "name".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 1, y: 0 }, "amount".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 1 }, "a".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 1, y: 1 }, "1".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 2 }, "b".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 1, y: 2 }, "2".into(), None);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 3, y: 0 },
+            CodeCellLanguage::Sql,
+            "SELECT SUM(amount) AS total FROM A0:B5".into(),
+            None,
+        );
+
+        // the result spills below a header row
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 3, y: 0 }),
+            Some(CellValue::Text("total".into()))
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 3, y: 1 }),
+            Some(CellValue::Number(3.into()))
+        );
+
+        // adding a row inside the queried range reruns the query
+        gc.set_cell_value(sheet_id, Pos { x: 1, y: 3 }, "4".into(), None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 3, y: 1 }),
+            Some(CellValue::Number(7.into()))
+        );
+
+        // errors are reported on the code cell
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 3, y: 0 },
+            CodeCellLanguage::Sql,
+            "SELECT price FROM A0:B5".into(),
+            None,
+        );
+        let output = gc
+            .sheet(sheet_id)
+            .get_code_cell(Pos { x: 3, y: 0 })
+            .unwrap()
+            .output
+            .clone()
+            .unwrap();
+        assert_eq!(
+            output.result,
+            CodeCellRunResult::Err {
+                error: Error {
+                    span: Some(Span { start: 7, end: 12 }),
+                    msg: ErrorMsg::UnknownColumn("price".into()),
+                }
+            }
+        );
+    }
+
     #[test]
     fn test_python_cancellation() {
         let mut gc = setup_python(None, "".into(), CellValue::Number(10.into()));
@@ -767,4 +1017,231 @@ mod test {
         assert!(transaction.complete);
         assert_eq!(transaction.cells_to_compute.len(), 0);
     }
+
+    #[test]
+    fn test_formula_cycle() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 0 },
+            CodeCellLanguage::Formula,
+            "B0 + 1".into(),
+            None,
+        );
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::Formula,
+            "A0 + 1".into(),
+            None,
+        );
+
+        // both cells in the cycle are set to an error and the transaction completes
+        assert!(gc.get_transaction_in_progress().is_none());
+        let sheet = gc.sheet(sheet_id);
+        for pos in [Pos { x: 0, y: 0 }, Pos { x: 1, y: 0 }] {
+            let code_cell = sheet.get_code_cell(pos).unwrap();
+            assert_eq!(
+                code_cell.get_error().unwrap().msg,
+                ErrorMsg::CircularReference
+            );
+        }
+
+        // breaking the cycle recomputes the cells that were in it
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::Formula,
+            "5".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(6.into()))
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(5.into()))
+        );
+    }
+
+    #[test]
+    fn test_formula_cycle_with_dependents() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        let formulas = [
+            (Pos { x: 3, y: 0 }, "C0 + 1"),
+            (Pos { x: 0, y: 0 }, "C0 + 1"),
+            (Pos { x: 1, y: 0 }, "A0 + 1"),
+            (Pos { x: 2, y: 0 }, "B0 + 1"),
+        ];
+        for (pos, formula) in formulas {
+            gc.set_cell_code(
+                sheet_id,
+                pos,
+                CodeCellLanguage::Formula,
+                formula.into(),
+                None,
+            );
+        }
+
+        // every cell in the cycle has an error, and cells outside it still run
+        assert!(gc.get_transaction_in_progress().is_none());
+        let sheet = gc.sheet(sheet_id);
+        for x in 0..3 {
+            let code_cell = sheet.get_code_cell(Pos { x, y: 0 }).unwrap();
+            assert_eq!(
+                code_cell.get_error().unwrap().msg,
+                ErrorMsg::CircularReference
+            );
+        }
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 3, y: 0 }),
+            Some(CellValue::Number(1.into()))
+        );
+
+        // changing an input to the cycle keeps the error
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 1 }, "1".into(), None);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::Formula,
+            "A0 + A1".into(),
+            None,
+        );
+        let code_cell = gc
+            .sheet(sheet_id)
+            .get_code_cell(Pos { x: 2, y: 0 })
+            .unwrap();
+        assert_eq!(
+            code_cell.get_error().unwrap().msg,
+            ErrorMsg::CircularReference
+        );
+
+        // breaking the cycle recomputes every cell
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 0 },
+            CodeCellLanguage::Formula,
+            "10".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 2, y: 0 }),
+            Some(CellValue::Number(12.into()))
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 3, y: 0 }),
+            Some(CellValue::Number(13.into()))
+        );
+    }
+
+    #[test]
+    fn test_python_formula_cycle() {
+        let mut gc = GridController::new();
+        gc.set_code_runner(CodeCellLanguage::Python, PendingCodeRunner);
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 0 },
+            CodeCellLanguage::Formula,
+            "B0 + 1".into(),
+            None,
+        );
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::Python,
+            "c(0, 0) + 1".into(),
+            None,
+        );
+
+        // python reads the formula cell
+        gc.calculation_get_cells(JsComputeGetCells::new(
+            crate::Rect::single_pos(Pos { x: 0, y: 0 }),
+            None,
+            None,
+        ));
+        let result = JsCodeResult::new(true, None, None, None, Some("2".into()), None, None, None);
+        gc.calculation_complete(result);
+
+        let transaction = gc.get_transaction_in_progress().unwrap();
+        assert!(transaction.complete);
+        let sheet = gc.sheet(sheet_id);
+        for pos in [Pos { x: 0, y: 0 }, Pos { x: 1, y: 0 }] {
+            let code_cell = sheet.get_code_cell(pos).unwrap();
+            assert_eq!(
+                code_cell.get_error().unwrap().msg,
+                ErrorMsg::CircularReference
+            );
+        }
+    }
+
+    #[test]
+    fn test_compute_order_runs_each_cell_once() {
+        let mut gc = GridController::new();
+        gc.set_code_runner(CodeCellLanguage::Python, PendingCodeRunner);
+        let sheet_id = gc.sheet_ids()[0];
+
+        // A0 feeds D0 directly and through B0 -> C0
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "1".into(), None);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::Formula,
+            "A0 + 1".into(),
+            None,
+        );
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 2, y: 0 },
+            CodeCellLanguage::Formula,
+            "B0 + 1".into(),
+            None,
+        );
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 3, y: 0 },
+            CodeCellLanguage::Python,
+            "c(0, 0) + c(2, 0)".into(),
+            None,
+        );
+        gc.calculation_get_cells(JsComputeGetCells::new(
+            crate::Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 2, y: 0 }),
+            None,
+            None,
+        ));
+        let result = JsCodeResult::new(true, None, None, None, Some("4".into()), None, None, None);
+        gc.calculation_complete(result);
+
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "10".into(), None);
+
+        // python runs last, after both of its inputs are final
+        let python_cell_ref = gc
+            .grid_mut()
+            .sheet_mut_from_id(sheet_id)
+            .get_or_create_cell_ref(Pos { x: 3, y: 0 });
+        let transaction = gc.get_transaction_in_progress().unwrap();
+        assert!(!transaction.complete);
+        assert_eq!(transaction.current_cell_ref, Some(python_cell_ref));
+        assert_eq!(
+            gc.sheet(sheet_id).get_cell_value(Pos { x: 2, y: 0 }),
+            Some(CellValue::Number(12.into()))
+        );
+
+        // and only runs once
+        let result = JsCodeResult::new(true, None, None, None, Some("22".into()), None, None, None);
+        gc.calculation_complete(result);
+        let transaction = gc.get_transaction_in_progress().unwrap();
+        assert!(transaction.complete);
+        assert_eq!(
+            gc.sheet(sheet_id).get_cell_value(Pos { x: 3, y: 0 }),
+            Some(CellValue::Number(22.into()))
+        );
+    }
 }
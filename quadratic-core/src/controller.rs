@@ -1,21 +1,32 @@
 #[cfg(feature = "js")]
 use wasm_bindgen::prelude::*;
 
+use std::collections::VecDeque;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
 use crate::{computation::TransactionInProgress, grid::Grid};
 
-use self::transactions::Transaction;
+use self::{
+    code_runner::CodeRunners,
+    dependencies::DependencyGraph,
+    transactions::{PendingTransaction, Transaction},
+};
 
 pub mod auto_complete;
 pub mod borders;
 pub mod cells;
 pub mod clipboard;
+pub mod code_runner;
 pub mod dependencies;
 pub mod export;
 pub mod formatting;
 pub mod formula;
 pub mod import;
+pub mod move_cells;
 pub mod operation;
 pub mod operations;
+pub mod rows_columns;
 pub mod sheet_offsets;
 pub mod sheets;
 pub mod spills;
@@ -29,9 +40,15 @@ pub mod update_code_cell_value;
 #[cfg_attr(feature = "js", wasm_bindgen)]
 pub struct GridController {
     grid: Grid,
+    dependencies: DependencyGraph,
     transaction_in_progress: Option<TransactionInProgress>,
+    pending_transactions: VecDeque<PendingTransaction>,
     undo_stack: Vec<Transaction>,
     redo_stack: Vec<Transaction>,
+    code_runners: CodeRunners,
+    /// Seeded random number generator for formulas such as `RAND`. If this is
+    /// `None`, formulas use a random seed.
+    rng: Option<StdRng>,
 }
 
 impl GridController {
@@ -40,10 +57,14 @@ impl GridController {
     }
     pub fn from_grid(grid: Grid) -> Self {
         GridController {
+            dependencies: DependencyGraph::new(&grid),
             grid,
             transaction_in_progress: None,
+            pending_transactions: VecDeque::new(),
             undo_stack: vec![],
             redo_stack: vec![],
+            code_runners: CodeRunners::default(),
+            rng: None,
         }
     }
     pub fn grid(&self) -> &Grid {
@@ -52,6 +73,20 @@ impl GridController {
     pub fn grid_mut(&mut self) -> &mut Grid {
         &mut self.grid
     }
+
+    /// Seeds the random number generator used by formulas, so that functions
+    /// such as `RAND` give the same results each time.
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+    }
+    /// Returns a random number generator for evaluating one formula, drawn
+    /// from the seeded generator if there is one. Otherwise the formula seeds
+    /// its own generator only if it needs one.
+    pub(crate) fn formula_rng(&mut self) -> Option<StdRng> {
+        self.rng
+            .as_mut()
+            .map(|rng| StdRng::seed_from_u64(rng.gen()))
+    }
 }
 
 #[cfg(test)]
@@ -2,10 +2,10 @@ use super::operation::Operation;
 use super::transactions::TransactionType;
 use super::GridController;
 use crate::controller::transaction_summary::TransactionSummary;
-use crate::grid::generate_borders;
+use crate::grid::{generate_borders, generate_borders_full, CellBorders, RegionRef};
 use crate::{
     grid::{BorderSelection, BorderStyle, SheetId},
-    Rect,
+    Pos, Rect,
 };
 
 impl GridController {
@@ -23,6 +23,55 @@ impl GridController {
         let ops = vec![Operation::SetBorders { region, borders }];
         self.set_in_progress_transaction(ops, cursor, false, TransactionType::Normal)
     }
+
+    /// Generates and returns the operations to set the borders of cells, where
+    /// each cell's position is relative to `start_pos`.
+    /// Does not commit the operations or create a transaction.
+    pub fn set_cell_borders_operations(
+        &mut self,
+        sheet_id: SheetId,
+        start_pos: Pos,
+        borders: &[(i64, i64, Option<CellBorders>)],
+    ) -> Vec<Operation> {
+        let sheet = self.grid.sheet_mut_from_id(sheet_id);
+        let mut ops = vec![];
+        borders.iter().for_each(|(x, y, cell_borders)| {
+            if let Some(cell_borders) = cell_borders {
+                let mut border_selections = vec![];
+                let mut border_styles = vec![];
+                let (column, _) = sheet.get_or_create_column(*x + start_pos.x);
+                let row_id = sheet.get_or_create_row(*y + start_pos.y);
+                let region = RegionRef {
+                    sheet: sheet.id,
+                    columns: vec![column.id],
+                    rows: vec![row_id.id],
+                };
+
+                cell_borders
+                    .borders
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, border_style)| {
+                        if let Some(border_style) = border_style.to_owned() {
+                            let border_selection = match index {
+                                0 => BorderSelection::Left,
+                                1 => BorderSelection::Top,
+                                2 => BorderSelection::Right,
+                                3 => BorderSelection::Bottom,
+                                _ => BorderSelection::Clear,
+                            };
+                            border_selections.push(border_selection);
+                            border_styles.push(Some(border_style));
+                        }
+                    });
+
+                let borders =
+                    generate_borders_full(sheet, &region, border_selections, border_styles);
+                ops.push(Operation::SetBorders { region, borders });
+            }
+        });
+        ops
+    }
 }
 
 #[cfg(test)]
@@ -253,6 +253,26 @@ impl GridController {
         self.set_in_progress_transaction(ops, cursor, true, TransactionType::Normal)
     }
 
+    /// Generates and returns the set of operations to delete the values, code,
+    /// formatting, and borders in a given region.
+    /// Does not commit the operations or create a transaction.
+    pub fn delete_cells_and_formatting_operations(
+        &mut self,
+        sheet_id: SheetId,
+        rect: Rect,
+    ) -> Vec<Operation> {
+        let mut ops = self.delete_cells_rect_operations(sheet_id, rect);
+        ops.extend(self.clear_formatting_operations(sheet_id, rect));
+        let region = self.existing_region(sheet_id, rect);
+        if let Some(size) = region.size() {
+            ops.push(Operation::SetCellFormats {
+                region,
+                attr: CellFmtArray::NumericCommas(RunLengthEncoding::repeat(None, size.len())),
+            });
+        }
+        ops
+    }
+
     /// Returns a region of the spreadsheet, assigning IDs to columns and rows
     /// as needed.
     pub fn region(&mut self, sheet_id: SheetId, rect: Rect) -> RegionRef {
@@ -3,10 +3,7 @@ use super::{
     transactions::TransactionType, GridController,
 };
 use crate::{
-    grid::{
-        generate_borders_full, get_cell_borders_in_rect, BorderSelection, CellBorders,
-        CodeCellValue, RegionRef, SheetId,
-    },
+    grid::{get_cell_borders_in_rect, CellBorders, CodeCellValue, SheetId},
     Array, ArraySize, CellValue, Pos, Rect,
 };
 use htmlescape;
@@ -248,41 +245,7 @@ impl GridController {
         });
 
         // add borders to the sheet
-        borders.iter().for_each(|(x, y, cell_borders)| {
-            if let Some(cell_borders) = cell_borders {
-                let mut border_selections = vec![];
-                let mut border_styles = vec![];
-                let (column, _) = sheet.get_or_create_column(*x + start_pos.x);
-                let row_id = sheet.get_or_create_row(*y + start_pos.y);
-                let region = RegionRef {
-                    sheet: sheet.id,
-                    columns: vec![column.id],
-                    rows: vec![row_id.id],
-                };
-
-                cell_borders
-                    .borders
-                    .iter()
-                    .enumerate()
-                    .for_each(|(index, border_style)| {
-                        if let Some(border_style) = border_style.to_owned() {
-                            let border_selection = match index {
-                                0 => BorderSelection::Left,
-                                1 => BorderSelection::Top,
-                                2 => BorderSelection::Right,
-                                3 => BorderSelection::Bottom,
-                                _ => BorderSelection::Clear,
-                            };
-                            border_selections.push(border_selection);
-                            border_styles.push(Some(border_style));
-                        }
-                    });
-
-                let borders =
-                    generate_borders_full(sheet, &region, border_selections, border_styles);
-                ops.push(Operation::SetBorders { region, borders });
-            }
-        });
+        ops.extend(self.set_cell_borders_operations(sheet_id, start_pos, &borders));
 
         self.set_in_progress_transaction(ops, cursor, compute, TransactionType::Normal)
     }
@@ -0,0 +1,338 @@
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+use crate::grid::CodeCellLanguage;
+
+use super::{
+    transaction_types::{CellsForArray, JsCodeResult, JsComputeGetCells},
+    GridController,
+};
+
+/// Callback used by a [`CodeRunner`] to read cells from the grid while code
+/// runs. Returns `None` if the cells could not be read (e.g. the code cell
+/// referenced itself), in which case the code cell is given an error.
+pub type GetCellsFn<'a> = dyn 'a + FnMut(JsComputeGetCells) -> Option<CellsForArray>;
+
+/// Outcome of starting to run a code cell.
+#[derive(Debug)]
+pub enum CodeRunnerResult {
+    /// The code ran to completion.
+    Complete(JsCodeResult),
+    /// The code is running asynchronously. The host reads cells using
+    /// [`GridController::calculation_get_cells()`] and finishes the run with
+    /// [`GridController::calculation_complete()`].
+    Pending,
+    /// The code could not be run (e.g. the interpreter is not loaded).
+    Failed(String),
+}
+
+/// Executor for code cells in one or more languages.
+pub trait CodeRunner {
+    /// Starts running `code_string`. Synchronous runners can read cells
+    /// using `get_cells`; async runners must ignore it and use
+    /// [`GridController::calculation_get_cells()`] instead.
+    fn run(
+        &mut self,
+        language: CodeCellLanguage,
+        code_string: &str,
+        get_cells: &mut GetCellsFn<'_>,
+    ) -> CodeRunnerResult;
+}
+
+/// Runner that leaves code cells pending. The host supplies each result by
+/// calling [`GridController::calculation_complete()`].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct PendingCodeRunner;
+
+impl CodeRunner for PendingCodeRunner {
+    fn run(
+        &mut self,
+        _language: CodeCellLanguage,
+        _code_string: &str,
+        _get_cells: &mut GetCellsFn<'_>,
+    ) -> CodeRunnerResult {
+        CodeRunnerResult::Pending
+    }
+}
+
+/// Runs code cells in the browser's web workers. Results are returned by TS
+/// through `calculationComplete()`.
+#[cfg(all(feature = "js", target_family = "wasm"))]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct JsCodeRunner;
+
+#[cfg(all(feature = "js", target_family = "wasm"))]
+impl CodeRunner for JsCodeRunner {
+    fn run(
+        &mut self,
+        language: CodeCellLanguage,
+        code_string: &str,
+        _get_cells: &mut GetCellsFn<'_>,
+    ) -> CodeRunnerResult {
+        let (result, not_loaded) = match language {
+            CodeCellLanguage::Python => (
+                crate::wasm_bindings::js::runPython(code_string.to_string()),
+                "Python interpreter not yet loaded (please run again)",
+            ),
+            CodeCellLanguage::JavaScript => (
+                crate::wasm_bindings::js::runJavascript(code_string.to_string()),
+                "JavaScript runner not yet loaded (please run again)",
+            ),
+            _ => {
+                return CodeRunnerResult::Failed(format!("{language} cannot be run in the browser"))
+            }
+        };
+
+        // run returns false if the runner is not loaded
+        if result == wasm_bindgen::JsValue::FALSE {
+            CodeRunnerResult::Failed(not_loaded.to_string())
+        } else {
+            CodeRunnerResult::Pending
+        }
+    }
+}
+
+/// Code runners registered for each language. Formulas and SQL are evaluated
+/// by the core unless a runner is registered for them.
+#[derive(Clone)]
+pub struct CodeRunners(HashMap<CodeCellLanguage, Rc<RefCell<dyn CodeRunner>>>);
+
+impl Default for CodeRunners {
+    /// Python and JavaScript run in the browser's web workers. Elsewhere they
+    /// are left pending until the host completes them.
+    fn default() -> Self {
+        #[cfg(all(feature = "js", target_family = "wasm"))]
+        let runner: Rc<RefCell<dyn CodeRunner>> = Rc::new(RefCell::new(JsCodeRunner));
+        #[cfg(not(all(feature = "js", target_family = "wasm")))]
+        let runner: Rc<RefCell<dyn CodeRunner>> = Rc::new(RefCell::new(PendingCodeRunner));
+        Self(HashMap::from([
+            (CodeCellLanguage::Python, Rc::clone(&runner)),
+            (CodeCellLanguage::JavaScript, runner),
+        ]))
+    }
+}
+
+impl fmt::Debug for CodeRunners {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl GridController {
+    /// Registers the runner used for code cells in `language`, replacing any
+    /// previous runner.
+    pub fn set_code_runner(
+        &mut self,
+        language: CodeCellLanguage,
+        runner: impl CodeRunner + 'static,
+    ) {
+        self.code_runners
+            .0
+            .insert(language, Rc::new(RefCell::new(runner)));
+    }
+
+    /// Unregisters the runner for code cells in `language`.
+    pub fn remove_code_runner(&mut self, language: CodeCellLanguage) {
+        self.code_runners.0.remove(&language);
+    }
+
+    /// Returns the runner registered for code cells in `language`.
+    pub fn code_runner(&self, language: CodeCellLanguage) -> Option<Rc<RefCell<dyn CodeRunner>>> {
+        self.code_runners.0.get(&language).cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{CodeRunner, CodeRunnerResult, GetCellsFn};
+    use crate::{
+        controller::{
+            transaction_types::{JsCodeResult, JsComputeGetCells},
+            GridController,
+        },
+        grid::{CodeCellLanguage, CodeCellRunResult},
+        CellValue, Pos, Rect,
+    };
+
+    /// Synchronous runner that sums the cells in the range given by the code
+    /// string, e.g. `0,0,0,1` for `A0:A1`.
+    struct SumRunner {
+        runs: Rc<RefCell<u32>>,
+    }
+
+    impl CodeRunner for SumRunner {
+        fn run(
+            &mut self,
+            _language: CodeCellLanguage,
+            code_string: &str,
+            get_cells: &mut GetCellsFn<'_>,
+        ) -> CodeRunnerResult {
+            *self.runs.borrow_mut() += 1;
+            let coords: Vec<i64> = code_string
+                .split(',')
+                .map(|n| n.trim().parse().unwrap())
+                .collect();
+            let rect = Rect::new_span(
+                Pos {
+                    x: coords[0],
+                    y: coords[1],
+                },
+                Pos {
+                    x: coords[2],
+                    y: coords[3],
+                },
+            );
+            let Some(cells) = get_cells(JsComputeGetCells::new(rect, None, Some(1))) else {
+                return CodeRunnerResult::Failed("unreachable".into());
+            };
+            let sum: f64 = cells
+                .get_cells()
+                .iter()
+                .filter_map(|cell| cell.get_value().parse::<f64>().ok())
+                .sum();
+            CodeRunnerResult::Complete(JsCodeResult::new(
+                true,
+                None,
+                None,
+                None,
+                Some(sum.to_string()),
+                None,
+                None,
+                None,
+            ))
+        }
+    }
+
+    struct FailingRunner;
+
+    impl CodeRunner for FailingRunner {
+        fn run(
+            &mut self,
+            _language: CodeCellLanguage,
+            _code_string: &str,
+            _get_cells: &mut GetCellsFn<'_>,
+        ) -> CodeRunnerResult {
+            CodeRunnerResult::Failed("runner is offline".into())
+        }
+    }
+
+    fn code_cell_error(gc: &GridController, pos: Pos) -> Option<String> {
+        let sheet = gc.sheet(gc.sheet_ids()[0]);
+        match &sheet.get_code_cell(pos)?.output.as_ref()?.result {
+            CodeCellRunResult::Ok { .. } => None,
+            CodeCellRunResult::Err { error } => Some(error.msg.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_sync_code_runner() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        let runs = Rc::new(RefCell::new(0));
+        gc.set_code_runner(
+            CodeCellLanguage::Python,
+            SumRunner {
+                runs: Rc::clone(&runs),
+            },
+        );
+
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "1".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 1 }, "2".into(), None);
+        let summary = gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::Python,
+            "0,0,0,1".into(),
+            None,
+        );
+
+        // the transaction completes without waiting for an async result
+        assert!(summary.save);
+        assert!(!gc.is_transaction_busy());
+        assert_eq!(*runs.borrow(), 1);
+        assert_eq!(
+            gc.sheet(sheet_id).get_cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+
+        // changing an input reruns the code cell and its dependents
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 2, y: 0 },
+            CodeCellLanguage::Formula,
+            "B0 * 10".into(),
+            None,
+        );
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 1 }, "5".into(), None);
+        assert_eq!(*runs.borrow(), 2);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(6.into()))
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 2, y: 0 }),
+            Some(CellValue::Number(60.into()))
+        );
+
+        // undo restores the previous result
+        gc.undo(None);
+        assert_eq!(
+            gc.sheet(sheet_id).get_cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+    }
+
+    #[test]
+    fn test_sync_code_runner_errors() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_code_runner(
+            CodeCellLanguage::JavaScript,
+            SumRunner {
+                runs: Rc::new(RefCell::new(0)),
+            },
+        );
+
+        // reading the code cell's own position is an error
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 0 },
+            CodeCellLanguage::JavaScript,
+            "0,0,1,1".into(),
+            None,
+        );
+        assert!(!gc.is_transaction_busy());
+        assert_eq!(
+            code_cell_error(&gc, Pos { x: 0, y: 0 }),
+            Some("JavaScript error: cell cannot reference itself at line 1".into())
+        );
+
+        gc.set_code_runner(CodeCellLanguage::JavaScript, FailingRunner);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::JavaScript,
+            "".into(),
+            None,
+        );
+        assert_eq!(
+            code_cell_error(&gc, Pos { x: 1, y: 0 }),
+            Some("JavaScript error: runner is offline".into())
+        );
+
+        gc.remove_code_runner(CodeCellLanguage::JavaScript);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 2, y: 0 },
+            CodeCellLanguage::JavaScript,
+            "".into(),
+            None,
+        );
+        assert_eq!(
+            code_cell_error(&gc, Pos { x: 2, y: 0 }),
+            Some("JavaScript error: No code runner registered for JavaScript".into())
+        );
+    }
+}
@@ -1,52 +1,240 @@
-use std::{self};
+use std::collections::{HashMap, HashSet};
 
-use std::collections::HashSet;
-
-use crate::grid::{CellRef, RegionRef};
+use crate::{
+    formulas::{find_cell_references, parse_formula, RangeRef},
+    grid::{
+        CellRef, CodeCellLanguage, CodeCellRunResult, CodeCellValue, Grid, RegionRef, Sheet,
+        SheetId,
+    },
+    ErrorMsg, Pos, Rect, Value,
+};
 
 use super::GridController;
 
-impl GridController {
-    pub fn get_dependent_cells(&self, cell: CellRef) -> Option<HashSet<CellRef>> {
-        let mut dependent_cells = HashSet::new();
-
-        self.grid.sheets().iter().for_each(|sheet| {
-            sheet.code_cells.iter().for_each(|(cell_ref, code_cell)| {
-                if let Some(output) = code_cell.output.as_ref() {
-                    if let Some(cells_accessed) = output.cells_accessed() {
-                        cells_accessed.iter().for_each(|cell_accessed| {
-                            if *cell_accessed == cell {
-                                dependent_cells.insert(*cell_ref);
-                            }
-                        });
-                    }
-                }
-            });
+/// Reverse index from cells to the code cells that read them.
+///
+/// This is kept up to date whenever a code cell's output changes so that
+/// finding the dependents of a cell does not require scanning every code cell
+/// in the grid.
+#[derive(Debug, Default, Clone)]
+pub struct DependencyGraph {
+    /// Maps an accessed cell to the code cells that read it.
+    dependents: HashMap<CellRef, HashSet<CellRef>>,
+    /// Maps a code cell to the cells it read during its last successful run.
+    cells_accessed: HashMap<CellRef, Vec<CellRef>>,
+    /// Maps a formula to the whole columns and rows that it references. These
+    /// are open-ended, so cells added to them later are also dependencies.
+    ranges_accessed: HashMap<CellRef, Vec<RangeRef>>,
+    /// Formulas that call a volatile function, such as `RAND`, and so must be
+    /// recomputed on every recalculation.
+    volatile: HashSet<CellRef>,
+}
+
+impl DependencyGraph {
+    /// Builds the graph from the `cells_accessed` of every code cell in the
+    /// grid.
+    pub fn new(grid: &Grid) -> Self {
+        let mut graph = Self::default();
+        grid.sheets()
+            .iter()
+            .for_each(|sheet| graph.add_sheet(sheet));
+        graph
+    }
+
+    /// Adds all code cells in a sheet to the graph.
+    pub fn add_sheet(&mut self, sheet: &Sheet) {
+        sheet.code_cells.iter().for_each(|(cell_ref, code_cell)| {
+            self.update(*cell_ref, Some(code_cell));
         });
+    }
 
-        if dependent_cells.is_empty() {
-            return None;
+    /// Removes all code cells in a sheet from the graph.
+    pub fn remove_sheet(&mut self, sheet: &Sheet) {
+        sheet.code_cells.keys().for_each(|cell_ref| {
+            self.update(*cell_ref, None);
+        });
+    }
+
+    /// Replaces the dependencies of a code cell with the cells accessed by
+    /// `code_cell`. Passing `None` removes the code cell from the graph.
+    ///
+    /// A circular reference error keeps the dependencies of the run that
+    /// detected it, so that breaking the cycle recomputes the cell.
+    pub fn update(&mut self, code_cell_ref: CellRef, code_cell: Option<&CodeCellValue>) {
+        let result = code_cell
+            .and_then(|code_cell| code_cell.output.as_ref())
+            .map(|output| &output.result);
+        if let Some(CodeCellRunResult::Err { error }) = result {
+            if error.msg == ErrorMsg::CircularReference {
+                return;
+            }
         }
 
-        Some(dependent_cells)
+        self.remove(code_cell_ref);
+        if let Some(code_cell) = code_cell {
+            // like volatility, these come from the code itself, so they are
+            // kept even if the last run failed
+            if is_volatile(code_cell) {
+                self.volatile.insert(code_cell_ref);
+            }
+            let ranges_accessed = col_and_row_ranges(code_cell);
+            if !ranges_accessed.is_empty() {
+                self.ranges_accessed.insert(code_cell_ref, ranges_accessed);
+            }
+        }
+        if let Some(CodeCellRunResult::Ok { cells_accessed, .. }) = result {
+            cells_accessed.iter().for_each(|cell_accessed| {
+                self.dependents
+                    .entry(*cell_accessed)
+                    .or_default()
+                    .insert(code_cell_ref);
+            });
+            self.cells_accessed
+                .insert(code_cell_ref, cells_accessed.clone());
+        }
     }
 
-    pub fn get_dependent_cells_for_region(&self, region: RegionRef) -> Option<HashSet<CellRef>> {
-        let mut dependent_cells = HashSet::new();
-
-        self.grid.sheets().iter().for_each(|sheet| {
-            sheet.code_cells.iter().for_each(|(cell_ref, code_cell)| {
-                if let Some(output) = code_cell.output.as_ref() {
-                    if let Some(cells_accessed) = output.cells_accessed() {
-                        cells_accessed.iter().for_each(|cell_accessed| {
-                            if region.contains(cell_accessed) {
-                                dependent_cells.insert(*cell_ref);
-                            }
-                        });
+    /// Removes a code cell's dependencies from the graph.
+    fn remove(&mut self, code_cell_ref: CellRef) {
+        self.ranges_accessed.remove(&code_cell_ref);
+        self.volatile.remove(&code_cell_ref);
+        if let Some(old_cells_accessed) = self.cells_accessed.remove(&code_cell_ref) {
+            old_cells_accessed.iter().for_each(|cell_accessed| {
+                if let Some(dependents) = self.dependents.get_mut(cell_accessed) {
+                    dependents.remove(&code_cell_ref);
+                    if dependents.is_empty() {
+                        self.dependents.remove(cell_accessed);
                     }
                 }
             });
-        });
+        }
+    }
+
+    /// Returns the code cells that read `cell`.
+    pub fn dependents(&self, cell: CellRef) -> Option<&HashSet<CellRef>> {
+        self.dependents.get(&cell)
+    }
+
+    /// Returns the cells that a code cell read during its last successful
+    /// run.
+    pub fn cells_accessed(&self, code_cell: CellRef) -> &[CellRef] {
+        self.cells_accessed
+            .get(&code_cell)
+            .map_or(&[], |cells| cells.as_slice())
+    }
+
+    /// Returns the formulas that call a volatile function.
+    pub fn volatile(&self) -> &HashSet<CellRef> {
+        &self.volatile
+    }
+
+    /// Returns the code cells that read any cell in `region`.
+    pub fn dependents_of_region(&self, region: &RegionRef) -> HashSet<CellRef> {
+        // iterate over whichever side is smaller
+        if region.len() <= self.dependents.len() {
+            region
+                .iter()
+                .filter_map(|cell_ref| self.dependents.get(&cell_ref))
+                .flatten()
+                .copied()
+                .collect()
+        } else {
+            self.dependents
+                .iter()
+                .filter(|(cell_ref, _)| region.contains(cell_ref))
+                .flat_map(|(_, dependents)| dependents)
+                .copied()
+                .collect()
+        }
+    }
+
+    /// Returns the code cells that reference whole columns or rows that
+    /// intersect `rect`.
+    pub fn dependents_of_col_and_row_ranges(
+        &self,
+        grid: &Grid,
+        sheet_id: SheetId,
+        rect: Rect,
+    ) -> HashSet<CellRef> {
+        self.ranges_accessed
+            .iter()
+            .filter(|(code_cell_ref, ranges)| {
+                ranges.iter().any(|range| {
+                    // references are parsed from the origin, so resolving them
+                    // from the origin gives the columns and rows as written
+                    let (start, end, sheet, x_or_y_range) = match range {
+                        RangeRef::ColRange { start, end, sheet } => {
+                            (start, end, sheet, rect.x_range())
+                        }
+                        RangeRef::RowRange { start, end, sheet } => {
+                            (start, end, sheet, rect.y_range())
+                        }
+                        _ => return false,
+                    };
+                    let range_sheet_id = match sheet {
+                        Some(name) => grid.sheet_from_name(name.clone()).map(|sheet| sheet.id),
+                        None => Some(code_cell_ref.sheet),
+                    };
+                    let (start, end) = (start.resolve_from(0), end.resolve_from(0));
+                    range_sheet_id == Some(sheet_id)
+                        && start.min(end) < x_or_y_range.end
+                        && start.max(end) >= x_or_y_range.start
+                })
+            })
+            .map(|(code_cell_ref, _)| *code_cell_ref)
+            .collect()
+    }
+}
+
+/// Returns the whole columns and rows referenced by a formula.
+fn col_and_row_ranges(code_cell: &CodeCellValue) -> Vec<RangeRef> {
+    if code_cell.language != CodeCellLanguage::Formula {
+        return vec![];
+    }
+    find_cell_references(&code_cell.code_string, Pos::ORIGIN)
+        .into_iter()
+        .map(|range_ref| range_ref.inner)
+        .filter(|range_ref| {
+            matches!(
+                range_ref,
+                RangeRef::ColRange { .. } | RangeRef::RowRange { .. }
+            )
+        })
+        .collect()
+}
+
+/// Returns whether a code cell is a formula that calls a volatile function.
+fn is_volatile(code_cell: &CodeCellValue) -> bool {
+    code_cell.language == CodeCellLanguage::Formula
+        && parse_formula(&code_cell.code_string, Pos::ORIGIN)
+            .is_ok_and(|formula| formula.is_volatile())
+}
+
+impl GridController {
+    /// Returns the formulas that must be recomputed on every recalculation.
+    pub fn get_volatile_cells(&self) -> HashSet<CellRef> {
+        self.dependencies.volatile().clone()
+    }
+
+    /// Returns the cells that a code cell read during its last successful
+    /// run.
+    pub fn get_cells_accessed(&self, code_cell: CellRef) -> &[CellRef] {
+        self.dependencies.cells_accessed(code_cell)
+    }
+
+    pub fn get_dependent_cells(&self, cell: CellRef) -> Option<HashSet<CellRef>> {
+        self.dependencies.dependents(cell).cloned()
+    }
+
+    pub fn get_dependent_cells_for_region(&self, region: RegionRef) -> Option<HashSet<CellRef>> {
+        let mut dependent_cells = self.dependencies.dependents_of_region(&region);
+        for rect in self.grid.sheet_from_id(region.sheet).region_rects(&region) {
+            dependent_cells.extend(self.dependencies.dependents_of_col_and_row_ranges(
+                &self.grid,
+                region.sheet,
+                rect,
+            ));
+        }
 
         if dependent_cells.is_empty() {
             return None;
@@ -54,22 +242,72 @@ impl GridController {
 
         Some(dependent_cells)
     }
+
+    /// Returns the code cells that read `cell`. If `cell` is a code cell, this
+    /// also includes the code cells that read its spilled output.
+    pub fn get_code_cell_dependents(&self, cell: CellRef) -> HashSet<CellRef> {
+        let mut dependent_cells = self
+            .dependencies
+            .dependents(cell)
+            .cloned()
+            .unwrap_or_default();
+
+        let sheet = self.grid.sheet_from_id(cell.sheet);
+        let Some(pos) = sheet.cell_ref_to_pos(cell) else {
+            return dependent_cells;
+        };
+        let mut rect = Rect::single_pos(pos);
+        if let Some(code_cell) = sheet.get_code_cell_from_ref(cell) {
+            if let Some(Value::Array(array)) =
+                code_cell.output.as_ref().and_then(|o| o.output_value())
+            {
+                if !code_cell.has_spill_error() {
+                    for (x, y) in array.size().iter() {
+                        let output_pos = Pos {
+                            x: pos.x + x as i64,
+                            y: pos.y + y as i64,
+                        };
+                        if let Some(output_cell) = sheet.try_get_cell_ref(output_pos) {
+                            if let Some(dependents) = self.dependencies.dependents(output_cell) {
+                                dependent_cells.extend(dependents);
+                            }
+                        }
+                    }
+                    rect = Rect::new_span(
+                        pos,
+                        Pos {
+                            x: pos.x + array.width() as i64 - 1,
+                            y: pos.y + array.height() as i64 - 1,
+                        },
+                    );
+                }
+            }
+        }
+        dependent_cells.extend(
+            self.dependencies
+                .dependents_of_col_and_row_ranges(&self.grid, cell.sheet, rect),
+        );
+        dependent_cells
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+
     use crate::{
         controller::GridController,
-        grid::{CodeCellRunOutput, CodeCellValue},
-        CellValue, Pos, Value,
+        grid::{CodeCellLanguage, CodeCellRunOutput, CodeCellValue, Grid},
+        CellValue, Pos, Rect, Value,
     };
 
     #[test]
     fn test_graph() {
-        let mut gc = GridController::new();
-        let cdc = gc.grid_mut();
-        let sheet_id = cdc.sheet_ids()[0];
-        let sheet = cdc.sheet_mut_from_id(sheet_id);
+        let mut grid = Grid::new();
+        let sheet_id = grid.sheet_ids()[0];
+        let sheet = grid.sheet_mut_from_id(sheet_id);
         sheet.set_cell_value(Pos { x: 0, y: 0 }, CellValue::Number(1.into()));
         sheet.set_cell_value(Pos { x: 0, y: 1 }, CellValue::Number(2.into()));
         let mut cells_accessed = vec![];
@@ -97,6 +335,8 @@ mod test {
         );
         let cell_ref02 = sheet.get_or_create_cell_ref(Pos { x: 0, y: 2 });
 
+        // the graph is rebuilt from the grid's code cells on import
+        let gc = GridController::from_grid(grid);
         assert_eq!(gc.get_dependent_cells(cell_ref00).unwrap().len(), 1);
         assert_eq!(
             gc.get_dependent_cells(cell_ref00).unwrap().iter().next(),
@@ -108,4 +348,213 @@ mod test {
         );
         assert_eq!(gc.get_dependent_cells(cell_ref02), None);
     }
+
+    #[test]
+    fn test_graph_incremental() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "1".into(), None);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::Formula,
+            "A0 + 1".into(),
+            None,
+        );
+
+        let sheet = gc.grid_mut().sheet_mut_from_id(sheet_id);
+        let cell_ref00 = sheet.get_or_create_cell_ref(Pos { x: 0, y: 0 });
+        let cell_ref01 = sheet.get_or_create_cell_ref(Pos { x: 0, y: 1 });
+        let cell_ref10 = sheet.get_or_create_cell_ref(Pos { x: 1, y: 0 });
+        let region = gc.region(sheet_id, Rect::new_span((0, 0).into(), (0, 1).into()));
+
+        assert_eq!(
+            gc.get_dependent_cells(cell_ref00),
+            Some([cell_ref10].into())
+        );
+        assert_eq!(
+            gc.get_dependent_cells_for_region(region.clone()),
+            Some([cell_ref10].into())
+        );
+
+        // changing the formula moves its dependency
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::Formula,
+            "A1 + 1".into(),
+            None,
+        );
+        assert_eq!(gc.get_dependent_cells(cell_ref00), None);
+        assert_eq!(
+            gc.get_dependent_cells(cell_ref01),
+            Some([cell_ref10].into())
+        );
+
+        // deleting the code cell removes it from the graph
+        gc.delete_cells_rect(sheet_id, Rect::single_pos((1, 0).into()), None);
+        assert_eq!(gc.get_dependent_cells(cell_ref01), None);
+        assert_eq!(gc.get_dependent_cells_for_region(region), None);
+
+        // undo restores the code cell along with its dependency
+        gc.undo(None);
+        assert_eq!(
+            gc.get_dependent_cells(cell_ref01),
+            Some([cell_ref10].into())
+        );
+    }
+
+    #[test]
+    fn test_col_and_row_range_dependents() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        let value = |gc: &GridController, pos| gc.sheet(sheet_id).get_cell_value(pos);
+
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "1".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 1 }, "2".into(), None);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::Formula,
+            "SUM(A:A)".into(),
+            None,
+        );
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 1 },
+            CodeCellLanguage::Formula,
+            "SUM(5:5)".into(),
+            None,
+        );
+        assert_eq!(
+            value(&gc, Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+        assert_eq!(
+            value(&gc, Pos { x: 1, y: 1 }),
+            Some(CellValue::Number(0.into()))
+        );
+
+        // appending a value below the data recomputes the formula
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 5 }, "10".into(), None);
+        assert_eq!(
+            value(&gc, Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(13.into()))
+        );
+        assert_eq!(
+            value(&gc, Pos { x: 1, y: 1 }),
+            Some(CellValue::Number(10.into()))
+        );
+
+        // so does a code cell whose output lands in the range
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 3, y: 5 },
+            CodeCellLanguage::Formula,
+            "{100, 1000}".into(),
+            None,
+        );
+        assert_eq!(
+            value(&gc, Pos { x: 1, y: 1 }),
+            Some(CellValue::Number(1110.into()))
+        );
+
+        gc.undo(None);
+        gc.undo(None);
+        assert_eq!(
+            value(&gc, Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+        assert_eq!(
+            value(&gc, Pos { x: 1, y: 1 }),
+            Some(CellValue::Number(0.into()))
+        );
+    }
+
+    #[test]
+    fn test_col_and_row_range_dependents_after_error() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+
+        // the formula fails while the column is empty
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 0 },
+            CodeCellLanguage::Formula,
+            "1 / SUM(C:C)".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet
+            .get_code_cell(Pos { x: 0, y: 0 })
+            .unwrap()
+            .get_error()
+            .is_some());
+
+        // filling the column recomputes it
+        gc.set_cell_value(sheet_id, Pos { x: 2, y: 3 }, "4".into(), None);
+        assert_eq!(
+            gc.sheet(sheet_id).get_cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(BigDecimal::from_str("0.25").unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_volatile_cells_recompute() {
+        let new_seeded_sheet = |seed| {
+            let mut gc = GridController::new();
+            gc.set_random_seed(seed);
+            let sheet_id = gc.sheet_ids()[0];
+            gc.set_cell_code(
+                sheet_id,
+                Pos { x: 0, y: 0 },
+                CodeCellLanguage::Formula,
+                "RANDBETWEEN(1, 1000000000)".into(),
+                None,
+            );
+            gc.set_cell_code(
+                sheet_id,
+                Pos { x: 1, y: 0 },
+                CodeCellLanguage::Formula,
+                "A0 * 0 + 1".into(),
+                None,
+            );
+            (gc, sheet_id)
+        };
+        let (mut gc, sheet_id) = new_seeded_sheet(42);
+        let value = |gc: &GridController, pos| gc.sheet(sheet_id).get_cell_value(pos);
+
+        let cell_ref = gc
+            .grid_mut()
+            .sheet_mut_from_id(sheet_id)
+            .get_or_create_cell_ref(Pos { x: 0, y: 0 });
+        assert_eq!(gc.get_volatile_cells(), [cell_ref].into());
+        let first = value(&gc, Pos { x: 0, y: 0 });
+
+        // the same seed gives the same results
+        let (gc2, sheet_id2) = new_seeded_sheet(42);
+        assert_eq!(
+            first,
+            gc2.sheet(sheet_id2).get_cell_value(Pos { x: 0, y: 0 })
+        );
+
+        // any change recomputes the volatile formula and its dependents
+        gc.set_cell_value(sheet_id, Pos { x: 5, y: 5 }, "1".into(), None);
+        assert_ne!(first, value(&gc, Pos { x: 0, y: 0 }));
+        assert_eq!(
+            value(&gc, Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(1.into()))
+        );
+
+        // replacing the formula stops it from recomputing
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 0 },
+            CodeCellLanguage::Formula,
+            "1".into(),
+            None,
+        );
+        assert!(gc.get_volatile_cells().is_empty());
+    }
 }
@@ -0,0 +1,364 @@
+use crate::{
+    formulas::{move_cell_references, move_sql_cell_references},
+    grid::{get_cell_borders_in_rect, CodeCellLanguage, CodeCellValue, SheetId},
+    util::date_string,
+    Array, ArraySize, CellValue, Pos, Rect,
+};
+
+use super::{
+    operation::Operation, transaction_summary::TransactionSummary, transactions::TransactionType,
+    GridController,
+};
+
+impl GridController {
+    /// Moves the values, code, formatting, and borders in `source` so that its
+    /// top-left cell is at `dest`, replacing anything already there. Formulas
+    /// that reference the moved cells are updated to follow them.
+    ///
+    /// Returns a [`TransactionSummary`].
+    pub fn move_cells(
+        &mut self,
+        sheet_id: SheetId,
+        source: Rect,
+        dest: Pos,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let ops = vec![Operation::MoveCells {
+            sheet_id,
+            source,
+            dest,
+        }];
+        self.set_in_progress_transaction(ops, cursor, true, TransactionType::Normal)
+    }
+
+    /// Returns the operations that make up [`Operation::MoveCells`].
+    pub(super) fn move_cells_operations(
+        &mut self,
+        sheet_id: SheetId,
+        source: Rect,
+        dest: Pos,
+    ) -> Vec<Operation> {
+        if source.min == dest {
+            return vec![];
+        }
+        let dest_rect = Rect::new_span(
+            dest,
+            Pos {
+                x: dest.x + source.width() as i64 - 1,
+                y: dest.y + source.height() as i64 - 1,
+            },
+        );
+        let move_references = |code_cell: &CodeCellValue, on_sheet: SheetId, pos: Pos| {
+            let is_moved_sheet = |name: Option<&str>| match name {
+                Some(name) => self
+                    .grid
+                    .sheet_from_name(name.to_string())
+                    .is_some_and(|sheet| sheet.id == sheet_id),
+                None => on_sheet == sheet_id,
+            };
+            let code = &code_cell.code_string;
+            let code_string = match code_cell.language {
+                CodeCellLanguage::Formula => {
+                    move_cell_references(code, pos, source, dest, is_moved_sheet)
+                }
+                CodeCellLanguage::Sql => {
+                    move_sql_cell_references(code, source, dest, is_moved_sheet)
+                }
+                // references in Python and JavaScript are not parsed
+                CodeCellLanguage::Python | CodeCellLanguage::JavaScript => return None,
+            };
+            (code_string != code_cell.code_string).then(|| CodeCellValue {
+                code_string,
+                last_modified: date_string(),
+                ..code_cell.clone()
+            })
+        };
+
+        // read the moved cells before anything changes
+        let sheet = self.grid.sheet_from_id(sheet_id);
+        let values = source
+            .y_range()
+            .flat_map(|y| source.x_range().map(move |x| Pos { x, y }))
+            .map(|pos| sheet.get_cell_value_only(pos).unwrap_or(CellValue::Blank))
+            .collect();
+        let size = ArraySize::new(source.width(), source.height())
+            .expect("error getting size of source rect");
+        let values = Array::new_row_major(size, values).expect("error constructing moved values");
+        let mut code = vec![];
+        for (&cell_ref, code_cell) in &sheet.code_cells {
+            if let Some(pos) = sheet.cell_ref_to_pos(cell_ref) {
+                if source.contains(pos) {
+                    let code_cell = move_references(code_cell, sheet_id, pos)
+                        .unwrap_or_else(|| code_cell.clone());
+                    let offset = (pos.x - source.min.x, pos.y - source.min.y);
+                    code.push((offset, code_cell));
+                }
+            }
+        }
+        let formats = self.get_all_cell_formats(sheet_id, source);
+        let borders = get_cell_borders_in_rect(sheet, source);
+
+        // update formulas elsewhere that reference the moved cells
+        let mut formula_ops = vec![];
+        for sheet in self.grid.sheets() {
+            for (&cell_ref, code_cell) in &sheet.code_cells {
+                let Some(pos) = sheet.cell_ref_to_pos(cell_ref) else {
+                    continue;
+                };
+                if sheet.id == sheet_id && (source.contains(pos) || dest_rect.contains(pos)) {
+                    continue;
+                }
+                if let Some(code_cell) = move_references(code_cell, sheet.id, pos) {
+                    formula_ops.push(Operation::SetCellCode {
+                        cell_ref,
+                        code_cell_value: Some(code_cell),
+                    });
+                }
+            }
+        }
+
+        let mut ops = self.delete_cells_and_formatting_operations(sheet_id, source);
+        ops.extend(self.delete_cells_and_formatting_operations(sheet_id, dest_rect));
+        let region = self.region(sheet_id, dest_rect);
+        ops.push(Operation::SetCellValues {
+            region: region.clone(),
+            values,
+        });
+        let sheet = self.grid.sheet_mut_from_id(sheet_id);
+        for ((x, y), code_cell) in code {
+            let cell_ref = sheet.get_or_create_cell_ref(Pos {
+                x: dest.x + x,
+                y: dest.y + y,
+            });
+            ops.push(Operation::SetCellCode {
+                cell_ref,
+                code_cell_value: Some(code_cell),
+            });
+        }
+        ops.extend(formats.into_iter().map(|attr| Operation::SetCellFormats {
+            region: region.clone(),
+            attr,
+        }));
+        ops.extend(self.set_cell_borders_operations(sheet_id, dest, &borders));
+        ops.extend(formula_ops);
+        ops
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        color::Rgba,
+        controller::GridController,
+        grid::{Bold, BorderSelection, BorderStyle, CellBorderLine, CodeCellLanguage, SheetId},
+        test_util::{code_string, has_border},
+        CellValue, Pos, Rect,
+    };
+
+    fn value(gc: &GridController, sheet_id: SheetId, pos: Pos) -> Option<CellValue> {
+        gc.sheet(sheet_id).get_cell_value(pos)
+    }
+
+    #[test]
+    fn test_move_cells() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "1".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 1, y: 0 }, "2".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 5, y: 5 }, "overwritten".into(), None);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 1 },
+            CodeCellLanguage::Formula,
+            "A0 + B0 + C0".into(),
+            None,
+        );
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 3, y: 0 },
+            CodeCellLanguage::Formula,
+            "SUM(A0:A1) * 10".into(),
+            None,
+        );
+        gc.set_cell_bold(
+            sheet_id,
+            Rect::single_pos(Pos { x: 0, y: 0 }),
+            Some(true),
+            None,
+        );
+        tokio_test::block_on(gc.set_borders(
+            sheet_id,
+            Rect::single_pos(Pos { x: 0, y: 0 }),
+            vec![BorderSelection::All],
+            Some(BorderStyle {
+                color: Rgba::default(),
+                line: CellBorderLine::Line1,
+            }),
+            None,
+        ));
+        gc.set_cell_value(sheet_id, Pos { x: 2, y: 0 }, "3".into(), None);
+        assert_eq!(
+            value(&gc, sheet_id, Pos { x: 3, y: 0 }),
+            Some(CellValue::Number(70.into()))
+        );
+
+        // move A0:A1 to F5
+        gc.move_cells(
+            sheet_id,
+            Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 0, y: 1 }),
+            Pos { x: 5, y: 5 },
+            None,
+        );
+        assert_eq!(value(&gc, sheet_id, Pos { x: 0, y: 0 }), None);
+        assert_eq!(value(&gc, sheet_id, Pos { x: 0, y: 1 }), None);
+        assert_eq!(
+            value(&gc, sheet_id, Pos { x: 5, y: 5 }),
+            Some(CellValue::Number(1.into()))
+        );
+        assert_eq!(
+            gc.sheet(sheet_id)
+                .get_formatting_value::<Bold>(Pos { x: 5, y: 5 }),
+            Some(true)
+        );
+        assert!(has_border(&gc, sheet_id, Pos { x: 5, y: 5 }));
+        assert!(!has_border(&gc, sheet_id, Pos { x: 0, y: 0 }));
+
+        // the moved formula keeps referencing cells outside the block
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 5, y: 6 }),
+            "F5 + B0 + C0"
+        );
+        assert_eq!(
+            value(&gc, sheet_id, Pos { x: 5, y: 6 }),
+            Some(CellValue::Number(6.into()))
+        );
+        // formulas elsewhere follow the moved cells
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 3, y: 0 }),
+            "SUM(F5:F6) * 10"
+        );
+        assert_eq!(
+            value(&gc, sheet_id, Pos { x: 3, y: 0 }),
+            Some(CellValue::Number(70.into()))
+        );
+
+        gc.undo(None);
+        assert_eq!(
+            value(&gc, sheet_id, Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(1.into()))
+        );
+        assert_eq!(
+            value(&gc, sheet_id, Pos { x: 5, y: 5 }),
+            Some(CellValue::Text("overwritten".into()))
+        );
+        assert_eq!(value(&gc, sheet_id, Pos { x: 5, y: 6 }), None);
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 0, y: 1 }),
+            "A0 + B0 + C0"
+        );
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 3, y: 0 }),
+            "SUM(A0:A1) * 10"
+        );
+        assert_eq!(
+            gc.sheet(sheet_id)
+                .get_formatting_value::<Bold>(Pos { x: 0, y: 0 }),
+            Some(true)
+        );
+        assert!(has_border(&gc, sheet_id, Pos { x: 0, y: 0 }));
+
+        gc.redo(None);
+        assert_eq!(
+            value(&gc, sheet_id, Pos { x: 5, y: 5 }),
+            Some(CellValue::Number(1.into()))
+        );
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 5, y: 6 }),
+            "F5 + B0 + C0"
+        );
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 3, y: 0 }),
+            "SUM(F5:F6) * 10"
+        );
+    }
+
+    #[test]
+    fn test_move_cells_overlapping() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        for x in 0..3 {
+            gc.set_cell_value(sheet_id, Pos { x, y: 0 }, (x + 1).to_string(), None);
+        }
+
+        // move A0:C0 one cell to the right
+        gc.move_cells(
+            sheet_id,
+            Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 2, y: 0 }),
+            Pos { x: 1, y: 0 },
+            None,
+        );
+        let values = (0..4)
+            .map(|x| value(&gc, sheet_id, Pos { x, y: 0 }))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                None,
+                Some(CellValue::Number(1.into())),
+                Some(CellValue::Number(2.into())),
+                Some(CellValue::Number(3.into())),
+            ]
+        );
+
+        gc.undo(None);
+        let values = (0..4)
+            .map(|x| value(&gc, sheet_id, Pos { x, y: 0 }))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                Some(CellValue::Number(1.into())),
+                Some(CellValue::Number(2.into())),
+                Some(CellValue::Number(3.into())),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_move_cells_sql() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "amount".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 1 }, "5".into(), None);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 3, y: 0 },
+            CodeCellLanguage::Sql,
+            "SELECT SUM(amount) AS total FROM A0:A1".into(),
+            None,
+        );
+
+        // move A0:A1 to F5
+        gc.move_cells(
+            sheet_id,
+            Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 0, y: 1 }),
+            Pos { x: 5, y: 5 },
+            None,
+        );
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 3, y: 0 }),
+            "SELECT SUM(amount) AS total FROM F5:F6"
+        );
+        assert_eq!(
+            value(&gc, sheet_id, Pos { x: 3, y: 1 }),
+            Some(CellValue::Number(5.into()))
+        );
+
+        gc.undo(None);
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 3, y: 0 }),
+            "SELECT SUM(amount) AS total FROM A0:A1"
+        );
+    }
+}
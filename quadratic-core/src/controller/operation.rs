@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     grid::{CellRef, CodeCellValue, ColumnId, RegionRef, RowId, Sheet, SheetBorders, SheetId},
-    Array,
+    Array, Pos, Rect,
 };
 
 use super::formatting::CellFmtArray;
@@ -54,6 +54,31 @@ pub enum Operation {
         row: RowId,
         new_size: f64,
     },
+    InsertColumns {
+        sheet_id: SheetId,
+        x: i64,
+        column_ids: Vec<ColumnId>,
+    },
+    DeleteColumns {
+        sheet_id: SheetId,
+        x: i64,
+        count: u32,
+    },
+    InsertRows {
+        sheet_id: SheetId,
+        y: i64,
+        row_ids: Vec<RowId>,
+    },
+    DeleteRows {
+        sheet_id: SheetId,
+        y: i64,
+        count: u32,
+    },
+    MoveCells {
+        sheet_id: SheetId,
+        source: Rect,
+        dest: Pos,
+    },
 }
 
 impl fmt::Display for Operation {
@@ -110,6 +135,47 @@ impl fmt::Display for Operation {
                 sheet_id, row, new_size
             ),
             Operation::SetBorders { .. } => write!(fmt, "SetBorders {{ todo }}"),
+            Operation::InsertColumns {
+                sheet_id,
+                x,
+                column_ids,
+            } => write!(
+                fmt,
+                "InsertColumns {{ sheet_id: {}, x: {}, count: {} }}",
+                sheet_id,
+                x,
+                column_ids.len()
+            ),
+            Operation::DeleteColumns { sheet_id, x, count } => write!(
+                fmt,
+                "DeleteColumns {{ sheet_id: {}, x: {}, count: {} }}",
+                sheet_id, x, count
+            ),
+            Operation::InsertRows {
+                sheet_id,
+                y,
+                row_ids,
+            } => write!(
+                fmt,
+                "InsertRows {{ sheet_id: {}, y: {}, count: {} }}",
+                sheet_id,
+                y,
+                row_ids.len()
+            ),
+            Operation::DeleteRows { sheet_id, y, count } => write!(
+                fmt,
+                "DeleteRows {{ sheet_id: {}, y: {}, count: {} }}",
+                sheet_id, y, count
+            ),
+            Operation::MoveCells {
+                sheet_id,
+                source,
+                dest,
+            } => write!(
+                fmt,
+                "MoveCells {{ sheet_id: {}, source: {:?}, dest: {:?} }}",
+                sheet_id, source, dest
+            ),
         }
     }
 }
@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 
 use indexmap::IndexSet;
+use itertools::Itertools;
 
-use crate::{grid::*, Array, CellValue, Pos};
+use crate::{grid::*, Array, Axis, CellValue, Pos, Rect};
 
 use super::{
     formatting::CellFmtArray,
@@ -68,6 +69,7 @@ impl GridController {
                 for (cell_ref, pos) in code_cells_to_delete {
                     let sheet = self.grid.sheet_mut_from_id(sheet_id);
                     let old_value = sheet.set_code_cell_value(pos, None);
+                    self.dependencies.update(cell_ref, None);
                     fetch_code_cell_difference(
                         self,
                         sheet_id,
@@ -145,14 +147,27 @@ impl GridController {
                 let sheet = self.grid.sheet_mut_from_id(sheet_id);
                 if compute {
                     if let Some(code_cell_value) = code_cell_value {
-                        let updated_code_cell_value =
-                            if let Some(old_code_cell_value) = old_code_cell_value.as_ref() {
-                                let mut updated_code_cell_value = code_cell_value.clone();
-                                updated_code_cell_value.output = old_code_cell_value.output.clone();
-                                updated_code_cell_value
+                        let mut updated_code_cell_value = code_cell_value.clone();
+                        if let Some(old_code_cell_value) = old_code_cell_value.as_ref() {
+                            updated_code_cell_value.output = old_code_cell_value.output.clone();
+                        }
+                        // new code may read different cells, so its old
+                        // dependencies are dropped until it runs again
+                        let code_changed = match old_code_cell_value.as_ref() {
+                            Some(old) => {
+                                old.language != code_cell_value.language
+                                    || old.code_string != code_cell_value.code_string
+                            }
+                            None => true,
+                        };
+                        self.dependencies.update(
+                            cell_ref,
+                            Some(if code_changed {
+                                &code_cell_value
                             } else {
-                                code_cell_value
-                            };
+                                &updated_code_cell_value
+                            }),
+                        );
                         sheet.set_code_cell_value(pos, Some(updated_code_cell_value));
                     } else {
                         fetch_code_cell_difference(
@@ -167,6 +182,7 @@ impl GridController {
                         );
                         let sheet = self.grid.sheet_mut_from_id(sheet_id);
                         sheet.set_code_cell_value(pos, None);
+                        self.dependencies.update(cell_ref, None);
                     }
                     cells_to_compute.insert(cell_ref);
                 } else {
@@ -183,6 +199,7 @@ impl GridController {
                     );
                     let sheet = self.grid.sheet_mut_from_id(sheet_id);
                     sheet.set_code_cell_value(pos, code_cell_value.clone());
+                    self.dependencies.update(cell_ref, code_cell_value.as_ref());
                 }
 
                 // TODO(ddimaria): resolve comment from @HactarCE:
@@ -325,6 +342,7 @@ impl GridController {
                 // todo: need to handle the case where sheet.order overlaps another sheet order
                 // this may happen after (1) delete a sheet; (2) MP update w/an added sheet; and (3) undo the deleted sheet
                 let sheet_id = sheet.id;
+                self.dependencies.add_sheet(&sheet);
                 self.grid
                     .add_sheet(Some(sheet))
                     .expect("duplicate sheet name");
@@ -334,6 +352,7 @@ impl GridController {
             }
             Operation::DeleteSheet { sheet_id } => {
                 let deleted_sheet = self.grid.remove_sheet(sheet_id);
+                self.dependencies.remove_sheet(&deleted_sheet);
                 summary.sheet_list_modified = true;
 
                 reverse_operations.push(Operation::AddSheet {
@@ -418,6 +437,165 @@ impl GridController {
                     });
                 }
             }
+
+            Operation::InsertColumns {
+                sheet_id,
+                x,
+                column_ids,
+            } => {
+                let old_bounds = self.rows_columns_bounds(sheet_id);
+                let sheet = self.grid.sheet_mut_from_id(sheet_id);
+                sheet.insert_columns(x, &column_ids);
+                sheets_with_changed_bounds.insert(sheet_id);
+                self.rows_columns_modified(sheet_id, Axis::X, x, old_bounds, summary);
+
+                reverse_operations.push(Operation::DeleteColumns {
+                    sheet_id,
+                    x,
+                    count: column_ids.len() as u32,
+                });
+            }
+
+            Operation::DeleteColumns { sheet_id, x, count } => {
+                let count = count as i64;
+                let old_bounds = self.rows_columns_bounds(sheet_id);
+                let sheet = self.grid.sheet_mut_from_id(sheet_id);
+                let column_ids = (x..x + count)
+                    .map(|x| sheet.get_or_create_column(x).0.id)
+                    .collect_vec();
+
+                // clear the columns first, so that undo can restore their
+                // contents once the columns are inserted again
+                if let GridBounds::NonEmpty(bounds) = old_bounds {
+                    let rect = Rect::new_span(
+                        Pos { x, y: bounds.min.y },
+                        Pos {
+                            x: x + count - 1,
+                            y: bounds.max.y,
+                        },
+                    );
+                    for op in self.delete_cells_and_formatting_operations(sheet_id, rect) {
+                        reverse_operations.extend(self.execute_operation(
+                            op,
+                            cells_updated,
+                            cells_to_compute,
+                            summary,
+                            sheets_with_changed_bounds,
+                            compute,
+                        ));
+                    }
+                }
+
+                let sheet = self.grid.sheet_mut_from_id(sheet_id);
+                for (&column, x) in column_ids.iter().zip(x..) {
+                    let old_size = sheet.offsets.reset_column_width(x);
+                    if old_size != sheet.offsets.column_width(x) {
+                        reverse_operations.push(Operation::ResizeColumn {
+                            sheet_id,
+                            column,
+                            new_size: old_size,
+                        });
+                    }
+                }
+                sheet.delete_columns(x, count);
+                sheets_with_changed_bounds.insert(sheet_id);
+                self.rows_columns_modified(sheet_id, Axis::X, x, old_bounds, summary);
+
+                reverse_operations.push(Operation::InsertColumns {
+                    sheet_id,
+                    x,
+                    column_ids,
+                });
+            }
+
+            Operation::InsertRows {
+                sheet_id,
+                y,
+                row_ids,
+            } => {
+                let old_bounds = self.rows_columns_bounds(sheet_id);
+                let sheet = self.grid.sheet_mut_from_id(sheet_id);
+                sheet.insert_rows(y, &row_ids);
+                sheets_with_changed_bounds.insert(sheet_id);
+                self.rows_columns_modified(sheet_id, Axis::Y, y, old_bounds, summary);
+
+                reverse_operations.push(Operation::DeleteRows {
+                    sheet_id,
+                    y,
+                    count: row_ids.len() as u32,
+                });
+            }
+
+            Operation::DeleteRows { sheet_id, y, count } => {
+                let count = count as i64;
+                let old_bounds = self.rows_columns_bounds(sheet_id);
+                let sheet = self.grid.sheet_mut_from_id(sheet_id);
+                let row_ids = (y..y + count)
+                    .map(|y| sheet.get_or_create_row(y).id)
+                    .collect_vec();
+
+                // clear the rows first, so that undo can restore their
+                // contents once the rows are inserted again
+                if let GridBounds::NonEmpty(bounds) = old_bounds {
+                    let rect = Rect::new_span(
+                        Pos { x: bounds.min.x, y },
+                        Pos {
+                            x: bounds.max.x,
+                            y: y + count - 1,
+                        },
+                    );
+                    for op in self.delete_cells_and_formatting_operations(sheet_id, rect) {
+                        reverse_operations.extend(self.execute_operation(
+                            op,
+                            cells_updated,
+                            cells_to_compute,
+                            summary,
+                            sheets_with_changed_bounds,
+                            compute,
+                        ));
+                    }
+                }
+
+                let sheet = self.grid.sheet_mut_from_id(sheet_id);
+                for (&row, y) in row_ids.iter().zip(y..) {
+                    let old_size = sheet.offsets.reset_row_height(y);
+                    if old_size != sheet.offsets.row_height(y) {
+                        reverse_operations.push(Operation::ResizeRow {
+                            sheet_id,
+                            row,
+                            new_size: old_size,
+                        });
+                    }
+                }
+                sheet.delete_rows(y, count);
+                sheets_with_changed_bounds.insert(sheet_id);
+                self.rows_columns_modified(sheet_id, Axis::Y, y, old_bounds, summary);
+
+                reverse_operations.push(Operation::InsertRows {
+                    sheet_id,
+                    y,
+                    row_ids,
+                });
+            }
+
+            Operation::MoveCells {
+                sheet_id,
+                source,
+                dest,
+            } => {
+                // a move is made of simpler operations, whose reverse
+                // operations undo it
+                for op in self.move_cells_operations(sheet_id, source, dest) {
+                    reverse_operations.extend(self.execute_operation(
+                        op,
+                        cells_updated,
+                        cells_to_compute,
+                        summary,
+                        sheets_with_changed_bounds,
+                        compute,
+                    ));
+                }
+            }
         };
         reverse_operations
     }
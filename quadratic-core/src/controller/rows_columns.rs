@@ -0,0 +1,438 @@
+use std::collections::HashSet;
+
+use crate::{
+    formulas::{adjust_cell_references, adjust_sql_cell_references, RefAdjust},
+    grid::{CodeCellLanguage, CodeCellValue, ColumnId, GridBounds, RowId, SheetId},
+    util::date_string,
+    Axis, Pos,
+};
+
+use super::{
+    operation::Operation,
+    transaction_summary::{
+        CellSheetsModified, TransactionSummary, CELL_SHEET_HEIGHT, CELL_SHEET_WIDTH,
+    },
+    transactions::TransactionType,
+    GridController,
+};
+
+impl GridController {
+    /// Inserts `count` empty columns at `x`, moving the columns at and after
+    /// `x` to the right and updating formulas that reference them.
+    pub fn insert_columns(
+        &mut self,
+        sheet_id: SheetId,
+        x: i64,
+        count: u32,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let column_ids = (0..count).map(|_| ColumnId::new()).collect();
+        let mut ops = vec![Operation::InsertColumns {
+            sheet_id,
+            x,
+            column_ids,
+        }];
+        ops.extend(
+            self.adjust_formulas_operations(sheet_id, RefAdjust::insert(Axis::X, x, count as i64)),
+        );
+        self.set_in_progress_transaction(ops, cursor, true, TransactionType::Normal)
+    }
+
+    /// Deletes `count` columns starting at `x`, moving the columns after them
+    /// to the left and updating formulas that reference them.
+    pub fn delete_columns(
+        &mut self,
+        sheet_id: SheetId,
+        x: i64,
+        count: u32,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let mut ops = vec![Operation::DeleteColumns { sheet_id, x, count }];
+        ops.extend(
+            self.adjust_formulas_operations(sheet_id, RefAdjust::delete(Axis::X, x, count as i64)),
+        );
+        self.set_in_progress_transaction(ops, cursor, true, TransactionType::Normal)
+    }
+
+    /// Inserts `count` empty rows at `y`, moving the rows at and below `y`
+    /// down and updating formulas that reference them.
+    pub fn insert_rows(
+        &mut self,
+        sheet_id: SheetId,
+        y: i64,
+        count: u32,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let row_ids = (0..count).map(|_| RowId::new()).collect();
+        let mut ops = vec![Operation::InsertRows {
+            sheet_id,
+            y,
+            row_ids,
+        }];
+        ops.extend(
+            self.adjust_formulas_operations(sheet_id, RefAdjust::insert(Axis::Y, y, count as i64)),
+        );
+        self.set_in_progress_transaction(ops, cursor, true, TransactionType::Normal)
+    }
+
+    /// Deletes `count` rows starting at `y`, moving the rows below them up and
+    /// updating formulas that reference them.
+    pub fn delete_rows(
+        &mut self,
+        sheet_id: SheetId,
+        y: i64,
+        count: u32,
+        cursor: Option<String>,
+    ) -> TransactionSummary {
+        let mut ops = vec![Operation::DeleteRows { sheet_id, y, count }];
+        ops.extend(
+            self.adjust_formulas_operations(sheet_id, RefAdjust::delete(Axis::Y, y, count as i64)),
+        );
+        self.set_in_progress_transaction(ops, cursor, true, TransactionType::Normal)
+    }
+
+    /// Returns the operations that rewrite the references in every formula
+    /// and SQL query after columns or rows of `sheet_id` are inserted or
+    /// deleted. Code cells that are deleted are skipped.
+    fn adjust_formulas_operations(&self, sheet_id: SheetId, adjust: RefAdjust) -> Vec<Operation> {
+        let mut ops = vec![];
+        for sheet in self.grid.sheets() {
+            let is_adjusted_sheet = |name: Option<&str>| match name {
+                Some(name) => self
+                    .grid
+                    .sheet_from_name(name.to_string())
+                    .is_some_and(|sheet| sheet.id == sheet_id),
+                None => sheet.id == sheet_id,
+            };
+            for (&cell_ref, code_cell) in &sheet.code_cells {
+                let Some(pos) = sheet.cell_ref_to_pos(cell_ref) else {
+                    continue;
+                };
+                if sheet.id == sheet_id && adjust.adjust_pos(pos).is_none() {
+                    continue;
+                }
+                let source = &code_cell.code_string;
+                let code_string = match code_cell.language {
+                    CodeCellLanguage::Formula => {
+                        adjust_cell_references(source, pos, adjust, is_adjusted_sheet)
+                    }
+                    CodeCellLanguage::Sql => {
+                        adjust_sql_cell_references(source, adjust, is_adjusted_sheet)
+                    }
+                    // references in Python and JavaScript are not parsed
+                    CodeCellLanguage::Python | CodeCellLanguage::JavaScript => continue,
+                };
+                if code_string != code_cell.code_string {
+                    ops.push(Operation::SetCellCode {
+                        cell_ref,
+                        code_cell_value: Some(CodeCellValue {
+                            code_string,
+                            last_modified: date_string(),
+                            ..code_cell.clone()
+                        }),
+                    });
+                }
+            }
+        }
+        ops
+    }
+
+    /// Returns the bounds of everything in a sheet that moves when columns or
+    /// rows are inserted or deleted.
+    pub(super) fn rows_columns_bounds(&self, sheet_id: SheetId) -> GridBounds {
+        let sheet = self.grid.sheet_from_id(sheet_id);
+        GridBounds::merge(sheet.bounds(false), sheet.borders().bounds())
+    }
+
+    /// Marks everything in a sheet at or after `start` along `axis` as
+    /// modified, where `old_bounds` are the bounds of the sheet before columns
+    /// or rows were inserted or deleted.
+    pub(super) fn rows_columns_modified(
+        &self,
+        sheet_id: SheetId,
+        axis: Axis,
+        start: i64,
+        old_bounds: GridBounds,
+        summary: &mut TransactionSummary,
+    ) {
+        summary.offsets_modified.push(sheet_id);
+        summary.fill_sheets_modified.push(sheet_id);
+        summary.border_sheets_modified.push(sheet_id);
+        summary.code_cells_modified.insert(sheet_id);
+        summary.generate_thumbnail = true;
+
+        let bounds = GridBounds::merge(old_bounds, self.rows_columns_bounds(sheet_id));
+        let GridBounds::NonEmpty(mut rect) = bounds else {
+            return;
+        };
+        match axis {
+            Axis::X => rect.min.x = rect.min.x.max(start),
+            Axis::Y => rect.min.y = rect.min.y.max(start),
+        }
+        if rect.min.x > rect.max.x || rect.min.y > rect.max.y {
+            return;
+        }
+        let mut modified = HashSet::new();
+        for x in (rect.min.x..=rect.max.x).step_by(CELL_SHEET_WIDTH as usize) {
+            for y in (rect.min.y..=rect.max.y).step_by(CELL_SHEET_HEIGHT as usize) {
+                modified.insert(CellSheetsModified::new(sheet_id, Pos { x, y }));
+            }
+            modified.insert(CellSheetsModified::new(sheet_id, Pos { x, y: rect.max.y }));
+        }
+        for y in (rect.min.y..=rect.max.y).step_by(CELL_SHEET_HEIGHT as usize) {
+            modified.insert(CellSheetsModified::new(sheet_id, Pos { x: rect.max.x, y }));
+        }
+        modified.insert(CellSheetsModified::new(sheet_id, rect.max));
+        summary.cell_sheets_modified.extend(modified);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        color::Rgba,
+        controller::GridController,
+        grid::{Bold, BorderSelection, BorderStyle, CellBorderLine, CodeCellLanguage, SheetId},
+        test_util::{code_string, has_border},
+        CellValue, ErrorMsg, Pos, Rect,
+    };
+
+    fn setup() -> (GridController, SheetId) {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 0 }, "1".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 1, y: 0 }, "2".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 2, y: 0 }, "3".into(), None);
+        gc.set_cell_bold(
+            sheet_id,
+            Rect::single_pos(Pos { x: 1, y: 0 }),
+            Some(true),
+            None,
+        );
+        gc.commit_single_resize(sheet_id, Some(1), None, 150.0, None);
+        tokio_test::block_on(gc.set_borders(
+            sheet_id,
+            Rect::single_pos(Pos { x: 1, y: 0 }),
+            vec![BorderSelection::All],
+            Some(BorderStyle {
+                color: Rgba::default(),
+                line: CellBorderLine::Line1,
+            }),
+            None,
+        ));
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 1 },
+            CodeCellLanguage::Formula,
+            "SUM(A0:C0) + B0".into(),
+            None,
+        );
+        (gc, sheet_id)
+    }
+
+    fn assert_columns_unchanged(gc: &GridController, sheet_id: SheetId) {
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(2.into()))
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 1, y: 0 }),
+            Some(true)
+        );
+        assert_eq!(sheet.offsets.column_width(1), 150.0);
+        assert!(has_border(gc, sheet_id, Pos { x: 1, y: 0 }));
+        assert_eq!(
+            code_string(gc, sheet_id, Pos { x: 0, y: 1 }),
+            "SUM(A0:C0) + B0"
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Number(8.into()))
+        );
+    }
+
+    #[test]
+    fn test_insert_columns() {
+        let (mut gc, sheet_id) = setup();
+        assert_columns_unchanged(&gc, sheet_id);
+
+        gc.insert_columns(sheet_id, 1, 2, None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.get_cell_value(Pos { x: 1, y: 0 }), None);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 3, y: 0 }),
+            Some(CellValue::Number(2.into()))
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 3, y: 0 }),
+            Some(true)
+        );
+        assert_eq!(sheet.offsets.column_width(3), 150.0);
+        assert!(!has_border(&gc, sheet_id, Pos { x: 1, y: 0 }));
+        assert!(has_border(&gc, sheet_id, Pos { x: 3, y: 0 }));
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 0, y: 1 }),
+            "SUM(A0:E0) + D0"
+        );
+
+        // inserted cells are included in the formula's range
+        gc.set_cell_value(sheet_id, Pos { x: 1, y: 0 }, "10".into(), None);
+        assert_eq!(
+            gc.sheet(sheet_id).get_cell_value(Pos { x: 0, y: 1 }),
+            Some(CellValue::Number(18.into()))
+        );
+
+        gc.undo(None);
+        gc.undo(None);
+        assert_columns_unchanged(&gc, sheet_id);
+
+        gc.redo(None);
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 0, y: 1 }),
+            "SUM(A0:E0) + D0"
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).get_cell_value(Pos { x: 3, y: 0 }),
+            Some(CellValue::Number(2.into()))
+        );
+    }
+
+    #[test]
+    fn test_delete_columns() {
+        let (mut gc, sheet_id) = setup();
+
+        gc.delete_columns(sheet_id, 1, 1, None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+        assert_eq!(sheet.get_formatting_value::<Bold>(Pos { x: 1, y: 0 }), None);
+        assert_eq!(sheet.offsets.column_width(1), 100.0);
+        assert!(!has_border(&gc, sheet_id, Pos { x: 1, y: 0 }));
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 0, y: 1 }),
+            "SUM(A0:B0) + #REF!"
+        );
+        let code_cell = gc.sheet(sheet_id).get_code_cell(Pos { x: 0, y: 1 });
+        assert_eq!(
+            code_cell.unwrap().output.as_ref().unwrap().std_err,
+            Some(ErrorMsg::BadCellReference.to_string())
+        );
+
+        gc.undo(None);
+        assert_columns_unchanged(&gc, sheet_id);
+
+        gc.redo(None);
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 0, y: 1 }),
+            "SUM(A0:B0) + #REF!"
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).get_cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(3.into()))
+        );
+    }
+
+    #[test]
+    fn test_insert_and_delete_rows() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        for y in 0..3 {
+            gc.set_cell_value(sheet_id, Pos { x: 0, y }, (y + 1).to_string(), None);
+        }
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 1, y: 0 },
+            CodeCellLanguage::Formula,
+            "A0:A2".into(),
+            None,
+        );
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 2, y: 3 },
+            CodeCellLanguage::Formula,
+            "SUM(A0:A2)".into(),
+            None,
+        );
+
+        gc.insert_rows(sheet_id, 1, 1, None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(code_string(&gc, sheet_id, Pos { x: 1, y: 0 }), "A0:A3");
+        assert_eq!(code_string(&gc, sheet_id, Pos { x: 2, y: 4 }), "SUM(A0:A3)");
+        // the spill is recomputed to include the inserted row
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 1, y: 1 }),
+            Some(CellValue::Blank)
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 1, y: 3 }),
+            Some(CellValue::Number(3.into()))
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 2, y: 4 }),
+            Some(CellValue::Number(6.into()))
+        );
+
+        gc.delete_rows(sheet_id, 0, 2, None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(code_string(&gc, sheet_id, Pos { x: 2, y: 2 }), "SUM(A0:A1)");
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 2, y: 2 }),
+            Some(CellValue::Number(5.into()))
+        );
+        // the code cell that spilled was deleted
+        assert_eq!(sheet.get_cell_value(Pos { x: 1, y: 0 }), None);
+
+        gc.undo(None);
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(code_string(&gc, sheet_id, Pos { x: 1, y: 0 }), "A0:A2");
+        assert_eq!(code_string(&gc, sheet_id, Pos { x: 2, y: 3 }), "SUM(A0:A2)");
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 1, y: 2 }),
+            Some(CellValue::Number(3.into()))
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 2, y: 3 }),
+            Some(CellValue::Number(6.into()))
+        );
+    }
+
+    #[test]
+    fn test_adjust_sql_references() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_value(sheet_id, Pos { x: 1, y: 0 }, "amount".into(), None);
+        gc.set_cell_value(sheet_id, Pos { x: 1, y: 1 }, "5".into(), None);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 3, y: 0 },
+            CodeCellLanguage::Sql,
+            "SELECT SUM(amount) AS total FROM A0:B1".into(),
+            None,
+        );
+
+        gc.insert_columns(sheet_id, 0, 1, None);
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 4, y: 0 }),
+            "SELECT SUM(amount) AS total FROM B0:C1"
+        );
+        assert_eq!(
+            gc.sheet(sheet_id).get_cell_value(Pos { x: 4, y: 1 }),
+            Some(CellValue::Number(5.into()))
+        );
+
+        gc.delete_columns(sheet_id, 1, 2, None);
+        assert_eq!(
+            code_string(&gc, sheet_id, Pos { x: 2, y: 0 }),
+            "SELECT SUM(amount) AS total FROM #REF!"
+        );
+        let code_cell = gc.sheet(sheet_id).get_code_cell(Pos { x: 2, y: 0 });
+        assert_eq!(
+            code_cell.unwrap().get_error().unwrap().msg,
+            ErrorMsg::BadCellReference
+        );
+    }
+}
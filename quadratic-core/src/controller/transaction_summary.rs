@@ -97,4 +97,32 @@ impl TransactionSummary {
         self.generate_thumbnail = false;
         self.save = true;
     }
+
+    /// Combines the changes of a later transaction into this summary.
+    pub fn merge(&mut self, other: TransactionSummary) {
+        for sheet_id in other.fill_sheets_modified {
+            if !self.fill_sheets_modified.contains(&sheet_id) {
+                self.fill_sheets_modified.push(sheet_id);
+            }
+        }
+        for sheet_id in other.border_sheets_modified {
+            if !self.border_sheets_modified.contains(&sheet_id) {
+                self.border_sheets_modified.push(sheet_id);
+            }
+        }
+        for sheet_id in other.offsets_modified {
+            if !self.offsets_modified.contains(&sheet_id) {
+                self.offsets_modified.push(sheet_id);
+            }
+        }
+        self.code_cells_modified.extend(other.code_cells_modified);
+        self.cell_sheets_modified.extend(other.cell_sheets_modified);
+        self.sheet_list_modified |= other.sheet_list_modified;
+        if other.cursor.is_some() {
+            self.cursor = other.cursor;
+        }
+        self.save |= other.save;
+        self.transaction_busy |= other.transaction_busy;
+        self.generate_thumbnail |= other.generate_thumbnail;
+    }
 }
@@ -82,6 +82,7 @@ impl CellsForArray {
     }
 }
 
+#[derive(Debug)]
 #[wasm_bindgen]
 pub struct JsCodeResult {
     success: bool,
@@ -124,16 +125,26 @@ impl JsCodeResult {
                     reverse_operations.extend(ops);
                     Value::Single(cell_value)
                 } else {
-                    unreachable!()
+                    // code that returns nothing, such as an empty array
+                    Value::Single(CellValue::Blank)
                 },
                 cells_accessed: cells_accessed.to_owned(),
             }
         } else {
-            let error_msg = self
-                .error_msg
-                .to_owned()
-                .unwrap_or_else(|| "Unknown Python Error".into());
-            let msg = ErrorMsg::PythonError(error_msg.into());
+            let msg = match language {
+                CodeCellLanguage::JavaScript => ErrorMsg::JavaScriptError(
+                    self.error_msg
+                        .to_owned()
+                        .unwrap_or_else(|| "Unknown JavaScript Error".into())
+                        .into(),
+                ),
+                _ => ErrorMsg::PythonError(
+                    self.error_msg
+                        .to_owned()
+                        .unwrap_or_else(|| "Unknown Python Error".into())
+                        .into(),
+                ),
+            };
             let span = self.line_number.map(|line_number| Span {
                 start: line_number,
                 end: line_number,
@@ -337,4 +348,29 @@ mod test {
         );
         assert_eq!(ops.len(), 3);
     }
+
+    #[test]
+    fn test_into_code_cell_value_no_output() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        let sheet = gc.grid_mut().sheet_mut_from_id(sheet_id);
+        let result = JsCodeResult::new(true, None, None, None, None, None, None, None);
+
+        let cell_ref = sheet.get_or_create_cell_ref(Pos { x: 0, y: 0 });
+        let mut ops: Vec<Operation> = vec![];
+        assert_eq!(
+            result
+                .into_code_cell_value(
+                    sheet,
+                    cell_ref,
+                    CodeCellLanguage::JavaScript,
+                    "return []".into(),
+                    &vec![],
+                    &mut ops
+                )
+                .output
+                .and_then(|output| output.output_value().cloned()),
+            Some(Value::Single(CellValue::Blank)),
+        );
+    }
 }
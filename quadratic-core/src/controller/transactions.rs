@@ -18,6 +18,26 @@ pub enum TransactionType {
     Redo,
 }
 
+/// A transaction requested while an async transaction was still running. These
+/// are applied in order once the in-flight transaction completes.
+#[derive(Debug, Clone)]
+pub enum PendingTransaction {
+    Operations {
+        operations: Vec<Operation>,
+        cursor: Option<String>,
+        compute: bool,
+        transaction_type: TransactionType,
+    },
+    // undo and redo are queued as requests so the stacks are read only after
+    // the in-flight transaction has been finalized
+    Undo {
+        cursor: Option<String>,
+    },
+    Redo {
+        cursor: Option<String>,
+    },
+}
+
 impl GridController {
     pub fn finalize_transaction(&mut self, transaction_in_progress: &TransactionInProgress) {
         let transaction: Transaction = transaction_in_progress.into();
@@ -42,13 +62,15 @@ impl GridController {
         compute: bool,
         transaction_type: TransactionType,
     ) -> TransactionSummary {
-        if self
-            .transaction_in_progress
-            .as_ref()
-            .is_some_and(|in_progress_transaction| !in_progress_transaction.complete)
-        {
-            // todo: add this to a queue of operations instead of setting the busy flag
-            return TransactionSummary::new(true);
+        if self.is_transaction_busy() {
+            self.pending_transactions
+                .push_back(PendingTransaction::Operations {
+                    operations,
+                    cursor,
+                    compute,
+                    transaction_type,
+                });
+            return TransactionSummary::default();
         }
         let mut transaction = TransactionInProgress::start_transaction(
             self,
@@ -69,6 +91,38 @@ impl GridController {
         summary
     }
 
+    /// Returns true if an async transaction is still running.
+    pub fn is_transaction_busy(&self) -> bool {
+        self.transaction_in_progress
+            .as_ref()
+            .is_some_and(|in_progress_transaction| !in_progress_transaction.complete)
+    }
+
+    /// Applies queued transactions in order until the queue is empty or one of
+    /// them waits on an async calculation. Returns the combined summary.
+    fn run_pending_transactions(&mut self) -> TransactionSummary {
+        let mut summary = TransactionSummary::default();
+        while !self.is_transaction_busy() {
+            let Some(pending) = self.pending_transactions.pop_front() else {
+                break;
+            };
+            let pending_summary = match pending {
+                PendingTransaction::Operations {
+                    operations,
+                    cursor,
+                    compute,
+                    transaction_type,
+                } => {
+                    self.set_in_progress_transaction(operations, cursor, compute, transaction_type)
+                }
+                PendingTransaction::Undo { cursor } => self.undo(cursor),
+                PendingTransaction::Redo { cursor } => self.redo(cursor),
+            };
+            summary.merge(pending_summary);
+        }
+        summary
+    }
+
     pub fn has_undo(&self) -> bool {
         !self.undo_stack.is_empty()
     }
@@ -76,6 +130,11 @@ impl GridController {
         !self.redo_stack.is_empty()
     }
     pub fn undo(&mut self, cursor: Option<String>) -> TransactionSummary {
+        if self.is_transaction_busy() {
+            self.pending_transactions
+                .push_back(PendingTransaction::Undo { cursor });
+            return TransactionSummary::default();
+        }
         if let Some(transaction) = self.undo_stack.pop() {
             let mut summary = self.set_in_progress_transaction(
                 transaction.ops,
@@ -90,6 +149,11 @@ impl GridController {
         }
     }
     pub fn redo(&mut self, cursor: Option<String>) -> TransactionSummary {
+        if self.is_transaction_busy() {
+            self.pending_transactions
+                .push_back(PendingTransaction::Redo { cursor });
+            return TransactionSummary::default();
+        }
         if let Some(transaction) = self.redo_stack.pop() {
             let mut summary = self.set_in_progress_transaction(
                 transaction.ops,
@@ -118,7 +182,9 @@ impl GridController {
 
             transaction.updated_bounds(self);
             if transaction.complete {
-                transaction.transaction_summary()
+                let mut summary = transaction.transaction_summary();
+                summary.merge(self.run_pending_transactions());
+                summary
             } else {
                 TransactionSummary::default()
             }
@@ -133,6 +199,16 @@ impl GridController {
         if let Some(transaction) = &mut self.transaction_in_progress.clone() {
             let result = transaction.get_cells(self, get_cells);
             self.transaction_in_progress = Some(transaction.to_owned());
+
+            // a sheet error can complete the transaction; TS then fetches the
+            // summary from whichever transaction is in progress
+            if transaction.complete && !self.pending_transactions.is_empty() {
+                let mut summary = transaction.transaction_summary();
+                summary.merge(self.run_pending_transactions());
+                if let Some(in_progress) = &mut self.transaction_in_progress {
+                    in_progress.summary = summary;
+                }
+            }
             result
         } else {
             panic!("Expected a transaction to still be running");
@@ -189,7 +265,8 @@ impl From<Pos> for CellHash {
 #[cfg(test)]
 mod tests {
     use crate::{
-        grid::{GridBounds, SheetId},
+        controller::code_runner::PendingCodeRunner,
+        grid::{CodeCellLanguage, GridBounds, SheetId},
         Array, CellValue, Pos, Rect,
     };
 
@@ -321,6 +398,114 @@ mod tests {
         assert_eq!(gc.grid().sheets()[0].bounds(true), expected);
     }
 
+    fn python_result(output_value: &str) -> JsCodeResult {
+        JsCodeResult::new(
+            true,
+            None,
+            None,
+            None,
+            Some(output_value.into()),
+            None,
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_transactions_queue_while_busy() {
+        let mut gc = GridController::new();
+        gc.set_code_runner(CodeCellLanguage::Python, PendingCodeRunner);
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 0 },
+            CodeCellLanguage::Python,
+            "1".into(),
+            None,
+        );
+        assert!(gc.is_transaction_busy());
+
+        // edits made while python is running are queued rather than dropped
+        let summary = gc.set_cell_value(sheet_id, Pos { x: 1, y: 0 }, "a".into(), None);
+        assert!(!summary.transaction_busy);
+        let summary = gc.set_cell_value(sheet_id, Pos { x: 2, y: 0 }, "b".into(), None);
+        assert!(!summary.transaction_busy);
+        assert_eq!(gc.pending_transactions.len(), 2);
+        assert_eq!(gc.sheet(sheet_id).get_cell_value(Pos { x: 1, y: 0 }), None);
+
+        // queued edits are applied in order once python completes
+        let summary = gc.calculation_complete(python_result("1"));
+        assert!(summary.save);
+        assert!(!gc.is_transaction_busy());
+        assert!(gc.pending_transactions.is_empty());
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(1.into()))
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Text("a".into()))
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 2, y: 0 }),
+            Some(CellValue::Text("b".into()))
+        );
+
+        // the python transaction is finalized before the queued edits
+        assert_eq!(gc.undo_stack.len(), 3);
+        gc.undo(None);
+        gc.undo(None);
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(sheet.get_cell_value(Pos { x: 1, y: 0 }), None);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 0, y: 0 }),
+            Some(CellValue::Number(1.into()))
+        );
+    }
+
+    #[test]
+    fn test_transactions_queue_undo_while_busy() {
+        let mut gc = GridController::new();
+        gc.set_code_runner(CodeCellLanguage::Python, PendingCodeRunner);
+        let sheet_id = gc.sheet_ids()[0];
+
+        gc.set_cell_value(sheet_id, Pos { x: 1, y: 0 }, "a".into(), None);
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 0 },
+            CodeCellLanguage::Python,
+            "1".into(),
+            None,
+        );
+
+        // undo waits for the python transaction, so it undoes the code cell
+        gc.undo(None);
+        assert_eq!(gc.undo_stack.len(), 1);
+        assert!(!gc.has_redo());
+
+        // an edit queued after the undo is applied after it
+        gc.set_cell_value(sheet_id, Pos { x: 2, y: 0 }, "b".into(), None);
+
+        gc.calculation_complete(python_result("1"));
+        assert!(gc.pending_transactions.is_empty());
+        let sheet = gc.sheet(sheet_id);
+        assert!(sheet.get_code_cell(Pos { x: 0, y: 0 }).is_none());
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Text("a".into()))
+        );
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 2, y: 0 }),
+            Some(CellValue::Text("b".into()))
+        );
+
+        // the new edit cleared the redo stack, as it would have without the queue
+        assert_eq!(gc.undo_stack.len(), 2);
+        assert!(!gc.has_redo());
+    }
+
     #[test]
     fn test_transactions_cell_hash() {
         let hash = "test".to_string();
@@ -105,6 +105,9 @@ pub fn update_code_cell_value(
         });
 
         let old_code_cell_value = sheet.set_code_cell_value(pos, updated_code_cell_value.clone());
+        grid_controller
+            .dependencies
+            .update(cell_ref, updated_code_cell_value.as_ref());
 
         // updates summary.thumbnail_dirty flag
         let sheet = grid_controller.grid.sheet_from_id(cell_ref.sheet);
@@ -43,6 +43,7 @@ impl Error {
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
 pub enum ErrorMsg {
     PythonError(Cow<'static, str>),
+    JavaScriptError(Cow<'static, str>),
 
     Spill,
 
@@ -69,6 +70,8 @@ pub enum ErrorMsg {
     BadFunctionName,
     BadCellReference,
     BadNumber,
+    UnknownColumn(Cow<'static, str>),
+    BadName(Cow<'static, str>),
 
     // Array size errors
     ExactArraySizeMismatch {
@@ -92,6 +95,7 @@ pub enum ErrorMsg {
 
     // Runtime errors
     CircularReference,
+    RecursionLimit,
     Overflow,
     DivideByZero,
     NegativeExponent,
@@ -99,7 +103,9 @@ pub enum ErrorMsg {
     Infinity,
     IndexOutOfBounds,
     NoMatch,
+    NotAvailable,
     InvalidArgument,
+    NoConvergence,
 }
 impl fmt::Display for ErrorMsg {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -107,6 +113,9 @@ impl fmt::Display for ErrorMsg {
             Self::PythonError(s) => {
                 write!(f, "Python error: {s}")
             }
+            Self::JavaScriptError(s) => {
+                write!(f, "JavaScript error: {s}")
+            }
             Self::Spill => {
                 write!(f, "Spill error")
             }
@@ -157,6 +166,12 @@ impl fmt::Display for ErrorMsg {
             Self::BadNumber => {
                 write!(f, "Bad numeric literal")
             }
+            Self::UnknownColumn(name) => {
+                write!(f, "There is no column named `{name}`")
+            }
+            Self::BadName(name) => {
+                write!(f, "There is nothing named `{name}`")
+            }
 
             Self::ExactArraySizeMismatch { expected, got } => {
                 write!(
@@ -212,6 +227,9 @@ impl fmt::Display for ErrorMsg {
             Self::CircularReference => {
                 write!(f, "Circular reference")
             }
+            Self::RecursionLimit => {
+                write!(f, "Too many nested function calls")
+            }
             Self::Overflow => {
                 write!(f, "Numeric overflow")
             }
@@ -233,9 +251,15 @@ impl fmt::Display for ErrorMsg {
             Self::NoMatch => {
                 write!(f, "No match found")
             }
+            Self::NotAvailable => {
+                write!(f, "Value not available")
+            }
             Self::InvalidArgument => {
                 write!(f, "Invalid argument")
             }
+            Self::NoConvergence => {
+                write!(f, "Calculation did not converge")
+            }
         }
     }
 }
@@ -254,6 +278,29 @@ impl ErrorMsg {
             msg: self,
         }
     }
+    /// Returns whether this error can be passed to a function as a value, so
+    /// that functions such as `IFERROR` can handle it. Errors in the formula
+    /// itself, such as an unknown function name or a missing argument, always
+    /// stop evaluation instead.
+    pub fn is_catchable(&self) -> bool {
+        !matches!(
+            self,
+            Self::Unimplemented
+                | Self::InternalError(_)
+                | Self::Unterminated(_)
+                | Self::Unexpected(_)
+                | Self::TooManyArguments { .. }
+                | Self::MissingRequiredArgument { .. }
+                | Self::BadFunctionName
+                | Self::BadName(_)
+        )
+    }
+    /// Returns whether this error means that a value is not available, like
+    /// `#N/A` in Excel. A lookup that finds no match counts, because Excel
+    /// returns `#N/A` in that case too.
+    pub fn is_not_available(&self) -> bool {
+        matches!(self, Self::NoMatch | Self::NotAvailable)
+    }
 }
 
 impl<T: Into<ErrorMsg>> From<T> for Error {
@@ -0,0 +1,418 @@
+//! Rewriting of cell references in formulas when columns or rows are inserted
+//! or deleted, or when cells are moved.
+
+use super::lexer::{self, Token};
+use super::{find_cell_references, CellRef, CellRefCoord, RangeRef};
+use crate::{Axis, Pos, Rect, Spanned};
+
+/// Text that replaces a reference to cells that have been deleted.
+pub const DELETED_REFERENCE: &str = "#REF!";
+
+/// Insertion or deletion of columns or rows, which moves every column or row
+/// after it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RefAdjust {
+    /// Whether columns ([`Axis::X`]) or rows ([`Axis::Y`]) are changed.
+    pub axis: Axis,
+    /// Index of the first column or row inserted or deleted.
+    pub start: i64,
+    /// Number of columns or rows inserted (if positive) or deleted (if
+    /// negative).
+    pub delta: i64,
+}
+impl RefAdjust {
+    /// Constructs an adjustment for inserting `count` columns or rows before
+    /// `start`.
+    pub fn insert(axis: Axis, start: i64, count: i64) -> Self {
+        Self {
+            axis,
+            start,
+            delta: count,
+        }
+    }
+    /// Constructs an adjustment for deleting `count` columns or rows starting
+    /// at `start`.
+    pub fn delete(axis: Axis, start: i64, count: i64) -> Self {
+        Self {
+            axis,
+            start,
+            delta: -count,
+        }
+    }
+
+    /// Returns the new index of a column or row, or `None` if it is deleted.
+    pub fn adjust(self, index: i64) -> Option<i64> {
+        if index < self.start {
+            Some(index)
+        } else if self.delta >= 0 || index >= self.start - self.delta {
+            Some(index + self.delta)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the new position of a cell, or `None` if it is deleted.
+    pub fn adjust_pos(self, pos: Pos) -> Option<Pos> {
+        Some(match self.axis {
+            Axis::X => Pos {
+                x: self.adjust(pos.x)?,
+                y: pos.y,
+            },
+            Axis::Y => Pos {
+                x: pos.x,
+                y: self.adjust(pos.y)?,
+            },
+        })
+    }
+
+    /// Returns the new endpoints of a range of columns or rows, which shrinks
+    /// if part of it is deleted. Returns `None` if the whole range is deleted.
+    fn adjust_range(self, a: i64, b: i64) -> Option<(i64, i64)> {
+        let (lo, hi) = (a.min(b), a.max(b));
+        let new_lo = self.adjust(lo).unwrap_or(self.start);
+        let new_hi = self.adjust(hi).unwrap_or(self.start - 1);
+        if new_hi < new_lo {
+            None
+        } else if a <= b {
+            Some((new_lo, new_hi))
+        } else {
+            Some((new_hi, new_lo))
+        }
+    }
+}
+
+/// Rewrites the A1-style cell references in a formula so that they follow the
+/// cells they refer to after `adjust`. A reference to cells that are all
+/// deleted is replaced with [`DELETED_REFERENCE`].
+///
+/// `is_adjusted_sheet` is called with the sheet name of each reference (or
+/// `None` if it has no sheet name) and returns whether that reference is on
+/// the sheet being changed.
+pub fn adjust_cell_references(
+    source: &str,
+    pos: Pos,
+    adjust: RefAdjust,
+    is_adjusted_sheet: impl Fn(Option<&str>) -> bool,
+) -> String {
+    let cell_refs = find_cell_references(source, pos);
+    adjust_references(source, cell_refs, pos, adjust, is_adjusted_sheet)
+}
+
+/// Rewrites the ranges of cells used as tables in a SQL query in the same way
+/// as [`adjust_cell_references()`]. These ranges are always absolute.
+pub fn adjust_sql_cell_references(
+    source: &str,
+    adjust: RefAdjust,
+    is_adjusted_sheet: impl Fn(Option<&str>) -> bool,
+) -> String {
+    let cell_refs = find_sql_cell_references(source);
+    adjust_references(source, cell_refs, Pos::ORIGIN, adjust, is_adjusted_sheet)
+}
+
+/// Returns the ranges of cells used as tables in a SQL query.
+fn find_sql_cell_references(source: &str) -> Vec<Spanned<RangeRef>> {
+    crate::sql::find_table_references(source)
+        .into_iter()
+        .map(|table_ref| table_ref.map(|(start, end)| RangeRef::CellRange { start, end }))
+        .collect()
+}
+
+fn adjust_references(
+    source: &str,
+    cell_refs: Vec<Spanned<RangeRef>>,
+    pos: Pos,
+    adjust: RefAdjust,
+    is_adjusted_sheet: impl Fn(Option<&str>) -> bool,
+) -> String {
+    rewrite_cell_references(
+        source,
+        cell_refs,
+        pos,
+        is_adjusted_sheet,
+        |start, end| match adjust.axis {
+            Axis::X => adjust
+                .adjust_range(start.x, end.x)
+                .map(|(x1, x2)| (Pos { x: x1, ..start }, Pos { x: x2, ..end })),
+            Axis::Y => adjust
+                .adjust_range(start.y, end.y)
+                .map(|(y1, y2)| (Pos { y: y1, ..start }, Pos { y: y2, ..end })),
+        },
+        |axis, start, end| match axis == adjust.axis {
+            true => adjust.adjust_range(start, end),
+            false => Some((start, end)),
+        },
+    )
+}
+
+/// Rewrites the A1-style cell references in a formula after the cells in
+/// `from` are moved so that the top-left cell is at `to`. Only references
+/// that are entirely within `from` are changed, so references to whole
+/// columns or rows never change.
+///
+/// `is_moved_sheet` is called with the sheet name of each reference (or
+/// `None` if it has no sheet name) and returns whether that reference is on
+/// the sheet where the cells moved.
+pub fn move_cell_references(
+    source: &str,
+    pos: Pos,
+    from: Rect,
+    to: Pos,
+    is_moved_sheet: impl Fn(Option<&str>) -> bool,
+) -> String {
+    let cell_refs = find_cell_references(source, pos);
+    move_references(source, cell_refs, pos, from, to, is_moved_sheet)
+}
+
+/// Rewrites the ranges of cells used as tables in a SQL query in the same way
+/// as [`move_cell_references()`].
+pub fn move_sql_cell_references(
+    source: &str,
+    from: Rect,
+    to: Pos,
+    is_moved_sheet: impl Fn(Option<&str>) -> bool,
+) -> String {
+    let cell_refs = find_sql_cell_references(source);
+    move_references(source, cell_refs, Pos::ORIGIN, from, to, is_moved_sheet)
+}
+
+fn move_references(
+    source: &str,
+    cell_refs: Vec<Spanned<RangeRef>>,
+    pos: Pos,
+    from: Rect,
+    to: Pos,
+    is_moved_sheet: impl Fn(Option<&str>) -> bool,
+) -> String {
+    let (dx, dy) = (to.x - from.min.x, to.y - from.min.y);
+    rewrite_cell_references(
+        source,
+        cell_refs,
+        pos,
+        is_moved_sheet,
+        |start, end| {
+            if from.contains(start) && from.contains(end) {
+                Some((
+                    Pos {
+                        x: start.x + dx,
+                        y: start.y + dy,
+                    },
+                    Pos {
+                        x: end.x + dx,
+                        y: end.y + dy,
+                    },
+                ))
+            } else {
+                Some((start, end))
+            }
+        },
+        |_, start, end| Some((start, end)),
+    )
+}
+
+/// Replaces each of `cell_refs` on a matching sheet with the result of
+/// `new_range`, which takes the start and end of the old reference and returns
+/// the start and end of the new one, or `None` if the cells were deleted.
+/// References to whole columns or rows are replaced using `new_col_or_row_range`
+/// in the same way.
+fn rewrite_cell_references(
+    source: &str,
+    cell_refs: Vec<Spanned<RangeRef>>,
+    pos: Pos,
+    is_matching_sheet: impl Fn(Option<&str>) -> bool,
+    new_range: impl Fn(Pos, Pos) -> Option<(Pos, Pos)>,
+    new_col_or_row_range: impl Fn(Axis, i64, i64) -> Option<(i64, i64)>,
+) -> String {
+    let mut ret = String::new();
+    let mut last_end = 0;
+
+    for cell_ref in cell_refs {
+        let span_start = cell_ref.span.start as usize;
+        let span_end = cell_ref.span.end as usize;
+        let text = &source[span_start..span_end];
+
+        let rewrite_cells = |start: &CellRef, end: &CellRef| {
+            if !is_matching_sheet(start.sheet.as_deref()) {
+                return None;
+            }
+            let (old_start, old_end) = (start.resolve_from(pos), end.resolve_from(pos));
+            match new_range(old_start, old_end) {
+                None => Some(DELETED_REFERENCE.to_string()),
+                Some(new_positions) if new_positions == (old_start, old_end) => None,
+                // Replace only the cell reference tokens, keeping any sheet
+                // names and `$` markers.
+                Some((new_start, new_end)) => Some(replace_tokens(
+                    text,
+                    Token::CellRef,
+                    [new_start, new_end],
+                    a1_string_like,
+                )),
+            }
+        };
+        let rewrite_col_or_row_range =
+            |axis: Axis, start: &CellRefCoord, end: &CellRefCoord, sheet: &Option<String>| {
+                if !is_matching_sheet(sheet.as_deref()) {
+                    return None;
+                }
+                let (token, base) = match axis {
+                    Axis::X => (Token::ColRangeRef, pos.x),
+                    Axis::Y => (Token::RowRangeRef, pos.y),
+                };
+                let old_range = (start.resolve_from(base), end.resolve_from(base));
+                match new_col_or_row_range(axis, old_range.0, old_range.1) {
+                    None => Some(DELETED_REFERENCE.to_string()),
+                    Some(new_range) if new_range == old_range => None,
+                    Some((new_start, new_end)) => {
+                        Some(replace_tokens(text, token, [()], |old, ()| {
+                            let (old_start, old_end) = old.split_once(':').unwrap_or((old, old));
+                            format!(
+                                "{}:{}",
+                                coord_string_like(old_start, axis, new_start),
+                                coord_string_like(old_end, axis, new_end),
+                            )
+                        }))
+                    }
+                }
+            };
+
+        let new_text = match &cell_ref.inner {
+            RangeRef::Cell { pos } => rewrite_cells(pos, pos),
+            RangeRef::CellRange { start, end } => rewrite_cells(start, end),
+            RangeRef::ColRange { start, end, sheet } => {
+                rewrite_col_or_row_range(Axis::X, start, end, sheet)
+            }
+            RangeRef::RowRange { start, end, sheet } => {
+                rewrite_col_or_row_range(Axis::Y, start, end, sheet)
+            }
+        };
+        let Some(new_text) = new_text else {
+            continue;
+        };
+
+        ret.push_str(&source[last_end..span_start]);
+        ret.push_str(&new_text);
+        last_end = span_end;
+    }
+
+    ret.push_str(&source[last_end..]);
+    ret
+}
+
+/// Replaces each `token` in `text` with `f` called on the old token text and
+/// the corresponding element of `new_values`, keeping any sheet names and
+/// whitespace.
+fn replace_tokens<T>(
+    text: &str,
+    token: Token,
+    new_values: impl IntoIterator<Item = T>,
+    f: impl Fn(&str, T) -> String,
+) -> String {
+    let mut new_text = String::new();
+    let mut text_last_end = 0;
+    let tokens = lexer::tokenize(text).filter(|t| t.inner == token);
+    for (token, new_value) in tokens.zip(new_values) {
+        let (token_start, token_end) = (token.span.start as usize, token.span.end as usize);
+        new_text.push_str(&text[text_last_end..token_start]);
+        new_text.push_str(&f(&text[token_start..token_end], new_value));
+        text_last_end = token_end;
+    }
+    new_text.push_str(&text[text_last_end..]);
+    new_text
+}
+
+/// Returns the A1-style reference to `pos`, with the same `$` markers as
+/// `old`.
+fn a1_string_like(old: &str, pos: Pos) -> String {
+    let column_is_absolute = old.starts_with('$');
+    let row_is_absolute = old[1..].contains('$');
+    format!(
+        "{}{}{}{}",
+        if column_is_absolute { "$" } else { "" },
+        crate::util::column_name(pos.x),
+        if row_is_absolute { "$" } else { "" },
+        row_string(pos.y),
+    )
+}
+
+/// Returns the A1-style column name or row number of `index`, with the same
+/// `$` marker as `old`.
+fn coord_string_like(old: &str, axis: Axis, index: i64) -> String {
+    let prefix = if old.starts_with('$') { "$" } else { "" };
+    match axis {
+        Axis::X => format!("{prefix}{}", crate::util::column_name(index)),
+        Axis::Y => format!("{prefix}{}", row_string(index)),
+    }
+}
+
+/// Returns the A1-style row number of `y`.
+fn row_string(y: i64) -> String {
+    if y < 0 {
+        format!("n{}", -y)
+    } else {
+        y.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adjust(source: &str, adjust: RefAdjust) -> String {
+        adjust_cell_references(source, pos![E8], adjust, |sheet| {
+            matches!(sheet, None | Some("Sheet 1"))
+        })
+    }
+
+    #[test]
+    fn test_adjust_cell_references_insert() {
+        let insert_columns = RefAdjust::insert(Axis::X, 1, 2);
+        assert_eq!("A1 + D2", adjust("A1 + B2", insert_columns));
+        assert_eq!("SUM($A$1:$E$3)", adjust("SUM($A$1:$C$3)", insert_columns));
+        assert_eq!("'Sheet 1'!D0", adjust("'Sheet 1'!B0", insert_columns));
+        assert_eq!("'Sheet 2'!B0", adjust("'Sheet 2'!B0", insert_columns));
+        assert_eq!("\"B2\" & A2", adjust("\"B2\" & A2", insert_columns));
+
+        let insert_rows = RefAdjust::insert(Axis::Y, 0, 1);
+        assert_eq!("A2 + Bn1 + C$1", adjust("A1 + Bn1 + C$0", insert_rows));
+        assert_eq!(
+            "SUM(A:B) + SUM(n1:$3)",
+            adjust("SUM(A:B) + SUM(n1:$2)", insert_rows)
+        );
+        assert_eq!("SUM(A:$E)", adjust("SUM(A:$C)", insert_columns));
+    }
+
+    #[test]
+    fn test_adjust_cell_references_delete() {
+        let delete_columns = RefAdjust::delete(Axis::X, 1, 2);
+        assert_eq!("A1 + #REF! + B1", adjust("A1 + C1 + D1", delete_columns));
+        assert_eq!("SUM(A1:C9)", adjust("SUM(A1:E9)", delete_columns));
+        assert_eq!("SUM(B1:B9)", adjust("SUM(B1:D9)", delete_columns));
+        assert_eq!("SUM(#REF!)", adjust("SUM(B1:C9)", delete_columns));
+        assert_eq!("SUM(B1:A1)", adjust("SUM(D1:A1)", delete_columns));
+
+        assert_eq!(
+            "SUM(A:B) + SUM(#REF!)",
+            adjust("SUM(A:D) + SUM(B:C)", delete_columns)
+        );
+
+        let delete_rows = RefAdjust::delete(Axis::Y, 5, 1);
+        assert_eq!("A4 + #REF! + A5", adjust("A4 + A5 + A6", delete_rows));
+        assert_eq!(
+            "SUM(4:5) + SUM(#REF!)",
+            adjust("SUM(4:6) + SUM(5:5)", delete_rows)
+        );
+    }
+
+    #[test]
+    fn test_move_cell_references() {
+        let from = Rect::new_span(pos![B1], pos![C2]);
+        let move_refs = |source| {
+            move_cell_references(source, pos![E8], from, pos![D5], |sheet| sheet.is_none())
+        };
+        assert_eq!("D5 + E6", move_refs("B1 + C2"));
+        assert_eq!("SUM(D5:E6)", move_refs("SUM(B1:C2)"));
+        assert_eq!("SUM(B1:C3) + A1", move_refs("SUM(B1:C3) + A1"));
+        assert_eq!("$D$5", move_refs("$B$1"));
+        assert_eq!("'Sheet 2'!B1", move_refs("'Sheet 2'!B1"));
+        assert_eq!("SUM(B:B)", move_refs("SUM(B:B)"));
+    }
+}
@@ -1,11 +1,15 @@
 use std::fmt;
+use std::rc::Rc;
 
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
 use super::*;
-use crate::{Array, ArraySize, CellValue, CodeResult, CoerceInto, ErrorMsg, Pos, Spanned, Value};
+use crate::{
+    Array, ArraySize, CellValue, CodeResult, CoerceInto, Error, ErrorMsg, Rect, SheetRect, Span,
+    Spanned, Value,
+};
 
 /// Abstract syntax tree of a formula expression.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -30,9 +34,13 @@ pub enum AstNodeContents {
     Paren(Box<AstNode>),
     Array(Vec<Vec<AstNode>>),
     CellRef(CellRef),
+    RangeRef(RangeRef),
     String(String),
     Number(f64),
     Bool(bool),
+    Name(String),
+    /// Reference to cells that have been deleted.
+    DeletedRef,
 }
 impl fmt::Display for AstNodeContents {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -56,10 +64,13 @@ impl fmt::Display for AstNodeContents {
                 a.iter().map(|row| row.iter().join(", ")).join("; "),
             ),
             AstNodeContents::CellRef(cellref) => write!(f, "{cellref}"),
+            AstNodeContents::RangeRef(rangeref) => write!(f, "{rangeref}"),
             AstNodeContents::String(s) => write!(f, "{s:?}"),
             AstNodeContents::Number(n) => write!(f, "{n:?}"),
             AstNodeContents::Bool(false) => write!(f, "FALSE"),
             AstNodeContents::Bool(true) => write!(f, "TRUE"),
+            AstNodeContents::Name(name) => write!(f, "{name}"),
+            AstNodeContents::DeletedRef => write!(f, "{DELETED_REFERENCE}"),
         }
     }
 }
@@ -69,15 +80,23 @@ impl AstNodeContents {
             AstNodeContents::Empty => "empty expression",
             AstNodeContents::FunctionCall { func, .. } => match func.inner.as_str() {
                 "=" | "==" | "<>" | "!=" | "<" | ">" | "<=" | ">=" => "comparison",
-                s if s.chars().all(|c| c.is_alphanumeric() || c == '_') => "function call",
+                s if s
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '.') =>
+                {
+                    "function call"
+                }
                 _ => "expression",
             },
             AstNodeContents::Paren(contents) => contents.inner.type_string(),
             AstNodeContents::Array(_) => "array literal",
             AstNodeContents::CellRef(_) => "cell reference",
+            AstNodeContents::RangeRef(_) => "column or row range reference",
             AstNodeContents::String(_) => "string literal",
             AstNodeContents::Number(_) => "numeric literal",
             AstNodeContents::Bool(_) => "boolean literal",
+            AstNodeContents::Name(_) => "name",
+            AstNodeContents::DeletedRef => "deleted reference",
         }
     }
 }
@@ -93,6 +112,39 @@ impl Spanned<AstNodeContents> {
             .with_span(self.span)),
         }
     }
+
+    /// Returns the name, or an error if the expression is not a name.
+    pub fn to_name(&self) -> CodeResult<Spanned<String>> {
+        match &self.inner {
+            AstNodeContents::Name(name) => Ok(Spanned {
+                span: self.span,
+                inner: name.clone(),
+            }),
+            _ => Err(ErrorMsg::Expected {
+                expected: "name".into(),
+                got: Some(self.inner.type_string().into()),
+            }
+            .with_span(self.span)),
+        }
+    }
+
+    /// Returns whether the expression is an omitted argument.
+    pub fn is_empty(&self) -> bool {
+        matches!(self.inner, AstNodeContents::Empty)
+    }
+
+    /// Returns whether the expression calls a volatile function.
+    fn is_volatile(&self) -> bool {
+        match &self.inner {
+            AstNodeContents::FunctionCall { func, args } => {
+                functions::lookup_function(&func.inner).is_some_and(|f| f.volatile)
+                    || args.iter().any(|arg| arg.is_volatile())
+            }
+            AstNodeContents::Paren(contents) => contents.is_volatile(),
+            AstNodeContents::Array(a) => a.iter().flatten().any(|elem| elem.is_volatile()),
+            _ => false,
+        }
+    }
 }
 
 impl Formula {
@@ -100,6 +152,12 @@ impl Formula {
     pub fn eval(&self, ctx: &mut Ctx<'_>) -> CodeResult<Value> {
         self.ast.eval(ctx)?.into_non_error_value()
     }
+
+    /// Returns whether the formula calls a volatile function such as `RAND`,
+    /// in which case it must be recomputed on every recalculation.
+    pub fn is_volatile(&self) -> bool {
+        self.ast.is_volatile()
+    }
 }
 
 impl AstNode {
@@ -109,65 +167,41 @@ impl AstNode {
 
             // Cell range
             AstNodeContents::FunctionCall { func, args } if func.inner == ":" => {
-                if args.len() != 2 {
-                    internal_error!("invalid arguments to cell range operator");
-                }
-                let ref1 = args[0].to_cell_ref()?;
-                let ref2 = args[1].to_cell_ref()?;
-                let sheet_name = ref1.sheet.clone();
-                let corner1 = ref1.resolve_from(ctx.pos.without_sheet());
-                let corner2 = ref2.resolve_from(ctx.pos.without_sheet());
-
-                let x1 = std::cmp::min(corner1.x, corner2.x);
-                let y1 = std::cmp::min(corner1.y, corner2.y);
-
-                let x2 = std::cmp::max(corner1.x, corner2.x);
-                let y2 = std::cmp::max(corner1.y, corner2.y);
-
-                let width = x2
-                    .saturating_sub(x1)
-                    .saturating_add(1)
-                    .try_into()
-                    .unwrap_or(u32::MAX);
-                let height = y2
-                    .saturating_sub(y1)
-                    .saturating_add(1)
-                    .try_into()
-                    .unwrap_or(u32::MAX);
-                if std::cmp::max(width, height) > crate::limits::CELL_RANGE_LIMIT {
-                    return Err(ErrorMsg::ArrayTooBig.with_span(self.span));
-                }
-
-                let mut flat_array = smallvec![];
-                // Reuse the same `CellRef` object so that we don't have to
-                // clone `sheet_name.`
-                let mut cell_ref = CellRef::absolute(sheet_name, Pos::ORIGIN); // We'll overwrite the position.
-                for y in y1..=y2 {
-                    cell_ref.y = CellRefCoord::Absolute(y);
-                    for x in x1..=x2 {
-                        cell_ref.x = CellRefCoord::Absolute(x);
-                        flat_array.push(ctx.get_cell(&cell_ref, self.span)?.inner);
-                    }
-                }
+                let sheet_rect = self.cell_range_to_sheet_rect(ctx, args)?;
+                ctx.get_sheet_rect_array(sheet_rect, self.span)?.into()
+            }
 
-                let size = ArraySize::new_or_err(width, height)?;
-                Array::new_row_major(size, flat_array)?.into()
+            // `LET`, `LAMBDA`, and names may evaluate to cell references or
+            // functions.
+            AstNodeContents::FunctionCall { func, .. } if is_special_form(&func.inner) => {
+                self.eval_arg(ctx)?.into_value(ctx)?.inner
             }
+            AstNodeContents::Name(_) => self.eval_arg(ctx)?.into_value(ctx)?.inner,
 
             // Other operator/function
             AstNodeContents::FunctionCall { func, args } => {
-                let mut arg_values = vec![];
-                for arg in args {
-                    arg_values.push(arg.eval(&mut *ctx)?);
-                }
-
                 let func_name = &func.inner;
-                match functions::lookup_function(func_name) {
-                    Some(f) => {
-                        let args = FormulaFnArgs::new(arg_values, self.span, f.name);
-                        (f.eval)(&mut *ctx, args)?
+                if let Some(ArgValue::Lambda(lambda)) = ctx.get_name(func_name).cloned() {
+                    // Function defined using `LAMBDA`
+                    let arg_values = args
+                        .iter()
+                        .map(|arg| arg.eval_arg_or_error(ctx))
+                        .try_collect()?;
+                    lambda
+                        .call(ctx, arg_values, self.span)?
+                        .into_value(ctx)?
+                        .inner
+                } else {
+                    match functions::lookup_function(func_name) {
+                        Some(f) => {
+                            // Arguments are evaluated as the function takes
+                            // them, so that functions such as `IF` can skip
+                            // arguments they do not need.
+                            let args = FormulaFnArgs::new(args, self.span, f.name);
+                            (f.eval)(&mut *ctx, args)?
+                        }
+                        None => return Err(ErrorMsg::BadFunctionName.with_span(func.span)),
                     }
-                    None => return Err(ErrorMsg::BadFunctionName.with_span(func.span)),
                 }
             }
 
@@ -187,7 +221,12 @@ impl AstNode {
                         return Err(ErrorMsg::NonRectangularArray.with_span(self.span));
                     }
                     for elem_expr in row {
-                        flat_array.push(elem_expr.eval(ctx)?.into_cell_value()?.inner);
+                        let elem = elem_expr.eval(ctx).and_then(|v| v.into_cell_value());
+                        flat_array.push(match elem {
+                            Ok(v) => v.inner,
+                            Err(e) if e.msg.is_catchable() => CellValue::Error(Box::new(e)),
+                            Err(e) => return Err(e),
+                        });
                     }
                 }
 
@@ -200,9 +239,16 @@ impl AstNode {
                 Array::from(ctx.get_cell(cell_ref, self.span)?.inner).into()
             }
 
+            AstNodeContents::RangeRef(range_ref) => {
+                ctx.get_col_or_row_range(range_ref, self.span)?.into()
+            }
+
             AstNodeContents::String(s) => Value::from(s.to_string()),
             AstNodeContents::Number(n) => Value::from(*n),
             AstNodeContents::Bool(b) => Value::from(*b),
+            AstNodeContents::DeletedRef => {
+                return Err(ErrorMsg::BadCellReference.with_span(self.span));
+            }
         };
 
         Ok(Spanned {
@@ -210,4 +256,139 @@ impl AstNode {
             inner: value,
         })
     }
+
+    /// Evaluates an argument to a function. Cell references and cell range
+    /// references are resolved, but the cells are not read until the function
+    /// asks for their values.
+    pub(super) fn eval_arg(&self, ctx: &mut Ctx<'_>) -> CodeResult<Spanned<ArgValue>> {
+        let sheet_rect = match &self.inner {
+            AstNodeContents::FunctionCall { func, args }
+                if func.inner.eq_ignore_ascii_case("LET") =>
+            {
+                return self.eval_let(ctx, args);
+            }
+            AstNodeContents::FunctionCall { func, args }
+                if func.inner.eq_ignore_ascii_case("LAMBDA") =>
+            {
+                return self.eval_lambda(ctx, args);
+            }
+            AstNodeContents::Name(name) => {
+                let value = ctx
+                    .get_name(name)
+                    .cloned()
+                    .ok_or_else(|| ErrorMsg::BadName(name.clone().into()).with_span(self.span))?;
+                return Ok(Spanned {
+                    span: self.span,
+                    inner: value,
+                });
+            }
+            AstNodeContents::FunctionCall { func, args } if func.inner == ":" => {
+                self.cell_range_to_sheet_rect(ctx, args)?
+            }
+            AstNodeContents::CellRef(cell_ref) => {
+                let pos = cell_ref.resolve_from(ctx.pos.without_sheet());
+                ctx.resolve_rect(&cell_ref.sheet, Rect::single_pos(pos), self.span)?
+            }
+            AstNodeContents::Paren(expr) => return expr.eval_arg(ctx),
+            _ => return Ok(self.eval(ctx)?.map(ArgValue::Value)),
+        };
+
+        Ok(Spanned {
+            span: self.span,
+            inner: ArgValue::Reference(sheet_rect),
+        })
+    }
+
+    /// Evaluates an argument to a function. Errors are passed to the function
+    /// as error values so that functions such as `IFERROR` can handle them,
+    /// except for errors in the formula itself, which are returned.
+    pub(super) fn eval_arg_or_error(&self, ctx: &mut Ctx<'_>) -> CodeResult<Spanned<ArgValue>> {
+        match self.eval_arg(ctx) {
+            Err(e) if e.msg.is_catchable() => Ok(error_arg(self.span, e)),
+            result => result,
+        }
+    }
+
+    /// Evaluates a call to `LET`, which binds each name to the value after it
+    /// and then evaluates the last argument with those names in scope.
+    fn eval_let(&self, ctx: &mut Ctx<'_>, args: &[AstNode]) -> CodeResult<Spanned<ArgValue>> {
+        let Some((body, bindings)) = args.split_last().filter(|(_, b)| b.len() % 2 == 0) else {
+            return Err(functions::missing_arg_error(
+                "LET",
+                "calculation",
+                self.span,
+            ));
+        };
+        if bindings.is_empty() {
+            return Err(functions::missing_arg_error("LET", "name1", self.span));
+        }
+
+        let outer_scope_len = ctx.scope().len();
+        let result = bindings
+            .iter()
+            .tuples()
+            .try_for_each(|(name, value)| {
+                let name = name.to_name()?;
+                let value = value.eval_arg_or_error(ctx)?;
+                ctx.push_name(name.inner, value.inner);
+                Ok(())
+            })
+            .and_then(|()| body.eval_arg(ctx));
+        ctx.truncate_scope(outer_scope_len);
+
+        Ok(Spanned {
+            span: self.span,
+            inner: result?.inner,
+        })
+    }
+
+    /// Evaluates a call to `LAMBDA`, which defines a function whose parameters
+    /// are all the arguments except the last, which is the body of the
+    /// function.
+    fn eval_lambda(&self, ctx: &mut Ctx<'_>, args: &[AstNode]) -> CodeResult<Spanned<ArgValue>> {
+        let Some((body, params)) = args.split_last() else {
+            return Err(functions::missing_arg_error(
+                "LAMBDA",
+                "calculation",
+                self.span,
+            ));
+        };
+        let lambda = Lambda {
+            params: params.iter().map(|param| param.to_name()).try_collect()?,
+            body: body.clone(),
+            scope: ctx.scope().to_vec(),
+        };
+        Ok(Spanned {
+            span: self.span,
+            inner: ArgValue::Lambda(Rc::new(lambda)),
+        })
+    }
+
+    /// Resolves the arguments to the cell range operator `:` into a region of
+    /// cells.
+    fn cell_range_to_sheet_rect(&self, ctx: &Ctx<'_>, args: &[AstNode]) -> CodeResult<SheetRect> {
+        if args.len() != 2 {
+            internal_error!("invalid arguments to cell range operator");
+        }
+        let ref1 = args[0].to_cell_ref()?;
+        let ref2 = args[1].to_cell_ref()?;
+        let corner1 = ref1.resolve_from(ctx.pos.without_sheet());
+        let corner2 = ref2.resolve_from(ctx.pos.without_sheet());
+        let rect = Rect::new_span(corner1, corner2);
+        ctx.resolve_rect(&ref1.sheet, rect, self.span)
+    }
+}
+
+/// Returns whether `func_name` is `LET` or `LAMBDA`, whose arguments are not
+/// evaluated like those of other functions.
+fn is_special_form(func_name: &str) -> bool {
+    func_name.eq_ignore_ascii_case("LET") || func_name.eq_ignore_ascii_case("LAMBDA")
+}
+
+/// Returns an error value to pass to a function as an argument.
+fn error_arg(span: Span, error: Error) -> Spanned<ArgValue> {
+    Spanned {
+        span,
+        inner: ArgValue::Value(CellValue::Error(Box::new(error)).into()),
+    }
 }
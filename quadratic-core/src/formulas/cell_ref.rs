@@ -11,13 +11,11 @@ use crate::Pos;
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
 #[serde(tag = "type")]
 pub enum RangeRef {
-    // this is not yet used...
     RowRange {
         start: CellRefCoord,
         end: CellRefCoord,
         sheet: Option<String>,
     },
-    // this is not yet used...
     ColRange {
         start: CellRefCoord,
         end: CellRefCoord,
@@ -63,6 +61,23 @@ impl RangeRef {
             RangeRef::Cell { pos } => pos.a1_string(base),
         }
     }
+
+    /// Parses an A1-style reference to whole columns, such as `A:C`, or whole
+    /// rows, such as `3:5`, relative to a given location.
+    pub fn parse_a1_col_or_row_range(s: &str, base: Pos) -> Option<RangeRef> {
+        let (start, end) = s.trim().split_once(':')?;
+        if let (Some(start), Some(end)) = (
+            CellRefCoord::parse_a1_col(start, base.x),
+            CellRefCoord::parse_a1_col(end, base.x),
+        ) {
+            let sheet = None;
+            return Some(RangeRef::ColRange { start, end, sheet });
+        }
+        let start = CellRefCoord::parse_a1_row(start, base.y)?;
+        let end = CellRefCoord::parse_a1_row(end, base.y)?;
+        let sheet = None;
+        Some(RangeRef::RowRange { start, end, sheet })
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
@@ -243,6 +258,47 @@ impl CellRefCoord {
         format!("{}{row}", self.prefix())
     }
 
+    /// Parses an A1-style column coordinate, such as `B` or `$nC`, relative to
+    /// a given column.
+    fn parse_a1_col(s: &str, base: i64) -> Option<Self> {
+        lazy_static! {
+            /// ^(\$?)(n?[A-Z]+)$
+            /// ^               $     match full string
+            ///  (\$?)                group 1: optional `$`
+            ///       (n?[A-Z]+)      group 2: column name
+            pub static ref A1_COLUMN_REGEX: Regex = Regex::new(r"^(\$?)(n?[A-Z]+)$").unwrap();
+        }
+
+        let captures = A1_COLUMN_REGEX.captures(s)?;
+        let col = crate::util::column_from_name(&captures[2])?;
+        Some(match captures[1].is_empty() {
+            true => CellRefCoord::Relative(col - base),
+            false => CellRefCoord::Absolute(col),
+        })
+    }
+    /// Parses an A1-style row coordinate, such as `3` or `$n2`, relative to a
+    /// given row.
+    fn parse_a1_row(s: &str, base: i64) -> Option<Self> {
+        lazy_static! {
+            /// ^(\$?)(n?)(\d+)$
+            /// ^               $     match full string
+            ///  (\$?)                group 1: optional `$`
+            ///       (n?)            group 2: optional `n`
+            ///           (\d+)       group 3: row number
+            pub static ref A1_ROW_REGEX: Regex = Regex::new(r"^(\$?)(n?)(\d+)$").unwrap();
+        }
+
+        let captures = A1_ROW_REGEX.captures(s)?;
+        let mut row = captures[3].parse::<i64>().ok()?;
+        if !captures[2].is_empty() {
+            row = -row;
+        }
+        Some(match captures[1].is_empty() {
+            true => CellRefCoord::Relative(row - base),
+            false => CellRefCoord::Absolute(row),
+        })
+    }
+
     /// Returns whether the coordinate is relative (i.e., no '$' prefix).
     #[cfg(test)]
     fn is_relative(self) -> bool {
@@ -300,4 +356,27 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_a1_col_or_row_range_parsing() {
+        let base_pos = pos![E8];
+        assert_eq!(
+            RangeRef::parse_a1_col_or_row_range("B:$D", base_pos),
+            Some(RangeRef::ColRange {
+                start: CellRefCoord::Relative(-3),
+                end: CellRefCoord::Absolute(3),
+                sheet: None,
+            }),
+        );
+        assert_eq!(
+            RangeRef::parse_a1_col_or_row_range("$n2:10", base_pos),
+            Some(RangeRef::RowRange {
+                start: CellRefCoord::Absolute(-2),
+                end: CellRefCoord::Relative(2),
+                sheet: None,
+            }),
+        );
+        assert_eq!(RangeRef::parse_a1_col_or_row_range("A:2", base_pos), None,);
+        assert_eq!(RangeRef::parse_a1_col_or_row_range("A1:B2", base_pos), None,);
+    }
 }
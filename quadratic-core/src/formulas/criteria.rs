@@ -144,6 +144,56 @@ impl Criterion {
             // Ignore blank values
             .filter_map_ok(|v| v.coerce_nonblank::<T>()))
     }
+
+    /// Iterates over values in `output_values_range`, excluding those where
+    /// any criterion does not match the corresponding value in its range. If
+    /// `output_values_range` is `None`, then the first range is used.
+    ///
+    /// Returns an error if the ranges are not all the same size.
+    pub fn iter_matching_all<'a>(
+        criteria: &'a [(Spanned<Array>, Criterion)],
+        output_values_range: Option<&'a Spanned<Array>>,
+    ) -> CodeResult<impl 'a + Iterator<Item = Spanned<&'a CellValue>>> {
+        let Some(output_values_range) =
+            output_values_range.or_else(|| criteria.first().map(|(range, _)| range))
+        else {
+            internal_error!("no criteria given");
+        };
+        for (eval_range, _criterion) in criteria {
+            eval_range.check_array_size_exact(output_values_range.inner.size())?;
+        }
+
+        Ok(output_values_range
+            .inner
+            .cell_values_slice()
+            .iter()
+            .enumerate()
+            .filter(|(i, _output_value)| {
+                criteria.iter().all(|(eval_range, criterion)| {
+                    criterion.matches(&eval_range.inner.cell_values_slice()[*i])
+                })
+            })
+            .map(|(_i, output_value)| output_value)
+            .with_all_same_span(output_values_range.span))
+    }
+    /// Iterates over values in `output_values_range` and coerces each one,
+    /// excluding those where any criterion does not match or where coercion
+    /// fails.
+    pub fn iter_matching_all_coerced<'a, T>(
+        criteria: &'a [(Spanned<Array>, Criterion)],
+        output_values_range: &'a Spanned<Array>,
+    ) -> CodeResult<impl 'a + Iterator<Item = CodeResult<T>>>
+    where
+        &'a CellValue: TryInto<T>,
+    {
+        Ok(
+            Self::iter_matching_all(criteria, Some(output_values_range))?
+                // Propogate errors
+                .map(|v| v.into_non_error_value())
+                // Ignore blank values
+                .filter_map_ok(|v| v.coerce_nonblank::<T>()),
+        )
+    }
 }
 
 fn strip_compare_fn_prefix(s: &str) -> Option<(CompareFn, &str)> {
@@ -1,9 +1,15 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use smallvec::SmallVec;
+use rand::{rngs::StdRng, SeedableRng};
+use regex::{Regex, RegexBuilder};
+use smallvec::{smallvec, SmallVec};
 
 use super::*;
-use crate::{grid::Grid, Array, CellValue, CodeResult, ErrorMsg, SheetPos, Span, Spanned, Value};
+use crate::{
+    grid::{Grid, Sheet},
+    Array, ArraySize, Axis, CellValue, CodeResult, ErrorMsg, Pos, Rect, SheetPos, SheetRect, Span,
+    Spanned, Value,
+};
 
 /// Formula execution context.
 pub struct Ctx<'ctx> {
@@ -13,6 +19,18 @@ pub struct Ctx<'ctx> {
     pub pos: SheetPos,
     /// Cells that have been accessed in evaluating the formula.
     pub cells_accessed: HashSet<SheetPos>,
+    /// Names defined using `LET` or as `LAMBDA` parameters that are in scope,
+    /// from outermost to innermost.
+    scope: Vec<(String, ArgValue)>,
+    /// Number of `LAMBDA` calls that are currently being evaluated.
+    call_depth: usize,
+    /// Regular expressions that have been compiled in evaluating the formula,
+    /// keyed by pattern and whether they are case-insensitive.
+    regex_cache: HashMap<(String, bool), Regex>,
+    /// Random number generator used by functions such as `RAND`. Set it to a
+    /// seeded generator to make the results deterministic; otherwise one is
+    /// seeded from entropy the first time it is needed.
+    pub rng: Option<StdRng>,
 }
 impl<'ctx> Ctx<'ctx> {
     /// Constructs a context for evaluating a formula at `pos` in `grid`.
@@ -21,19 +39,85 @@ impl<'ctx> Ctx<'ctx> {
             grid,
             pos,
             cells_accessed: HashSet::new(),
+            scope: vec![],
+            call_depth: 0,
+            regex_cache: HashMap::new(),
+            rng: None,
         }
     }
 
+    /// Returns the random number generator, seeding it from entropy if it has
+    /// not been set.
+    pub fn rng(&mut self) -> &mut StdRng {
+        self.rng.get_or_insert_with(StdRng::from_entropy)
+    }
+
+    /// Returns the value of the innermost name in scope that matches `name`
+    /// (case-insensitive), or `None` if there is none.
+    pub fn get_name(&self, name: &str) -> Option<&ArgValue> {
+        self.scope
+            .iter()
+            .rev()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+    /// Returns the names that are currently in scope.
+    pub fn scope(&self) -> &[(String, ArgValue)] {
+        &self.scope
+    }
+    /// Brings a name into scope, shadowing any existing name that matches it.
+    pub fn push_name(&mut self, name: String, value: ArgValue) {
+        self.scope.push((name, value));
+    }
+    /// Removes names from scope until only the first `len` remain.
+    pub fn truncate_scope(&mut self, len: usize) {
+        self.scope.truncate(len);
+    }
+    /// Replaces the names in scope, returning the old ones.
+    pub fn replace_scope(&mut self, scope: Vec<(String, ArgValue)>) -> Vec<(String, ArgValue)> {
+        std::mem::replace(&mut self.scope, scope)
+    }
+
+    /// Records the start of a call to a function defined using `LAMBDA`, or
+    /// returns an error if too many calls are already in progress, such as
+    /// when a function calls itself without end.
+    pub fn enter_call(&mut self, span: Span) -> CodeResult<()> {
+        if self.call_depth >= crate::limits::MAX_CALL_DEPTH {
+            return Err(ErrorMsg::RecursionLimit.with_span(span));
+        }
+        self.call_depth += 1;
+        Ok(())
+    }
+    /// Records the end of a call started using [`Ctx::enter_call()`].
+    pub fn exit_call(&mut self) {
+        self.call_depth -= 1;
+    }
+
+    /// Compiles `pattern` into a regular expression, reusing the result if the
+    /// same pattern has already been compiled in evaluating the formula.
+    /// Returns an error with the span of `pattern` if it is not a valid
+    /// regular expression.
+    pub fn get_regex(
+        &mut self,
+        pattern: &Spanned<String>,
+        case_insensitive: bool,
+    ) -> CodeResult<Regex> {
+        let key = (pattern.inner.clone(), case_insensitive);
+        if let Some(regex) = self.regex_cache.get(&key) {
+            return Ok(regex.clone());
+        }
+        let regex = RegexBuilder::new(&pattern.inner)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|_| ErrorMsg::InvalidArgument.with_span(pattern.span))?;
+        self.regex_cache.insert(key, regex.clone());
+        Ok(regex)
+    }
+
     /// Fetches the contents of the cell at `ref_pos` evaluated at `base_pos`,
     /// or returns an error in the case of a circular reference.
     pub fn get_cell(&mut self, ref_pos: &CellRef, span: Span) -> CodeResult<Spanned<CellValue>> {
-        let sheet = match &ref_pos.sheet {
-            Some(sheet_name) => self
-                .grid
-                .sheet_from_name(sheet_name.clone()) // TODO: should not need clone
-                .ok_or(ErrorMsg::BadCellReference.with_span(span))?,
-            None => self.grid.sheet_from_id(self.pos.sheet_id),
-        };
+        let sheet = self.sheet(&ref_pos.sheet, span)?;
         let ref_pos = ref_pos.resolve_from(self.pos.without_sheet());
         let ref_pos_with_sheet = ref_pos.with_sheet(sheet.id);
         if ref_pos_with_sheet == self.pos {
@@ -46,6 +130,119 @@ impl<'ctx> Ctx<'ctx> {
         Ok(Spanned { inner: value, span })
     }
 
+    /// Fetches the contents of the cells in `rect`, or returns an error in the
+    /// case of a circular reference.
+    pub fn get_cell_array(
+        &mut self,
+        sheet_name: Option<String>,
+        rect: Rect,
+        span: Span,
+    ) -> CodeResult<Array> {
+        let sheet_rect = self.resolve_rect(&sheet_name, rect, span)?;
+        self.get_sheet_rect_array(sheet_rect, span)
+    }
+
+    /// Returns the region of cells in `rect` on the sheet named `sheet_name`,
+    /// or on the sheet where the formula is being evaluated if there is no
+    /// name, without reading any cells.
+    pub fn resolve_rect(
+        &self,
+        sheet_name: &Option<String>,
+        rect: Rect,
+        span: Span,
+    ) -> CodeResult<SheetRect> {
+        let sheet = self.sheet(sheet_name, span)?;
+        Ok(SheetRect {
+            min: rect.min,
+            max: rect.max,
+            sheet_id: sheet.id,
+        })
+    }
+
+    /// Fetches the contents of the cells in `sheet_rect`, or returns an error
+    /// in the case of a circular reference.
+    pub fn get_sheet_rect_array(&mut self, sheet_rect: SheetRect, span: Span) -> CodeResult<Array> {
+        let size = sheet_rect_size(sheet_rect, span)?;
+        let sheet = self.grid.sheet_from_id(sheet_rect.sheet_id);
+
+        let mut flat_array = smallvec![];
+        for y in sheet_rect.y_range() {
+            for x in sheet_rect.x_range() {
+                let pos = Pos { x, y };
+                let pos_with_sheet = pos.with_sheet(sheet.id);
+                if pos_with_sheet == self.pos {
+                    return Err(ErrorMsg::CircularReference.with_span(span));
+                }
+                self.cells_accessed.insert(pos_with_sheet);
+                flat_array.push(sheet.get_cell_value(pos).unwrap_or(CellValue::Blank));
+            }
+        }
+
+        Array::new_row_major(size, flat_array)
+    }
+
+    /// Fetches the contents of the whole columns or rows in `range_ref`,
+    /// clamped to the cells in the sheet that contain data, or returns an error
+    /// in the case of a circular reference.
+    pub fn get_col_or_row_range(&mut self, range_ref: &RangeRef, span: Span) -> CodeResult<Array> {
+        let (axis, start, end, sheet_name) = match range_ref {
+            RangeRef::ColRange { start, end, sheet } => (Axis::X, *start, *end, sheet),
+            RangeRef::RowRange { start, end, sheet } => (Axis::Y, *start, *end, sheet),
+            _ => internal_error!("expected column or row range reference"),
+        };
+        let base = match axis {
+            Axis::X => self.pos.x,
+            Axis::Y => self.pos.y,
+        };
+        let (start, end) = (start.resolve_from(base), end.resolve_from(base));
+        let (start, end) = (std::cmp::min(start, end), std::cmp::max(start, end));
+        let len = end
+            .saturating_sub(start)
+            .saturating_add(1)
+            .try_into()
+            .unwrap_or(u32::MAX);
+        if len > crate::limits::CELL_RANGE_LIMIT {
+            return Err(ErrorMsg::ArrayTooBig.with_span(span));
+        }
+
+        let sheet = self.sheet(sheet_name, span)?;
+        if sheet.id == self.pos.sheet_id && (start..=end).contains(&base) {
+            return Err(ErrorMsg::CircularReference.with_span(span));
+        }
+        let rect = match axis {
+            Axis::X => sheet
+                .columns_bounds(start, end, true)
+                .map(|(y1, y2)| Rect::new_span(Pos { x: start, y: y1 }, Pos { x: end, y: y2 })),
+            Axis::Y => sheet
+                .rows_bounds(start, end, true)
+                .map(|(x1, x2)| Rect::new_span(Pos { x: x1, y: start }, Pos { x: x2, y: end })),
+        };
+        match rect {
+            Some(rect) => self.get_cell_array(sheet_name.clone(), rect, span),
+            // There is no data in the range, so return a single row or column
+            // of blank cells.
+            None => {
+                let size = match axis {
+                    Axis::X => ArraySize::new_or_err(len, 1)?,
+                    Axis::Y => ArraySize::new_or_err(1, len)?,
+                };
+                Ok(Array::new_empty(size))
+            }
+        }
+    }
+
+    /// Returns the sheet with the given name, or the sheet where the formula
+    /// is being evaluated if there is no name.
+    fn sheet(&self, sheet_name: &Option<String>, span: Span) -> CodeResult<&'ctx Sheet> {
+        match sheet_name {
+            Some(sheet_name) => self
+                .grid
+                .sheet_from_name(sheet_name.clone()) // TODO: should not need clone
+                .ok_or(ErrorMsg::BadCellReference.with_span(span)),
+            None => Ok(self.grid.sheet_from_id(self.pos.sheet_id)),
+        }
+    }
+
     /// Evaluates a function once for each corresponding set of values from
     /// `arrays`.
     ///
@@ -86,10 +283,29 @@ impl<'ctx> Ctx<'ctx> {
                 args_buffer.push(array.get(x, y)?);
             }
 
-            values.push(f(self, &args_buffer)?);
+            // Errors in individual elements do not stop evaluation of the
+            // rest of the array.
+            values.push(f(self, &args_buffer).unwrap_or_else(|e| CellValue::Error(Box::new(e))));
         }
 
         let result = Array::new_row_major(size, values)?;
         Ok(Value::Array(result))
     }
 }
+
+/// Returns the size of the array of cells in `sheet_rect`, or an error if it is
+/// too big.
+pub(crate) fn sheet_rect_size(sheet_rect: SheetRect, span: Span) -> CodeResult<ArraySize> {
+    let len = |min: i64, max: i64| -> u32 {
+        max.saturating_sub(min)
+            .saturating_add(1)
+            .try_into()
+            .unwrap_or(u32::MAX)
+    };
+    let width = len(sheet_rect.min.x, sheet_rect.max.x);
+    let height = len(sheet_rect.min.y, sheet_rect.max.y);
+    if std::cmp::max(width, height) > crate::limits::CELL_RANGE_LIMIT {
+        return Err(ErrorMsg::ArrayTooBig.with_span(span));
+    }
+    Ok(ArraySize::new_or_err(width, height)?)
+}
@@ -0,0 +1,595 @@
+use std::collections::HashMap;
+
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Array functions",
+    docs: "These functions return arrays, which spill into the cells below \
+           and to the right of the formula.",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns the rows of `array` for which the corresponding value
+            /// in `include` is truthy.
+            ///
+            /// If `include` is a single row, then columns are filtered instead
+            /// of rows. If no rows (or columns) are included, then `if_empty`
+            /// is returned, or an error if `if_empty` is omitted.
+            #[examples(
+                "FILTER(A1:C10, B1:B10 > 5)",
+                "FILTER(A1:C10, A1:A10 = \"x\", \"none\")"
+            )]
+            fn FILTER(
+                span: Span,
+                array: (Spanned<Array>),
+                include: (Spanned<Array>),
+                if_empty: (Option<Value>),
+            ) {
+                let axis = include.array_linear_axis()?.unwrap_or(Axis::Y);
+                include.check_array_size_on(axis, array.inner.size()[axis].get())?;
+                let mut indices = vec![];
+                for (i, value) in include.inner.cell_values_slice().iter().enumerate() {
+                    if bool::try_from(value).map_err(|e| e.with_span(include.span))? {
+                        indices.push(i as u32);
+                    }
+                }
+                match (indices.is_empty(), if_empty) {
+                    (true, Some(if_empty)) => if_empty,
+                    (true, None) => return Err(ErrorMsg::EmptyArray.with_span(span)),
+                    (false, _) => Value::from(
+                        array
+                            .inner
+                            .select(axis, &indices)
+                            .map_err(|e| e.with_span(span))?,
+                    ),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Sorts the rows of `array` by the values in column
+            /// `sort_index`.
+            ///
+            /// `sort_index` defaults to 1. `sort_order` is `1` (default) for
+            /// ascending order or `-1` for descending order. If `by_column` is
+            /// true, then columns are sorted by the values in row
+            /// `sort_index` instead.
+            #[examples("SORT(A1:C10)", "SORT(A1:C10, 2, -1)", "SORT(A1:F2, 1, 1, TRUE)")]
+            fn SORT(
+                span: Span,
+                array: (Spanned<Array>),
+                sort_index: (Option<Spanned<i64>>),
+                sort_order: (Option<Spanned<i64>>),
+                by_column: (Option<bool>),
+            ) {
+                let axis = match by_column.unwrap_or(false) {
+                    true => Axis::X,
+                    false => Axis::Y,
+                };
+                let key_index = match sort_index {
+                    Some(i) => one_based_index(i, array.inner.size()[axis.other_axis()].get())?,
+                    None => 0,
+                };
+                let key = array.inner.line(axis.other_axis(), key_index).collect_vec();
+                let descending = is_descending(sort_order)?;
+                sort_lines(span, &array.inner, axis, &[(key, descending)])?
+            }
+        ),
+        formula_fn!(
+            /// Sorts the rows of `array` by the values in one or more other
+            /// arrays.
+            ///
+            /// Each `by_array` must be a single column with the same height as
+            /// `array`, and may be followed by a sort order: `1` for ascending
+            /// order (default) or `-1` for descending order. If each `by_array`
+            /// is a single row with the same width as `array`, then columns
+            /// are sorted instead of rows.
+            #[examples("SORTBY(A1:B10, C1:C10)", "SORTBY(A1:B10, C1:C10, -1, D1:D10, 1)")]
+            fn SORTBY(
+                span: Span,
+                array: (Spanned<Array>),
+                by_arrays_and_sort_orders: (Iter<Spanned<Value>>),
+            ) {
+                let args = by_arrays_and_sort_orders.try_collect::<_, Vec<_>, _>()?;
+                if args.is_empty() {
+                    return Err(util::missing_arg_error("SORTBY", "by_array", span));
+                }
+
+                let mut axis = None;
+                let mut keys = vec![];
+                for chunk in args.chunks(2) {
+                    let by_array = chunk[0].clone().map(Array::from);
+                    let key_axis = *axis.get_or_insert(match by_array.array_linear_axis()? {
+                        Some(key_axis) => key_axis,
+                        None => Axis::Y,
+                    });
+                    by_array.array_linear_length(key_axis)?;
+                    by_array.check_array_size_on(key_axis, array.inner.size()[key_axis].get())?;
+                    let sort_order = match chunk.get(1) {
+                        Some(v) if v.inner != Value::Single(CellValue::Blank) => {
+                            Some(v.clone().try_coerce::<i64>()?)
+                        }
+                        _ => None,
+                    };
+                    keys.push((by_array, is_descending(sort_order)?));
+                }
+                let keys = keys
+                    .iter()
+                    .map(|(by_array, descending)| {
+                        (
+                            by_array.inner.cell_values_slice().iter().collect_vec(),
+                            *descending,
+                        )
+                    })
+                    .collect_vec();
+                sort_lines(span, &array.inner, axis.unwrap_or(Axis::Y), &keys)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the unique rows in `array`, in the order they first
+            /// appear. Text is compared case-insensitively.
+            ///
+            /// If `by_column` is true, then unique columns are returned
+            /// instead. If `exactly_once` is true, then only rows (or columns)
+            /// that appear exactly once are returned.
+            #[examples("UNIQUE(A1:A10)", "UNIQUE(A1:C10, FALSE, TRUE)")]
+            fn UNIQUE(
+                span: Span,
+                array: (Spanned<Array>),
+                by_column: (Option<bool>),
+                exactly_once: (Option<bool>),
+            ) {
+                let axis = match by_column.unwrap_or(false) {
+                    true => Axis::X,
+                    false => Axis::Y,
+                };
+                let mut first_index_and_count: HashMap<Vec<String>, (u32, usize)> = HashMap::new();
+                for i in 0..array.inner.size()[axis].get() {
+                    let key = array
+                        .inner
+                        .line(axis, i)
+                        .map(unique_key)
+                        .try_collect::<_, Vec<_>, _>()
+                        .map_err(|e| e.with_span(array.span))?;
+                    first_index_and_count.entry(key).or_insert((i, 0)).1 += 1;
+                }
+                let exactly_once = exactly_once.unwrap_or(false);
+                let indices = first_index_and_count
+                    .into_values()
+                    .filter(|&(_, count)| !exactly_once || count == 1)
+                    .map(|(i, _)| i)
+                    .sorted()
+                    .collect_vec();
+                array
+                    .inner
+                    .select(axis, &indices)
+                    .map_err(|e| e.with_span(span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns an array of `rows` rows and `columns` columns containing
+            /// a sequence of numbers, starting at `start` and increasing by
+            /// `step`.
+            ///
+            /// `columns`, `start`, and `step` all default to 1. The sequence
+            /// fills each row before moving to the next one.
+            #[examples("SEQUENCE(10)", "SEQUENCE(3, 4, 0, 10)")]
+            fn SEQUENCE(
+                span: Span,
+                rows: (Spanned<i64>),
+                columns: (Option<Spanned<i64>>),
+                start: (Option<f64>),
+                step: (Option<f64>),
+            ) {
+                let rows = util::positive_len(rows)?;
+                let columns = columns.map_or(Ok(1), util::positive_len)?;
+                let size = util::array_size(span, columns, rows)?;
+                let start = start.unwrap_or(1.0);
+                let step = step.unwrap_or(1.0);
+                let values = (0..size.len())
+                    .map(|i| CellValue::from(start + step * i as f64))
+                    .collect();
+                Array::new_row_major(size, values)?
+            }
+        ),
+        formula_fn!(
+            /// Swaps the rows and columns of an array.
+            #[examples("TRANSPOSE(A1:C10)")]
+            fn TRANSPOSE(array: Array) {
+                array.transpose()
+            }
+        ),
+        formula_fn!(
+            /// Stacks arrays vertically. Arrays narrower than the widest array
+            /// are padded with blank cells.
+            #[examples("VSTACK(A1:C5, E1:G5)", "VSTACK({1, 2}, {3, 4})")]
+            fn VSTACK(span: Span, arrays: (Iter<Spanned<Array>>)) {
+                stack(span, Axis::Y, arrays)?
+            }
+        ),
+        formula_fn!(
+            /// Stacks arrays horizontally. Arrays shorter than the tallest
+            /// array are padded with blank cells.
+            #[examples("HSTACK(A1:A5, C1:C5)", "HSTACK({1; 2}, {3; 4})")]
+            fn HSTACK(span: Span, arrays: (Iter<Spanned<Array>>)) {
+                stack(span, Axis::X, arrays)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the first `rows` rows and `columns` columns of `array`.
+            ///
+            /// If `rows` or `columns` is negative, then rows or columns are
+            /// taken from the end of the array instead. If either is omitted,
+            /// then all rows or columns are included. Returns an error if
+            /// either is zero.
+            #[examples("TAKE(A1:C10, 3)", "TAKE(A1:C10, -2, 1)", "TAKE(A1:C10, , 2)")]
+            fn TAKE(
+                span: Span,
+                array: (Spanned<Array>),
+                rows: (Option<Spanned<i64>>),
+                columns: (Option<Spanned<i64>>),
+            ) {
+                let mut array = array.inner;
+                for (axis, count) in [(Axis::Y, rows), (Axis::X, columns)] {
+                    if let Some(count) = count {
+                        if count.inner == 0 {
+                            return Err(ErrorMsg::EmptyArray.with_span(count.span));
+                        }
+                        let len = array.size()[axis].get() as i64;
+                        let n = count.inner.clamp(-len, len);
+                        let indices = match n >= 0 {
+                            true => (0..n).collect_vec(),
+                            false => (len + n..len).collect_vec(),
+                        };
+                        array = select_i64(span, &array, axis, &indices)?;
+                    }
+                }
+                array
+            }
+        ),
+        formula_fn!(
+            /// Returns `array` without its first `rows` rows and `columns`
+            /// columns.
+            ///
+            /// If `rows` or `columns` is negative, then rows or columns are
+            /// removed from the end of the array instead. Returns an error if
+            /// every row or column is removed.
+            #[examples("DROP(A1:C10, 1)", "DROP(A1:C10, -2, 1)", "DROP(A1:C10, , 1)")]
+            fn DROP(
+                span: Span,
+                array: (Spanned<Array>),
+                rows: (Option<Spanned<i64>>),
+                columns: (Option<Spanned<i64>>),
+            ) {
+                let mut array = array.inner;
+                for (axis, count) in [(Axis::Y, rows), (Axis::X, columns)] {
+                    if let Some(count) = count {
+                        let len = array.size()[axis].get() as i64;
+                        let n = count.inner.clamp(-len, len);
+                        let indices = match n >= 0 {
+                            true => (n..len).collect_vec(),
+                            false => (0..len + n).collect_vec(),
+                        };
+                        array = select_i64(span, &array, axis, &indices)?;
+                    }
+                }
+                array
+            }
+        ),
+        formula_fn!(
+            /// Returns the columns of `array` with the given numbers, in
+            /// order. Column numbers start at 1, and negative numbers count
+            /// from the end of the array.
+            #[examples("CHOOSECOLS(A1:E10, 1, 3)", "CHOOSECOLS(A1:E10, -1)")]
+            fn CHOOSECOLS(span: Span, array: (Spanned<Array>), columns: (Iter<Spanned<i64>>)) {
+                choose_lines(span, "CHOOSECOLS", "columns", array.inner, Axis::X, columns)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the rows of `array` with the given numbers, in order.
+            /// Row numbers start at 1, and negative numbers count from the end
+            /// of the array.
+            #[examples("CHOOSEROWS(A1:E10, 1, 3)", "CHOOSEROWS(A1:E10, -1)")]
+            fn CHOOSEROWS(span: Span, array: (Spanned<Array>), rows: (Iter<Spanned<i64>>)) {
+                choose_lines(span, "CHOOSEROWS", "rows", array.inner, Axis::Y, rows)?
+            }
+        ),
+    ]
+}
+
+/// Converts a 1-based index to a 0-based index, returning an error if it is
+/// out of range.
+fn one_based_index(index: Spanned<i64>, len: u32) -> CodeResult<u32> {
+    match index.inner {
+        i @ 1.. if i <= len as i64 => Ok(i as u32 - 1),
+        _ => Err(ErrorMsg::IndexOutOfBounds.with_span(index.span)),
+    }
+}
+
+/// Returns whether a sort order argument indicates descending order.
+fn is_descending(sort_order: Option<Spanned<i64>>) -> CodeResult<bool> {
+    match sort_order {
+        None | Some(Spanned { inner: 1, .. }) => Ok(false),
+        Some(Spanned { inner: -1, .. }) => Ok(true),
+        Some(Spanned { span, .. }) => Err(ErrorMsg::InvalidArgument.with_span(span)),
+    }
+}
+
+/// Sorts the rows (for `Axis::Y`) or columns (for `Axis::X`) of an array. Each
+/// key contains one value per row (or column) and whether to sort it in
+/// descending order. Later keys break ties in earlier keys.
+fn sort_lines(
+    span: Span,
+    array: &Array,
+    axis: Axis,
+    keys: &[(Vec<&CellValue>, bool)],
+) -> CodeResult<Array> {
+    // `CellValue::cmp()` only fails on errors, so return the first one before
+    // sorting. Without errors, the comparison is a total order.
+    let first_error = keys.iter().flat_map(|(key, _)| key).find_map(|v| match v {
+        CellValue::Error(e) => Some(e),
+        _ => None,
+    });
+    if let Some(e) = first_error {
+        return Err((**e).clone());
+    }
+
+    let mut indices = (0..array.size()[axis].get()).collect_vec();
+    indices.sort_by(|&a, &b| {
+        keys.iter()
+            .map(|(key, descending)| {
+                let ordering = key[a as usize]
+                    .cmp(key[b as usize])
+                    .unwrap_or(std::cmp::Ordering::Equal);
+                if *descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    array.select(axis, &indices).map_err(|e| e.with_span(span))
+}
+
+/// Returns a key for comparing values in `UNIQUE()`.
+fn unique_key(value: &CellValue) -> Result<String, ErrorMsg> {
+    Ok(match value {
+        CellValue::Blank => String::new(),
+        CellValue::Text(s) => format!("t{}", s.to_uppercase()),
+        CellValue::Number(n) => format!("n{}", n.normalized()),
+        CellValue::Logical(b) => format!("b{b}"),
+        CellValue::Instant(i) => format!("i{}", i.seconds),
+        CellValue::Duration(d) => format!("d{d:?}"),
+        CellValue::Error(e) => return Err(e.msg.clone()),
+    })
+}
+
+/// Selects rows or columns using indices that are known to be in range.
+fn select_i64(span: Span, array: &Array, axis: Axis, indices: &[i64]) -> CodeResult<Array> {
+    let indices = indices.iter().map(|&i| i as u32).collect_vec();
+    array.select(axis, &indices).map_err(|e| e.with_span(span))
+}
+
+fn stack(
+    span: Span,
+    axis: Axis,
+    arrays: impl Iterator<Item = CodeResult<Spanned<Array>>>,
+) -> CodeResult<Array> {
+    let arrays = arrays.map_ok(|a| a.inner).try_collect::<_, Vec<_>, _>()?;
+    Array::concat(axis, &arrays).map_err(|e| e.with_span(span))
+}
+
+fn choose_lines(
+    span: Span,
+    func_name: &'static str,
+    arg_name: &'static str,
+    array: Array,
+    axis: Axis,
+    indices: impl Iterator<Item = CodeResult<Spanned<i64>>>,
+) -> CodeResult<Array> {
+    let len = array.size()[axis].get();
+    let indices = indices
+        .map(|index| {
+            let index = index?;
+            match index.inner {
+                i @ ..=-1 if i >= -(len as i64) => Ok((len as i64 + i) as u32),
+                _ => one_based_index(index, len),
+            }
+        })
+        .try_collect::<_, Vec<_>, _>()?;
+    if indices.is_empty() {
+        return Err(util::missing_arg_error(func_name, arg_name, span));
+    }
+    array.select(axis, &indices).map_err(|e| e.with_span(span))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        controller::GridController,
+        formulas::tests::*,
+        grid::{CodeCellLanguage, CodeCellRunResult},
+    };
+
+    fn grid_with_table() -> Grid {
+        grid_with_rows(&[&["b", "3"], &["a", "1"], &["c", "2"], &["A", "1"]])
+    }
+
+    #[test]
+    fn test_formula_filter() {
+        let g = grid_with_table();
+        assert_eq!(
+            "{b, 3; c, 2}",
+            eval_to_string(&g, "FILTER(A1:B4, B1:B4 > 1)")
+        );
+        assert_eq!(
+            "{1; 3}",
+            eval_to_string(&g, "FILTER({1, 2; 3, 4}, {TRUE, FALSE})")
+        );
+        assert_eq!(
+            "none",
+            eval_to_string(&g, "FILTER(A1:B4, B1:B4 > 5, \"none\")")
+        );
+        expect_err(&ErrorMsg::EmptyArray, &g, "FILTER(A1:B4, B1:B4 > 5)");
+        expect_err(
+            &ErrorMsg::ExactArrayAxisMismatch {
+                axis: Axis::Y,
+                expected: 4,
+                got: 2,
+            },
+            &g,
+            "FILTER(A1:B4, B1:B2 > 1)",
+        );
+    }
+
+    #[test]
+    fn test_formula_sort() {
+        let g = grid_with_table();
+        assert_eq!(
+            "{a, 1; A, 1; b, 3; c, 2}",
+            eval_to_string(&g, "SORT(A1:B4)")
+        );
+        assert_eq!(
+            "{b, 3; c, 2; a, 1; A, 1}",
+            eval_to_string(&g, "SORT(A1:B4, 2, -1)"),
+        );
+        assert_eq!(
+            "{1, 2, 3}",
+            eval_to_string(&g, "SORT({3, 1, 2}, 1, 1, TRUE)")
+        );
+        expect_err(&ErrorMsg::IndexOutOfBounds, &g, "SORT(A1:B4, 3)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "SORT(A1:B4, 1, 0)");
+
+        assert_eq!("{a; A; c; b}", eval_to_string(&g, "SORTBY(A1:A4, B1:B4)"));
+        assert_eq!(
+            "{A; a; c; b}",
+            eval_to_string(&g, "SORTBY(A1:A4, B1:B4, 1, {4; 3; 2; 1})"),
+        );
+        assert_eq!(
+            "{A; a; c; b}",
+            eval_to_string(&g, "SORTBY(A1:A4, B1:B4, 1, {4; 3; 2; 1}, 1)"),
+        );
+        assert_eq!(
+            "{b; c; a; A}",
+            eval_to_string(&g, "SORTBY(A1:A4, B1:B4, -1)"),
+        );
+        assert_eq!(
+            "{z, x, y}",
+            eval_to_string(&g, "SORTBY({\"x\", \"y\", \"z\"}, {2, 3, 1})"),
+        );
+        expect_err(
+            &ErrorMsg::MissingRequiredArgument {
+                func_name: "SORTBY".into(),
+                arg_name: "by_array".into(),
+            },
+            &g,
+            "SORTBY(A1:A4)",
+        );
+
+        expect_err(&ErrorMsg::DivideByZero, &g, "SORT({3; 1/0; 1; 2})");
+        expect_err(&ErrorMsg::DivideByZero, &g, "SORTBY({1; 2}, {1; 1/0})");
+        expect_err(
+            &ErrorMsg::DivideByZero,
+            &g,
+            "SORTBY({1; 2}, {1; 1}, 1, {2; 1/0})",
+        );
+    }
+
+    #[test]
+    fn test_formula_unique() {
+        let g = grid_with_table();
+        assert_eq!("{b; a; c}", eval_to_string(&g, "UNIQUE(A1:A4)"));
+        assert_eq!("{b; c}", eval_to_string(&g, "UNIQUE(A1:A4, FALSE, TRUE)"));
+        assert_eq!("{3; 1; 2}", eval_to_string(&g, "UNIQUE(B1:B4)"));
+        assert_eq!("{1, 2}", eval_to_string(&g, "UNIQUE({1, 2, 1, 2}, TRUE)"));
+        assert_eq!("{b, 3; a, 1; c, 2}", eval_to_string(&g, "UNIQUE(A1:B4)"),);
+        expect_err(&ErrorMsg::EmptyArray, &g, "UNIQUE({1, 1}, TRUE, TRUE)");
+    }
+
+    #[test]
+    fn test_formula_sequence_and_reshape() {
+        let g = grid_with_table();
+        assert_eq!("{1; 2; 3}", eval_to_string(&g, "SEQUENCE(3)"));
+        assert_eq!(
+            "{0, 10, 20; 30, 40, 50}",
+            eval_to_string(&g, "SEQUENCE(2, 3, 0, 10)"),
+        );
+        assert_eq!("{1, 1.5}", eval_to_string(&g, "SEQUENCE(1, 2, 1, 0.5)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "SEQUENCE(0)");
+        expect_err(&ErrorMsg::ArrayTooBig, &g, "SEQUENCE(100000, 100000)");
+
+        assert_eq!(
+            "{1, 3; 2, 4}",
+            eval_to_string(&g, "TRANSPOSE({1, 2; 3, 4})")
+        );
+        assert_eq!(
+            "{1, 2; 3, 4; 5, }",
+            eval_to_string(&g, "VSTACK({1, 2}, {3, 4}, 5)"),
+        );
+        assert_eq!("{1, 3; 2, }", eval_to_string(&g, "HSTACK({1; 2}, 3)"),);
+    }
+
+    #[test]
+    fn test_formula_take_drop_choose() {
+        let g = grid_with_table();
+        assert_eq!("{b, 3; a, 1}", eval_to_string(&g, "TAKE(A1:B4, 2)"));
+        assert_eq!("{A}", eval_to_string(&g, "TAKE(A1:B4, -1, 1)"));
+        assert_eq!("{3; 1; 2; 1}", eval_to_string(&g, "TAKE(A1:B4, , -1)"));
+        assert_eq!(
+            "{b, 3; a, 1; c, 2; A, 1}",
+            eval_to_string(&g, "TAKE(A1:B4, 99)")
+        );
+        expect_err(&ErrorMsg::EmptyArray, &g, "TAKE(A1:B4, 0)");
+
+        assert_eq!("{c, 2; A, 1}", eval_to_string(&g, "DROP(A1:B4, 2)"));
+        assert_eq!("{b; a; c}", eval_to_string(&g, "DROP(A1:B4, -1, -1)"));
+        expect_err(&ErrorMsg::EmptyArray, &g, "DROP(A1:B4, 4)");
+
+        assert_eq!(
+            "{3, b; 1, a; 2, c; 1, A}",
+            eval_to_string(&g, "CHOOSECOLS(A1:B4, 2, 1)"),
+        );
+        assert_eq!(
+            "{A, 1; b, 3}",
+            eval_to_string(&g, "CHOOSEROWS(A1:B4, -1, 1)")
+        );
+        expect_err(&ErrorMsg::IndexOutOfBounds, &g, "CHOOSEROWS(A1:B4, 5)");
+        expect_err(&ErrorMsg::IndexOutOfBounds, &g, "CHOOSECOLS(A1:B4, 0)");
+    }
+
+    #[test]
+    fn test_array_functions_spill() {
+        let mut gc = GridController::new();
+        let sheet_id = gc.sheet_ids()[0];
+        gc.set_cell_code(
+            sheet_id,
+            Pos { x: 0, y: 0 },
+            CodeCellLanguage::Formula,
+            "SEQUENCE(3)".into(),
+            None,
+        );
+        let sheet = gc.sheet(sheet_id);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 0, y: 2 }),
+            Some(CellValue::Number(3.into()))
+        );
+
+        // a value in the way causes a spill error
+        gc.set_cell_value(sheet_id, Pos { x: 0, y: 1 }, "x".into(), None);
+        let code_cell = gc
+            .sheet(sheet_id)
+            .get_code_cell(Pos { x: 0, y: 0 })
+            .unwrap();
+        assert!(code_cell.has_spill_error());
+        assert!(matches!(
+            code_cell.output.as_ref().unwrap().result,
+            CodeCellRunResult::Ok { .. }
+        ));
+    }
+}
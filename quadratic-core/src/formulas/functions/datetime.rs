@@ -0,0 +1,672 @@
+use std::collections::HashSet;
+
+use chrono::{Datelike, Days, Months, NaiveDate, NaiveDateTime, Utc, Weekday};
+
+use crate::{Duration, Instant};
+
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Date & time functions",
+    docs: "Dates and times are in UTC. A date is a time instant at midnight \
+           on that day.\
+           \n\n\
+           Adding a time duration to a time instant, or subtracting one from \
+           it, returns a new time instant. Subtracting two time instants \
+           returns the time duration between them. Adding or subtracting a \
+           number to a time instant adds or subtracts that many days.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns the current date and time.
+            #[include_args_in_completion(false)]
+            #[volatile(true)]
+            #[examples("NOW()")]
+            fn NOW() {
+                Instant::from_naive(Utc::now().naive_utc())
+            }
+        ),
+        formula_fn!(
+            /// Returns the current date.
+            #[include_args_in_completion(false)]
+            #[volatile(true)]
+            #[examples("TODAY()")]
+            fn TODAY() {
+                Instant::from_date(Utc::now().date_naive())
+            }
+        ),
+        formula_fn!(
+            /// Returns the date with the given `year`, `month`, and `day`.
+            ///
+            /// If `month` is outside the range 1 to 12, or `day` is outside the
+            /// range of days in that month, then the date rolls over into an
+            /// earlier or later month. For example, `DATE(2024, 13, 1)` is the
+            /// same as `DATE(2025, 1, 1)`, and `DATE(2024, 3, 0)` is the last
+            /// day of February 2024.
+            #[examples("DATE(2024, 1, 15)", "DATE(A1, B1 + 1, 0)")]
+            #[zip_map]
+            fn DATE(span: Span, [year]: i64, [month]: (Spanned<i64>), [day]: (Spanned<i64>)) {
+                let months = minus_one(month)?;
+                let days = minus_one(day)?;
+                i32::try_from(year)
+                    .ok()
+                    .and_then(|year| NaiveDate::from_ymd_opt(year, 1, 1))
+                    .and_then(|date| add_months(date, months))
+                    .and_then(|date| add_days(date, days))
+                    .map(Instant::from_date)
+                    .ok_or_else(|| ErrorMsg::Overflow.with_span(*span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns a time duration of `hour` hours, `minute` minutes, and
+            /// `second` seconds.
+            ///
+            /// Add the result to a date to get a time instant on that day.
+            #[examples("TIME(13, 30, 0)", "DATE(2024, 1, 15) + TIME(9, 0, 0)")]
+            #[zip_map]
+            fn TIME([hour]: f64, [minute]: f64, [second]: f64) {
+                Duration::from_seconds(hour * 3600.0 + minute * 60.0 + second)
+            }
+        ),
+        formula_fn!(
+            /// Parses a date, optionally with a time of day, from a string.
+            ///
+            /// Supported formats include `2024-01-15`, `2024-01-15 13:30:00`,
+            /// `1/15/2024`, and `January 15, 2024`. Returns an error if the
+            /// string cannot be parsed.
+            #[examples("DATEVALUE(\"2024-01-15\")", "DATEVALUE(\"2024-01-15 13:30\")")]
+            #[zip_map]
+            fn DATEVALUE([date_string]: (Spanned<String>)) {
+                Instant::parse(&date_string.inner)
+                    .ok_or_else(|| ErrorMsg::InvalidArgument.with_span(date_string.span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the year of a date.
+            #[examples("YEAR(DATE(2024, 1, 15)) = 2024", "YEAR(A1)")]
+            #[zip_map]
+            fn YEAR([date]: (Spanned<Instant>)) {
+                to_date(date)?.year()
+            }
+        ),
+        formula_fn!(
+            /// Returns the month of a date, from 1 (January) to 12 (December).
+            #[examples("MONTH(DATE(2024, 1, 15)) = 1", "MONTH(A1)")]
+            #[zip_map]
+            fn MONTH([date]: (Spanned<Instant>)) {
+                to_date(date)?.month()
+            }
+        ),
+        formula_fn!(
+            /// Returns the day of the month of a date, from 1 to 31.
+            #[examples("DAY(DATE(2024, 1, 15)) = 15", "DAY(A1)")]
+            #[zip_map]
+            fn DAY([date]: (Spanned<Instant>)) {
+                to_date(date)?.day()
+            }
+        ),
+        formula_fn!(
+            /// Returns the hour of a time instant or time duration, from 0 to
+            /// 23.
+            #[examples("HOUR(TIME(13, 30, 0)) = 13", "HOUR(NOW())")]
+            #[zip_map]
+            fn HOUR([time]: (Spanned<CellValue>)) {
+                (seconds_of_day(*time)? / 3600.0).floor() as i64
+            }
+        ),
+        formula_fn!(
+            /// Returns the minute of a time instant or time duration, from 0
+            /// to 59.
+            #[examples("MINUTE(TIME(13, 30, 0)) = 30", "MINUTE(NOW())")]
+            #[zip_map]
+            fn MINUTE([time]: (Spanned<CellValue>)) {
+                (seconds_of_day(*time)? / 60.0).floor() as i64 % 60
+            }
+        ),
+        formula_fn!(
+            /// Returns the second of a time instant or time duration, from 0
+            /// to 59.
+            #[examples("SECOND(TIME(13, 30, 15)) = 15", "SECOND(NOW())")]
+            #[zip_map]
+            fn SECOND([time]: (Spanned<CellValue>)) {
+                seconds_of_day(*time)?.floor() as i64 % 60
+            }
+        ),
+        formula_fn!(
+            /// Returns the day of the week of a date as a number.
+            ///
+            /// `return_type` determines how days are numbered:
+            ///
+            /// - `1` (default) - Sunday is 1 and Saturday is 7
+            /// - `2` - Monday is 1 and Sunday is 7
+            /// - `3` - Monday is 0 and Sunday is 6
+            /// - `11` to `17` - Monday (`11`) through Sunday (`17`) is 1 and
+            ///   the following days count up to 7
+            #[examples("WEEKDAY(DATE(2024, 1, 15)) = 2", "WEEKDAY(A1, 2)")]
+            #[zip_map]
+            fn WEEKDAY([date]: (Spanned<Instant>), [return_type]: (Option<Spanned<i64>>)) {
+                let days_from_monday = to_date(date)?.weekday().num_days_from_monday() as i64;
+                let (first_day_from_monday, first_number) = match return_type {
+                    None | Some(Spanned { inner: 1, .. }) => (6, 1),
+                    Some(Spanned { inner: 2, .. }) => (0, 1),
+                    Some(Spanned { inner: 3, .. }) => (0, 0),
+                    Some(Spanned {
+                        inner: t @ 11..=17, ..
+                    }) => (t - 11, 1),
+                    Some(Spanned { span, .. }) => {
+                        return Err(ErrorMsg::InvalidArgument.with_span(span));
+                    }
+                };
+                (days_from_monday - first_day_from_monday).rem_euclid(7) + first_number
+            }
+        ),
+        formula_fn!(
+            /// Returns the date that is `months` months after `start_date`.
+            /// `months` may be negative.
+            ///
+            /// If the day of the month does not exist in the resulting month,
+            /// then the last day of that month is used instead.
+            #[examples("EDATE(DATE(2024, 1, 31), 1)", "EDATE(A1, -12)")]
+            #[zip_map]
+            fn EDATE(span: Span, [start_date]: (Spanned<Instant>), [months]: i64) {
+                add_months(to_date(start_date)?, months)
+                    .map(Instant::from_date)
+                    .ok_or_else(|| ErrorMsg::Overflow.with_span(*span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the last day of the month that is `months` months after
+            /// `start_date`. `months` may be negative.
+            #[examples("EOMONTH(DATE(2024, 1, 15), 1)", "EOMONTH(A1, 0)")]
+            #[zip_map]
+            fn EOMONTH(span: Span, [start_date]: (Spanned<Instant>), [months]: i64) {
+                let start_date = to_date(start_date)?;
+                start_date
+                    .with_day(1)
+                    .and_then(|first_of_month| add_months(first_of_month, months.checked_add(1)?))
+                    .and_then(|date| date.pred_opt())
+                    .map(Instant::from_date)
+                    .ok_or_else(|| ErrorMsg::Overflow.with_span(*span))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the difference between two dates in the given `unit`.
+            ///
+            /// `unit` is one of the following:
+            ///
+            /// - `"Y"` - whole years
+            /// - `"M"` - whole months
+            /// - `"D"` - days
+            /// - `"MD"` - days, ignoring whole months
+            /// - `"YM"` - whole months, ignoring whole years
+            /// - `"YD"` - days, ignoring whole years
+            ///
+            /// Returns an error if `start_date` is after `end_date`.
+            #[examples(
+                "DATEDIF(DATE(2020, 3, 15), DATE(2024, 1, 1), \"Y\") = 3",
+                "DATEDIF(A1, TODAY(), \"D\")"
+            )]
+            #[zip_map]
+            fn DATEDIF(
+                span: Span,
+                [start_date]: (Spanned<Instant>),
+                [end_date]: (Spanned<Instant>),
+                [unit]: (Spanned<String>),
+            ) {
+                let start = to_date(start_date)?;
+                let end = to_date(end_date)?;
+                if start > end {
+                    return Err(ErrorMsg::InvalidArgument.with_span(*span));
+                }
+                let months = whole_months_between(start, end);
+                let days_after_months = |months| match add_months(start, months) {
+                    Some(date) => Ok((end - date).num_days()),
+                    None => Err(ErrorMsg::Overflow.with_span(*span)),
+                };
+                match unit.inner.to_ascii_uppercase().as_str() {
+                    "Y" => months / 12,
+                    "M" => months,
+                    "D" => (end - start).num_days(),
+                    "MD" => days_after_months(months)?,
+                    "YM" => months % 12,
+                    "YD" => days_after_months(months / 12 * 12)?,
+                    _ => return Err(ErrorMsg::InvalidArgument.with_span(unit.span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of working days from `start_date` to
+            /// `end_date`, including both. Working days are Monday through
+            /// Friday, excluding any dates in `holidays`.
+            ///
+            /// If `start_date` is after `end_date`, then the result is
+            /// negative.
+            #[examples(
+                "NETWORKDAYS(DATE(2024, 1, 1), DATE(2024, 1, 31))",
+                "NETWORKDAYS(A1, B1, C1:C10)"
+            )]
+            #[zip_map]
+            fn NETWORKDAYS(
+                [start_date]: (Spanned<Instant>),
+                [end_date]: (Spanned<Instant>),
+                holidays: (Iter<Instant>),
+            ) {
+                let start = to_date(start_date)?;
+                let end = to_date(end_date)?;
+                let (first, last, sign) = match start <= end {
+                    true => (start, end, 1),
+                    false => (end, start, -1),
+                };
+                let holidays = holiday_dates(holidays);
+                let holiday_count = holidays
+                    .iter()
+                    .filter(|&&date| first <= date && date <= last && is_weekday(date))
+                    .count() as i64;
+                sign * (count_weekdays(first, last) - holiday_count)
+            }
+        ),
+        formula_fn!(
+            /// Returns the date that is `days` working days after
+            /// `start_date`. Working days are Monday through Friday, excluding
+            /// any dates in `holidays`. `days` may be negative.
+            #[examples("WORKDAY(DATE(2024, 1, 15), 10)", "WORKDAY(A1, -5, C1:C10)")]
+            #[zip_map]
+            fn WORKDAY(
+                span: Span,
+                [start_date]: (Spanned<Instant>),
+                [days]: i64,
+                holidays: (Iter<Instant>),
+            ) {
+                let holidays = holiday_dates(holidays);
+                let date = add_workdays(to_date(start_date)?, days, &holidays)
+                    .ok_or_else(|| ErrorMsg::Overflow.with_span(*span))?;
+                Instant::from_date(date)
+            }
+        ),
+    ]
+}
+
+/// Returns the date of a time instant, or an error if it is out of range.
+fn to_date(instant: Spanned<Instant>) -> CodeResult<NaiveDate> {
+    to_date_time(instant).map(|date_time| date_time.date())
+}
+
+/// Returns the date and time of a time instant, or an error if it is out of
+/// range.
+fn to_date_time(instant: Spanned<Instant>) -> CodeResult<NaiveDateTime> {
+    instant
+        .inner
+        .to_naive()
+        .ok_or_else(|| ErrorMsg::Overflow.with_span(instant.span))
+}
+
+/// Returns the number of seconds since midnight of a time instant, or the
+/// number of seconds into the last day of a time duration.
+fn seconds_of_day(time: Spanned<&CellValue>) -> CodeResult<f64> {
+    match time.inner {
+        CellValue::Duration(d) => Ok(d.seconds.rem_euclid(86400.0)),
+        _ => {
+            let instant = time.try_coerce::<Instant>()?;
+            instant
+                .inner
+                .seconds_since_midnight()
+                .ok_or_else(|| ErrorMsg::Overflow.with_span(instant.span))
+        }
+    }
+}
+
+/// Subtracts one from an argument, or returns an error if it is too small.
+fn minus_one(n: Spanned<i64>) -> CodeResult<i64> {
+    n.inner
+        .checked_sub(1)
+        .ok_or_else(|| ErrorMsg::Overflow.with_span(n.span))
+}
+
+/// Adds a number of months to a date, clamping the day of the month to the end
+/// of the month if necessary.
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let abs_months = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    match months >= 0 {
+        true => date.checked_add_months(abs_months),
+        false => date.checked_sub_months(abs_months),
+    }
+}
+
+/// Adds a number of days to a date.
+fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    let abs_days = Days::new(days.unsigned_abs());
+    match days >= 0 {
+        true => date.checked_add_days(abs_days),
+        false => date.checked_sub_days(abs_days),
+    }
+}
+
+/// Returns the number of whole months from `start` to `end`.
+fn whole_months_between(start: NaiveDate, end: NaiveDate) -> i64 {
+    let mut months =
+        (end.year() as i64 - start.year() as i64) * 12 + end.month() as i64 - start.month() as i64;
+    if end.day() < start.day() {
+        months -= 1;
+    }
+    months
+}
+
+fn is_weekday(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Returns the number of weekdays from `first` to `last`, including both.
+fn count_weekdays(first: NaiveDate, last: NaiveDate) -> i64 {
+    let total_days = (last - first).num_days() + 1;
+    let first_weekday = first.weekday().num_days_from_monday() as i64;
+    let remaining_weekdays = (0..total_days % 7)
+        .filter(|i| (first_weekday + i) % 7 < 5)
+        .count() as i64;
+    total_days / 7 * 5 + remaining_weekdays
+}
+
+/// Returns the date that is `days` weekdays after `date`, not counting any
+/// dates in `holidays`, or `None` if it is out of range.
+fn add_workdays(date: NaiveDate, days: i64, holidays: &HashSet<NaiveDate>) -> Option<NaiveDate> {
+    let forward = days > 0;
+    let step = |date: NaiveDate, days: u64| match forward {
+        true => date.checked_add_days(Days::new(days)),
+        false => date.checked_sub_days(Days::new(days)),
+    };
+    let next_weekday = |mut date: NaiveDate| loop {
+        date = step(date, 1)?;
+        if is_weekday(date) {
+            return Some(date);
+        }
+    };
+
+    // Counting from a weekend is the same as counting from the weekday just
+    // before it, and counting from a weekday keeps whole weeks on weekdays.
+    let mut start = date;
+    while days != 0 && !is_weekday(start) {
+        start = match forward {
+            true => start.pred_opt(),
+            false => start.succ_opt(),
+        }?;
+    }
+
+    // Every whole week has exactly five weekdays.
+    let mut end = step(start, days.unsigned_abs() / 5 * 7)?;
+    for _ in 0..days.unsigned_abs() % 5 {
+        end = next_weekday(end)?;
+    }
+
+    // Each holiday on a weekday between `date` and `end` adds another day.
+    // Visiting them in order also counts holidays that `end` moves past.
+    let mut holidays: Vec<NaiveDate> = holidays
+        .iter()
+        .copied()
+        .filter(|&holiday| is_weekday(holiday))
+        .collect();
+    holidays.sort_unstable();
+    if !forward {
+        holidays.reverse();
+    }
+    for holiday in holidays {
+        let is_in_range = match forward {
+            true => date < holiday && holiday <= end,
+            false => end <= holiday && holiday < date,
+        };
+        if is_in_range {
+            end = next_weekday(end)?;
+        }
+    }
+    Some(end)
+}
+
+fn holiday_dates(holidays: &[Instant]) -> HashSet<NaiveDate> {
+    holidays.iter().filter_map(|h| h.to_date()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    #[test]
+    fn test_formula_date_and_time() {
+        let g = Grid::new();
+        assert_eq!("2024-01-15", eval_to_string(&g, "DATE(2024, 1, 15)"));
+        assert_eq!("2025-01-01", eval_to_string(&g, "DATE(2024, 13, 1)"));
+        assert_eq!("2024-02-29", eval_to_string(&g, "DATE(2024, 3, 0)"));
+        assert_eq!("2023-12-31", eval_to_string(&g, "DATE(2024, 1, 0)"));
+        expect_err(&ErrorMsg::Overflow, &g, "DATE(2024, 1, -1e300)");
+        expect_err(&ErrorMsg::Overflow, &g, "DATE(2024, -1e300, 1)");
+        assert_eq!(
+            "13 hours, 30 minutes",
+            eval_to_string(&g, "TIME(13, 30, 0)")
+        );
+        assert_eq!(
+            "2024-01-15 13:30:00",
+            eval_to_string(&g, "DATE(2024, 1, 15) + TIME(13, 30, 0)"),
+        );
+        assert_eq!(
+            "2024-01-15 13:30:00",
+            eval_to_string(&g, "DATEVALUE(\"2024-01-15 13:30\")"),
+        );
+        assert_eq!("2024-01-15", eval_to_string(&g, "DATEVALUE(\"1/15/2024\")"));
+        assert_eq!(
+            "2024-01-15",
+            eval_to_string(&g, "DATEVALUE(\"January 15, 2024\")"),
+        );
+        expect_err(&ErrorMsg::InvalidArgument, &g, "DATEVALUE(\"tomorrow\")");
+        expect_err(&ErrorMsg::Overflow, &g, "DATE(99999999999, 1, 1)");
+
+        let now = eval(&g, "NOW()").into_cell_value().unwrap();
+        assert!(matches!(now, CellValue::Instant(_)));
+        assert_eq!("TRUE", eval_to_string(&g, "TODAY() <= NOW()"));
+    }
+
+    #[test]
+    fn test_formula_date_parts() {
+        let g = Grid::new();
+        let t = "DATEVALUE(\"2024-01-15 13:30:15\")";
+        assert_eq!("2024", eval_to_string(&g, &format!("YEAR({t})")));
+        assert_eq!("1", eval_to_string(&g, &format!("MONTH({t})")));
+        assert_eq!("15", eval_to_string(&g, &format!("DAY({t})")));
+        assert_eq!("13", eval_to_string(&g, &format!("HOUR({t})")));
+        assert_eq!("30", eval_to_string(&g, &format!("MINUTE({t})")));
+        assert_eq!("15", eval_to_string(&g, &format!("SECOND({t})")));
+        assert_eq!("13", eval_to_string(&g, "HOUR(TIME(37, 0, 0))"));
+        assert_eq!("2024", eval_to_string(&g, "YEAR(\"2024-01-15\")"));
+
+        // 2024-01-15 is a Monday
+        assert_eq!("2", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 15))"));
+        assert_eq!("1", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 15), 2)"));
+        assert_eq!("0", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 15), 3)"));
+        assert_eq!("7", eval_to_string(&g, "WEEKDAY(DATE(2024, 1, 15), 12)"));
+        assert_eq!(
+            "{1, 7}",
+            eval_to_string(&g, "WEEKDAY(DATE(2024, 1, {14, 20}))")
+        );
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "WEEKDAY(DATE(2024, 1, 15), 4)",
+        );
+    }
+
+    #[test]
+    fn test_formula_date_offsets() {
+        let g = Grid::new();
+        assert_eq!(
+            "2024-02-29",
+            eval_to_string(&g, "EDATE(DATE(2024, 1, 31), 1)")
+        );
+        assert_eq!(
+            "2023-01-31",
+            eval_to_string(&g, "EDATE(DATE(2024, 1, 31), -12)")
+        );
+        assert_eq!(
+            "2024-02-29",
+            eval_to_string(&g, "EOMONTH(DATE(2024, 1, 15), 1)")
+        );
+        assert_eq!(
+            "2023-12-31",
+            eval_to_string(&g, "EOMONTH(DATE(2024, 1, 15), -1)")
+        );
+
+        let dif = |unit: &str| {
+            eval_to_string(
+                &g,
+                &format!("DATEDIF(DATE(2020, 3, 15), DATE(2024, 1, 10), \"{unit}\")"),
+            )
+        };
+        assert_eq!("3", dif("Y"));
+        assert_eq!("45", dif("M"));
+        assert_eq!("1396", dif("D"));
+        assert_eq!("26", dif("MD"));
+        assert_eq!("9", dif("YM"));
+        assert_eq!("301", dif("YD"));
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "DATEDIF(DATE(2024, 1, 2), DATE(2024, 1, 1), \"D\")",
+        );
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "DATEDIF(DATE(2024, 1, 1), DATE(2024, 1, 2), \"W\")",
+        );
+    }
+
+    #[test]
+    fn test_formula_working_days() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        sheet.set_cell_value(pos![A1], CellValue::Text("2024-01-15".into()));
+        sheet.set_cell_value(pos![A2], CellValue::Text("2024-01-20".into()));
+
+        assert_eq!(
+            "23",
+            eval_to_string(&g, "NETWORKDAYS(DATE(2024, 1, 1), DATE(2024, 1, 31))"),
+        );
+        assert_eq!(
+            "-23",
+            eval_to_string(&g, "NETWORKDAYS(DATE(2024, 1, 31), DATE(2024, 1, 1))"),
+        );
+        assert_eq!(
+            "22",
+            eval_to_string(
+                &g,
+                "NETWORKDAYS(DATE(2024, 1, 1), DATE(2024, 1, 31), A1:A3)"
+            ),
+        );
+        assert_eq!(
+            "0",
+            eval_to_string(&g, "NETWORKDAYS(DATE(2024, 1, 13), DATE(2024, 1, 14))")
+        );
+
+        assert_eq!(
+            "2024-01-26",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 12), 10)")
+        );
+        assert_eq!(
+            "2024-01-29",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 12), 10, A1:A2)")
+        );
+        assert_eq!(
+            "2024-01-05",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 12), -5)")
+        );
+        assert_eq!(
+            "2024-01-13",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 13), 0)")
+        );
+        // Starting on a weekend counts from the next weekday.
+        assert_eq!(
+            "2024-01-19",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 13), 5)")
+        );
+        assert_eq!(
+            "2024-01-19",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 14), 5)")
+        );
+        assert_eq!(
+            "2024-01-08",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 13), -5)")
+        );
+        assert_eq!(
+            "2024-01-22",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 13), 6)")
+        );
+
+        // Moving past one holiday can reach the next.
+        let holidays = "{DATE(2024, 1, 15), DATE(2024, 1, 26), DATE(2024, 1, 29)}";
+        assert_eq!(
+            "2024-01-31",
+            eval_to_string(&g, &format!("WORKDAY(DATE(2024, 1, 12), 10, {holidays})"))
+        );
+        assert_eq!(
+            "2024-01-17",
+            eval_to_string(&g, &format!("WORKDAY(DATE(2024, 2, 2), -10, {holidays})"))
+        );
+        assert_eq!(
+            "2034-01-02",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 1), 2610)")
+        );
+        assert_eq!(
+            "2013-12-30",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 1), -2610)")
+        );
+        assert_eq!(
+            "5857-01-26",
+            eval_to_string(&g, "WORKDAY(DATE(2024, 1, 1), 1000000)")
+        );
+    }
+
+    #[test]
+    fn test_formula_date_arithmetic() {
+        let g = Grid::new();
+        assert_eq!("2024-01-22", eval_to_string(&g, "DATE(2024, 1, 15) + 7"));
+        assert_eq!("2024-01-22", eval_to_string(&g, "7 + DATE(2024, 1, 15)"));
+        assert_eq!("2024-01-08", eval_to_string(&g, "DATE(2024, 1, 15) - 7"));
+        assert_eq!(
+            "31 days",
+            eval_to_string(&g, "DATE(2024, 2, 1) - DATE(2024, 1, 1)"),
+        );
+        assert_eq!(
+            "-1 day, -2 hours",
+            eval_to_string(&g, "DATE(2024, 1, 1) - (DATE(2024, 1, 2) + TIME(2, 0, 0))"),
+        );
+        assert_eq!(
+            "2024-01-14 22:00:00",
+            eval_to_string(&g, "DATE(2024, 1, 15) - TIME(2, 0, 0)"),
+        );
+        assert_eq!(
+            "1 hour, 30 minutes",
+            eval_to_string(&g, "TIME(1, 0, 0) + TIME(0, 30, 0)"),
+        );
+        assert_eq!("-1 hour", eval_to_string(&g, "-TIME(1, 0, 0)"));
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, "DATE(2024, 1, 15) < DATE(2024, 1, 16)"),
+        );
+        expect_err(
+            &ErrorMsg::Expected {
+                expected: "number".into(),
+                got: Some("time instant".into()),
+            },
+            &g,
+            "DATE(2024, 1, 15) + DATE(2024, 1, 16)",
+        );
+        expect_err(
+            &ErrorMsg::Expected {
+                expected: "time duration".into(),
+                got: Some("number".into()),
+            },
+            &g,
+            "TIME(1, 0, 0) + 1",
+        );
+    }
+}
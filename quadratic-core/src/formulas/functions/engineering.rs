@@ -0,0 +1,688 @@
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Engineering functions",
+    docs: "Binary, octal, and hexadecimal numbers are written as text with at \
+           most 10 digits. Negative numbers are written in two's complement, so \
+           the first digit is the sign. For example, `\"1111111111\"` is \
+           -1 in binary.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        // Number bases
+        formula_fn!(
+            /// Converts a binary number to decimal.
+            ///
+            /// Returns an error if `number` has more than 10 digits.
+            #[examples("BIN2DEC(\"1010\") = 10", "BIN2DEC(\"1111111111\") = -1")]
+            #[zip_map]
+            fn BIN2DEC([number]: (Spanned<String>)) {
+                from_twos_complement(number, 2)?
+            }
+        ),
+        formula_fn!(
+            /// Converts an octal number to decimal.
+            ///
+            /// Returns an error if `number` has more than 10 digits.
+            #[examples("OCT2DEC(\"17\") = 15", "OCT2DEC(\"7777777777\") = -1")]
+            #[zip_map]
+            fn OCT2DEC([number]: (Spanned<String>)) {
+                from_twos_complement(number, 8)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a hexadecimal number to decimal.
+            ///
+            /// Returns an error if `number` has more than 10 digits.
+            #[examples("HEX2DEC(\"FF\") = 255", "HEX2DEC(\"FFFFFFFFFF\") = -1")]
+            #[zip_map]
+            fn HEX2DEC([number]: (Spanned<String>)) {
+                from_twos_complement(number, 16)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a decimal number to binary.
+            ///
+            /// `number` must be between -512 and 511. If `places` is given,
+            /// the result is padded with zeros to that many digits. `places`
+            /// is ignored for negative numbers, which always have 10 digits.
+            #[examples("DEC2BIN(10) = \"1010\"", "DEC2BIN(10, 8) = \"00001010\"")]
+            #[zip_map]
+            fn DEC2BIN([number]: (Spanned<f64>), [places]: (Option<Spanned<f64>>)) {
+                to_twos_complement(number, places, 2)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a decimal number to hexadecimal.
+            ///
+            /// `number` must be between -549755813888 and 549755813887. If
+            /// `places` is given, the result is padded with zeros to that many
+            /// digits. `places` is ignored for negative numbers, which always
+            /// have 10 digits.
+            #[examples("DEC2HEX(255) = \"FF\"", "DEC2HEX(255, 4) = \"00FF\"")]
+            #[zip_map]
+            fn DEC2HEX([number]: (Spanned<f64>), [places]: (Option<Spanned<f64>>)) {
+                to_twos_complement(number, places, 16)?
+            }
+        ),
+        formula_fn!(
+            /// Converts a number to text in the given `radix`, which must be
+            /// between 2 and 36.
+            ///
+            /// Digits above 9 are written as the letters `A` to `Z`. If
+            /// `min_length` is given, the result is padded with zeros to at
+            /// least that many digits.
+            #[examples("BASE(255, 16) = \"FF\"", "BASE(5, 2, 8) = \"00000101\"")]
+            #[zip_map]
+            fn BASE(
+                [number]: (Spanned<f64>),
+                [radix]: (Spanned<f64>),
+                [min_length]: (Option<Spanned<f64>>),
+            ) {
+                let number = truncate_in_range(number, 0, MAX_SAFE_INTEGER)?;
+                let radix = truncate_in_range(radix, 2, 36)? as u32;
+                let min_length =
+                    min_length.map_or(Ok(0), |n| truncate_in_range(n, 0, 255))? as usize;
+                format!("{:0>min_length$}", to_radix_string(number as u64, radix))
+            }
+        ),
+        formula_fn!(
+            /// Converts text representing a number in the given `radix` to
+            /// decimal. `radix` must be between 2 and 36.
+            ///
+            /// Letters are treated as digits above 9, ignoring case.
+            #[examples("DECIMAL(\"FF\", 16) = 255", "DECIMAL(\"zz\", 36) = 1295")]
+            #[zip_map]
+            fn DECIMAL([text]: (Spanned<String>), [radix]: (Spanned<f64>)) {
+                let radix = truncate_in_range(radix, 2, 36)? as u32;
+                match parse_radix(&text.inner, radix) {
+                    Some(n) if text.inner.len() <= 255 && n <= MAX_SAFE_INTEGER as u64 => n as f64,
+                    _ => return Err(ErrorMsg::InvalidArgument.with_span(text.span)),
+                }
+            }
+        ),
+        // Bitwise operations
+        formula_fn!(
+            /// Returns the bitwise AND of two numbers.
+            ///
+            /// Both numbers must be integers between 0 and 2^48 - 1.
+            #[examples("BITAND(12, 10) = 8")]
+            #[zip_map]
+            fn BITAND([number1]: (Spanned<f64>), [number2]: (Spanned<f64>)) {
+                (to_bits(number1)? & to_bits(number2)?) as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the bitwise OR of two numbers.
+            ///
+            /// Both numbers must be integers between 0 and 2^48 - 1.
+            #[examples("BITOR(12, 10) = 14")]
+            #[zip_map]
+            fn BITOR([number1]: (Spanned<f64>), [number2]: (Spanned<f64>)) {
+                (to_bits(number1)? | to_bits(number2)?) as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the bitwise XOR of two numbers.
+            ///
+            /// Both numbers must be integers between 0 and 2^48 - 1.
+            #[examples("BITXOR(12, 10) = 6")]
+            #[zip_map]
+            fn BITXOR([number1]: (Spanned<f64>), [number2]: (Spanned<f64>)) {
+                (to_bits(number1)? ^ to_bits(number2)?) as f64
+            }
+        ),
+        formula_fn!(
+            /// Shifts the bits of `number` left by `shift_amount`, which is
+            /// equivalent to multiplying by 2^`shift_amount`. A negative
+            /// `shift_amount` shifts right instead.
+            ///
+            /// `number` must be an integer between 0 and 2^48 - 1, and so must
+            /// the result.
+            #[examples("BITLSHIFT(5, 2) = 20")]
+            #[zip_map]
+            fn BITLSHIFT(span: Span, [number]: (Spanned<f64>), [shift_amount]: (Spanned<f64>)) {
+                let shift_amount = truncate_in_range(shift_amount, -53, 53)?;
+                bit_shift(*span, number, shift_amount)?
+            }
+        ),
+        formula_fn!(
+            /// Shifts the bits of `number` right by `shift_amount`, which is
+            /// equivalent to dividing by 2^`shift_amount` and rounding down. A
+            /// negative `shift_amount` shifts left instead.
+            ///
+            /// `number` must be an integer between 0 and 2^48 - 1, and so must
+            /// the result.
+            #[examples("BITRSHIFT(20, 2) = 5")]
+            #[zip_map]
+            fn BITRSHIFT(span: Span, [number]: (Spanned<f64>), [shift_amount]: (Spanned<f64>)) {
+                let shift_amount = truncate_in_range(shift_amount, -53, 53)?;
+                bit_shift(*span, number, -shift_amount)?
+            }
+        ),
+        // Units
+        formula_fn!(
+            /// Converts `number` from one unit of measurement to another.
+            ///
+            /// Unit names are case-sensitive. Returns an error if either unit
+            /// is not recognized or if the units measure different quantities.
+            ///
+            /// - Mass: `g`, `sg`, `lbm`, `u`, `ozm`, `grain`, `cwt`,
+            ///   `uk_cwt`, `stone`, `ton`, `uk_ton`
+            /// - Distance: `m`, `mi`, `Nmi`, `in`, `ft`, `yd`, `ang`, `ell`,
+            ///   `ly`, `parsec`, `survey_mi`
+            /// - Time: `yr`, `day`, `hr`, `mn`, `sec`
+            /// - Pressure: `Pa`, `atm`, `mmHg`, `psi`, `Torr`
+            /// - Force: `N`, `dyn`, `lbf`, `pond`
+            /// - Energy: `J`, `e`, `c`, `cal`, `eV`, `HPh`, `Wh`, `flb`,
+            ///   `BTU`
+            /// - Power: `W`, `HP`, `PS`
+            /// - Magnetism: `T`, `ga`
+            /// - Temperature: `C`, `F`, `K`, `Rank`, `Reau`
+            /// - Volume: `l`, `tsp`, `tbs`, `oz`, `cup`, `pt`, `uk_pt`,
+            ///   `qt`, `uk_qt`, `gal`, `uk_gal`, `m3`, `in3`, `ft3`, `yd3`,
+            ///   `barrel`
+            /// - Area: `m2`, `ar`, `ha`, `in2`, `ft2`, `yd2`, `mi2`,
+            ///   `uk_acre`, `us_acre`
+            /// - Information: `bit`, `byte`
+            /// - Speed: `m/s`, `m/h`, `mph`, `kn`, `admkn`
+            ///
+            /// Metric units, such as `g`, `m`, and `l`, may be preceded by a
+            /// metric prefix, such as `k` for kilo or `u` for micro.
+            /// Information units may also be preceded by a binary prefix, such
+            /// as `ki` for kibi (2^10) or `Mi` for mebi (2^20).
+            #[examples(
+                "CONVERT(1, \"mi\", \"km\")",
+                "CONVERT(100, \"C\", \"F\") = 212",
+                "CONVERT(A1, \"kibyte\", \"bit\")"
+            )]
+            #[zip_map]
+            fn CONVERT(
+                [number]: f64,
+                [from_unit]: (Spanned<String>),
+                [to_unit]: (Spanned<String>),
+            ) {
+                let not_available =
+                    |unit: &Spanned<String>| ErrorMsg::NotAvailable.with_span(unit.span);
+                let (from, from_scale) =
+                    find_unit(&from_unit.inner).ok_or_else(|| not_available(&from_unit))?;
+                let (to, to_scale) =
+                    find_unit(&to_unit.inner).ok_or_else(|| not_available(&to_unit))?;
+                if from.quantity != to.quantity {
+                    return Err(not_available(&to_unit));
+                }
+                let base = number * from_scale * from.factor + from.offset;
+                (base - to.offset) / (to_scale * to.factor)
+            }
+        ),
+    ]
+}
+
+/// Largest integer that can be represented exactly by an `f64`.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Maximum number of digits in a binary, octal, or hexadecimal number.
+const MAX_DIGITS: u32 = 10;
+
+/// Exclusive upper bound for the operands of bitwise functions.
+const BITS_LIMIT: u64 = 1 << 48;
+
+/// Truncates a number to an integer, returning an error if it is outside the
+/// range `min..=max`.
+fn truncate_in_range(n: Spanned<f64>, min: i64, max: i64) -> CodeResult<i64> {
+    let truncated = n.inner.trunc();
+    match min as f64 <= truncated && truncated <= max as f64 {
+        true => Ok(truncated as i64),
+        false => Err(ErrorMsg::InvalidArgument.with_span(n.span)),
+    }
+}
+
+/// Returns the number of bits represented by [`MAX_DIGITS`] digits in `radix`,
+/// which must be 2, 8, or 16.
+fn twos_complement_bits(radix: u32) -> u32 {
+    MAX_DIGITS * radix.trailing_zeros()
+}
+
+/// Parses a number with at most [`MAX_DIGITS`] digits in `radix`, treating it
+/// as two's complement.
+fn from_twos_complement(number: Spanned<String>, radix: u32) -> CodeResult<i64> {
+    let bits = twos_complement_bits(radix);
+    match parse_radix(&number.inner, radix) {
+        Some(n) if number.inner.len() <= MAX_DIGITS as usize => match n >> (bits - 1) {
+            0 => Ok(n as i64),
+            _ => Ok(n as i64 - (1 << bits)),
+        },
+        _ => Err(ErrorMsg::InvalidArgument.with_span(number.span)),
+    }
+}
+
+/// Formats a number in `radix`, which must be 2, 8, or 16, using two's
+/// complement for negative numbers and padding positive numbers with zeros to
+/// `places` digits.
+fn to_twos_complement(
+    number: Spanned<f64>,
+    places: Option<Spanned<f64>>,
+    radix: u32,
+) -> CodeResult<String> {
+    let bits = twos_complement_bits(radix);
+    let number = truncate_in_range(number, -(1 << (bits - 1)), (1 << (bits - 1)) - 1)?;
+    if number < 0 {
+        return Ok(to_radix_string((number + (1 << bits)) as u64, radix));
+    }
+    let digits = to_radix_string(number as u64, radix);
+    match places {
+        None => Ok(digits),
+        Some(places) => {
+            let places_span = places.span;
+            match truncate_in_range(places, 1, MAX_DIGITS as i64)? as usize {
+                places if digits.len() <= places => Ok(format!("{digits:0>places$}")),
+                _ => Err(ErrorMsg::InvalidArgument.with_span(places_span)),
+            }
+        }
+    }
+}
+
+/// Parses a nonnegative integer in `radix`, ignoring case. Returns `None` if
+/// the string contains a character that is not a digit or if the number does
+/// not fit in a `u64`.
+fn parse_radix(s: &str, radix: u32) -> Option<u64> {
+    s.chars().try_fold(0_u64, |n, c| {
+        n.checked_mul(radix as u64)?
+            .checked_add(c.to_digit(radix)? as u64)
+    })
+}
+
+/// Formats a nonnegative integer in `radix` using uppercase letters for digits
+/// above 9.
+fn to_radix_string(mut n: u64, radix: u32) -> String {
+    let mut digits = vec![];
+    loop {
+        let digit = std::char::from_digit((n % radix as u64) as u32, radix).unwrap_or('?');
+        digits.push(digit.to_ascii_uppercase());
+        n /= radix as u64;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+/// Converts an operand of a bitwise function to an integer, returning an error
+/// if it is not an integer between 0 and 2^48 - 1.
+fn to_bits(n: Spanned<f64>) -> CodeResult<u64> {
+    match n.inner.fract() == 0.0 && 0.0 <= n.inner && n.inner < BITS_LIMIT as f64 {
+        true => Ok(n.inner as u64),
+        false => Err(ErrorMsg::InvalidArgument.with_span(n.span)),
+    }
+}
+
+/// Shifts the bits of `number` left by `shift_amount`, or right if it is
+/// negative.
+fn bit_shift(span: Span, number: Spanned<f64>, shift_amount: i64) -> CodeResult<f64> {
+    let number = to_bits(number)? as u128;
+    let result = match shift_amount {
+        0.. => number << shift_amount,
+        _ => number >> -shift_amount,
+    };
+    match result < BITS_LIMIT as u128 {
+        true => Ok(result as f64),
+        false => Err(ErrorMsg::InvalidArgument.with_span(span)),
+    }
+}
+
+/// Physical quantity measured by a unit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Quantity {
+    Mass,
+    Distance,
+    Time,
+    Pressure,
+    Force,
+    Energy,
+    Power,
+    Magnetism,
+    Temperature,
+    Volume,
+    Area,
+    Information,
+    Speed,
+}
+
+/// Unit of measurement for `CONVERT`.
+struct Unit {
+    names: &'static [&'static str],
+    quantity: Quantity,
+    /// Value of one of this unit in the base unit for its quantity.
+    factor: f64,
+    /// Value of zero of this unit in the base unit for its quantity. This is
+    /// only nonzero for temperatures.
+    offset: f64,
+    /// Power to which a metric prefix is raised, such as 2 for `km2`, or 0 if
+    /// the unit does not accept prefixes.
+    prefix_power: i32,
+}
+
+const fn unit(
+    names: &'static [&'static str],
+    quantity: Quantity,
+    factor: f64,
+    prefix_power: i32,
+) -> Unit {
+    Unit {
+        names,
+        quantity,
+        factor,
+        offset: 0.0,
+        prefix_power,
+    }
+}
+
+const fn temperature(names: &'static [&'static str], factor: f64, offset: f64) -> Unit {
+    Unit {
+        names,
+        quantity: Quantity::Temperature,
+        factor,
+        offset,
+        prefix_power: 0,
+    }
+}
+
+/// Units accepted by `CONVERT`. The base units are grams, meters, seconds,
+/// pascals, newtons, joules, watts, teslas, kelvins, cubic meters, square
+/// meters, bits, and meters per second.
+#[rustfmt::skip]
+const UNITS: &[Unit] = {
+    use Quantity::*;
+    &[
+        unit(&["g"], Mass, 1.0, 1),
+        unit(&["sg"], Mass, 14593.902937206364, 0),
+        unit(&["lbm"], Mass, 453.59237, 0),
+        unit(&["u"], Mass, 1.66053906660e-24, 1),
+        unit(&["ozm"], Mass, 28.349523125, 0),
+        unit(&["grain"], Mass, 0.06479891, 0),
+        unit(&["cwt", "shweight"], Mass, 45359.237, 0),
+        unit(&["uk_cwt", "lcwt", "hweight"], Mass, 50802.34544, 0),
+        unit(&["stone"], Mass, 6350.29318, 0),
+        unit(&["ton"], Mass, 907184.74, 0),
+        unit(&["uk_ton", "LTON", "brton"], Mass, 1016046.9088, 0),
+
+        unit(&["m"], Distance, 1.0, 1),
+        unit(&["mi"], Distance, 1609.344, 0),
+        unit(&["Nmi"], Distance, 1852.0, 0),
+        unit(&["in"], Distance, 0.0254, 0),
+        unit(&["ft"], Distance, 0.3048, 0),
+        unit(&["yd"], Distance, 0.9144, 0),
+        unit(&["ang"], Distance, 1e-10, 1),
+        unit(&["ell"], Distance, 1.143, 0),
+        unit(&["ly"], Distance, 9460730472580800.0, 1),
+        unit(&["parsec", "pc"], Distance, 3.085677581491367e16, 1),
+        unit(&["survey_mi"], Distance, 1609.3472186944373, 0),
+
+        unit(&["yr"], Time, 31557600.0, 0),
+        unit(&["day", "d"], Time, 86400.0, 0),
+        unit(&["hr"], Time, 3600.0, 0),
+        unit(&["mn", "min"], Time, 60.0, 0),
+        unit(&["sec", "s"], Time, 1.0, 1),
+
+        unit(&["Pa", "p"], Pressure, 1.0, 1),
+        unit(&["atm", "at"], Pressure, 101325.0, 1),
+        unit(&["mmHg"], Pressure, 133.322, 1),
+        unit(&["psi"], Pressure, 6894.757293168361, 0),
+        unit(&["Torr"], Pressure, 133.32236842105263, 0),
+
+        unit(&["N"], Force, 1.0, 1),
+        unit(&["dyn", "dy"], Force, 1e-5, 1),
+        unit(&["lbf"], Force, 4.4482216152605, 0),
+        unit(&["pond"], Force, 0.00980665, 1),
+
+        unit(&["J"], Energy, 1.0, 1),
+        unit(&["e"], Energy, 1e-7, 1),
+        unit(&["c"], Energy, 4.184, 1),
+        unit(&["cal"], Energy, 4.1868, 1),
+        unit(&["eV", "ev"], Energy, 1.602176634e-19, 1),
+        unit(&["HPh", "hh"], Energy, 2684519.537696173, 0),
+        unit(&["Wh", "wh"], Energy, 3600.0, 1),
+        unit(&["flb"], Energy, 1.3558179483314004, 0),
+        unit(&["BTU", "btu"], Energy, 1055.05585262, 0),
+
+        unit(&["W", "w"], Power, 1.0, 1),
+        unit(&["HP", "h"], Power, 745.6998715822702, 0),
+        unit(&["PS"], Power, 735.49875, 0),
+
+        unit(&["T"], Magnetism, 1.0, 1),
+        unit(&["ga"], Magnetism, 1e-4, 1),
+
+        temperature(&["C", "cel"], 1.0, 273.15),
+        temperature(&["F", "fah"], 5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0),
+        temperature(&["Rank"], 5.0 / 9.0, 0.0),
+        temperature(&["Reau"], 1.25, 273.15),
+        unit(&["K", "kel"], Temperature, 1.0, 1),
+
+        unit(&["l", "L", "lt"], Volume, 1e-3, 1),
+        unit(&["tsp"], Volume, 4.92892159375e-6, 0),
+        unit(&["tbs"], Volume, 1.478676478125e-5, 0),
+        unit(&["oz"], Volume, 2.95735295625e-5, 0),
+        unit(&["cup"], Volume, 2.365882365e-4, 0),
+        unit(&["pt", "us_pt"], Volume, 4.73176473e-4, 0),
+        unit(&["uk_pt"], Volume, 5.6826125e-4, 0),
+        unit(&["qt"], Volume, 9.46352946e-4, 0),
+        unit(&["uk_qt"], Volume, 1.1365225e-3, 0),
+        unit(&["gal"], Volume, 3.785411784e-3, 0),
+        unit(&["uk_gal"], Volume, 4.54609e-3, 0),
+        unit(&["m3"], Volume, 1.0, 3),
+        unit(&["in3"], Volume, 1.6387064e-5, 0),
+        unit(&["ft3"], Volume, 0.028316846592, 0),
+        unit(&["yd3"], Volume, 0.764554857984, 0),
+        unit(&["barrel"], Volume, 0.158987294928, 0),
+
+        unit(&["m2"], Area, 1.0, 2),
+        unit(&["ar"], Area, 100.0, 1),
+        unit(&["ha"], Area, 10000.0, 0),
+        unit(&["in2"], Area, 6.4516e-4, 0),
+        unit(&["ft2"], Area, 0.09290304, 0),
+        unit(&["yd2"], Area, 0.83612736, 0),
+        unit(&["mi2"], Area, 2589988.110336, 0),
+        unit(&["uk_acre"], Area, 4046.8564224, 0),
+        unit(&["us_acre"], Area, 4046.872609874252, 0),
+
+        unit(&["bit"], Information, 1.0, 1),
+        unit(&["byte"], Information, 8.0, 1),
+
+        unit(&["m/s", "m/sec"], Speed, 1.0, 1),
+        unit(&["m/h", "m/hr"], Speed, 1.0 / 3600.0, 1),
+        unit(&["mph"], Speed, 0.44704, 0),
+        unit(&["kn"], Speed, 1852.0 / 3600.0, 0),
+        unit(&["admkn"], Speed, 1853.184 / 3600.0, 0),
+    ]
+};
+
+/// Metric prefixes accepted by `CONVERT`. Two-letter prefixes come first so
+/// that `da` is not mistaken for `d`.
+const METRIC_PREFIXES: &[(&str, f64)] = &[
+    ("da", 1e1),
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+    ("a", 1e-18),
+    ("z", 1e-21),
+    ("y", 1e-24),
+];
+
+/// Binary prefixes accepted by `CONVERT` for information units.
+const BINARY_PREFIXES: &[(&str, f64)] = &[
+    ("Yi", (1_u128 << 80) as f64),
+    ("Zi", (1_u128 << 70) as f64),
+    ("Ei", (1_u64 << 60) as f64),
+    ("Pi", (1_u64 << 50) as f64),
+    ("Ti", (1_u64 << 40) as f64),
+    ("Gi", (1_u64 << 30) as f64),
+    ("Mi", (1_u64 << 20) as f64),
+    ("ki", (1_u64 << 10) as f64),
+];
+
+/// Looks up a unit by name, returning the unit and the scale of its prefix.
+fn find_unit(name: &str) -> Option<(&'static Unit, f64)> {
+    let find_exact = |name| UNITS.iter().find(|unit| unit.names.contains(&name));
+
+    if let Some(unit) = find_exact(name) {
+        return Some((unit, 1.0));
+    }
+    BINARY_PREFIXES
+        .iter()
+        .chain(METRIC_PREFIXES)
+        .find_map(|&(prefix, scale)| {
+            let unit = find_exact(name.strip_prefix(prefix)?)?;
+            let is_binary = prefix.ends_with('i');
+            let accepts_prefix = match is_binary {
+                true => unit.quantity == Quantity::Information,
+                false => unit.prefix_power != 0,
+            };
+            accepts_prefix.then(|| (unit, scale.powi(unit.prefix_power)))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{formulas::tests::*, util::assert_f64_approx_eq};
+
+    #[test]
+    fn test_formula_number_bases() {
+        let g = Grid::new();
+
+        assert_eq!("10", eval_to_string(&g, "BIN2DEC(\"1010\")"));
+        assert_eq!("10", eval_to_string(&g, "BIN2DEC(1010)"));
+        assert_eq!("0", eval_to_string(&g, "BIN2DEC(\"\")"));
+        assert_eq!("511", eval_to_string(&g, "BIN2DEC(\"0111111111\")"));
+        assert_eq!("-512", eval_to_string(&g, "BIN2DEC(\"1000000000\")"));
+        assert_eq!("-1", eval_to_string(&g, "BIN2DEC(\"1111111111\")"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BIN2DEC(\"12\")");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BIN2DEC(\"10000000000\")");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BIN2DEC(\"-1\")");
+
+        assert_eq!("15", eval_to_string(&g, "OCT2DEC(17)"));
+        assert_eq!("-1", eval_to_string(&g, "OCT2DEC(\"7777777777\")"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "OCT2DEC(8)");
+
+        assert_eq!("255", eval_to_string(&g, "HEX2DEC(\"ff\")"));
+        assert_eq!(
+            "549755813887",
+            eval_to_string(&g, "HEX2DEC(\"7FFFFFFFFF\")"),
+        );
+        assert_eq!("-1", eval_to_string(&g, "HEX2DEC(\"FFFFFFFFFF\")"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "HEX2DEC(\"G\")");
+
+        assert_eq!("1010", eval_to_string(&g, "DEC2BIN(10.9)"));
+        assert_eq!("00001010", eval_to_string(&g, "DEC2BIN(10, 8)"));
+        assert_eq!("1111111111", eval_to_string(&g, "DEC2BIN(-1, 2)"));
+        assert_eq!("1000000000", eval_to_string(&g, "DEC2BIN(-512)"));
+        assert_eq!("{0, 1, 10}", eval_to_string(&g, "DEC2BIN({0, 1, 2})"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "DEC2BIN(512)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "DEC2BIN(-513)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "DEC2BIN(10, 3)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "DEC2BIN(10, 11)");
+
+        assert_eq!("FF", eval_to_string(&g, "DEC2HEX(255)"));
+        assert_eq!("00FF", eval_to_string(&g, "DEC2HEX(255, 4)"));
+        assert_eq!("FFFFFFFF9C", eval_to_string(&g, "DEC2HEX(-100)"));
+        assert_eq!("-100", eval_to_string(&g, "HEX2DEC(DEC2HEX(-100))"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "DEC2HEX(2^39)");
+
+        assert_eq!("FF", eval_to_string(&g, "BASE(255, 16)"));
+        assert_eq!("00000101", eval_to_string(&g, "BASE(5, 2, 8)"));
+        assert_eq!("ZZ", eval_to_string(&g, "BASE(1295, 36)"));
+        assert_eq!("0", eval_to_string(&g, "BASE(0, 2)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BASE(-1, 2)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BASE(10, 37)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BASE(10, 2, 256)");
+
+        assert_eq!("255", eval_to_string(&g, "DECIMAL(\"FF\", 16)"));
+        assert_eq!("1295", eval_to_string(&g, "DECIMAL(\"zz\", 36)"));
+        assert_eq!("5", eval_to_string(&g, "DECIMAL(101, 2)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "DECIMAL(\"2\", 2)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "DECIMAL(\"1\", 1)");
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "DECIMAL(\"ZZZZZZZZZZZZZZZZ\", 36)",
+        );
+    }
+
+    #[test]
+    fn test_formula_bitwise() {
+        let g = Grid::new();
+
+        assert_eq!("8", eval_to_string(&g, "BITAND(12, 10)"));
+        assert_eq!("14", eval_to_string(&g, "BITOR(12, 10)"));
+        assert_eq!("6", eval_to_string(&g, "BITXOR(12, 10)"));
+        assert_eq!("{0, 1, 0}", eval_to_string(&g, "BITAND({1, 3, 5}, 2) / 2"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BITAND(1.5, 1)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BITOR(-1, 1)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BITXOR(2^48, 1)");
+
+        assert_eq!("20", eval_to_string(&g, "BITLSHIFT(5, 2)"));
+        assert_eq!("1", eval_to_string(&g, "BITLSHIFT(5, -2)"));
+        assert_eq!("5", eval_to_string(&g, "BITRSHIFT(20, 2)"));
+        assert_eq!("80", eval_to_string(&g, "BITRSHIFT(20, -2)"));
+        assert_eq!("0", eval_to_string(&g, "BITRSHIFT(20, 53)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BITLSHIFT(1, 48)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "BITLSHIFT(1, 54)");
+    }
+
+    #[test]
+    fn test_formula_convert() {
+        let g = Grid::new();
+
+        assert_f64_approx_eq(1.609344, &eval_to_string(&g, "CONVERT(1, \"mi\", \"km\")"));
+        assert_f64_approx_eq(12.0, &eval_to_string(&g, "CONVERT(1, \"ft\", \"in\")"));
+        assert_f64_approx_eq(2.204623, &eval_to_string(&g, "CONVERT(1, \"kg\", \"lbm\")"));
+        assert_f64_approx_eq(1e6, &eval_to_string(&g, "CONVERT(1, \"km2\", \"m2\")"));
+        assert_f64_approx_eq(1000.0, &eval_to_string(&g, "CONVERT(1, \"m3\", \"l\")"));
+        assert_f64_approx_eq(10.0, &eval_to_string(&g, "CONVERT(1, \"dam\", \"m\")"));
+        assert_f64_approx_eq(
+            8192.0,
+            &eval_to_string(&g, "CONVERT(1, \"kibyte\", \"bit\")"),
+        );
+        assert_f64_approx_eq(
+            8000.0,
+            &eval_to_string(&g, "CONVERT(1, \"kbyte\", \"bit\")"),
+        );
+        assert_f64_approx_eq(86400.0, &eval_to_string(&g, "CONVERT(1, \"day\", \"sec\")"));
+        assert_f64_approx_eq(1.852, &eval_to_string(&g, "CONVERT(1, \"kn\", \"km/h\")"));
+
+        assert_f64_approx_eq(212.0, &eval_to_string(&g, "CONVERT(100, \"C\", \"F\")"));
+        assert_f64_approx_eq(-40.0, &eval_to_string(&g, "CONVERT(-40, \"F\", \"C\")"));
+        assert_f64_approx_eq(273.15, &eval_to_string(&g, "CONVERT(0, \"cel\", \"K\")"));
+        assert_f64_approx_eq(491.67, &eval_to_string(&g, "CONVERT(0, \"C\", \"Rank\")"));
+        assert_f64_approx_eq(80.0, &eval_to_string(&g, "CONVERT(100, \"C\", \"Reau\")"));
+
+        // unknown units and units of different quantities
+        expect_err(&ErrorMsg::NotAvailable, &g, "CONVERT(1, \"m\", \"kg\")");
+        expect_err(
+            &ErrorMsg::NotAvailable,
+            &g,
+            "CONVERT(1, \"furlong\", \"m\")",
+        );
+        expect_err(&ErrorMsg::NotAvailable, &g, "CONVERT(1, \"M\", \"m\")");
+        // only some units accept prefixes
+        expect_err(&ErrorMsg::NotAvailable, &g, "CONVERT(1, \"kft\", \"ft\")");
+        expect_err(&ErrorMsg::NotAvailable, &g, "CONVERT(1, \"kim\", \"m\")");
+    }
+}
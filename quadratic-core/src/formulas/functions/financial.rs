@@ -0,0 +1,688 @@
+use crate::Instant;
+
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Financial functions",
+    docs: "Money paid out is negative and money received is positive. For \
+           example, the payment on a loan that is received (positive present \
+           value) is negative.\
+           \n\n\
+           `rate` is the interest rate per period, so an annual rate of 6% \
+           paid monthly is `6% / 12` or `0.005`. If `due_at_start` is `TRUE`, \
+           then payments are due at the beginning of each period; otherwise \
+           they are due at the end.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns the payment per period for a loan or investment with
+            /// constant payments and a constant interest rate.
+            ///
+            /// - `nper` is the number of periods.
+            /// - `pv` is the present value.
+            /// - `fv` is the future value, which is 0 if omitted.
+            #[examples("PMT(5% / 12, 360, 200000)", "PMT(A1 / 12, A2 * 12, A3, 0, TRUE)")]
+            #[zip_map]
+            fn PMT(
+                span: Span,
+                [rate]: f64,
+                [nper]: f64,
+                [pv]: f64,
+                [fv]: (Option<f64>),
+                [due_at_start]: (Option<bool>),
+            ) {
+                let due_at_start = due_at_start.unwrap_or(false);
+                util::finite(*span, pmt(rate, nper, pv, fv.unwrap_or(0.0), due_at_start))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the present value of a loan or investment with constant
+            /// payments `pmt` and a constant interest rate.
+            ///
+            /// - `nper` is the number of periods.
+            /// - `fv` is the future value, which is 0 if omitted.
+            #[examples("PV(5% / 12, 360, -1073.64)", "PV(A1, A2, 0, 10000)")]
+            #[zip_map]
+            fn PV(
+                span: Span,
+                [rate]: f64,
+                [nper]: f64,
+                [pmt]: f64,
+                [fv]: (Option<f64>),
+                [due_at_start]: (Option<bool>),
+            ) {
+                let fv = fv.unwrap_or(0.0);
+                let due_at_start = due_at_start.unwrap_or(false);
+                let pv = if rate == 0.0 {
+                    -(fv + pmt * nper)
+                } else {
+                    let growth = (1.0 + rate).powf(nper);
+                    -(fv + pmt * annuity(rate, nper, due_at_start)) / growth
+                };
+                util::finite(*span, pv)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the future value of a loan or investment with constant
+            /// payments `pmt` and a constant interest rate.
+            ///
+            /// - `nper` is the number of periods.
+            /// - `pv` is the present value, which is 0 if omitted.
+            #[examples("FV(5% / 12, 120, -100)", "FV(A1, A2, -100, -1000, TRUE)")]
+            #[zip_map]
+            fn FV(
+                span: Span,
+                [rate]: f64,
+                [nper]: f64,
+                [pmt]: f64,
+                [pv]: (Option<f64>),
+                [due_at_start]: (Option<bool>),
+            ) {
+                let due_at_start = due_at_start.unwrap_or(false);
+                util::finite(*span, fv(rate, nper, pmt, pv.unwrap_or(0.0), due_at_start))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of periods for a loan or investment with
+            /// constant payments `pmt` and a constant interest rate.
+            ///
+            /// - `pv` is the present value.
+            /// - `fv` is the future value, which is 0 if omitted.
+            #[examples("NPER(5% / 12, -1000, 50000)")]
+            #[zip_map]
+            fn NPER(
+                span: Span,
+                [rate]: f64,
+                [pmt]: f64,
+                [pv]: f64,
+                [fv]: (Option<f64>),
+                [due_at_start]: (Option<bool>),
+            ) {
+                let fv = fv.unwrap_or(0.0);
+                let due_at_start = due_at_start.unwrap_or(false);
+                let nper = if rate == 0.0 {
+                    -(pv + fv) / pmt
+                } else {
+                    let pmt = pmt * if due_at_start { 1.0 + rate } else { 1.0 };
+                    ((pmt - fv * rate) / (pmt + pv * rate)).ln() / rate.ln_1p()
+                };
+                util::finite(*span, nper)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the interest rate per period of a loan or investment
+            /// with constant payments `pmt`.
+            ///
+            /// - `nper` is the number of periods.
+            /// - `pv` is the present value.
+            /// - `fv` is the future value, which is 0 if omitted.
+            /// - `guess` is a starting estimate for the rate, which is 10% if
+            ///   omitted.
+            ///
+            /// The rate is found by iteration, and an error is returned if it
+            /// cannot be found.
+            #[examples("RATE(360, -1073.64, 200000) * 12", "RATE(A1, A2, A3, 0, FALSE, 5%)")]
+            #[zip_map]
+            fn RATE(
+                span: Span,
+                [nper]: f64,
+                [pmt]: f64,
+                [pv]: f64,
+                [fv]: (Option<f64>),
+                [due_at_start]: (Option<bool>),
+                [guess]: (Option<f64>),
+            ) {
+                let fv = fv.unwrap_or(0.0);
+                let due_at_start = due_at_start.unwrap_or(false);
+                // Find the rate where the loan or investment is paid off.
+                solve_rate(*span, guess.unwrap_or(0.1), |rate| {
+                    fv - self::fv(rate, nper, pmt, pv, due_at_start)
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Returns the interest paid in period `per` of a loan or
+            /// investment with constant payments and a constant interest rate.
+            /// Periods start at 1.
+            ///
+            /// - `nper` is the number of periods.
+            /// - `pv` is the present value.
+            /// - `fv` is the future value, which is 0 if omitted.
+            #[examples("IPMT(5% / 12, 1, 360, 200000)")]
+            #[zip_map]
+            fn IPMT(
+                span: Span,
+                [rate]: f64,
+                [per]: (Spanned<f64>),
+                [nper]: f64,
+                [pv]: f64,
+                [fv]: (Option<f64>),
+                [due_at_start]: (Option<bool>),
+            ) {
+                let fv = fv.unwrap_or(0.0);
+                let due_at_start = due_at_start.unwrap_or(false);
+                check_period(per, nper)?;
+                util::finite(*span, ipmt(rate, per.inner, nper, pv, fv, due_at_start))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the payment on the principal in period `per` of a loan
+            /// or investment with constant payments and a constant interest
+            /// rate. Periods start at 1.
+            ///
+            /// - `nper` is the number of periods.
+            /// - `pv` is the present value.
+            /// - `fv` is the future value, which is 0 if omitted.
+            #[examples("PPMT(5% / 12, 1, 360, 200000)")]
+            #[zip_map]
+            fn PPMT(
+                span: Span,
+                [rate]: f64,
+                [per]: (Spanned<f64>),
+                [nper]: f64,
+                [pv]: f64,
+                [fv]: (Option<f64>),
+                [due_at_start]: (Option<bool>),
+            ) {
+                let fv = fv.unwrap_or(0.0);
+                let due_at_start = due_at_start.unwrap_or(false);
+                check_period(per, nper)?;
+                let pmt = pmt(rate, nper, pv, fv, due_at_start);
+                let ipmt = ipmt(rate, per.inner, nper, pv, fv, due_at_start);
+                util::finite(*span, pmt - ipmt)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the net present value of a series of cash flows at
+            /// regular intervals, given a discount `rate` per period.
+            ///
+            /// The first cash flow is discounted by one period, so it should
+            /// be at the end of the first period.
+            #[examples("NPV(8%, A1:A10)", "NPV(8%, -10000, 3000, 4200, 6800)")]
+            fn NPV(span: Span, rate: f64, values: (Iter<f64>)) {
+                let values: Vec<f64> = values.try_collect()?;
+                util::finite(span, npv(rate, &values))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the net present value of a series of cash flows at the
+            /// given `dates`, given an annual discount `rate`.
+            ///
+            /// Cash flows are discounted to the first date, using a 365-day
+            /// year.
+            #[examples("XNPV(9%, B1:B5, A1:A5)")]
+            fn XNPV(span: Span, rate: f64, values: (Spanned<Array>), dates: (Spanned<Array>)) {
+                let cash_flows = dated_cash_flows(&values, &dates)?;
+                util::finite(span, xnpv(rate, &cash_flows))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the internal rate of return of a series of cash flows
+            /// at regular intervals. This is the rate at which the net present
+            /// value of the cash flows is zero.
+            ///
+            /// `guess` is a starting estimate for the rate, which is 10% if
+            /// omitted. The rate is found by iteration, and an error is
+            /// returned if it cannot be found.
+            #[examples("IRR(A1:A10)", "IRR({-10000, 3000, 4200, 6800})")]
+            fn IRR(span: Span, values: (Spanned<Value>), guess: (Option<f64>)) {
+                let values: Vec<f64> = values.into_iter::<f64>().without_spans().try_collect()?;
+                solve_rate(span, guess.unwrap_or(0.1), |rate| npv(rate, &values))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the annual internal rate of return of a series of cash
+            /// flows at the given `dates`. This is the rate at which the
+            /// result of `XNPV` is zero.
+            ///
+            /// `guess` is a starting estimate for the rate, which is 10% if
+            /// omitted. The rate is found by iteration, and an error is
+            /// returned if it cannot be found.
+            #[examples("XIRR(B1:B5, A1:A5)")]
+            fn XIRR(
+                span: Span,
+                values: (Spanned<Array>),
+                dates: (Spanned<Array>),
+                guess: (Option<f64>),
+            ) {
+                let cash_flows = dated_cash_flows(&values, &dates)?;
+                solve_rate(span, guess.unwrap_or(0.1), |rate| xnpv(rate, &cash_flows))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the depreciation of an asset for one period using the
+            /// straight-line method.
+            ///
+            /// - `cost` is the initial cost of the asset.
+            /// - `salvage` is the value at the end of its life.
+            /// - `life` is the number of periods over which it depreciates.
+            #[examples("SLN(30000, 7500, 10)")]
+            #[zip_map]
+            fn SLN(span: Span, [cost]: f64, [salvage]: f64, [life]: f64) {
+                util::checked_div(*span, cost - salvage, life)
+            }
+        ),
+        formula_fn!(
+            /// Returns the depreciation of an asset in period `period` using
+            /// the fixed-declining balance method. Periods start at 1.
+            ///
+            /// - `cost` is the initial cost of the asset.
+            /// - `salvage` is the value at the end of its life.
+            /// - `life` is the number of periods over which it depreciates.
+            /// - `month` is the number of months in the first year, which is
+            ///   12 if omitted. If it is less than 12, then there is an extra
+            ///   period at the end for the remaining months.
+            #[examples("DB(1000000, 100000, 6, 1, 7)")]
+            #[zip_map]
+            fn DB(
+                span: Span,
+                [cost]: f64,
+                [salvage]: f64,
+                [life]: i64,
+                [period]: i64,
+                [month]: (Option<i64>),
+            ) {
+                let month = month.unwrap_or(12);
+                let last_period = if month < 12 {
+                    life.checked_add(1)
+                } else {
+                    Some(life)
+                };
+                if cost < 0.0
+                    || salvage < 0.0
+                    || life < 1
+                    || !(1..=12).contains(&month)
+                    || !last_period.is_some_and(|last| (1..=last).contains(&period))
+                {
+                    return Err(ErrorMsg::InvalidArgument.with_span(*span));
+                }
+
+                // The rate is rounded to three decimal places.
+                let rate = if cost == 0.0 {
+                    0.0
+                } else {
+                    let rate = 1.0 - (salvage / cost).powf(1.0 / life as f64);
+                    (rate * 1000.0).round() / 1000.0
+                };
+                let first_year_fraction = month as f64 / 12.0;
+
+                // Each period after the first depreciates by `rate` of the
+                // value remaining at the start of that period.
+                let depreciation = if period == 1 {
+                    cost * rate * first_year_fraction
+                } else {
+                    let remaining = cost
+                        * (1.0 - rate * first_year_fraction)
+                        * (1.0 - rate).powf((period - 2) as f64);
+                    match period > life {
+                        true => remaining * rate * (1.0 - first_year_fraction),
+                        false => remaining * rate,
+                    }
+                };
+                util::finite(*span, depreciation)?
+            }
+        ),
+    ]
+}
+
+/// Returns an error if `per` is not between 1 and `nper`.
+fn check_period(per: Spanned<f64>, nper: f64) -> CodeResult<()> {
+    if 1.0 <= per.inner && per.inner <= nper {
+        Ok(())
+    } else {
+        Err(ErrorMsg::InvalidArgument.with_span(per.span))
+    }
+}
+
+/// Returns the future value of a payment of 1 per period. `rate` must not be
+/// zero.
+fn annuity(rate: f64, nper: f64, due_at_start: bool) -> f64 {
+    let value = ((1.0 + rate).powf(nper) - 1.0) / rate;
+    if due_at_start {
+        value * (1.0 + rate)
+    } else {
+        value
+    }
+}
+
+fn pmt(rate: f64, nper: f64, pv: f64, fv: f64, due_at_start: bool) -> f64 {
+    if rate == 0.0 {
+        -(pv + fv) / nper
+    } else {
+        -(pv * (1.0 + rate).powf(nper) + fv) / annuity(rate, nper, due_at_start)
+    }
+}
+
+fn fv(rate: f64, nper: f64, pmt: f64, pv: f64, due_at_start: bool) -> f64 {
+    if rate == 0.0 {
+        -(pv + pmt * nper)
+    } else {
+        -(pv * (1.0 + rate).powf(nper) + pmt * annuity(rate, nper, due_at_start))
+    }
+}
+
+fn ipmt(rate: f64, per: f64, nper: f64, pv: f64, fv: f64, due_at_start: bool) -> f64 {
+    let pmt = pmt(rate, nper, pv, fv, due_at_start);
+    if due_at_start && per == 1.0 {
+        // The first payment is made before any interest accrues.
+        0.0
+    } else if due_at_start {
+        (self::fv(rate, per - 2.0, pmt, pv, due_at_start) - pmt) * rate
+    } else {
+        // Interest on the balance at the start of the period.
+        self::fv(rate, per - 1.0, pmt, pv, due_at_start) * rate
+    }
+}
+
+/// Returns the net present value of cash flows at the end of each period.
+fn npv(rate: f64, values: &[f64]) -> f64 {
+    std::iter::successors(Some(1.0 + rate), |factor| Some(factor * (1.0 + rate)))
+        .zip(values)
+        .map(|(factor, value)| value / factor)
+        .sum()
+}
+
+/// Returns the net present value of cash flows, where each cash flow is a pair
+/// of the number of years since the first cash flow and the amount.
+fn xnpv(rate: f64, cash_flows: &[(f64, f64)]) -> f64 {
+    cash_flows
+        .iter()
+        .map(|(years, value)| value / (1.0 + rate).powf(*years))
+        .sum()
+}
+
+/// Returns pairs of the number of years since the first date and the amount of
+/// each cash flow.
+fn dated_cash_flows(
+    values: &Spanned<Array>,
+    dates: &Spanned<Array>,
+) -> CodeResult<Vec<(f64, f64)>> {
+    dates.check_array_size_exact(values.inner.size())?;
+
+    let dates: Vec<Instant> = dates
+        .inner
+        .cell_values_slice()
+        .iter()
+        .map(|date| {
+            Spanned {
+                span: dates.span,
+                inner: date,
+            }
+            .try_coerce::<Instant>()
+            .map(|date| date.inner)
+        })
+        .try_collect()?;
+    let values: Vec<f64> = values
+        .inner
+        .cell_values_slice()
+        .iter()
+        .map(|value| {
+            Spanned {
+                span: values.span,
+                inner: value,
+            }
+            .try_coerce::<f64>()
+            .map(|value| value.inner)
+        })
+        .try_collect()?;
+
+    const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 60.0 * 60.0;
+    let first_date = dates[0];
+    Ok(std::iter::zip(dates, values)
+        .map(|(date, value)| {
+            let years = date.duration_since(first_date).seconds / SECONDS_PER_YEAR;
+            (years, value)
+        })
+        .collect())
+}
+
+/// Finds a rate greater than -100% at which `f` is zero, or returns an error
+/// if there is none.
+///
+/// Newton's method is tried first, starting from `guess`. If that does not
+/// converge, then bisection is used on the first interval found where `f`
+/// changes sign.
+fn solve_rate(span: Span, guess: f64, f: impl Fn(f64) -> f64) -> CodeResult<f64> {
+    const MAX_ITERATIONS: usize = 100;
+    const TOLERANCE: f64 = 1e-10;
+
+    let mut rate = guess;
+    for _ in 0..MAX_ITERATIONS {
+        let h = 1e-6 * rate.abs().max(1.0);
+        let slope = (f(rate + h) - f(rate - h)) / (2.0 * h);
+        let step = f(rate) / slope;
+        rate -= step;
+        if !rate.is_finite() || rate <= -1.0 {
+            break;
+        }
+        if step.abs() < TOLERANCE * rate.abs().max(1.0) {
+            return Ok(rate);
+        }
+    }
+
+    const BRACKETS: &[f64] = &[
+        -0.999, -0.99, -0.9, -0.5, -0.2, 0.0, 0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0, 100.0, 1000.0,
+    ];
+    for (&lo, &hi) in BRACKETS.iter().tuple_windows() {
+        let (mut lo, mut hi) = (lo, hi);
+        let (f_lo, f_hi) = (f(lo), f(hi));
+        if !(f_lo.is_finite() && f_hi.is_finite()) || f_lo.signum() == f_hi.signum() {
+            continue;
+        }
+        let lo_sign = f_lo.signum();
+        for _ in 0..MAX_ITERATIONS {
+            let mid = (lo + hi) / 2.0;
+            if f(mid).signum() == lo_sign {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+            if hi - lo < TOLERANCE * mid.abs().max(1.0) {
+                return Ok(mid);
+            }
+        }
+    }
+
+    Err(ErrorMsg::NoConvergence.with_span(span))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{formulas::tests::*, util::assert_f64_approx_eq};
+
+    #[test]
+    fn test_formula_pmt_pv_fv() {
+        let g = Grid::new();
+
+        assert_f64_approx_eq(
+            -1073.643246,
+            &eval_to_string(&g, "PMT(5% / 12, 360, 200000)"),
+        );
+        assert_f64_approx_eq(
+            -1069.188295,
+            &eval_to_string(&g, "PMT(5% / 12, 360, 200000, 0, TRUE)"),
+        );
+        assert_f64_approx_eq(-100.0, &eval_to_string(&g, "PMT(0, 10, 1000)"));
+        assert_f64_approx_eq(
+            -129.081161,
+            &eval_to_string(&g, "PMT(6% / 12, 18 * 12, 0, 50000)"),
+        );
+
+        assert_f64_approx_eq(
+            200000.0,
+            &eval_to_string(&g, "PV(5% / 12, 360, -1073.643246)"),
+        );
+        assert_f64_approx_eq(-2000.0, &eval_to_string(&g, "PV(0, 10, 100, 1000)"));
+        assert_f64_approx_eq(
+            -59777.145851,
+            &eval_to_string(&g, "PV(8% / 12, 12 * 20, 500)"),
+        );
+
+        assert_f64_approx_eq(
+            2581.403374,
+            &eval_to_string(&g, "FV(6% / 12, 10, -200, -500, TRUE)"),
+        );
+        assert_f64_approx_eq(15528.227945, &eval_to_string(&g, "FV(5% / 12, 120, -100)"));
+        assert_f64_approx_eq(1500.0, &eval_to_string(&g, "FV(0, 10, -100, -500)"));
+        expect_err(&ErrorMsg::Overflow, &g, "FV(1, 2000, -100, -500)");
+
+        assert_eq!("{-100, -50}", eval_to_string(&g, "PMT(0, {10, 20}, 1000)"));
+    }
+
+    #[test]
+    fn test_formula_nper_rate() {
+        let g = Grid::new();
+
+        assert_f64_approx_eq(
+            59.673866,
+            &eval_to_string(&g, "NPER(12% / 12, -100, -1000, 10000, TRUE)"),
+        );
+        assert_f64_approx_eq(10.0, &eval_to_string(&g, "NPER(0, -100, 1000)"));
+        assert_f64_approx_eq(
+            360.0,
+            &eval_to_string(&g, "NPER(5% / 12, -1073.643246, 200000)"),
+        );
+
+        assert_f64_approx_eq(
+            0.05,
+            &eval_to_string(&g, "RATE(360, -1073.643246, 200000) * 12"),
+        );
+        assert_f64_approx_eq(
+            0.0077014724,
+            &eval_to_string(&g, "RATE(4 * 12, -200, 8000)"),
+        );
+        assert_f64_approx_eq(0.0, &eval_to_string(&g, "RATE(10, -100, 1000)"));
+
+        expect_err(&ErrorMsg::NoConvergence, &g, "RATE(10, 100, 1000)");
+    }
+
+    #[test]
+    fn test_formula_ipmt_ppmt() {
+        let g = Grid::new();
+
+        assert_f64_approx_eq(
+            -66.666667,
+            &eval_to_string(&g, "IPMT(10% / 12, 1, 3 * 12, 8000)"),
+        );
+        assert_f64_approx_eq(-292.447133, &eval_to_string(&g, "IPMT(10%, 3, 3, 8000)"));
+        assert_f64_approx_eq(0.0, &eval_to_string(&g, "IPMT(10%, 1, 3, 8000, 0, TRUE)"));
+        assert_f64_approx_eq(
+            -75.623186,
+            &eval_to_string(&g, "PPMT(10% / 12, 1, 2 * 12, 2000)"),
+        );
+        assert_f64_approx_eq(
+            -27598.053550,
+            &eval_to_string(&g, "PPMT(8%, 10, 10, 200000)"),
+        );
+
+        // Interest and principal add up to the payment in every period.
+        assert_eq!(
+            "TRUE",
+            eval_to_string(
+                &g,
+                "ABS(SUM(IPMT(1%, {1, 2, 3, 4, 5, 6}, 6, 1000, 0, TRUE) + PPMT(1%, {1, 2, 3, 4, 5, 6}, 6, 1000, 0, TRUE)) - 6 * PMT(1%, 6, 1000, 0, TRUE)) < 0.000001",
+            ),
+        );
+        assert_eq!(
+            "TRUE",
+            eval_to_string(
+                &g,
+                "ABS(SUM(PPMT(1%, {1, 2, 3, 4, 5, 6}, 6, 1000, 0, TRUE)) + 1000) < 0.000001"
+            ),
+        );
+
+        expect_err(&ErrorMsg::InvalidArgument, &g, "IPMT(10%, 0, 3, 8000)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "PPMT(10%, 4, 3, 8000)");
+    }
+
+    #[test]
+    fn test_formula_npv_irr() {
+        let g = Grid::new();
+
+        assert_f64_approx_eq(
+            1188.443412,
+            &eval_to_string(&g, "NPV(10%, -10000, 3000, 4200, 6800)"),
+        );
+        assert_f64_approx_eq(
+            1922.061555,
+            &eval_to_string(&g, "NPV(8%, {8000, 9200, 10000, 12000, 14500}) - 40000"),
+        );
+
+        assert_f64_approx_eq(
+            -0.021244848,
+            &eval_to_string(&g, "IRR({-70000, 12000, 15000, 18000, 21000})"),
+        );
+        assert_f64_approx_eq(
+            0.086630948,
+            &eval_to_string(&g, "IRR({-70000, 12000, 15000, 18000, 21000, 26000})"),
+        );
+        assert_f64_approx_eq(
+            -0.443506941,
+            &eval_to_string(&g, "IRR({-70000, 12000, 15000}, -10%)"),
+        );
+        assert_f64_approx_eq(
+            0.0,
+            &eval_to_string(&g, "NPV(IRR({-100, 30, 40, 50}), {-100, 30, 40, 50})"),
+        );
+
+        expect_err(&ErrorMsg::NoConvergence, &g, "IRR({100, 200, 300})");
+    }
+
+    #[test]
+    fn test_formula_xnpv_xirr() {
+        let g = grid_with_rows(&[
+            &["2008-01-01", "-10000"],
+            &["2008-03-01", "2750"],
+            &["2008-10-30", "4250"],
+            &["2009-02-15", "3250"],
+            &["2009-04-01", "2750"],
+        ]);
+
+        assert_f64_approx_eq(2086.647602, &eval_to_string(&g, "XNPV(9%, B1:B5, A1:A5)"));
+        assert_f64_approx_eq(0.373362535, &eval_to_string(&g, "XIRR(B1:B5, A1:A5)"));
+        assert_f64_approx_eq(
+            0.0,
+            &eval_to_string(&g, "XNPV(XIRR(B1:B5, A1:A5), B1:B5, A1:A5)"),
+        );
+
+        assert_eq!(
+            ErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 5).unwrap(),
+                got: ArraySize::new(1, 4).unwrap(),
+            },
+            eval_to_err(&g, "XNPV(9%, B1:B5, A1:A4)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_depreciation() {
+        let g = Grid::new();
+
+        assert_eq!("2250", eval_to_string(&g, "SLN(30000, 7500, 10)"));
+        expect_err(&ErrorMsg::DivideByZero, &g, "SLN(30000, 7500, 0)");
+
+        let expected = [
+            186083.33, 259639.42, 176814.44, 120410.64, 81999.64, 55841.76, 15845.10,
+        ];
+        for (i, expected) in expected.into_iter().enumerate() {
+            let formula = format!("DB(1000000, 100000, 6, {}, 7)", i + 1);
+            let actual = eval_to_string(&g, &formula).parse::<f64>().unwrap();
+            assert!((expected - actual).abs() < 0.01, "{formula} = {actual}");
+        }
+        assert_f64_approx_eq(319000.0, &eval_to_string(&g, "DB(1000000, 100000, 6, 1)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "DB(1000000, 100000, 6, 7)");
+        assert_eq!("0", eval_to_string(&g, "DB(1, 0, 1e300, 1e300)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "DB(1, 0, 1e300, 1e300, 6)");
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "DB(1000000, 100000, 6, 1, 13)",
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Information functions",
+    docs: "",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        formula_fn!(
+            /// Returns `TRUE` if `value` is an error, and `FALSE` otherwise.
+            #[examples("ISERROR(A1 / B1)")]
+            #[zip_map]
+            fn ISERROR([value]: CellValue) {
+                matches!(value, CellValue::Error(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is a "value not available" error, and
+            /// `FALSE` otherwise.
+            ///
+            /// Lookup functions such as `XLOOKUP` return this error when no
+            /// match is found, as does `NA`.
+            #[examples("ISNA(XLOOKUP(5, A1:A10, B1:B10))")]
+            #[zip_map]
+            fn ISNA([value]: CellValue) {
+                matches!(value, CellValue::Error(e) if e.msg.is_not_available())
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is blank, and `FALSE` otherwise.
+            ///
+            /// The empty string is not blank.
+            #[examples("ISBLANK(A1)")]
+            #[zip_map]
+            fn ISBLANK([value]: CellValue) {
+                value.is_blank()
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is a number, and `FALSE` otherwise.
+            ///
+            /// Dates, times, and durations are not numbers.
+            #[examples("ISNUMBER(A1)")]
+            #[zip_map]
+            fn ISNUMBER([value]: CellValue) {
+                matches!(value, CellValue::Number(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is text, and `FALSE` otherwise.
+            #[examples("ISTEXT(A1)")]
+            #[zip_map]
+            fn ISTEXT([value]: CellValue) {
+                matches!(value, CellValue::Text(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `value` is `TRUE` or `FALSE`, and `FALSE`
+            /// otherwise.
+            #[examples("ISLOGICAL(A1)")]
+            #[zip_map]
+            fn ISLOGICAL([value]: CellValue) {
+                matches!(value, CellValue::Logical(_))
+            }
+        ),
+        formula_fn!(
+            /// Returns a number identifying the kind of error in `error`, or a
+            /// "value not available" error if `error` is not an error.
+            ///
+            /// | Code | Kind of error                             |
+            /// | ---: | :---------------------------------------- |
+            /// |    2 | Divide by zero                            |
+            /// |    3 | Invalid value (used for most errors)      |
+            /// |    4 | Invalid cell reference or index           |
+            /// |    5 | Unknown function or other name            |
+            /// |    6 | Invalid number, such as infinity or `NaN` |
+            /// |    7 | Value not available, such as no match     |
+            /// |    9 | Spill error                               |
+            #[examples("ERROR.TYPE(A1 / B1)", "IF(ISERROR(A1), ERROR.TYPE(A1), 0)")]
+            #[zip_map]
+            fn "ERROR.TYPE"(span: Span, [error]: CellValue) {
+                match error {
+                    CellValue::Error(e) => error_type_code(&e.msg),
+                    _ => return Err(ErrorMsg::NotAvailable.with_span(*span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns a "value not available" error.
+            ///
+            /// This is useful for marking values that are missing.
+            #[include_args_in_completion(false)]
+            #[examples("NA()", "IF(A1 = \"\", NA(), A1)")]
+            fn NA(span: Span) {
+                CellValue::Error(Box::new(ErrorMsg::NotAvailable.with_span(span)))
+            }
+        ),
+        formula_fn!(
+            /// Returns a number identifying the type of `value`.
+            ///
+            /// - 1 = number, date, time, duration, or blank
+            /// - 2 = text
+            /// - 4 = logical (`TRUE` or `FALSE`)
+            /// - 16 = error
+            /// - 64 = array with more than one value
+            #[examples("TYPE(A1)", "TYPE(A1:A10)")]
+            fn TYPE(value: Value) {
+                match &value {
+                    Value::Array(a) if a.size().len() > 1 => 64,
+                    _ => match value.into_cell_value()? {
+                        CellValue::Blank
+                        | CellValue::Number(_)
+                        | CellValue::Instant(_)
+                        | CellValue::Duration(_) => 1,
+                        CellValue::Text(_) => 2,
+                        CellValue::Logical(_) => 4,
+                        CellValue::Error(_) => 16,
+                    },
+                }
+            }
+        ),
+    ]
+}
+
+/// Returns the number used by `ERROR.TYPE` to identify a kind of error. These
+/// numbers match the error codes used by Excel.
+fn error_type_code(error: &ErrorMsg) -> u32 {
+    match error {
+        ErrorMsg::DivideByZero => 2,
+        ErrorMsg::BadCellReference | ErrorMsg::CircularReference | ErrorMsg::IndexOutOfBounds => 4,
+        ErrorMsg::Overflow
+        | ErrorMsg::NegativeExponent
+        | ErrorMsg::NotANumber
+        | ErrorMsg::Infinity
+        | ErrorMsg::NoConvergence
+        | ErrorMsg::RecursionLimit => 6,
+        ErrorMsg::NoMatch | ErrorMsg::NotAvailable => 7,
+        ErrorMsg::Spill => 9,
+        _ => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formulas::tests::*;
+
+    #[test]
+    fn test_formula_is_functions() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        sheet.set_cell_value(pos![A1], 10);
+        sheet.set_cell_value(pos![A2], "hello");
+        sheet.set_cell_value(pos![A3], true);
+        sheet.set_cell_value(pos![A4], "");
+
+        let check = |func: &str, expected: &str| {
+            let formula = format!("{func}({{A1; A2; A3; A4; A5; 1/0; NA()}})");
+            assert_eq!(expected, eval_to_string(&g, &formula), "{formula}");
+        };
+        check("ISERROR", "{FALSE; FALSE; FALSE; FALSE; FALSE; TRUE; TRUE}");
+        check("ISNA", "{FALSE; FALSE; FALSE; FALSE; FALSE; FALSE; TRUE}");
+        check(
+            "ISBLANK",
+            "{FALSE; FALSE; FALSE; FALSE; TRUE; FALSE; FALSE}",
+        );
+        check(
+            "ISNUMBER",
+            "{TRUE; FALSE; FALSE; FALSE; FALSE; FALSE; FALSE}",
+        );
+        check("ISTEXT", "{FALSE; TRUE; FALSE; TRUE; FALSE; FALSE; FALSE}");
+        check(
+            "ISLOGICAL",
+            "{FALSE; FALSE; TRUE; FALSE; FALSE; FALSE; FALSE}",
+        );
+
+        assert_eq!("TRUE", eval_to_string(&g, "ISBLANK(A5)"));
+        assert_eq!("TRUE", eval_to_string(&g, "ISERROR(SQRT(\"x\"))"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISERROR(SQRT(4))"));
+        expect_err(&ErrorMsg::BadFunctionName, &g, "ISERROR(NOSUCHFUNCTION())");
+
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, "ISNA(XLOOKUP(5, {1, 2}, {3, 4}))")
+        );
+        assert_eq!("TRUE", eval_to_string(&g, "ISNA(MATCH(5, {1, 2}, 0))"));
+        assert_eq!("FALSE", eval_to_string(&g, "ISNA(1 / 0)"));
+    }
+
+    #[test]
+    fn test_formula_error_type() {
+        let g = Grid::new();
+
+        assert_eq!("2", eval_to_string(&g, "ERROR.TYPE(1 / 0)"));
+        expect_err(
+            &ErrorMsg::BadFunctionName,
+            &g,
+            "ERROR.TYPE(NOSUCHFUNCTION())",
+        );
+        assert_eq!("7", eval_to_string(&g, "ERROR.TYPE(NA())"));
+        assert_eq!("7", eval_to_string(&g, "error.type(XLOOKUP(5, {1}, {2}))"));
+        assert_eq!("3", eval_to_string(&g, "ERROR.TYPE(\"a\" + 1)"));
+        assert_eq!("{2, 7}", eval_to_string(&g, "ERROR.TYPE({1 / 0, NA()})"));
+        expect_err(&ErrorMsg::NotAvailable, &g, "ERROR.TYPE(1)");
+        expect_err(&ErrorMsg::NotAvailable, &g, "NA()");
+    }
+
+    #[test]
+    fn test_formula_type() {
+        let mut g = Grid::new();
+        g.sheets_mut()[0].set_cell_value(pos![A1], "hello");
+
+        assert_eq!("1", eval_to_string(&g, "TYPE(3)"));
+        assert_eq!("1", eval_to_string(&g, "TYPE(A2)"));
+        assert_eq!("1", eval_to_string(&g, "TYPE(DATE(2024, 1, 1))"));
+        assert_eq!("2", eval_to_string(&g, "TYPE(A1)"));
+        assert_eq!("4", eval_to_string(&g, "TYPE(FALSE)"));
+        assert_eq!("16", eval_to_string(&g, "TYPE(1 / 0)"));
+        assert_eq!("64", eval_to_string(&g, "TYPE(A1:A2)"));
+        assert_eq!("64", eval_to_string(&g, "TYPE({1, 2})"));
+    }
+}
@@ -0,0 +1,498 @@
+use super::*;
+
+pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
+    include_in_docs: true,
+    include_in_completions: true,
+    name: "Lambda functions",
+    docs: "`LET` gives names to values so that they can be reused, and \
+           `LAMBDA` defines a function that can be given a name using `LET` \
+           or passed to functions such as `MAP`.\
+           \n\n\
+           Names are case-insensitive. They may contain letters, digits, and \
+           underscores, but must not start with a digit or look like a cell \
+           reference such as `A1`.\
+           \n\n",
+    get_functions,
+};
+
+fn get_functions() -> Vec<FormulaFunction> {
+    vec![
+        // `LET` and `LAMBDA` are evaluated by the AST because their arguments
+        // are names and expressions rather than values.
+        FormulaFunction {
+            name: "LET",
+            arg_completion: Some("${1:name1}, ${2:value1}, ${3:calculation}"),
+            usage: "name1, value1, [name2, value2, ...], calculation",
+            examples: &[
+                "LET(x, A1 * 2, x + 1)",
+                "LET(total, SUM(A1:A10), count, COUNT(A1:A10), total / count)",
+            ],
+            doc: "Gives the name `name1` to `value1`, `name2` to `value2`, \
+                  etc., and then returns `calculation`, which may use those \
+                  names. Each value may use the names given before it.",
+            volatile: false,
+            eval: |_, _| internal_error!("LET should be evaluated by the AST"),
+        },
+        FormulaFunction {
+            name: "LAMBDA",
+            arg_completion: Some("${1:parameter}, ${2:calculation}"),
+            usage: "[parameters...], calculation",
+            examples: &[
+                "LAMBDA(x, x * 2)",
+                "LET(hypot, LAMBDA(a, b, SQRT(a^2 + b^2)), hypot(3, 4))",
+            ],
+            doc: "Returns a function that takes the named `parameters` and \
+                  returns `calculation`, which may use those names.\n\n\
+                  The function can be called by giving it a name using `LET`, \
+                  or by passing it to a function such as `MAP`.",
+            volatile: false,
+            eval: |_, _| internal_error!("LAMBDA should be evaluated by the AST"),
+        },
+        formula_fn!(
+            /// Calls `lambda` with each value in `array` and returns an array
+            /// of the results.
+            ///
+            /// If more arrays are given before `lambda`, then `lambda` is
+            /// called with the corresponding value from each array. Arrays are
+            /// expanded to the same size as with operators such as `+`.
+            #[examples(
+                "MAP(A1:A10, LAMBDA(x, x * 2))",
+                "MAP(A1:A10, B1:B10, LAMBDA(a, b, MAX(a, b)))"
+            )]
+            fn MAP(
+                ctx: Ctx,
+                span: Span,
+                array: (Spanned<Value>),
+                arrays_and_lambda: (Iter<Spanned<ArgValue>>),
+            ) {
+                let mut args = arrays_and_lambda.collect::<CodeResult<Vec<_>>>()?;
+                let lambda = match args.pop() {
+                    Some(lambda) => lambda.into_lambda()?,
+                    None => return Err(util::missing_arg_error("MAP", "lambda", span)),
+                };
+                let mut arrays = vec![array];
+                for arg in args {
+                    arrays.push(arg.into_value(ctx)?);
+                }
+                ctx.zip_map(&arrays, |ctx, values| {
+                    let args = values.iter().map(|v| v.map(|v| Value::from(v.clone())));
+                    Ok(call_for_cell_value(ctx, &lambda, args, span))
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Calls `lambda` with an accumulator and each value in `array`,
+            /// and returns the final value of the accumulator.
+            ///
+            /// The accumulator starts as `initial_value`, and after each call
+            /// it is replaced by the result of `lambda`.
+            #[examples(
+                "REDUCE(0, A1:A10, LAMBDA(total, x, total + x))",
+                "REDUCE(1, {1, 2, 3, 4}, LAMBDA(product, x, product * x))"
+            )]
+            fn REDUCE(
+                ctx: Ctx,
+                span: Span,
+                initial_value: (Spanned<Value>),
+                array: (Spanned<Array>),
+                lambda: (Spanned<ArgValue>),
+            ) {
+                let lambda = lambda.into_lambda()?;
+                let mut accumulator = initial_value;
+                for value in array.inner.cell_values_slice() {
+                    let value = Spanned {
+                        span: array.span,
+                        inner: Value::from(value.clone()),
+                    };
+                    accumulator = lambda.call_with_values(ctx, [accumulator, value], span)?;
+                }
+                accumulator.inner
+            }
+        ),
+        formula_fn!(
+            /// Calls `lambda` with an accumulator and each value in `array`,
+            /// like `REDUCE`, and returns an array of the same size as `array`
+            /// containing each intermediate value of the accumulator.
+            #[examples(
+                "SCAN(0, A1:A10, LAMBDA(total, x, total + x))",
+                "SCAN(\"\", {\"a\", \"b\", \"c\"}, LAMBDA(s, x, s & x))"
+            )]
+            fn SCAN(
+                ctx: Ctx,
+                span: Span,
+                initial_value: (Spanned<Value>),
+                array: (Spanned<Array>),
+                lambda: (Spanned<ArgValue>),
+            ) {
+                let lambda = lambda.into_lambda()?;
+                let mut accumulator = initial_value;
+                let values = array
+                    .inner
+                    .cell_values_slice()
+                    .iter()
+                    .map(|value| {
+                        let value = Spanned {
+                            span: array.span,
+                            inner: Value::from(value.clone()),
+                        };
+                        let args = [accumulator.clone(), value];
+                        let result = call_for_cell_value(ctx, &lambda, args, span);
+                        accumulator = Spanned {
+                            span,
+                            inner: Value::from(result.clone()),
+                        };
+                        result
+                    })
+                    .collect();
+                Array::new_row_major(array.inner.size(), values)?
+            }
+        ),
+        formula_fn!(
+            /// Calls `lambda` with each row of `array` and returns a column of
+            /// the results.
+            #[examples("BYROW(A1:C10, LAMBDA(row, SUM(row)))")]
+            fn BYROW(ctx: Ctx, span: Span, array: (Spanned<Array>), lambda: (Spanned<ArgValue>)) {
+                let lambda = lambda.into_lambda()?;
+                let values = array
+                    .inner
+                    .rows()
+                    .map(|row| {
+                        let row = Spanned {
+                            span: array.span,
+                            inner: Value::from(Array::from(vec![row.to_vec()])),
+                        };
+                        call_for_cell_value(ctx, &lambda, [row], span)
+                    })
+                    .collect();
+                let size = ArraySize::new_or_err(1, array.inner.height())?;
+                Array::new_row_major(size, values)?
+            }
+        ),
+        formula_fn!(
+            /// Calls `lambda` with each column of `array` and returns a row of
+            /// the results.
+            #[examples("BYCOL(A1:C10, LAMBDA(column, MAX(column)))")]
+            fn BYCOL(ctx: Ctx, span: Span, array: (Spanned<Array>), lambda: (Spanned<ArgValue>)) {
+                let lambda = lambda.into_lambda()?;
+                let values = (0..array.inner.width())
+                    .map(|x| {
+                        let column = array.inner.line(Axis::X, x).cloned().collect_vec();
+                        let column = Spanned {
+                            span: array.span,
+                            inner: Value::from(Array::from(vec![column]).transpose()),
+                        };
+                        call_for_cell_value(ctx, &lambda, [column], span)
+                    })
+                    .collect();
+                let size = ArraySize::new_or_err(array.inner.width(), 1)?;
+                Array::new_row_major(size, values)?
+            }
+        ),
+        formula_fn!(
+            /// Returns an array with the given number of `rows` and `columns`,
+            /// where each value is the result of calling `lambda` with the
+            /// row and column number of that value. Row and column numbers
+            /// start at 1.
+            #[examples(
+                "MAKEARRAY(3, 3, LAMBDA(row, column, row * column))",
+                "MAKEARRAY(5, 1, LAMBDA(row, column, row ^ 2))"
+            )]
+            fn MAKEARRAY(
+                ctx: Ctx,
+                span: Span,
+                rows: (Spanned<i64>),
+                columns: (Spanned<i64>),
+                lambda: (Spanned<ArgValue>),
+            ) {
+                let rows = util::positive_len(rows)?;
+                let columns = util::positive_len(columns)?;
+                let lambda = lambda.into_lambda()?;
+                let size = util::array_size(span, columns, rows)?;
+                let values = size
+                    .iter()
+                    .map(|(x, y)| {
+                        let args = [y + 1, x + 1].map(|i| Spanned {
+                            span,
+                            inner: Value::from(i),
+                        });
+                        call_for_cell_value(ctx, &lambda, args, span)
+                    })
+                    .collect();
+                Array::new_row_major(size, values)?
+            }
+        ),
+    ]
+}
+
+/// Calls `lambda` with `args` and returns the result as a single value. Errors
+/// are returned as error values so that they do not stop evaluation of the
+/// rest of an array.
+fn call_for_cell_value(
+    ctx: &mut Ctx<'_>,
+    lambda: &Lambda,
+    args: impl IntoIterator<Item = Spanned<Value>>,
+    span: Span,
+) -> CellValue {
+    lambda
+        .call_with_values(ctx, args, span)
+        .and_then(|value| value.into_cell_value())
+        .map_or_else(|e| CellValue::Error(Box::new(e)), |value| value.inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::formulas::tests::*;
+
+    #[test]
+    fn test_formula_let() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        sheet.set_cell_value(pos![A1], 10);
+        sheet.set_cell_value(pos![A2], 20);
+
+        assert_eq!("11", eval_to_string(&g, "LET(x, 10, x + 1)"));
+        assert_eq!("11", eval_to_string(&g, "let(X, 10, x + 1)"));
+        assert_eq!("21", eval_to_string(&g, "LET(x, A1, y, x * 2, y + 1)"));
+        assert_eq!("30", eval_to_string(&g, "LET(cells, A1:A2, SUM(cells))"));
+        assert_eq!("2", eval_to_string(&g, "LET(cells, A1:A2, ROWS(cells))"));
+        assert_eq!("{1, 2}", eval_to_string(&g, "LET(a_1, {1, 2}, a_1)"));
+
+        // Names may start with `TRUE` or `FALSE`.
+        assert_eq!("6", eval_to_string(&g, "LET(trueish, 5, trueish + 1)"));
+        assert_eq!(
+            "FALSE",
+            eval_to_string(&g, "LET(false_flag, TRUE, NOT(false_flag))")
+        );
+        assert_eq!("TRUE", eval_to_string(&g, "LET(x, true, x)"));
+
+        // Inner names shadow outer names.
+        assert_eq!("5", eval_to_string(&g, "LET(x, 1, LET(x, 5, x))"));
+        assert_eq!("6", eval_to_string(&g, "LET(x, 1, LET(x, 5, x) + x)"));
+
+        // Errors are values, so they can be handled later.
+        assert_eq!("0", eval_to_string(&g, "LET(x, 1 / 0, IFERROR(x, 0))"));
+
+        // Names are only in scope within the `LET`.
+        expect_err(&ErrorMsg::BadName("x".into()), &g, "LET(x, 1, x) + x");
+        expect_err(&ErrorMsg::BadName("y".into()), &g, "LET(x, y, y, 1, x)");
+        expect_err(&ErrorMsg::BadName("foo".into()), &g, "foo + 1");
+
+        assert_eq!(
+            ErrorMsg::Expected {
+                expected: "name".into(),
+                got: Some("cell reference".into()),
+            },
+            eval_to_err(&g, "LET(A1, 1, 2)").msg,
+        );
+        assert_eq!(
+            ErrorMsg::MissingRequiredArgument {
+                func_name: "LET".into(),
+                arg_name: "calculation".into(),
+            },
+            eval_to_err(&g, "LET(x, 1)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_let_cells_accessed() {
+        let g = Grid::new();
+        let sheet_id = g.sheets()[0].id;
+
+        // Only cells that are actually read are accessed.
+        let mut ctx = Ctx::new(&g, pos![A0].with_sheet(sheet_id));
+        let formula = parse_formula("LET(r, B1:C3, s, D1, ROWS(r) + s)", Pos::ORIGIN).unwrap();
+        assert_eq!("3", formula.eval(&mut ctx).unwrap().to_string());
+        assert_eq!(
+            HashSet::from([pos![D1].with_sheet(sheet_id)]),
+            ctx.cells_accessed,
+        );
+    }
+
+    #[test]
+    fn test_formula_lambda() {
+        let g = Grid::new();
+
+        assert_eq!("7", eval_to_string(&g, "LET(f, LAMBDA(x, x + 1), f(6))"));
+        assert_eq!(
+            "5",
+            eval_to_string(&g, "LET(hypot, LAMBDA(a, b, SQRT(a^2 + b^2)), hypot(3, 4))"),
+        );
+        assert_eq!("42", eval_to_string(&g, "LET(f, LAMBDA(42), f())"));
+
+        // Names are lexically scoped.
+        assert_eq!(
+            "11",
+            eval_to_string(&g, "LET(n, 10, f, LAMBDA(x, x + n), n, 100, f(1))"),
+        );
+        // Parameters shadow other names.
+        assert_eq!(
+            "2",
+            eval_to_string(&g, "LET(x, 1, f, LAMBDA(x, x * 2), f(1))")
+        );
+        // Functions can be passed to other functions.
+        assert_eq!(
+            "81",
+            eval_to_string(
+                &g,
+                "LET(twice, LAMBDA(f, x, f(f(x))), sq, LAMBDA(x, x ^ 2), twice(sq, 3))"
+            ),
+        );
+
+        assert_eq!(
+            ErrorMsg::Expected {
+                expected: "value".into(),
+                got: Some("function".into()),
+            },
+            eval_to_err(&g, "LAMBDA(x, x)").msg,
+        );
+        assert_eq!(
+            ErrorMsg::MissingRequiredArgument {
+                func_name: "LAMBDA".into(),
+                arg_name: "b".into(),
+            },
+            eval_to_err(&g, "LET(f, LAMBDA(a, b, a + b), f(1))").msg,
+        );
+        assert_eq!(
+            ErrorMsg::TooManyArguments {
+                func_name: "LAMBDA".into(),
+                max_arg_count: 1,
+            },
+            eval_to_err(&g, "LET(f, LAMBDA(a, a), f(1, 2))").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_lambda_recursion() {
+        let g = Grid::new();
+
+        // A function can call itself if it is passed to itself.
+        let fact = "LET(fact, LAMBDA(f, n, IF(n <= 1, 1, n * f(f, n - 1))), fact(fact, {}))";
+        assert_eq!("3628800", eval_to_string(&g, &fact.replace("{}", "10")));
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, &format!("{} > 0", fact.replace("{}", "30"))),
+        );
+
+        // Recursion without end is an error rather than a stack overflow.
+        expect_err(
+            &ErrorMsg::RecursionLimit,
+            &g,
+            "LET(f, LAMBDA(g, n, g(g, n)), f(f, 1))",
+        );
+        expect_err(&ErrorMsg::RecursionLimit, &g, &fact.replace("{}", "1000"));
+        assert_eq!(
+            "6",
+            eval_to_string(&g, "ERROR.TYPE(LET(f, LAMBDA(g, g(g)), f(f)))"),
+        );
+    }
+
+    #[test]
+    fn test_formula_map() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        sheet.set_cell_value(pos![A1], 1);
+        sheet.set_cell_value(pos![A2], 2);
+        sheet.set_cell_value(pos![A3], 3);
+
+        assert_eq!(
+            "{2; 4; 6}",
+            eval_to_string(&g, "MAP(A1:A3, LAMBDA(x, x * 2))"),
+        );
+        assert_eq!(
+            "{11, 21; 12, 22; 13, 23}",
+            eval_to_string(&g, "MAP(A1:A3, {10, 20}, LAMBDA(a, b, a + b))"),
+        );
+        assert_eq!(
+            "{1; 4; 9}",
+            eval_to_string(&g, "LET(sq, LAMBDA(x, x * x), MAP(A1:A3, sq))"),
+        );
+        assert_eq!("6", eval_to_string(&g, "MAP(3, LAMBDA(x, x * 2))"));
+
+        // Errors in individual values do not stop evaluation.
+        assert_eq!(
+            "{TRUE, FALSE}",
+            eval_to_string(&g, "ISERROR(MAP({0, 1}, LAMBDA(x, 1 / x)))"),
+        );
+
+        assert_eq!(
+            ErrorMsg::MissingRequiredArgument {
+                func_name: "MAP".into(),
+                arg_name: "lambda".into(),
+            },
+            eval_to_err(&g, "MAP(A1:A3)").msg,
+        );
+        assert_eq!(
+            ErrorMsg::Expected {
+                expected: "function".into(),
+                got: Some("value".into()),
+            },
+            eval_to_err(&g, "MAP(A1:A3, 5)").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_reduce_scan() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "10",
+            eval_to_string(&g, "REDUCE(0, {1, 2; 3, 4}, LAMBDA(a, x, a + x))"),
+        );
+        assert_eq!(
+            "24",
+            eval_to_string(&g, "REDUCE(1, {1, 2, 3, 4}, LAMBDA(a, x, a * x))"),
+        );
+        assert_eq!(
+            "{1, 3; 6, 10}",
+            eval_to_string(&g, "SCAN(0, {1, 2; 3, 4}, LAMBDA(a, x, a + x))"),
+        );
+        assert_eq!(
+            "{a, ab, abc}",
+            eval_to_string(&g, "SCAN(\"\", {\"a\", \"b\", \"c\"}, LAMBDA(s, x, s & x))"),
+        );
+        expect_err(
+            &ErrorMsg::DivideByZero,
+            &g,
+            "REDUCE(0, {1, 0}, LAMBDA(a, x, a + 1 / x))",
+        );
+    }
+
+    #[test]
+    fn test_formula_byrow_bycol() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "{3; 7; 11}",
+            eval_to_string(&g, "BYROW({1, 2; 3, 4; 5, 6}, LAMBDA(row, SUM(row)))"),
+        );
+        assert_eq!(
+            "{9, 12}",
+            eval_to_string(&g, "BYCOL({1, 2; 3, 4; 5, 6}, LAMBDA(col, SUM(col)))"),
+        );
+        assert_eq!(
+            "{3, 3}",
+            eval_to_string(&g, "BYCOL({1, 2; 3, 4; 5, 6}, LAMBDA(col, ROWS(col)))"),
+        );
+    }
+
+    #[test]
+    fn test_formula_makearray() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "{1, 2, 3; 2, 4, 6}",
+            eval_to_string(&g, "MAKEARRAY(2, 3, LAMBDA(r, c, r * c))"),
+        );
+        assert_eq!(
+            "{1,1; 2,1}",
+            eval_to_string(&g, "MAKEARRAY(2, 1, LAMBDA(r, c, r & \",\" & c))"),
+        );
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "MAKEARRAY(0, 3, LAMBDA(r, c, r))",
+        );
+    }
+}
@@ -72,20 +72,233 @@ fn get_functions() -> Vec<FormulaFunction> {
         formula_fn!(
             /// Returns `t` if `condition` is truthy and `f` if `condition` is
             /// falsey.
+            ///
+            /// Only the value that is returned is evaluated, so `f` is never
+            /// evaluated if `condition` is truthy and `t` is never evaluated
+            /// if `condition` is falsey. If `condition` is an array, then each
+            /// value is evaluated only if it is needed by some element.
             #[examples(
                 "IF(A2<0, \"A2 is negative\", \"A2 is nonnegative\")",
                 "IF(A2<0, \"A2 is negative\", IF(A2>0, \"A2 is positive\", \"A2 is zero\"))"
             )]
-            #[zip_map]
-            fn IF([condition]: bool, [t]: CellValue, [f]: CellValue) {
-                if condition { t } else { f }.clone()
+            fn IF(ctx: Ctx, condition: (Spanned<Value>), t: LazyArg, f: LazyArg) {
+                select(ctx, condition, |ctx| t.eval(ctx), |ctx| f.eval(ctx))?
+            }
+        ),
+        formula_fn!(
+            /// Evaluates each condition in order and returns the value after
+            /// the first one that is truthy. Conditions and values after that
+            /// are not evaluated.
+            ///
+            /// Returns a "value not available" error if no condition is
+            /// truthy.
+            #[examples(
+                "IFS(A1<0, \"negative\", A1>0, \"positive\", TRUE, \"zero\")",
+                "IFS(B2>=90, \"A\", B2>=80, \"B\", B2>=70, \"C\")"
+            )]
+            fn IFS(ctx: Ctx, span: Span, conditions_and_values: (Iter<LazyArg>)) {
+                let mut args = conditions_and_values.peekable();
+                if args.peek().is_none() {
+                    return Err(util::missing_arg_error("IFS", "condition", span));
+                }
+                ifs(ctx, span, &mut args)?
+            }
+        ),
+        formula_fn!(
+            /// Compares `expression` to each case in order and returns the
+            /// value after the first case that is equal to it. If the number
+            /// of arguments after `expression` is odd, then the last one is a
+            /// default value that is returned if no case matches.
+            ///
+            /// Returns a "value not available" error if no case matches and
+            /// there is no default value. Only the value that is returned is
+            /// evaluated, and cases after the first match are not evaluated.
+            #[examples(
+                "SWITCH(A1, 1, \"one\", 2, \"two\", \"many\")",
+                "SWITCH(WEEKDAY(B3), 1, \"Sunday\", 7, \"Saturday\", \"weekday\")"
+            )]
+            fn SWITCH(
+                ctx: Ctx,
+                span: Span,
+                expression: (Spanned<Value>),
+                cases_and_values: (Iter<LazyArg>),
+            ) {
+                let mut args = cases_and_values.peekable();
+                if args.peek().is_none() {
+                    return Err(util::missing_arg_error("SWITCH", "case", span));
+                }
+                switch(ctx, span, &expression, &mut args)?
+            }
+        ),
+        formula_fn!(
+            /// Returns `value` if it is not an error, and `value_if_error` if
+            /// it is an error.
+            ///
+            /// `value_if_error` is only evaluated if `value` is an error.
+            #[examples("IFERROR(A1 / B1, 0)", "IFERROR(XLOOKUP(5, A1:A10, B1:B10), \"none\")")]
+            fn IFERROR(ctx: Ctx, value: (Spanned<Value>), value_if_error: LazyArg) {
+                replace_where(ctx, value, value_if_error, |v| {
+                    matches!(v, CellValue::Error(_))
+                })?
+            }
+        ),
+        formula_fn!(
+            /// Returns `value` if it is not a "value not available" error, and
+            /// `value_if_na` if it is. Other errors are returned unchanged.
+            ///
+            /// Lookup functions such as `XLOOKUP` return this error when no
+            /// match is found, as does `NA`. `value_if_na` is only evaluated
+            /// if `value` is this error.
+            #[examples("IFNA(XLOOKUP(5, A1:A10, B1:B10), \"none\")")]
+            fn IFNA(ctx: Ctx, value: (Spanned<Value>), value_if_na: LazyArg) {
+                replace_where(
+                    ctx,
+                    value,
+                    value_if_na,
+                    |v| matches!(v, CellValue::Error(e) if e.msg.is_not_available()),
+                )?
             }
         ),
     ]
 }
 
+/// Returns a value that is blank everywhere, used in place of a value that
+/// was not evaluated.
+fn blank(span: Span) -> Spanned<Value> {
+    Spanned {
+        span,
+        inner: CellValue::Blank.into(),
+    }
+}
+
+/// Takes elements from `if_true` where `condition` is truthy and from
+/// `if_false` where it is falsey, evaluating each of them only if some element
+/// of `condition` needs it.
+fn select(
+    ctx: &mut Ctx<'_>,
+    condition: Spanned<Value>,
+    if_true: impl FnOnce(&mut Ctx<'_>) -> CodeResult<Spanned<Value>>,
+    if_false: impl FnOnce(&mut Ctx<'_>) -> CodeResult<Spanned<Value>>,
+) -> CodeResult<Value> {
+    let needs = |b: bool| {
+        condition
+            .iter_cell_values()
+            .any(|c| matches!(c.try_coerce::<bool>(), Ok(Spanned { inner, .. }) if inner == b))
+    };
+    let t = if needs(true) {
+        if_true(ctx)?
+    } else {
+        blank(condition.span)
+    };
+    let f = if needs(false) {
+        if_false(ctx)?
+    } else {
+        blank(condition.span)
+    };
+
+    ctx.zip_map(&[condition, t, f], |_ctx, args| {
+        let value = match args[0].try_coerce::<bool>()?.inner {
+            true => args[1].inner,
+            false => args[2].inner,
+        };
+        Ok(value.clone())
+    })
+}
+
+/// Evaluates the remaining arguments to `IFS`, which come in pairs of
+/// condition and value.
+fn ifs<'a>(
+    ctx: &mut Ctx<'_>,
+    span: Span,
+    args: &mut impl Iterator<Item = LazyArg<'a>>,
+) -> CodeResult<Value> {
+    let Some(condition) = args.next() else {
+        return Ok(not_available(span));
+    };
+    let Some(value) = args.next() else {
+        return Err(util::missing_arg_error("IFS", "value", span));
+    };
+    let condition = condition.eval(ctx)?;
+    select(
+        ctx,
+        condition,
+        |ctx| value.eval(ctx),
+        |ctx| {
+            Ok(Spanned {
+                span,
+                inner: ifs(ctx, span, args)?,
+            })
+        },
+    )
+}
+
+/// Evaluates the remaining arguments to `SWITCH`, which come in pairs of case
+/// and value with an optional default value at the end.
+fn switch<'a>(
+    ctx: &mut Ctx<'_>,
+    span: Span,
+    expression: &Spanned<Value>,
+    args: &mut impl Iterator<Item = LazyArg<'a>>,
+) -> CodeResult<Value> {
+    let Some(case) = args.next() else {
+        return Ok(not_available(span));
+    };
+    let Some(value) = args.next() else {
+        // The last argument is the default value.
+        return Ok(case.eval(ctx)?.inner);
+    };
+    let case = case.eval(ctx)?;
+    let condition = Spanned {
+        span: case.span,
+        inner: ctx.zip_map(&[expression.clone(), case], |_ctx, args| {
+            Ok(args[0].inner.eq(args[1].inner)?.into())
+        })?,
+    };
+    select(
+        ctx,
+        condition,
+        |ctx| value.eval(ctx),
+        |ctx| {
+            Ok(Spanned {
+                span,
+                inner: switch(ctx, span, expression, args)?,
+            })
+        },
+    )
+}
+
+fn not_available(span: Span) -> Value {
+    CellValue::Error(Box::new(ErrorMsg::NotAvailable.with_span(span))).into()
+}
+
+/// Replaces the elements of `value` for which `predicate` returns `true` with
+/// the corresponding elements of `replacement`, which is only evaluated if
+/// some element needs to be replaced.
+fn replace_where(
+    ctx: &mut Ctx<'_>,
+    value: Spanned<Value>,
+    replacement: LazyArg<'_>,
+    predicate: impl Fn(&CellValue) -> bool,
+) -> CodeResult<Value> {
+    let replacement = if value.inner.cell_values_slice().iter().any(&predicate) {
+        replacement.eval(ctx)?
+    } else {
+        blank(value.span)
+    };
+
+    ctx.zip_map(&[value, replacement], |_ctx, args| {
+        let value = match predicate(args[0].inner) {
+            true => args[1].inner,
+            false => args[0].inner,
+        };
+        Ok(value.clone())
+    })
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use crate::formulas::tests::*;
 
     #[test]
@@ -103,4 +316,159 @@ mod tests {
         let mut ctx = Ctx::new(&g, pos![B0].with_sheet(sheet_id));
         assert_eq!("nope".to_string(), form.eval(&mut ctx).unwrap().to_string());
     }
+
+    #[test]
+    fn test_formula_iferror() {
+        let g = Grid::new();
+
+        assert_eq!("5", eval_to_string(&g, "IFERROR(10 / 2, 0)"));
+        assert_eq!("0", eval_to_string(&g, "IFERROR(10 / 0, 0)"));
+        assert_eq!("oops", eval_to_string(&g, "IFERROR(SQRT(\"x\"), \"oops\")"));
+        // Errors in the formula itself are not caught.
+        expect_err(
+            &ErrorMsg::BadFunctionName,
+            &g,
+            "IFERROR(NOSUCHFUNCTION(), \"none\")",
+        );
+        expect_err(
+            &ErrorMsg::BadFunctionName,
+            &g,
+            "IFERROR({1, NOSUCHFUNCTION()}, 0)",
+        );
+        expect_err(&ErrorMsg::BadName("x".into()), &g, "IFERROR(x + 1, 0)");
+        assert_eq!("{1, 0, 3}", eval_to_string(&g, "IFERROR(3 / {3, 0, 1}, 0)"));
+        // Errors propagate through other functions.
+        assert_eq!("-1", eval_to_string(&g, "IFERROR(ABS(1 / 0) + 1, -1)"));
+
+        assert_eq!("none", eval_to_string(&g, "IFNA(NA(), \"none\")"));
+        assert_eq!(
+            "none",
+            eval_to_string(&g, "IFNA(XLOOKUP(5, {1, 2}, {3, 4}), \"none\")")
+        );
+        // A lookup with no match counts as a value that is not available.
+        assert_eq!(
+            "none",
+            eval_to_string(&g, "IFNA(MATCH(5, {1, 2}, 0), \"none\")")
+        );
+        assert_eq!("2", eval_to_string(&g, "IFNA(1 + 1, \"none\")"));
+        expect_err(&ErrorMsg::DivideByZero, &g, "IFNA(1 / 0, \"none\")");
+    }
+
+    #[test]
+    fn test_formula_if_is_lazy() {
+        let mut g = Grid::new();
+        let sheet = &mut g.sheets_mut()[0];
+        sheet.set_cell_value(pos![A1], 0);
+        let sheet_id = sheet.id;
+
+        assert_eq!("0", eval_to_string(&g, "IF(A1=0, 0, 1/A1)"));
+        assert_eq!("1", eval_to_string(&g, "IF(TRUE, 1, NOSUCHFUNCTION())"));
+        assert_eq!(
+            "{1, 2}",
+            eval_to_string(&g, "IF({TRUE, TRUE}, {1, 2}, 1/0)")
+        );
+        assert_eq!(
+            "{1, 4}",
+            eval_to_string(&g, "IF({TRUE, FALSE}, {1, 2}, {3, 4})")
+        );
+        expect_err(&ErrorMsg::DivideByZero, &g, "IF(1/0, 1, 2)");
+        expect_err(&ErrorMsg::DivideByZero, &g, "IF(FALSE, 1, 1/A1)");
+
+        // Cells in the branch that is not taken are not accessed.
+        let mut ctx = Ctx::new(&g, pos![D1].with_sheet(sheet_id));
+        let formula = parse_formula("IF(A1=0, B1, C1:C3)", pos![D1]).unwrap();
+        formula.eval(&mut ctx).unwrap();
+        assert_eq!(
+            HashSet::from([pos![A1].with_sheet(sheet_id), pos![B1].with_sheet(sheet_id)]),
+            ctx.cells_accessed,
+        );
+
+        let mut ctx = Ctx::new(&g, pos![D1].with_sheet(sheet_id));
+        let formula = parse_formula("IFERROR(A1, B1)", pos![D1]).unwrap();
+        formula.eval(&mut ctx).unwrap();
+        assert_eq!(
+            HashSet::from([pos![A1].with_sheet(sheet_id)]),
+            ctx.cells_accessed,
+        );
+    }
+
+    #[test]
+    fn test_formula_ifs() {
+        let g = Grid::new();
+
+        let grade = |score: i64| {
+            eval_to_string(
+                &g,
+                &format!("IFS({score}>=90, \"A\", {score}>=80, \"B\", TRUE, \"C\")"),
+            )
+        };
+        assert_eq!("A", grade(95));
+        assert_eq!("B", grade(85));
+        assert_eq!("C", grade(50));
+
+        // Later conditions and values are not evaluated.
+        assert_eq!("1", eval_to_string(&g, "IFS(TRUE, 1, 1/0, 2)"));
+        assert_eq!("2", eval_to_string(&g, "IFS(FALSE, 1/0, TRUE, 2)"));
+        assert_eq!(
+            "{1, 2, Value not available}",
+            eval_to_string(&g, "IFS({1, 2, 3}=1, 1, {1, 2, 3}=2, 2)"),
+        );
+
+        expect_err(&ErrorMsg::NotAvailable, &g, "IFS(FALSE, 1)");
+        expect_err(&ErrorMsg::DivideByZero, &g, "IFS(1/0, 1)");
+        assert_eq!(
+            ErrorMsg::MissingRequiredArgument {
+                func_name: "IFS".into(),
+                arg_name: "value".into(),
+            },
+            eval_to_err(&g, "IFS(FALSE, 1, TRUE)").msg,
+        );
+        assert_eq!(
+            ErrorMsg::MissingRequiredArgument {
+                func_name: "IFS".into(),
+                arg_name: "condition".into(),
+            },
+            eval_to_err(&g, "IFS()").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_switch() {
+        let g = Grid::new();
+
+        let name = |n: i64| {
+            eval_to_string(
+                &g,
+                &format!("SWITCH({n}, 1, \"one\", 2, \"two\", \"many\")"),
+            )
+        };
+        assert_eq!("one", name(1));
+        assert_eq!("two", name(2));
+        assert_eq!("many", name(3));
+        assert_eq!(
+            "b",
+            eval_to_string(&g, "SWITCH(\"B\", \"a\", \"a\", \"b\", \"b\")")
+        );
+        assert_eq!(
+            "{one, two}",
+            eval_to_string(&g, "SWITCH({1, 2}, 1, \"one\", 2, \"two\")")
+        );
+
+        // Cases after the first match and values not returned are not
+        // evaluated.
+        assert_eq!("1", eval_to_string(&g, "SWITCH(1, 1, 1, 1/0, 1/0, 1/0)"));
+
+        expect_err(
+            &ErrorMsg::NotAvailable,
+            &g,
+            "SWITCH(3, 1, \"one\", 2, \"two\")",
+        );
+        assert_eq!(
+            ErrorMsg::MissingRequiredArgument {
+                func_name: "SWITCH".into(),
+                arg_name: "case".into(),
+            },
+            eval_to_err(&g, "SWITCH(3)").msg,
+        );
+    }
 }
@@ -1,7 +1,9 @@
+use std::ops::Range;
+
 use regex::Regex;
 use smallvec::smallvec;
 
-use crate::ArraySize;
+use crate::{ArraySize, Pos, SheetRect};
 
 use super::*;
 
@@ -187,20 +189,7 @@ fn get_functions() -> Vec<FormulaFunction> {
                         ErrorMsg::NoMatch.with_span(span),
                     ))),
                 });
-                let search_mode_span = search_mode.map_or(span, |arg| arg.span);
-                let match_mode = LookupMatchMode::try_from(match_mode)?;
-                let search_mode = LookupSearchMode::try_from(search_mode)?;
-
-                // Check for invalid combination
-                if match_mode == LookupMatchMode::Wildcard {
-                    match search_mode {
-                        LookupSearchMode::LinearForward | LookupSearchMode::LinearReverse => (), //ok
-                        LookupSearchMode::BinaryAscending | LookupSearchMode::BinaryDescending => {
-                            // not ok -- can't do binary search with wildcard
-                            return Err(ErrorMsg::InvalidArgument.with_span(search_mode_span));
-                        }
-                    }
-                }
+                let (match_mode, search_mode) = lookup_modes(match_mode, search_mode, span)?;
 
                 // Give more concise names so it's easier to keep track of them
                 // while reading this code.
@@ -274,9 +263,350 @@ fn get_functions() -> Vec<FormulaFunction> {
                 Array::new_row_major(result_size, final_output_array)?
             }
         ),
+        formula_fn!(
+            /// Returns the cells in `range` at a given `row` and `column`.
+            ///
+            /// `row` and `column` are 1-based. If `row` is zero or omitted,
+            /// then the whole column is returned. If `column` is zero or
+            /// omitted, then the whole row is returned. If `range` is a single
+            /// row and `column` is omitted, then `row` is used as the column
+            /// instead.
+            ///
+            /// If `range` is a cell reference, then only the returned cells are
+            /// read.
+            #[examples("INDEX(A1:C10, 4, 2)", "INDEX(A1:A10, 3)", "INDEX(A1:C10, 0, 2)")]
+            fn INDEX(
+                ctx: Ctx,
+                range: (Spanned<ArgValue>),
+                row: (Option<Spanned<i64>>),
+                column: (Option<Spanned<i64>>),
+            ) {
+                let size = range.inner.size(range.span)?;
+                let (row, column) = match (row, column) {
+                    (Some(index), None) if size.h.get() == 1 => (None, Some(index)),
+                    other => other,
+                };
+                let xs = index_range(size.w.get(), column)?;
+                let ys = index_range(size.h.get(), row)?;
+                sub_range(ctx, range, xs, ys)?
+            }
+        ),
+        formula_fn!(
+            /// Searches for a value in a linear range and returns its 1-based
+            /// position, or an error if no match is found.
+            ///
+            /// `search_range` must be either a single row or a single column.
+            ///
+            /// # Match types
+            ///
+            /// There are three match types:
+            ///
+            /// - 1 = largest value less than or equal to `search_key`
+            ///   (default)
+            /// - 0 = exact match
+            /// - -1 = smallest value greater than or equal to `search_key`
+            ///
+            /// Match type 1 uses a [binary
+            /// search](https://en.wikipedia.org/wiki/Binary_search_algorithm),
+            /// so `search_range` must be sorted, with smaller values at the top
+            /// or left and larger values at the bottom or right. Match type -1
+            /// requires that `search_range` is sorted in the opposite
+            /// direction.
+            ///
+            /// If the match type is 0 and `search_key` is text containing `?`
+            /// or `*`, then `search_key` is a wildcard pattern.
+            #[doc = see_docs_for_more_about_wildcards!()]
+            ///
+            /// If `search_key` is an array, then a search will be performed for
+            /// each element.
+            #[examples("MATCH(17, A1:A10)", "MATCH(\"zebra\", A1:Z1, 0)")]
+            #[zip_map]
+            fn MATCH(
+                span: Span,
+                [search_key]: CellValue,
+                search_range: (Spanned<Array>),
+                match_type: (Option<Spanned<i64>>),
+            ) {
+                let (match_mode, search_mode) = match match_type {
+                    None | Some(Spanned { inner: 1, .. }) => (
+                        LookupMatchMode::NextSmaller,
+                        LookupSearchMode::BinaryAscending,
+                    ),
+                    Some(Spanned { inner: 0, .. }) => (
+                        LookupMatchMode::exact_or_wildcard(search_key),
+                        LookupSearchMode::LinearForward,
+                    ),
+                    Some(Spanned { inner: -1, .. }) => (
+                        LookupMatchMode::NextLarger,
+                        LookupSearchMode::BinaryDescending,
+                    ),
+                    Some(Spanned { span, .. }) => {
+                        return Err(ErrorMsg::InvalidArgument.with_span(*span));
+                    }
+                };
+                let haystack = linear_values(search_range)?;
+                let i = lookup(search_key, &haystack, match_mode, search_mode)?
+                    .ok_or_else(|| ErrorMsg::NoMatch.with_span(*span))?;
+                i as i64 + 1
+            }
+        ),
+        formula_fn!(
+            /// Searches for a value in a linear range and returns its 1-based
+            /// position, or an error if no match is found.
+            ///
+            /// `search_range` must be either a single row or a single column.
+            ///
+            /// `match_mode` and `search_mode` work the same as in `XLOOKUP`.
+            /// The default match mode is exact match, and the default search
+            /// mode is linear search.
+            #[doc = see_docs_for_more_about_wildcards!()]
+            ///
+            /// If `search_key` is an array, then a search will be performed for
+            /// each element.
+            #[examples("XMATCH(\"zebra\", A1:Z1)", "XMATCH(50, C4:C834, -1, 2)")]
+            #[zip_map]
+            fn XMATCH(
+                span: Span,
+                [search_key]: CellValue,
+                search_range: (Spanned<Array>),
+                match_mode: (Option<Spanned<i64>>),
+                search_mode: (Option<Spanned<i64>>),
+            ) {
+                let (match_mode, search_mode) = lookup_modes(*match_mode, *search_mode, *span)?;
+                let haystack = linear_values(search_range)?;
+                let i = lookup(search_key, &haystack, match_mode, search_mode)?
+                    .ok_or_else(|| ErrorMsg::NoMatch.with_span(*span))?;
+                i as i64 + 1
+            }
+        ),
+        formula_fn!(
+            /// Returns a range of cells offset from `reference` by a given
+            /// number of rows and columns.
+            ///
+            /// `rows` and `columns` may be negative. If `height` or `width` is
+            /// omitted, then the result has the same height or width as
+            /// `reference`.
+            ///
+            /// Only the returned cells are read.
+            #[examples("OFFSET(A1, 2, 3)", "OFFSET(A1:B2, 1, 0, 3, 2)")]
+            fn OFFSET(
+                ctx: Ctx,
+                reference: (Spanned<ArgValue>),
+                rows: i64,
+                columns: i64,
+                height: (Option<Spanned<i64>>),
+                width: (Option<Spanned<i64>>),
+            ) {
+                let sheet_rect = reference_arg(&reference)?;
+                let size = reference.inner.size(reference.span)?;
+                let height = offset_len(height, size.h.get())?;
+                let width = offset_len(width, size.w.get())?;
+
+                let offset = |coord: i64, delta: i64| {
+                    coord
+                        .checked_add(delta)
+                        .ok_or(ErrorMsg::Overflow.with_span(reference.span))
+                };
+                let min = Pos {
+                    x: offset(sheet_rect.min.x, columns)?,
+                    y: offset(sheet_rect.min.y, rows)?,
+                };
+                let max = Pos {
+                    x: offset(min.x, width - 1)?,
+                    y: offset(min.y, height - 1)?,
+                };
+                let sheet_id = sheet_rect.sheet_id;
+                ctx.get_sheet_rect_array(SheetRect { min, max, sheet_id }, reference.span)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the value at `index` in `values`.
+            ///
+            /// `index` is 1-based. Only the chosen value is evaluated.
+            #[examples(
+                "CHOOSE(2, \"apple\", \"banana\", \"cherry\")",
+                "CHOOSE(A1, B1:B10, C1:C10)"
+            )]
+            fn CHOOSE(ctx: Ctx, index: (Spanned<i64>), values: (Iter<LazyArg>)) {
+                let i = usize::try_from(index.inner)
+                    .ok()
+                    .and_then(|i| i.checked_sub(1))
+                    .ok_or(ErrorMsg::IndexOutOfBounds.with_span(index.span))?;
+                values
+                    .nth(i)
+                    .ok_or(ErrorMsg::IndexOutOfBounds.with_span(index.span))?
+                    .eval(ctx)?
+                    .inner
+            }
+        ),
+        formula_fn!(
+            /// Returns the row number of a cell reference.
+            ///
+            /// If `reference` is a range, then returns a column containing the
+            /// number of each row in the range. If `reference` is omitted, then
+            /// returns the row number of the cell containing the formula.
+            #[examples("ROW()", "ROW(B3)", "ROW(A1:A10)")]
+            fn ROW(ctx: Ctx, reference: (Option<Spanned<ArgValue>>)) {
+                match reference {
+                    None => Value::from(ctx.pos.y),
+                    Some(reference) => {
+                        let sheet_rect = reference_arg(&reference)?;
+                        sheet_rect_size(sheet_rect, reference.span)?;
+                        let ys = sheet_rect.y_range();
+                        Value::from(Array::from(
+                            ys.map(|y| vec![CellValue::from(y)]).collect_vec(),
+                        ))
+                    }
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the column number of a cell reference. Column `A` is
+            /// column number 1.
+            ///
+            /// If `reference` is a range, then returns a row containing the
+            /// number of each column in the range. If `reference` is omitted,
+            /// then returns the column number of the cell containing the
+            /// formula.
+            #[examples("COLUMN()", "COLUMN(B3)", "COLUMN(A1:J1)")]
+            fn COLUMN(ctx: Ctx, reference: (Option<Spanned<ArgValue>>)) {
+                match reference {
+                    None => Value::from(ctx.pos.x + 1),
+                    Some(reference) => {
+                        let sheet_rect = reference_arg(&reference)?;
+                        sheet_rect_size(sheet_rect, reference.span)?;
+                        let xs = sheet_rect.x_range();
+                        Value::from(Array::from(vec![xs
+                            .map(|x| CellValue::from(x + 1))
+                            .collect_vec()]))
+                    }
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of rows in a range or array.
+            ///
+            /// This does not read any cells.
+            #[examples("ROWS(A1:C10)", "ROWS({1, 2; 3, 4; 5, 6})")]
+            fn ROWS(range: (Spanned<ArgValue>)) {
+                range.inner.size(range.span)?.h.get()
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of columns in a range or array.
+            ///
+            /// This does not read any cells.
+            #[examples("COLUMNS(A1:C10)", "COLUMNS({1, 2; 3, 4; 5, 6})")]
+            fn COLUMNS(range: (Spanned<ArgValue>)) {
+                range.inner.size(range.span)?.w.get()
+            }
+        ),
     ]
 }
 
+/// Parses the match mode and search mode arguments for `XLOOKUP` and
+/// `XMATCH`, and checks that they are compatible.
+fn lookup_modes(
+    match_mode: Option<Spanned<i64>>,
+    search_mode: Option<Spanned<i64>>,
+    span: Span,
+) -> CodeResult<(LookupMatchMode, LookupSearchMode)> {
+    let search_mode_span = search_mode.map_or(span, |arg| arg.span);
+    let match_mode = LookupMatchMode::try_from(match_mode)?;
+    let search_mode = LookupSearchMode::try_from(search_mode)?;
+
+    // Check for invalid combination
+    if match_mode == LookupMatchMode::Wildcard {
+        match search_mode {
+            LookupSearchMode::LinearForward | LookupSearchMode::LinearReverse => (), //ok
+            LookupSearchMode::BinaryAscending | LookupSearchMode::BinaryDescending => {
+                // not ok -- can't do binary search with wildcard
+                return Err(ErrorMsg::InvalidArgument.with_span(search_mode_span));
+            }
+        }
+    }
+
+    Ok((match_mode, search_mode))
+}
+
+/// Returns the values in a single row or column, or an error if the array is
+/// not linear.
+fn linear_values(array: &Spanned<Array>) -> CodeResult<Vec<&CellValue>> {
+    array.array_linear_axis()?;
+    Ok(array.inner.cell_values_slice().iter().collect())
+}
+
+/// Returns the region of cells referenced by `arg`, or an error if it is not
+/// a cell reference.
+fn reference_arg(arg: &Spanned<ArgValue>) -> CodeResult<SheetRect> {
+    match arg.inner {
+        ArgValue::Reference(sheet_rect) => Ok(sheet_rect),
+        ArgValue::Value(_) | ArgValue::Lambda(_) => Err(ErrorMsg::Expected {
+            expected: "cell reference".into(),
+            got: None,
+        }
+        .with_span(arg.span)),
+    }
+}
+
+/// Returns the 0-based indices selected by a 1-based `index` along an axis of
+/// length `len`. If `index` is zero or omitted, the whole axis is selected.
+fn index_range(len: u32, index: Option<Spanned<i64>>) -> CodeResult<Range<u32>> {
+    match index {
+        None | Some(Spanned { inner: 0, .. }) => Ok(0..len),
+        Some(Spanned { inner, span }) => {
+            let i = u32::try_from(inner - 1)
+                .ok()
+                .filter(|&i| i < len)
+                .ok_or(ErrorMsg::IndexOutOfBounds.with_span(span))?;
+            Ok(i..i + 1)
+        }
+    }
+}
+
+/// Returns the length of an `OFFSET` result along an axis, or `default` if
+/// `len` is omitted.
+fn offset_len(len: Option<Spanned<i64>>, default: u32) -> CodeResult<i64> {
+    match len {
+        None => Ok(default.into()),
+        Some(Spanned { inner, .. }) if inner > 0 => Ok(inner),
+        Some(Spanned { span, .. }) => Err(ErrorMsg::InvalidArgument.with_span(span)),
+    }
+}
+
+/// Returns the cells of `range` in columns `xs` and rows `ys`. If `range` is a
+/// cell reference, only those cells are read.
+fn sub_range(
+    ctx: &mut Ctx<'_>,
+    range: Spanned<ArgValue>,
+    xs: Range<u32>,
+    ys: Range<u32>,
+) -> CodeResult<Array> {
+    match range.inner {
+        ArgValue::Reference(sheet_rect) => {
+            let min = Pos {
+                x: sheet_rect.min.x + xs.start as i64,
+                y: sheet_rect.min.y + ys.start as i64,
+            };
+            let max = Pos {
+                x: sheet_rect.min.x + xs.end as i64 - 1,
+                y: sheet_rect.min.y + ys.end as i64 - 1,
+            };
+            let sheet_id = sheet_rect.sheet_id;
+            ctx.get_sheet_rect_array(SheetRect { min, max, sheet_id }, range.span)
+        }
+        _ => {
+            let array = Array::from(range.into_value(ctx)?.inner);
+            let size = ArraySize::new_or_err(xs.len() as u32, ys.len() as u32)?;
+            let values = ys
+                .cartesian_product(xs)
+                .map(|(y, x)| array.get(x, y).cloned())
+                .try_collect()?;
+            Ok(Array::new_row_major(size, values)?)
+        }
+    }
+}
+
 /// Performs a `LOOKUP` and returns the index of the best match.
 fn lookup<V: ToString + AsRef<CellValue>>(
     needle: &CellValue,
@@ -406,6 +736,16 @@ enum LookupMatchMode {
     NextLarger = 1,
     Wildcard = 2,
 }
+impl LookupMatchMode {
+    /// Returns the wildcard match mode if `needle` is text containing `?` or
+    /// `*`, and the exact match mode otherwise.
+    fn exact_or_wildcard(needle: &CellValue) -> Self {
+        match needle {
+            CellValue::Text(s) if s.contains(['?', '*']) => LookupMatchMode::Wildcard,
+            _ => LookupMatchMode::Exact,
+        }
+    }
+}
 impl TryFrom<Option<Spanned<i64>>> for LookupMatchMode {
     type Error = Error;
 
@@ -459,6 +799,7 @@ impl TryFrom<Option<Spanned<i64>>> for LookupSearchMode {
 #[cfg(test)]
 mod tests {
     use std::borrow::Cow;
+    use std::collections::HashSet;
 
     use lazy_static::lazy_static;
     use smallvec::smallvec;
@@ -1026,4 +1367,172 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_formula_index() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("{two}", eval_to_string(&g, "INDEX(A1:C4, 2, 2)"));
+        assert_eq!("{50, fifty, mute}", eval_to_string(&g, "INDEX(A1:C4, 3)"));
+        assert_eq!(
+            "{wan; tu; mute; ale}",
+            eval_to_string(&g, "INDEX(A1:C4, 0, 3)")
+        );
+        assert_eq!("{100}", eval_to_string(&g, "INDEX(A1:A4, 4)"));
+        assert_eq!("{2}", eval_to_string(&g, "INDEX({1, 2, 3}, 2)"));
+        assert_eq!("{3}", eval_to_string(&g, "INDEX({1, 2; 3, 4}, 2, 1)"));
+
+        expect_err(&ErrorMsg::IndexOutOfBounds, &g, "INDEX(A1:C4, 5, 1)");
+        expect_err(&ErrorMsg::IndexOutOfBounds, &g, "INDEX(A1:C4, 1, 4)");
+        expect_err(&ErrorMsg::IndexOutOfBounds, &g, "INDEX(A1:C4, -1)");
+
+        // Only the returned cell is accessed.
+        let sheet_id = g.sheets()[0].id;
+        let mut ctx = Ctx::new(&g, pos![A0].with_sheet(sheet_id));
+        let form = parse_formula("INDEX(A1:C4, 2, 3)", pos![A0]).unwrap();
+        assert_eq!("{tu}", form.eval(&mut ctx).unwrap().to_string());
+        assert_eq!(
+            HashSet::from([pos![C2].with_sheet(sheet_id)]),
+            ctx.cells_accessed,
+        );
+    }
+
+    #[test]
+    fn test_formula_match() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        // Next smaller (default)
+        assert_eq!("3", eval_to_string(&g, "MATCH(50, A1:A4)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(60, A1:A4, 1)"));
+        expect_err(&ErrorMsg::NoMatch, &g, "MATCH(0, A1:A4)");
+
+        // Exact
+        assert_eq!("2", eval_to_string(&g, "MATCH(\"TWO\", B1:B4, 0)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(\"f*\", B1:B4, 0)"));
+        assert_eq!("2", eval_to_string(&g, "MATCH(\"t?o\", B1:B4, 0)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(\"wan\", A1:C1, 0)"));
+        expect_err(&ErrorMsg::NoMatch, &g, "MATCH(60, A1:A4, 0)");
+
+        // Next larger
+        assert_eq!("1", eval_to_string(&g, "MATCH(60, {100, 50, 2, 1}, -1)"));
+        assert_eq!("3", eval_to_string(&g, "MATCH(2, {100, 50, 2, 1}, -1)"));
+
+        // Zip-mapped search key
+        assert_eq!("{2; 4}", eval_to_string(&g, "MATCH({2; 100}, A1:A4, 0)"));
+
+        expect_err(&ErrorMsg::InvalidArgument, &g, "MATCH(1, A1:A4, 2)");
+        expect_err(&ErrorMsg::NonLinearArray, &g, "MATCH(1, A1:C4)");
+    }
+
+    #[test]
+    fn test_formula_xmatch() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("3", eval_to_string(&g, "XMATCH(\"fifty\", B1:B4)"));
+        assert_eq!("3", eval_to_string(&g, "XMATCH(60, A1:A4, -1)"));
+        assert_eq!("4", eval_to_string(&g, "XMATCH(60, A1:A4, 1)"));
+        assert_eq!("4", eval_to_string(&g, "XMATCH(60, A1:A4, 1, 2)"));
+        assert_eq!("3", eval_to_string(&g, "XMATCH(\"*e*\", C1:C4, 2)"));
+        assert_eq!("4", eval_to_string(&g, "XMATCH(\"*e*\", C1:C4, 2, -1)"));
+        assert_eq!("{1, 4}", eval_to_string(&g, "XMATCH({1, 100}, A1:A4)"));
+
+        expect_err(&ErrorMsg::NoMatch, &g, "XMATCH(3, A1:A4)");
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "XMATCH(\"a*\", C1:C4, 2, 2)",
+        );
+        expect_err(&ErrorMsg::InvalidArgument, &g, "XMATCH(1, A1:A4, 3)");
+    }
+
+    #[test]
+    fn test_formula_offset() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("{tu}", eval_to_string(&g, "OFFSET(A1, 1, 2)"));
+        assert_eq!("{1}", eval_to_string(&g, "OFFSET(B2, -1, -1)"));
+        assert_eq!(
+            "{1, one, wan; 2, two, tu}",
+            eval_to_string(&g, "OFFSET(A1, 0, 0, 2, 3)"),
+        );
+        assert_eq!(
+            "{fifty, mute; hundred, ale}",
+            eval_to_string(&g, "OFFSET(A1:B2, 2, 1)"),
+        );
+        assert_eq!("{ale}", eval_to_string(&g, "OFFSET((A1:B2), 3, 2, 1, 1)"));
+
+        expect_err(&ErrorMsg::InvalidArgument, &g, "OFFSET(A1, 0, 0, 0)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "OFFSET(A1, 0, 0, 1, -2)");
+        expect_err(&ErrorMsg::CircularReference, &g, "OFFSET(A1, -1, 0)");
+        assert!(matches!(
+            eval_to_err(&g, "OFFSET({1, 2}, 0, 0)").msg,
+            ErrorMsg::Expected { .. },
+        ));
+
+        // Only the returned cells are accessed.
+        let sheet_id = g.sheets()[0].id;
+        let mut ctx = Ctx::new(&g, pos![A0].with_sheet(sheet_id));
+        let form = parse_formula("OFFSET(A1:B2, 2, 1)", pos![A0]).unwrap();
+        form.eval(&mut ctx).unwrap();
+        assert_eq!(
+            HashSet::from(
+                [pos![B3], pos![C3], pos![B4], pos![C4]].map(|pos| pos.with_sheet(sheet_id))
+            ),
+            ctx.cells_accessed,
+        );
+    }
+
+    #[test]
+    fn test_formula_choose() {
+        let g = Grid::from_array(pos![A1], &NUMBERS_LOOKUP_ARRAY);
+
+        assert_eq!("b", eval_to_string(&g, "CHOOSE(2, \"a\", \"b\", \"c\")"));
+        assert_eq!("{1; 2}", eval_to_string(&g, "CHOOSE(1, A1:A2, B1:B2)"));
+        expect_err(&ErrorMsg::IndexOutOfBounds, &g, "CHOOSE(3, \"a\", \"b\")");
+        expect_err(&ErrorMsg::IndexOutOfBounds, &g, "CHOOSE(0, \"a\")");
+        assert_eq!("a", eval_to_string(&g, "CHOOSE(1, \"a\", 1 / 0)"));
+
+        // Cells in the options that are not chosen are not accessed.
+        let sheet_id = g.sheets()[0].id;
+        let mut ctx = Ctx::new(&g, pos![D1].with_sheet(sheet_id));
+        let form = parse_formula("CHOOSE(A1, B1, C1:C3)", pos![D1]).unwrap();
+        form.eval(&mut ctx).unwrap();
+        assert_eq!(
+            HashSet::from([pos![A1], pos![B1]].map(|pos| pos.with_sheet(sheet_id))),
+            ctx.cells_accessed,
+        );
+    }
+
+    #[test]
+    fn test_formula_row_column() {
+        let g = Grid::new();
+
+        assert_eq!("{3}", eval_to_string(&g, "ROW(B3)"));
+        assert_eq!("{2}", eval_to_string(&g, "COLUMN(B3)"));
+        assert_eq!("{4; 5; 6}", eval_to_string(&g, "ROW(C4:D6)"));
+        assert_eq!("{3, 4}", eval_to_string(&g, "COLUMN(C4:D6)"));
+        expect_err(&ErrorMsg::ArrayTooBig, &g, "ROW(A1:A99999999999)");
+        expect_err(&ErrorMsg::ArrayTooBig, &g, "COLUMN(A1:ZZZZZZZ1)");
+        assert_eq!("3", eval_to_string(&g, "ROWS(C4:D6)"));
+        assert_eq!("2", eval_to_string(&g, "COLUMNS(C4:D6)"));
+        assert_eq!("3", eval_to_string(&g, "ROWS({1, 2; 3, 4; 5, 6})"));
+        assert_eq!("2", eval_to_string(&g, "COLUMNS({1, 2; 3, 4; 5, 6})"));
+        assert_eq!("1", eval_to_string(&g, "ROWS(7)"));
+
+        let sheet_id = g.sheets()[0].id;
+        assert_eq!(
+            "5",
+            eval_to_string_at(&g, pos![C5].with_sheet(sheet_id), "ROW()")
+        );
+        assert_eq!(
+            "3",
+            eval_to_string_at(&g, pos![C5].with_sheet(sheet_id), "COLUMN()")
+        );
+
+        // No cells are read.
+        let mut ctx = Ctx::new(&g, pos![A0].with_sheet(sheet_id));
+        let form = parse_formula("ROW(A1:B2) + COLUMNS(A0:C0)", pos![A0]).unwrap();
+        form.eval(&mut ctx).unwrap();
+        assert!(ctx.cells_accessed.is_empty());
+    }
 }
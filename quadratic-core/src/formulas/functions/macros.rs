@@ -75,6 +75,9 @@ macro_rules! see_docs_for_more_about_wildcards {
 ///
 /// - `#[doc = "..."]` (or doc comments using `///`) - user-facing documentation
 /// - `#[operator]` - removes the function from documentation
+/// - `#[include_args_in_completion(false)]` - omits arguments from the
+///   autocomplete snippet
+/// - `#[volatile(true)]` - recomputes the function on every recalculation
 /// - `#[examples("EXAMPLE()", "EXAMPLE(A, B)")]` - example usages
 /// - `#[zip_map]` - if certain arguments are arrays, **zip** them together
 ///                       and **map** a **pure** function over them.
@@ -94,6 +97,14 @@ macro_rules! see_docs_for_more_about_wildcards {
 /// - `f64` - coerce to `f64`
 /// - `bool` - coerce to `bool`
 ///
+/// Lazy types:
+/// - `Spanned<ArgValue>` - keep cell references without reading the cells
+/// - `Iter<Spanned<ArgValue>>` - repeating version of `Spanned<ArgValue>`
+/// - `LazyArg` - do not evaluate the argument until the function asks for it,
+///   so that arguments the function does not need are never evaluated
+/// - `Option<LazyArg>` and `Iter<LazyArg>` - optional and repeating versions
+///   of `LazyArg`
+///
 /// Generic types:
 /// - `arg: Option< ... >` - optional argument (type is `Option< ... >`)
 /// - `arg: Iter< ... >` - repeating argument (type is `impl Iterator<Item= ... >`)
@@ -124,6 +135,7 @@ macro_rules! formula_fn {
             usage: "",
             examples: &[],
             doc: "",
+            volatile: false,
             eval: formula_fn_eval!(
                 { $($body)* };
                 $(#[$($attr)*])*
@@ -136,23 +148,27 @@ macro_rules! formula_fn {
         #[doc = $doc:expr]
         $(#[doc = $additional_doc:expr])*
         $(#[include_args_in_completion($include_args_in_completion:expr)])?
+        $(#[volatile($volatile:expr)])?
         #[examples($($example_str:expr),+ $(,)?)]
         $(#[$($attr:tt)*])*
-        fn $fn_name:ident( $($params:tt)* ) { $($body:tt)* }
+        fn $fn_name:tt( $($params:tt)* ) { $($body:tt)* }
     ) => {{
         let params_list = params_list!($($params)*);
 
         // Default to `true`
         let include_args_in_completion = [$($include_args_in_completion, )? true][0];
+        // Default to `false`
+        let volatile = [$($volatile, )? false][0];
 
         $crate::formulas::functions::FormulaFunction {
-            name: stringify!($fn_name),
+            name: formula_fn_name!($fn_name),
             arg_completion: include_args_in_completion.then(|| {
                 $crate::formulas::params::arg_completion_string(&params_list)
             }),
             usage: $crate::formulas::params::usage_string(&params_list),
             examples: &[$($example_str),+],
             doc: concat!($doc $(, "\n", $additional_doc)*),
+            volatile,
             eval: formula_fn_eval!(
                 { $($body)* };
                 $(#[$($attr)*])*
@@ -162,11 +178,23 @@ macro_rules! formula_fn {
     }};
 }
 
+/// Returns the name of a formula function as a string. Names that are not
+/// valid Rust identifiers, such as `ERROR.TYPE`, are written as string
+/// literals.
+macro_rules! formula_fn_name {
+    ($fn_name:ident) => {
+        stringify!($fn_name)
+    };
+    ($fn_name:literal) => {
+        $fn_name
+    };
+}
+
 /// Constructs the `eval` function for a `FormulaFunction`.
 macro_rules! formula_fn_eval {
     ($($tok:tt)*) => {{
         #[allow(unused_mut)]
-        let ret: FormulaFn = |_ctx: &mut Ctx<'_>, mut _args: FormulaFnArgs| -> CodeResult<Value> {
+        let ret: FormulaFn = |_ctx: &mut Ctx<'_>, mut _args: FormulaFnArgs<'_>| -> CodeResult<Value> {
             formula_fn_eval_inner!(_ctx, _args, $($tok)*)
         };
         ret
@@ -229,6 +257,10 @@ macro_rules! formula_fn_eval_inner {
         formula_fn_args!(@assign($ctx, $args); $($params)*);
         $args.error_if_more_args()?;
 
+        // Assign the context last, because taking arguments may need to read
+        // cells using the context.
+        formula_fn_args!(@assign_ctx($ctx); $($params)*);
+
         // Evaluate the body of the function.
         Ok(Value::from($body))
     }};
@@ -267,8 +299,14 @@ macro_rules! formula_fn_arg {
 
     // Context argument
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Ctx) => {
+        // Do nothing; we will assign this argument after all the others.
+    };
+    (@assign_ctx($ctx:ident); $arg_name:ident: Ctx) => {
         let $arg_name: &mut Ctx<'_> = &mut *$ctx; // Reborrow context
     };
+    (@assign_ctx $data:tt; $arg_name:tt: $($arg_type:tt)*) => {
+        // Do nothing; this is not the context argument.
+    };
     // Span argument
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Span) => {
         let $arg_name = $args.span;
@@ -297,17 +335,34 @@ macro_rules! formula_fn_arg {
     };
 
     // Repeating argument
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< ArgValue >>) => {
+        formula_fn_arg!(@assign($ctx, $args); $arg_name: Iter< Spanned< ArgValue > >)
+    };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< ArgValue > >) => {
+        // Do not read referenced cells.
+        let mut $arg_name = $args.take_rest_args($ctx)?.map(CodeResult::Ok);
+    };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< LazyArg >) => {
+        // Do not evaluate arguments.
+        let mut $arg_name = $args.take_rest_lazy();
+    };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Value >>) => {
+        formula_fn_arg!(@assign($ctx, $args); $arg_name: Iter< Spanned< Value > >)
+    };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Array >>) => {
+        formula_fn_arg!(@assign($ctx, $args); $arg_name: Iter< Spanned< Array > >)
+    };
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Value > >) => {
         // Do not flatten `Value`s.
-        let mut $arg_name = $args.take_rest().map(CodeResult::Ok);
+        let mut $arg_name = $args.take_rest($ctx)?.map(CodeResult::Ok);
     };
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< Array > >) => {
         // Do not flatten arrays.
-        let mut $arg_name = $args.take_rest().map(Array::from).map(CodeResult::Ok);
+        let mut $arg_name = $args.take_rest($ctx)?.map(|v| v.map(Array::from)).map(CodeResult::Ok);
     };
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Iter< Spanned< $($arg_type:tt)*) => {
         // Flatten into iterator over non-array type.
-        let remaining_args = $args.take_rest();
+        let remaining_args = $args.take_rest($ctx)?;
         let $arg_name = remaining_args.flat_map(|arg_value| {
             formula_fn_convert_arg!(arg_value, Value -> Iter< Spanned< $($arg_type)*)
         });
@@ -318,9 +373,28 @@ macro_rules! formula_fn_arg {
         let mut $arg_name = $arg_name.without_spans();
     };
 
+    // Argument that may be a reference to cells that have not been read
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Option< Spanned< ArgValue >>) => {
+        formula_fn_arg!(@assign($ctx, $args); $arg_name: Option< Spanned< ArgValue > >)
+    };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Option< Spanned< ArgValue > >) => {
+        let $arg_name = $args.take_next_optional_arg($ctx)?;
+    };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Spanned< ArgValue >) => {
+        let $arg_name = $args.take_next_required_arg($ctx, stringify!($arg_name))?;
+    };
+
+    // Argument that has not been evaluated
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: Option< LazyArg >) => {
+        let $arg_name = $args.take_next_optional_lazy();
+    };
+    (@assign($ctx:ident, $args:ident); $arg_name:ident: LazyArg) => {
+        let $arg_name = $args.take_next_required_lazy(stringify!($arg_name))?;
+    };
+
     // Optional argument
     (@assign($ctx:ident, $args:ident); $arg_name:ident: Option< $($arg_type:tt)*) => {
-        let $arg_name = match $args.take_next_optional() {
+        let $arg_name = match $args.take_next_optional($ctx)? {
             // $($arg_type)* will include an extra `>` at the end, and that's ok.
             Some(arg_value) => Some(formula_fn_convert_arg!(arg_value, Value -> $($arg_type)*)),
             None => None,
@@ -329,7 +403,7 @@ macro_rules! formula_fn_arg {
 
     // Required argument
     (@assign($ctx:ident, $args:ident); $arg_name:ident: $($arg_type:tt)*) => {
-        let arg_value = $args.take_next_required(stringify!($arg_name))?;
+        let arg_value = $args.take_next_required($ctx, stringify!($arg_name))?;
         let $arg_name = formula_fn_convert_arg!(arg_value, Value -> $($arg_type)*);
     };
 
@@ -356,7 +430,7 @@ macro_rules! formula_fn_arg {
         // If the argument is present, store it in `$args_to_zip_map` and store
         // the index in `$arg_name`.
         let $arg_name: Option<usize>;
-        match $args.take_next_optional() {
+        match $args.take_next_optional($ctx)? {
             Some(arg_value) => {
                 $arg_name = Some($args_to_zip_map.len());
                 $args_to_zip_map.push(arg_value);
@@ -379,7 +453,7 @@ macro_rules! formula_fn_arg {
         // Store the actual argument in `$args_to_zip_map`, and store its index
         // in `$arg_name`.
         let $arg_name = $args_to_zip_map.len();
-        $args_to_zip_map.push($args.take_next_required(stringify!($arg_name))?);
+        $args_to_zip_map.push($args.take_next_required($ctx, stringify!($arg_name))?);
     };
     (@unzip($ctx:ident, $zipped_args:ident); [$arg_name:ident]: $($arg_type:tt)*) => {
         // Grab the index stored above and unpack the argument into `$arg_name`
@@ -486,6 +560,7 @@ macro_rules! params_list {
     // Entry points (at the bottom so that the other rules take priority)
     () => { vec![] };
     ($($arg_name:tt: $arg_type:tt),+ $(,)?) => {{
+        #[allow(unused_mut)] // Parameters may all be hidden, such as `Span`.
         let mut result = vec![];
 
         $(
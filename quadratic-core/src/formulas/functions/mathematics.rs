@@ -1,3 +1,6 @@
+use bigdecimal::RoundingMode;
+use rand::Rng;
+
 use super::*;
 
 pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
@@ -41,6 +44,27 @@ fn get_functions() -> Vec<FormulaFunction> {
                 numbers.sum::<CodeResult<f64>>()
             }
         ),
+        formula_fn!(
+            /// Adds values in `numbers_range` wherever the corresponding values
+            /// in every `eval_range` meet the corresponding `criteria`.
+            ///
+            /// Each `eval_range` must be the same size as `numbers_range`.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "SUMIFS(C1:C10, A1:A10, \">0\")",
+                "SUMIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn SUMIFS(
+                span: Span,
+                numbers_range: (Spanned<Array>),
+                eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs("SUMIFS", span, eval_ranges_and_criteria)?;
+                let numbers =
+                    Criterion::iter_matching_all_coerced::<f64>(&criteria, &numbers_range)?;
+                numbers.sum::<CodeResult<f64>>()
+            }
+        ),
         formula_fn!(
             /// Multiplies all values.
             /// Returns `1` if given no values.
@@ -49,6 +73,35 @@ fn get_functions() -> Vec<FormulaFunction> {
                 numbers.product::<CodeResult<f64>>()
             }
         ),
+        formula_fn!(
+            /// Multiplies corresponding values in the arrays, and then adds
+            /// the products. All arrays must be the same size.
+            ///
+            /// Values that are not numbers are treated as zero.
+            #[examples("SUMPRODUCT(A1:A10, B1:B10)", "SUMPRODUCT({1, 2}, {3, 4})")]
+            fn SUMPRODUCT(span: Span, arrays: (Iter<Spanned<Array>>)) {
+                let arrays: Vec<Spanned<Array>> = arrays.try_collect()?;
+                let Some(first) = arrays.first() else {
+                    return Err(util::missing_arg_error("SUMPRODUCT", "arrays", span));
+                };
+                let mut products = vec![1.0; first.inner.cell_values_slice().len()];
+                for array in &arrays {
+                    array.check_array_size_exact(first.inner.size())?;
+                    let values = array.inner.cell_values_slice().iter();
+                    for (product, value) in std::iter::zip(&mut products, values) {
+                        let value = Spanned {
+                            span: array.span,
+                            inner: value,
+                        };
+                        *product *= match value.coerce_or_none::<f64>() {
+                            Some(n) => n?.inner,
+                            None => 0.0,
+                        };
+                    }
+                }
+                products.into_iter().sum::<f64>()
+            }
+        ),
         formula_fn!(
             /// Returns the absolute value of a number.
             #[examples("ABS(-4)")]
@@ -65,6 +118,399 @@ fn get_functions() -> Vec<FormulaFunction> {
                 number.sqrt()
             }
         ),
+        formula_fn!(
+            /// Returns the sign of a number: `1` if it is positive, `-1` if it
+            /// is negative, or `0` if it is zero.
+            #[examples("SIGN(-4)")]
+            #[zip_map]
+            fn SIGN([number]: f64) {
+                if number == 0.0 {
+                    0.0
+                } else {
+                    number.signum()
+                }
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number to `digits` decimal places, rounding halfway
+            /// values away from zero. If `digits` is omitted, the number is
+            /// rounded to an integer. If `digits` is negative, the number is
+            /// rounded to the left of the decimal point.
+            #[examples("ROUND(2.675, 2)", "ROUND(1234, -2)")]
+            #[zip_map]
+            fn ROUND(span: Span, [number]: f64, [digits]: (Option<i64>)) {
+                util::round_to_digits(*span, number, digits.unwrap_or(0), RoundingMode::HalfUp)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number away from zero to `digits` decimal places. If
+            /// `digits` is omitted, the number is rounded to an integer. If
+            /// `digits` is negative, the number is rounded to the left of the
+            /// decimal point.
+            #[examples("ROUNDUP(3.14159, 2)", "ROUNDUP(-3.2)")]
+            #[zip_map]
+            fn ROUNDUP(span: Span, [number]: f64, [digits]: (Option<i64>)) {
+                util::round_to_digits(*span, number, digits.unwrap_or(0), RoundingMode::Up)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number toward zero to `digits` decimal places. If
+            /// `digits` is omitted, the number is rounded to an integer. If
+            /// `digits` is negative, the number is rounded to the left of the
+            /// decimal point.
+            #[examples("ROUNDDOWN(3.14159, 2)", "ROUNDDOWN(-3.8)")]
+            #[zip_map]
+            fn ROUNDDOWN(span: Span, [number]: f64, [digits]: (Option<i64>)) {
+                util::round_to_digits(*span, number, digits.unwrap_or(0), RoundingMode::Down)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number to the nearest multiple of `multiple`, rounding
+            /// halfway values away from zero.
+            ///
+            /// Returns an error if `number` and `multiple` have different
+            /// signs.
+            #[examples("MROUND(10, 3)", "MROUND(1.3, 0.2)")]
+            #[zip_map]
+            fn MROUND(span: Span, [number]: f64, [multiple]: f64) {
+                if number * multiple < 0.0 {
+                    return Err(ErrorMsg::InvalidArgument.with_span(*span));
+                }
+                util::round_to_multiple(*span, number, multiple, RoundingMode::HalfUp)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number up to the nearest multiple of `significance`,
+            /// which is `1` if omitted.
+            ///
+            /// If `number` and `significance` are both negative, then the
+            /// number is rounded away from zero instead. Returns an error if
+            /// `number` is positive and `significance` is negative.
+            #[examples("CEILING(2.5, 2)", "CEILING(-2.5, -2)", "CEILING(1.3)")]
+            #[zip_map]
+            fn CEILING(span: Span, [number]: f64, [significance]: (Option<f64>)) {
+                let significance = significance.unwrap_or(1.0);
+                if number > 0.0 && significance < 0.0 {
+                    return Err(ErrorMsg::InvalidArgument.with_span(*span));
+                }
+                util::round_to_multiple(*span, number, significance, RoundingMode::Ceiling)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number down to the nearest multiple of `significance`,
+            /// which is `1` if omitted.
+            ///
+            /// If `number` and `significance` are both negative, then the
+            /// number is rounded toward zero instead. Returns an error if
+            /// `number` is positive and `significance` is negative.
+            #[examples("FLOOR(2.5, 2)", "FLOOR(-2.5, -2)", "FLOOR(1.7)")]
+            #[zip_map]
+            fn FLOOR(span: Span, [number]: f64, [significance]: (Option<f64>)) {
+                let significance = significance.unwrap_or(1.0);
+                if number > 0.0 && significance < 0.0 {
+                    return Err(ErrorMsg::InvalidArgument.with_span(*span));
+                }
+                util::round_to_multiple(*span, number, significance, RoundingMode::Floor)?
+            }
+        ),
+        formula_fn!(
+            /// Rounds a number down to the nearest integer. For negative
+            /// numbers, this rounds away from zero.
+            #[examples("INT(8.9)", "INT(-8.9)")]
+            #[zip_map]
+            fn INT(span: Span, [number]: f64) {
+                util::round_to_digits(*span, number, 0, RoundingMode::Floor)?
+            }
+        ),
+        formula_fn!(
+            /// Truncates a number to `digits` decimal places by removing the
+            /// remaining digits. If `digits` is omitted, the number is
+            /// truncated to an integer.
+            #[examples("TRUNC(8.9)", "TRUNC(-8.9)", "TRUNC(3.14159, 2)")]
+            #[zip_map]
+            fn TRUNC(span: Span, [number]: f64, [digits]: (Option<i64>)) {
+                util::round_to_digits(*span, number, digits.unwrap_or(0), RoundingMode::Down)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the remainder after dividing `number` by `divisor`. The
+            /// result has the same sign as `divisor`.
+            #[examples("MOD(3, 2)", "MOD(-3, 2)")]
+            #[zip_map]
+            fn MOD(span: Span, [number]: f64, [divisor]: f64) {
+                if divisor == 0.0 {
+                    return Err(ErrorMsg::DivideByZero.with_span(*span));
+                }
+                let remainder = number % divisor;
+                if remainder != 0.0 && (remainder < 0.0) != (divisor < 0.0) {
+                    remainder + divisor
+                } else {
+                    remainder
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns `base` raised to the power of `exponent`. This is
+            /// equivalent to `base ^ exponent`.
+            #[examples("POWER(2, 10)")]
+            #[zip_map]
+            fn POWER([base]: f64, [exponent]: f64) {
+                base.powf(exponent)
+            }
+        ),
+        formula_fn!(
+            /// Returns *e* raised to the power of a number.
+            #[examples("EXP(1)")]
+            #[zip_map]
+            fn EXP([number]: f64) {
+                number.exp()
+            }
+        ),
+        formula_fn!(
+            /// Returns the natural logarithm of a number, which must be
+            /// positive.
+            #[examples("LN(50)")]
+            #[zip_map]
+            fn LN([number]: (Spanned<f64>)) {
+                util::positive(number)?.ln()
+            }
+        ),
+        formula_fn!(
+            /// Returns the logarithm of a number to the base `base`, which is
+            /// `10` if omitted. Both must be positive.
+            #[examples("LOG(100)", "LOG(8, 2)")]
+            #[zip_map]
+            fn LOG(span: Span, [number]: (Spanned<f64>), [base]: (Option<Spanned<f64>>)) {
+                let number = util::positive(number)?;
+                let base = match base {
+                    Some(base) => util::positive(base)?,
+                    None => 10.0,
+                };
+                util::checked_div(*span, number.ln(), base.ln())?
+            }
+        ),
+        formula_fn!(
+            /// Returns the base-10 logarithm of a number, which must be
+            /// positive.
+            #[examples("LOG10(100)")]
+            #[zip_map]
+            fn LOG10([number]: (Spanned<f64>)) {
+                util::positive(number)?.log10()
+            }
+        ),
+        formula_fn!(
+            /// Returns the greatest common divisor of the numbers, which are
+            /// truncated to integers and must not be negative.
+            #[examples("GCD(24, 36)", "GCD(A1:A10)")]
+            fn GCD(numbers: (Iter<Spanned<f64>>)) {
+                let mut result = 0;
+                for n in numbers {
+                    result = gcd(result, to_natural(n?)?);
+                }
+                result as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the least common multiple of the numbers, which are
+            /// truncated to integers and must not be negative.
+            #[examples("LCM(4, 6)", "LCM(A1:A10)")]
+            fn LCM(span: Span, numbers: (Iter<Spanned<f64>>)) {
+                let mut result: u64 = 1;
+                for n in numbers {
+                    let n = to_natural(n?)?;
+                    if n == 0 {
+                        return Ok(Value::from(0.0));
+                    }
+                    result = (result / gcd(result, n))
+                        .checked_mul(n)
+                        .filter(|&lcm| lcm <= MAX_SAFE_INTEGER)
+                        .ok_or_else(|| ErrorMsg::Overflow.with_span(span))?;
+                }
+                result as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the factorial of a number, which is truncated to an
+            /// integer and must not be negative.
+            #[examples("FACT(5)")]
+            #[zip_map]
+            fn FACT(span: Span, [number]: (Spanned<f64>)) {
+                // 171! is too large to represent
+                match to_natural(number)? {
+                    n @ 0..=170 => (1..=n).map(|i| i as f64).product::<f64>(),
+                    _ => return Err(ErrorMsg::Overflow.with_span(*span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of ways to choose `k` items from `n` items
+            /// when order does not matter. Both are truncated to integers.
+            #[examples("COMBIN(8, 2)")]
+            #[zip_map]
+            fn COMBIN(span: Span, [n]: (Spanned<f64>), [k]: (Spanned<f64>)) {
+                let (n, k) = choose_args(n, k)?;
+                let k = k.min(n - k);
+                let combinations =
+                    finite_product(*span, (1..=k).map(|i| (n - k + i) as f64 / i as f64))?;
+                combinations.round()
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of ways to choose `k` items from `n` items
+            /// when order matters. Both are truncated to integers.
+            #[examples("PERMUT(8, 2)")]
+            #[zip_map]
+            fn PERMUT(span: Span, [n]: (Spanned<f64>), [k]: (Spanned<f64>)) {
+                let (n, k) = choose_args(n, k)?;
+                finite_product(*span, (n - k + 1..=n).map(|i| i as f64))?
+            }
+        ),
+        // Matrices
+        formula_fn!(
+            /// Returns the [matrix product](https://en.wikipedia.org/wiki/Matrix_multiplication)
+            /// of `array1` and `array2`.
+            ///
+            /// The result has as many rows as `array1` and as many columns as
+            /// `array2`. Returns an error if the width of `array1` is not the
+            /// same as the height of `array2`, or if either contains a value
+            /// that is not a number.
+            #[examples("MMULT(A1:B2, C1:D2)", "MMULT({1, 2; 3, 4}, {5; 6})")]
+            fn MMULT(span: Span, array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                let (width, height) = (array1.inner.width(), array2.inner.height());
+                if width != height {
+                    return Err(ErrorMsg::ArrayAxisMismatch {
+                        axis: Axis::Y,
+                        expected: width,
+                        got: height,
+                    }
+                    .with_span(array2.span));
+                }
+                let a = util::matrix_from_array(&array1)?;
+                let b = util::matrix_from_array(&array2)?;
+                util::matrix_to_array(span, &util::matrix_product(&a, &b))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the [determinant](https://en.wikipedia.org/wiki/Determinant)
+            /// of a square matrix.
+            ///
+            /// Returns an error if `matrix` is not square or contains a value
+            /// that is not a number.
+            #[examples("MDETERM(A1:C3)", "MDETERM({1, 2; 3, 4}) = -2")]
+            fn MDETERM(span: Span, matrix: (Spanned<Array>)) {
+                util::square_matrix_size(&matrix)?;
+                util::finite(span, util::determinant(util::matrix_from_array(&matrix)?))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the [inverse](https://en.wikipedia.org/wiki/Invertible_matrix)
+            /// of a square matrix.
+            ///
+            /// Returns an error if `matrix` is not square, contains a value
+            /// that is not a number, or is singular (its determinant is zero).
+            #[examples("MINVERSE(A1:C3)", "MINVERSE({4, 7; 2, 6})")]
+            fn MINVERSE(span: Span, matrix: (Spanned<Array>)) {
+                util::square_matrix_size(&matrix)?;
+                let inverse = util::inverse_matrix(&util::matrix_from_array(&matrix)?)
+                    .ok_or_else(|| ErrorMsg::InvalidArgument.with_span(matrix.span))?;
+                util::matrix_to_array(span, &inverse)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the identity matrix with `dimension` rows and columns.
+            ///
+            /// Returns an error if `dimension` is less than 1.
+            #[examples("MUNIT(3)")]
+            fn MUNIT(span: Span, dimension: (Spanned<i64>)) {
+                let n = util::positive_len(dimension)?;
+                util::array_size(span, n, n)?;
+                util::matrix_to_array(span, &util::identity_matrix(n as usize))?
+            }
+        ),
+        // Random numbers
+        formula_fn!(
+            /// Returns a random number between 0 (inclusive) and 1
+            /// (exclusive).
+            ///
+            /// A new random number is generated each time the sheet is
+            /// recalculated.
+            #[include_args_in_completion(false)]
+            #[volatile(true)]
+            #[examples("RAND()", "RAND() * 10")]
+            fn RAND(ctx: Ctx) {
+                ctx.rng().gen::<f64>()
+            }
+        ),
+        formula_fn!(
+            /// Returns a random integer between `bottom` and `top`, inclusive.
+            ///
+            /// `bottom` is rounded up and `top` is rounded down to the nearest
+            /// integer. Returns an error if there is no integer between them.
+            ///
+            /// A new random number is generated each time the sheet is
+            /// recalculated.
+            #[volatile(true)]
+            #[examples("RANDBETWEEN(1, 6)", "RANDBETWEEN(-100, 100)")]
+            fn RANDBETWEEN(ctx: Ctx, bottom: (Spanned<f64>), top: (Spanned<f64>)) {
+                let bottom = to_safe_integer(bottom.map(f64::ceil))?;
+                let top_span = top.span;
+                let top = to_safe_integer(top.map(f64::floor))?;
+                if bottom > top {
+                    return Err(ErrorMsg::InvalidArgument.with_span(top_span));
+                }
+                ctx.rng().gen_range(bottom..=top) as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns an array of random numbers with `rows` rows and
+            /// `columns` columns.
+            ///
+            /// The numbers are between `min` (inclusive) and `max`
+            /// (exclusive), which default to 0 and 1. If `whole_number` is
+            /// true, the numbers are instead integers between `min` and `max`,
+            /// inclusive.
+            ///
+            /// New random numbers are generated each time the sheet is
+            /// recalculated.
+            #[volatile(true)]
+            #[examples("RANDARRAY(5)", "RANDARRAY(3, 4, 1, 100, TRUE)")]
+            fn RANDARRAY(
+                ctx: Ctx,
+                span: Span,
+                rows: (Option<Spanned<i64>>),
+                columns: (Option<Spanned<i64>>),
+                min: (Option<Spanned<f64>>),
+                max: (Option<Spanned<f64>>),
+                whole_number: (Option<bool>),
+            ) {
+                let rows = rows.map_or(Ok(1), util::positive_len)?;
+                let columns = columns.map_or(Ok(1), util::positive_len)?;
+                let size = util::array_size(span, columns, rows)?;
+                let min = min.unwrap_or(Spanned { span, inner: 0.0 });
+                let max = max.unwrap_or(Spanned { span, inner: 1.0 });
+                if min.inner > max.inner {
+                    return Err(ErrorMsg::InvalidArgument.with_span(max.span));
+                }
+                let rng = ctx.rng();
+                let values = if whole_number.unwrap_or(false) {
+                    let min = to_safe_integer(min.map(f64::ceil))?;
+                    let max_span = max.span;
+                    let max = to_safe_integer(max.map(f64::floor))?;
+                    if min > max {
+                        return Err(ErrorMsg::InvalidArgument.with_span(max_span));
+                    }
+                    (0..size.len())
+                        .map(|_| CellValue::from(rng.gen_range(min..=max)))
+                        .collect()
+                } else {
+                    let (min, max) = (min.inner, max.inner);
+                    (0..size.len())
+                        .map(|_| CellValue::from(min + (max - min) * rng.gen::<f64>()))
+                        .collect()
+                };
+                Array::new_row_major(size, values)?
+            }
+        ),
         // Constants
         formula_fn!(
             /// Returns π, the circle constant.
@@ -83,6 +529,53 @@ fn get_functions() -> Vec<FormulaFunction> {
     ]
 }
 
+/// Largest integer that can be represented exactly by an `f64`.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Truncates a number to an integer, returning an error if it is negative or
+/// too large to be represented exactly.
+fn to_natural(n: Spanned<f64>) -> CodeResult<u64> {
+    let truncated = n.inner.trunc();
+    match 0.0 <= truncated && truncated <= MAX_SAFE_INTEGER as f64 {
+        true => Ok(truncated as u64),
+        false => Err(ErrorMsg::InvalidArgument.with_span(n.span)),
+    }
+}
+
+/// Converts an integer-valued number to an `i64`, returning an error if it is
+/// too large to be represented exactly.
+fn to_safe_integer(n: Spanned<f64>) -> CodeResult<i64> {
+    match n.inner.abs() <= MAX_SAFE_INTEGER as f64 {
+        true => Ok(n.inner as i64),
+        false => Err(ErrorMsg::InvalidArgument.with_span(n.span)),
+    }
+}
+
+/// Validates the arguments to `COMBIN` or `PERMUT`.
+fn choose_args(n: Spanned<f64>, k: Spanned<f64>) -> CodeResult<(u64, u64)> {
+    let n = to_natural(n)?;
+    let k_span = k.span;
+    match to_natural(k)? {
+        k if k <= n => Ok((n, k)),
+        _ => Err(ErrorMsg::InvalidArgument.with_span(k_span)),
+    }
+}
+
+/// Multiplies `factors`, returning an error as soon as the product overflows so
+/// that long products stop early.
+fn finite_product(span: Span, factors: impl Iterator<Item = f64>) -> CodeResult<f64> {
+    factors
+        .into_iter()
+        .try_fold(1.0, |product, factor| util::finite(span, product * factor))
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
 #[cfg(test)]
 mod tests {
     use crate::formulas::tests::*;
@@ -137,6 +630,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sumifs() {
+        let g = Grid::new();
+        assert_eq!(
+            "60",
+            eval_to_string(&g, "SUMIFS(2^0..10, 0..10, \"<=5\", 0..10, \">1\")"),
+        );
+        assert_eq!("0", eval_to_string(&g, "SUMIFS(0..10, 0..10, \">20\")"));
+
+        // Error on range size mismatch.
+        assert_eq!(
+            ErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 11).unwrap(),
+                got: ArraySize::new(1, 6).unwrap(),
+            },
+            eval_to_err(&g, "SUMIFS(0..10, 0..10, \">1\", 0..5, \">1\")").msg,
+        );
+
+        // Error on missing criteria.
+        assert_eq!(
+            ErrorMsg::MissingRequiredArgument {
+                func_name: "SUMIFS".into(),
+                arg_name: "criteria".into(),
+            },
+            eval_to_err(&g, "SUMIFS(0..10, 0..10, \">1\", 0..10)").msg,
+        );
+        assert_eq!(
+            ErrorMsg::MissingRequiredArgument {
+                func_name: "SUMIFS".into(),
+                arg_name: "eval_range".into(),
+            },
+            eval_to_err(&g, "SUMIFS(0..10)").msg,
+        );
+    }
+
     #[test]
     fn test_product() {
         let g = Grid::new();
@@ -260,4 +788,311 @@ mod tests {
                 .msg,
         );
     }
+
+    #[test]
+    fn test_sumproduct() {
+        let g = Grid::new();
+        assert_eq!(
+            "156",
+            eval_to_string(&g, "SUMPRODUCT({3, 4; 8, 6; 1, 9}, {2, 7; 6, 7; 5, 3})"),
+        );
+        assert_eq!("6", eval_to_string(&g, "SUMPRODUCT({1, 2, 3})"));
+        // Values that are not numbers are treated as zero.
+        assert_eq!("3", eval_to_string(&g, "SUMPRODUCT({1, \"a\"}, {3, 4})"));
+        assert_eq!(
+            ErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(2, 1).unwrap(),
+                got: ArraySize::new(3, 1).unwrap(),
+            },
+            eval_to_err(&g, "SUMPRODUCT({1, 2}, {1, 2, 3})").msg,
+        );
+        assert_eq!(
+            ErrorMsg::MissingRequiredArgument {
+                func_name: "SUMPRODUCT".into(),
+                arg_name: "arrays".into(),
+            },
+            eval_to_err(&g, "SUMPRODUCT()").msg,
+        );
+    }
+
+    #[test]
+    fn test_round() {
+        let g = Grid::new();
+        // Rounding is done in decimal, not binary.
+        assert_eq!("2.68", eval_to_string(&g, "ROUND(2.675, 2)"));
+        assert_eq!("1.01", eval_to_string(&g, "ROUND(1.005, 2)"));
+        assert_eq!("3", eval_to_string(&g, "ROUND(2.5)"));
+        assert_eq!("-3", eval_to_string(&g, "ROUND(-2.5)"));
+        assert_eq!("1200", eval_to_string(&g, "ROUND(1234, -2)"));
+        assert_eq!("0", eval_to_string(&g, "ROUND(1234.5678, -5)"));
+        assert_eq!("2.5", eval_to_string(&g, "ROUND(2.5, 3)"));
+        assert_eq!("2", eval_to_string(&g, "ROUND(1.999, 2)"));
+        assert_eq!("0", eval_to_string(&g, "ROUND(0.001, 2)"));
+        assert_eq!("{1.2, 1.23}", eval_to_string(&g, "ROUND(1.234, {1, 2})"));
+        // Extreme numbers of digits.
+        assert_eq!("1.5", eval_to_string(&g, "ROUND(1.5, 1e15)"));
+        assert_eq!("1.5", eval_to_string(&g, "ROUND(1.5, 1e300)"));
+        assert_eq!("0", eval_to_string(&g, "ROUND(1, -1e15)"));
+        assert_eq!("0", eval_to_string(&g, "ROUND(-1e300, -1e300)"));
+        assert_eq!("0", eval_to_string(&g, "ROUND(1.7e308, -310)"));
+        assert_eq!("0", eval_to_string(&g, "ROUNDDOWN(5, -1e15)"));
+        assert_eq!("0", eval_to_string(&g, "ROUNDUP(0, -1e15)"));
+        expect_err(&ErrorMsg::Overflow, &g, "ROUNDUP(1, -1e15)");
+        expect_err(&ErrorMsg::Overflow, &g, "ROUNDUP(-1, -1e300)");
+
+        assert_eq!("3.15", eval_to_string(&g, "ROUNDUP(3.14159, 2)"));
+        assert_eq!("-4", eval_to_string(&g, "ROUNDUP(-3.2)"));
+        assert_eq!("31500", eval_to_string(&g, "ROUNDUP(31415.92654, -2)"));
+        assert_eq!("3.14", eval_to_string(&g, "ROUNDDOWN(3.14159, 2)"));
+        assert_eq!("-3", eval_to_string(&g, "ROUNDDOWN(-3.8)"));
+    }
+
+    #[test]
+    fn test_mround_ceiling_floor() {
+        let g = Grid::new();
+        assert_eq!("9", eval_to_string(&g, "MROUND(10, 3)"));
+        assert_eq!("-9", eval_to_string(&g, "MROUND(-10, -3)"));
+        assert_eq!("1.4", eval_to_string(&g, "MROUND(1.3, 0.2)"));
+        assert_eq!("0", eval_to_string(&g, "MROUND(5, 0)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "MROUND(5, -2)");
+
+        assert_eq!("4", eval_to_string(&g, "CEILING(2.5, 2)"));
+        assert_eq!("-4", eval_to_string(&g, "CEILING(-2.5, -2)"));
+        assert_eq!("-2", eval_to_string(&g, "CEILING(-2.5, 2)"));
+        assert_eq!("2", eval_to_string(&g, "CEILING(1.3)"));
+        assert_eq!("4.45", eval_to_string(&g, "CEILING(4.42, 0.05)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "CEILING(2.5, -2)");
+
+        assert_eq!("2", eval_to_string(&g, "FLOOR(2.5, 2)"));
+        assert_eq!("-2", eval_to_string(&g, "FLOOR(-2.5, -2)"));
+        assert_eq!("-4", eval_to_string(&g, "FLOOR(-2.5, 2)"));
+        assert_eq!("1", eval_to_string(&g, "FLOOR(1.7)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "FLOOR(2.5, -2)");
+    }
+
+    #[test]
+    fn test_int_trunc_mod_sign() {
+        let g = Grid::new();
+        assert_eq!("8", eval_to_string(&g, "INT(8.9)"));
+        assert_eq!("-9", eval_to_string(&g, "INT(-8.9)"));
+        assert_eq!("-8", eval_to_string(&g, "TRUNC(-8.9)"));
+        assert_eq!("3.14", eval_to_string(&g, "TRUNC(3.14159, 2)"));
+
+        assert_eq!("1", eval_to_string(&g, "MOD(3, 2)"));
+        assert_eq!("1", eval_to_string(&g, "MOD(-3, 2)"));
+        assert_eq!("-1", eval_to_string(&g, "MOD(3, -2)"));
+        assert_eq!("1.5", eval_to_string(&g, "MOD(5.5, 2)"));
+        expect_err(&ErrorMsg::DivideByZero, &g, "MOD(3, 0)");
+
+        assert_eq!("{-1, 0, 1}", eval_to_string(&g, "SIGN({-4, 0, 0.5})"));
+    }
+
+    #[test]
+    fn test_power_exp_log() {
+        let g = Grid::new();
+        assert_eq!("1024", eval_to_string(&g, "POWER(2, 10)"));
+        crate::util::assert_f64_approx_eq(std::f64::consts::E, &eval_to_string(&g, "EXP(1)"));
+        assert_eq!("3", eval_to_string(&g, "LN(EXP(3))"));
+        assert_eq!("2", eval_to_string(&g, "LOG(100)"));
+        assert_eq!("3", eval_to_string(&g, "LOG(8, 2)"));
+        assert_eq!("3", eval_to_string(&g, "LOG10(1000)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "LN(0)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "LOG(-1)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "LOG(8, 0)");
+        expect_err(&ErrorMsg::DivideByZero, &g, "LOG(8, 1)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "LOG10(-10)");
+    }
+
+    #[test]
+    fn test_gcd_lcm() {
+        let g = Grid::new();
+        assert_eq!("12", eval_to_string(&g, "GCD(24, 36)"));
+        assert_eq!("1", eval_to_string(&g, "GCD(7, 1..5)"));
+        assert_eq!("5", eval_to_string(&g, "GCD(5, 0)"));
+        assert_eq!("2", eval_to_string(&g, "GCD(4.9, 2)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "GCD(-4, 2)");
+
+        assert_eq!("12", eval_to_string(&g, "LCM(4, 6)"));
+        assert_eq!("2520", eval_to_string(&g, "LCM(1..10)"));
+        assert_eq!("0", eval_to_string(&g, "LCM(4, 0)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "LCM(-4, 2)");
+        expect_err(&ErrorMsg::Overflow, &g, "LCM(1..50)");
+    }
+
+    #[test]
+    fn test_fact_combin_permut() {
+        let g = Grid::new();
+        assert_eq!("120", eval_to_string(&g, "FACT(5)"));
+        assert_eq!("1", eval_to_string(&g, "FACT(0)"));
+        assert_eq!("1", eval_to_string(&g, "FACT(1.9)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "FACT(-1)");
+        expect_err(&ErrorMsg::Overflow, &g, "FACT(171)");
+        expect_err(&ErrorMsg::Overflow, &g, "FACT(1e15)");
+
+        assert_eq!("28", eval_to_string(&g, "COMBIN(8, 2)"));
+        assert_eq!("1", eval_to_string(&g, "COMBIN(8, 0)"));
+        assert_eq!("2598960", eval_to_string(&g, "COMBIN(52, 5)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "COMBIN(2, 3)");
+        expect_err(&ErrorMsg::Overflow, &g, "COMBIN(1e15, 1e14)");
+        assert_eq!("1000000000000000", eval_to_string(&g, "COMBIN(1e15, 1)"));
+
+        assert_eq!("970200", eval_to_string(&g, "PERMUT(100, 3)"));
+        assert_eq!("1", eval_to_string(&g, "PERMUT(5, 0)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "PERMUT(2, 3)");
+        expect_err(&ErrorMsg::Overflow, &g, "PERMUT(1e15, 1e14)");
+        expect_err(&ErrorMsg::Overflow, &g, "PERMUT(1e15, 1e15)");
+    }
+
+    #[test]
+    fn test_matrix_functions() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "{19, 22; 43, 50}",
+            eval_to_string(&g, "MMULT({1, 2; 3, 4}, {5, 6; 7, 8})"),
+        );
+        assert_eq!(
+            "{17; 39}",
+            eval_to_string(&g, "MMULT({1, 2; 3, 4}, {5; 6})")
+        );
+        assert_eq!("{32}", eval_to_string(&g, "MMULT({1, 2, 3}, {4; 5; 6})"));
+        assert_eq!(
+            ErrorMsg::ArrayAxisMismatch {
+                axis: crate::Axis::Y,
+                expected: 2,
+                got: 3,
+            },
+            eval_to_err(&g, "MMULT({1, 2; 3, 4}, {1; 2; 3})").msg,
+        );
+        expect_err(
+            &ErrorMsg::Expected {
+                expected: "number".into(),
+                got: Some("text".into()),
+            },
+            &g,
+            "MMULT({1, \"a\"}, {1; 2})",
+        );
+
+        assert_eq!("-2", eval_to_string(&g, "MDETERM({1, 2; 3, 4})"));
+        assert_eq!(
+            "1",
+            eval_to_string(&g, "ROUND(MDETERM({1, 2, 3; 0, 1, 4; 5, 6, 0}), 9)"),
+        );
+        assert_eq!("0", eval_to_string(&g, "MDETERM({1, 2; 2, 4})"));
+        assert_eq!("7", eval_to_string(&g, "MDETERM(7)"));
+        assert_eq!(
+            ErrorMsg::ArrayAxisMismatch {
+                axis: crate::Axis::X,
+                expected: 1,
+                got: 2,
+            },
+            eval_to_err(&g, "MDETERM({1, 2})").msg,
+        );
+
+        assert_eq!(
+            "{0.6, -0.7; -0.2, 0.4}",
+            eval_to_string(&g, "ROUND(MINVERSE({4, 7; 2, 6}), 9)"),
+        );
+        assert_eq!(
+            "{-24, 18, 5; 20, -15, -4; -5, 4, 1}",
+            eval_to_string(&g, "ROUND(MINVERSE({1, 2, 3; 0, 1, 4; 5, 6, 0}), 9)"),
+        );
+        assert_eq!(
+            "{1, 0; 0, 1}",
+            eval_to_string(&g, "ROUND(MMULT({4, 7; 2, 6}, MINVERSE({4, 7; 2, 6})), 9)"),
+        );
+        expect_err(&ErrorMsg::InvalidArgument, &g, "MINVERSE({1, 2; 2, 4})");
+
+        // Results too large to represent are errors.
+        assert_eq!(
+            "6",
+            eval_to_string(&g, "ERROR.TYPE(MMULT({1e300}, {1e300}))")
+        );
+        assert_eq!(
+            "{FALSE, TRUE}",
+            eval_to_string(&g, "ISERROR(MMULT({1, 1e300}, {1, 0; 0, 1e300}))"),
+        );
+        assert_eq!("6", eval_to_string(&g, "ERROR.TYPE(MINVERSE({1e-320}))"));
+        expect_err(&ErrorMsg::Overflow, &g, "MDETERM({1e300, 0; 0, 1e300})");
+
+        assert_eq!(
+            "{1, 0, 0; 0, 1, 0; 0, 0, 1}",
+            eval_to_string(&g, "MUNIT(3)")
+        );
+        assert_eq!("{1}", eval_to_string(&g, "MUNIT(1)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "MUNIT(0)");
+        expect_err(&ErrorMsg::ArrayTooBig, &g, "MUNIT(100000)");
+    }
+
+    fn eval_seeded(g: &Grid, seed: u64, s: &str) -> Value {
+        use rand::SeedableRng;
+
+        let mut ctx = Ctx::new(g, Pos::ORIGIN.with_sheet(g.sheets()[0].id));
+        ctx.rng = Some(rand::rngs::StdRng::seed_from_u64(seed));
+        parse_formula(s, Pos::ORIGIN)
+            .unwrap()
+            .eval(&mut ctx)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_random_numbers() {
+        let g = Grid::new();
+
+        for _ in 0..20 {
+            let n = eval(&g, "RAND()").to_string().parse::<f64>().unwrap();
+            assert!((0.0..1.0).contains(&n));
+
+            let n = eval(&g, "RANDBETWEEN(-2.5, 2.5)").to_string();
+            assert!(["-2", "-1", "0", "1", "2"].contains(&n.as_str()));
+        }
+        assert_eq!("3", eval_to_string(&g, "RANDBETWEEN(3, 3)"));
+        assert_eq!("4", eval_to_string(&g, "RANDBETWEEN(3.5, 4.5)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "RANDBETWEEN(5, 4)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "RANDBETWEEN(1.2, 1.8)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "RANDBETWEEN(0, 1e300)");
+
+        let Value::Array(a) = eval(&g, "RANDARRAY(3, 2, 10, 20)") else {
+            panic!("expected array");
+        };
+        assert_eq!((2, 3), (a.width(), a.height()));
+        for v in a.cell_values_slice() {
+            let n = f64::try_from(v).unwrap();
+            assert!((10.0..20.0).contains(&n));
+        }
+        assert_eq!(
+            "{7, 7; 7, 7}",
+            eval_to_string(&g, "RANDARRAY(2, 2, 7, 7, TRUE)"),
+        );
+        assert_eq!(
+            "{1; 1}",
+            eval_to_string(&g, "RANDARRAY(2, , 0.5, 1.5, TRUE)"),
+        );
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, "AND(RANDARRAY(10, 10, 0, 1, TRUE) <= 1)"),
+        );
+        expect_err(&ErrorMsg::InvalidArgument, &g, "RANDARRAY(0)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "RANDARRAY(2, 2, 5, 1)");
+        expect_err(&ErrorMsg::ArrayTooBig, &g, "RANDARRAY(100000, 100000)");
+
+        // The same seed gives the same results.
+        for formula in ["RAND()", "RANDBETWEEN(1, 1000000)", "RANDARRAY(3, 3)"] {
+            assert_eq!(eval_seeded(&g, 1, formula), eval_seeded(&g, 1, formula));
+            assert_ne!(eval_seeded(&g, 1, formula), eval_seeded(&g, 2, formula));
+        }
+
+        // Only formulas that use random numbers seed a generator.
+        let mut ctx = Ctx::new(&g, Pos::ORIGIN.with_sheet(g.sheets()[0].id));
+        parse_formula("1 + 1", Pos::ORIGIN)
+            .unwrap()
+            .eval(&mut ctx)
+            .unwrap();
+        assert!(ctx.rng.is_none());
+        parse_formula("RAND()", Pos::ORIGIN)
+            .unwrap()
+            .eval(&mut ctx)
+            .unwrap();
+        assert!(ctx.rng.is_some());
+    }
 }
@@ -1,11 +1,18 @@
 use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
 
 use itertools::Itertools;
 use lazy_static::lazy_static;
 
 #[macro_use]
 mod macros;
+mod array;
+mod datetime;
+mod engineering;
+mod financial;
+mod info;
+mod lambda;
 mod logic;
 mod lookup;
 mod mathematics;
@@ -15,10 +22,14 @@ mod string;
 mod trigonometry;
 mod util;
 
-use super::{CellRef, Criterion, Ctx, Param, ParamKind};
+pub(super) use util::missing_arg_error;
+pub(crate) use util::round_decimal_to_digits;
+
+use super::ctx::sheet_rect_size;
+use super::{AstNode, CellRef, Criterion, Ctx, Lambda, Param, ParamKind};
 use crate::{
-    Array, Axis, CellValue, CodeResult, CoerceInto, Error, ErrorMsg, IsBlank, Span, Spanned,
-    SpannedIterExt, Value,
+    Array, ArraySize, Axis, CellValue, CodeResult, CoerceInto, Error, ErrorMsg, IsBlank, SheetRect,
+    Span, Spanned, SpannedIterExt, Value,
 };
 
 pub fn lookup_function(name: &str) -> Option<&'static FormulaFunction> {
@@ -31,8 +42,14 @@ pub const CATEGORIES: &[FormulaFunctionCategory] = &[
     trigonometry::CATEGORY,
     statistics::CATEGORY,
     logic::CATEGORY,
+    info::CATEGORY,
     string::CATEGORY,
+    datetime::CATEGORY,
+    financial::CATEGORY,
+    engineering::CATEGORY,
     lookup::CATEGORY,
+    array::CATEGORY,
+    lambda::CATEGORY,
 ];
 
 lazy_static! {
@@ -54,61 +71,193 @@ lazy_static! {
     };
 }
 
-/// Argument values passed to a formula function.
-pub struct FormulaFnArgs {
+/// Argument value passed to a formula function.
+#[derive(Debug, Clone)]
+pub enum ArgValue {
+    /// Value that has already been evaluated.
+    Value(Value),
+    /// Region of cells in the grid, which are only read when the function asks
+    /// for their values.
+    Reference(SheetRect),
+    /// Function defined using `LAMBDA`.
+    Lambda(Rc<Lambda>),
+}
+impl ArgValue {
+    /// Returns the size of the value, or of the referenced region of cells.
+    pub fn size(&self, span: Span) -> CodeResult<ArraySize> {
+        match self {
+            ArgValue::Value(Value::Single(_)) => Ok(ArraySize::_1X1),
+            ArgValue::Value(Value::Array(a)) => Ok(a.size()),
+            ArgValue::Reference(sheet_rect) => sheet_rect_size(*sheet_rect, span),
+            ArgValue::Lambda(_) => Err(ArgValue::expected_value_error().with_span(span)),
+        }
+    }
+    /// Returns whether the argument is a blank value.
+    pub fn is_blank(&self) -> bool {
+        matches!(self, ArgValue::Value(Value::Single(CellValue::Blank)))
+    }
+
+    fn expected_value_error() -> ErrorMsg {
+        ErrorMsg::Expected {
+            expected: "value".into(),
+            got: Some("function".into()),
+        }
+    }
+}
+impl Spanned<ArgValue> {
+    /// Returns the value, reading referenced cells from the grid.
+    pub fn into_value(self, ctx: &mut Ctx<'_>) -> CodeResult<Spanned<Value>> {
+        let span = self.span;
+        let inner = match self.inner {
+            ArgValue::Value(v) => v,
+            // Cell references return arrays (even 1x1) for Excel
+            // compatibility.
+            ArgValue::Reference(sheet_rect) => ctx.get_sheet_rect_array(sheet_rect, span)?.into(),
+            ArgValue::Lambda(_) => return Err(ArgValue::expected_value_error().with_span(span)),
+        };
+        Ok(Spanned { span, inner })
+    }
+    /// Returns the function defined using `LAMBDA`, or an error if the
+    /// argument is not a function.
+    pub fn into_lambda(self) -> CodeResult<Rc<Lambda>> {
+        let got = match self.inner {
+            ArgValue::Lambda(lambda) => return Ok(lambda),
+            // Propagate errors, such as from an undefined name.
+            ArgValue::Value(Value::Single(CellValue::Error(e))) => return Err(*e),
+            ArgValue::Value(_) => "value",
+            ArgValue::Reference(_) => "cell reference",
+        };
+        Err(ErrorMsg::Expected {
+            expected: "function".into(),
+            got: Some(got.into()),
+        }
+        .with_span(self.span))
+    }
+}
+
+/// Argument to a formula function that is only evaluated when the function
+/// asks for its value, such as the branches of `IF`.
+#[derive(Debug, Copy, Clone)]
+pub struct LazyArg<'a>(&'a AstNode);
+impl LazyArg<'_> {
+    /// Returns the span of the argument.
+    pub fn span(self) -> Span {
+        self.0.span
+    }
+    /// Evaluates the argument without reading any referenced cells. Errors are
+    /// returned as error values, except for errors in the formula itself.
+    pub fn eval_arg(self, ctx: &mut Ctx<'_>) -> CodeResult<Spanned<ArgValue>> {
+        self.0.eval_arg_or_error(ctx)
+    }
+    /// Evaluates the argument.
+    pub fn eval(self, ctx: &mut Ctx<'_>) -> CodeResult<Spanned<Value>> {
+        self.eval_arg(ctx)?.into_value(ctx)
+    }
+}
+
+/// Arguments passed to a formula function. Each argument is evaluated when it
+/// is taken.
+pub struct FormulaFnArgs<'a> {
     pub span: Span,
-    values: VecDeque<Spanned<Value>>,
+    args: VecDeque<&'a AstNode>,
     func_name: &'static str,
     args_popped: usize,
 }
-impl FormulaFnArgs {
-    /// Constructs a set of arguments values.
-    pub fn new(
-        values: impl Into<VecDeque<Spanned<Value>>>,
-        span: Span,
-        func_name: &'static str,
-    ) -> Self {
+impl<'a> FormulaFnArgs<'a> {
+    /// Constructs a set of arguments from unevaluated expressions.
+    pub fn new(args: &'a [AstNode], span: Span, func_name: &'static str) -> Self {
         Self {
             span,
-            values: values.into(),
+            args: args.iter().collect(),
             func_name,
             args_popped: 0,
         }
     }
-    /// Takes the next argument.
-    fn take_next(&mut self) -> Option<Spanned<Value>> {
-        if !self.values.is_empty() {
+    /// Takes the next argument without evaluating it.
+    fn take_next(&mut self) -> Option<LazyArg<'a>> {
+        if !self.args.is_empty() {
             self.args_popped += 1;
         }
-        self.values.pop_front()
+        self.args.pop_front().map(LazyArg)
     }
-    /// Takes the next argument, or returns `None` if there is none or the
-    /// argument is blank˙.
-    pub fn take_next_optional(&mut self) -> Option<Spanned<Value>> {
+    fn missing_arg_error(&self, arg_name: impl Into<Cow<'static, str>>) -> Error {
+        util::missing_arg_error(self.func_name, arg_name, self.span)
+    }
+    /// Takes the next argument without evaluating it, or returns `None` if
+    /// there is none or the argument is empty.
+    pub fn take_next_optional_lazy(&mut self) -> Option<LazyArg<'a>> {
+        self.take_next().filter(|arg| !arg.0.is_empty())
+    }
+    /// Takes the next argument without evaluating it, or returns an error if
+    /// there is none.
+    pub fn take_next_required_lazy(
+        &mut self,
+        arg_name: impl Into<Cow<'static, str>>,
+    ) -> CodeResult<LazyArg<'a>> {
         self.take_next()
-            .filter(|v| v.inner != Value::Single(CellValue::Blank))
+            .ok_or_else(|| self.missing_arg_error(arg_name))
+    }
+    /// Takes the rest of the arguments without evaluating them.
+    pub fn take_rest_lazy(&mut self) -> impl Iterator<Item = LazyArg<'a>> {
+        std::mem::take(&mut self.args).into_iter().map(LazyArg)
+    }
+    /// Takes the next argument without reading any referenced cells, or
+    /// returns `None` if there is none or the argument is blank.
+    pub fn take_next_optional_arg(
+        &mut self,
+        ctx: &mut Ctx<'_>,
+    ) -> CodeResult<Option<Spanned<ArgValue>>> {
+        let arg = self.take_next().map(|arg| arg.eval_arg(ctx)).transpose()?;
+        Ok(arg.filter(|v| !v.inner.is_blank()))
+    }
+    /// Takes the next argument without reading any referenced cells, or
+    /// returns an error if there is none.
+    pub fn take_next_required_arg(
+        &mut self,
+        ctx: &mut Ctx<'_>,
+        arg_name: impl Into<Cow<'static, str>>,
+    ) -> CodeResult<Spanned<ArgValue>> {
+        self.take_next_required_lazy(arg_name)?.eval_arg(ctx)
+    }
+    /// Takes the next argument, or returns `None` if there is none or the
+    /// argument is blank.
+    pub fn take_next_optional(&mut self, ctx: &mut Ctx<'_>) -> CodeResult<Option<Spanned<Value>>> {
+        self.take_next_optional_arg(ctx)?
+            .map(|arg| arg.into_value(ctx))
+            .transpose()
     }
     /// Takes the next argument, or returns an error if there is none.
     pub fn take_next_required(
         &mut self,
+        ctx: &mut Ctx<'_>,
         arg_name: impl Into<Cow<'static, str>>,
     ) -> CodeResult<Spanned<Value>> {
-        self.take_next().ok_or_else(|| {
-            ErrorMsg::MissingRequiredArgument {
-                func_name: self.func_name.into(),
-                arg_name: arg_name.into(),
-            }
-            .with_span(self.span)
-        })
+        self.take_next_required_arg(ctx, arg_name)?.into_value(ctx)
+    }
+    /// Takes the rest of the arguments without reading any referenced cells.
+    pub fn take_rest_args(
+        &mut self,
+        ctx: &mut Ctx<'_>,
+    ) -> CodeResult<impl Iterator<Item = Spanned<ArgValue>>> {
+        self.take_rest_lazy()
+            .map(|arg| arg.eval_arg(ctx))
+            .collect::<CodeResult<Vec<_>>>()
+            .map(|args| args.into_iter())
     }
     /// Takes the rest of the arguments and iterates over them.
-    pub fn take_rest(&mut self) -> impl Iterator<Item = Spanned<Value>> {
-        std::mem::take(&mut self.values).into_iter()
+    pub fn take_rest(
+        &mut self,
+        ctx: &mut Ctx<'_>,
+    ) -> CodeResult<impl Iterator<Item = Spanned<Value>>> {
+        self.take_rest_args(ctx)?
+            .map(|arg| arg.into_value(ctx))
+            .collect::<CodeResult<Vec<_>>>()
+            .map(|values| values.into_iter())
     }
 
     /// Returns an error if there are any arguments that have not been taken.
     pub fn error_if_more_args(&self) -> CodeResult<()> {
-        if let Some(next_arg) = self.values.front() {
+        if let Some(next_arg) = self.args.front() {
             Err(ErrorMsg::TooManyArguments {
                 func_name: self.func_name.into(),
                 max_arg_count: self.args_popped,
@@ -121,7 +270,7 @@ impl FormulaFnArgs {
 }
 
 /// Function pointer that represents the body of a formula function.
-pub type FormulaFn = for<'a> fn(&'a mut Ctx<'_>, FormulaFnArgs) -> CodeResult<Value>;
+pub type FormulaFn = fn(&mut Ctx<'_>, FormulaFnArgs<'_>) -> CodeResult<Value>;
 
 /// Formula function with associated metadata.
 pub struct FormulaFunction {
@@ -130,6 +279,10 @@ pub struct FormulaFunction {
     pub usage: &'static str,
     pub examples: &'static [&'static str],
     pub doc: &'static str,
+    /// Whether the function may return a different result each time it is
+    /// evaluated, such as `RAND` or `NOW`. Cells containing a volatile
+    /// function are recomputed on every recalculation.
+    pub volatile: bool,
     pub eval: FormulaFn,
 }
 impl FormulaFunction {
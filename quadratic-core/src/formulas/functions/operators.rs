@@ -1,4 +1,4 @@
-use crate::ArraySize;
+use crate::{ArraySize, Duration, Instant};
 
 use super::*;
 
@@ -25,17 +25,23 @@ fn get_functions() -> Vec<FormulaFunction> {
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "+"([a]: f64, [b]: (Option<f64>)) {
-                a + b.unwrap_or(0.0)
+            fn "+"(span: Span, [a]: (Spanned<CellValue>), [b]: (Option<Spanned<CellValue>>)) {
+                match b {
+                    Some(b) => add(*span, *a, *b)?,
+                    None => CellValue::from(a.try_coerce::<f64>()?.inner),
+                }
             }
         ),
         formula_fn!(
             #[operator]
             #[zip_map]
-            fn "-"([a]: f64, [b]: (Option<f64>)) {
+            fn "-"(span: Span, [a]: (Spanned<CellValue>), [b]: (Option<Spanned<CellValue>>)) {
                 match b {
-                    Some(b) => a - b,
-                    None => -a
+                    Some(b) => subtract(*span, *a, *b)?,
+                    None => match a.inner {
+                        CellValue::Duration(d) => CellValue::Duration(-*d),
+                        _ => CellValue::from(-a.try_coerce::<f64>()?.inner),
+                    },
                 }
             }
         ),
@@ -95,6 +101,66 @@ fn get_functions() -> Vec<FormulaFunction> {
     ]
 }
 
+/// Number of seconds in a day, which is the unit used when adding a number to
+/// a time instant.
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Adds two values, which may be numbers, time instants, or time durations.
+/// Adding a number to a time instant adds that many days.
+fn add(span: Span, a: Spanned<&CellValue>, b: Spanned<&CellValue>) -> CodeResult<CellValue> {
+    let overflow = || ErrorMsg::Overflow.with_span(span);
+    Ok(match (a.inner, b.inner) {
+        (CellValue::Instant(i), CellValue::Duration(d))
+        | (CellValue::Duration(d), CellValue::Instant(i)) => {
+            CellValue::Instant(i.checked_add(*d).ok_or_else(overflow)?)
+        }
+        (CellValue::Duration(d1), CellValue::Duration(d2)) => {
+            CellValue::Duration(d1.checked_add(*d2).ok_or_else(overflow)?)
+        }
+        (CellValue::Instant(i), _) => add_days(span, *i, b.try_coerce::<f64>()?.inner)?,
+        (_, CellValue::Instant(i)) => add_days(span, *i, a.try_coerce::<f64>()?.inner)?,
+        (CellValue::Duration(_), _) => return Err(expected_duration(b)),
+        _ => CellValue::from(a.try_coerce::<f64>()?.inner + b.try_coerce::<f64>()?.inner),
+    })
+}
+
+/// Subtracts two values, which may be numbers, time instants, or time
+/// durations. Subtracting two time instants returns the duration between them.
+fn subtract(span: Span, a: Spanned<&CellValue>, b: Spanned<&CellValue>) -> CodeResult<CellValue> {
+    let overflow = || ErrorMsg::Overflow.with_span(span);
+    Ok(match (a.inner, b.inner) {
+        (CellValue::Instant(i1), CellValue::Instant(i2)) => {
+            CellValue::Duration(i1.duration_since(*i2))
+        }
+        (CellValue::Instant(i), CellValue::Duration(d)) => {
+            CellValue::Instant(i.checked_sub(*d).ok_or_else(overflow)?)
+        }
+        (CellValue::Duration(d1), CellValue::Duration(d2)) => {
+            CellValue::Duration(d1.checked_add(-*d2).ok_or_else(overflow)?)
+        }
+        (CellValue::Instant(i), _) => add_days(span, *i, -b.try_coerce::<f64>()?.inner)?,
+        (CellValue::Duration(_), _) => return Err(expected_duration(b)),
+        _ => CellValue::from(a.try_coerce::<f64>()?.inner - b.try_coerce::<f64>()?.inner),
+    })
+}
+
+/// Adds a number of days to a time instant.
+fn add_days(span: Span, instant: Instant, days: f64) -> CodeResult<CellValue> {
+    let duration = Duration::from_seconds(days * SECONDS_PER_DAY);
+    let ret = instant.checked_add(duration);
+    Ok(CellValue::Instant(
+        ret.ok_or_else(|| ErrorMsg::Overflow.with_span(span))?,
+    ))
+}
+
+fn expected_duration(value: Spanned<&CellValue>) -> Error {
+    ErrorMsg::Expected {
+        expected: "time duration".into(),
+        got: Some(value.inner.type_name().into()),
+    }
+    .with_span(value.span)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::formulas::tests::*;
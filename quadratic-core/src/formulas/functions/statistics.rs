@@ -42,6 +42,28 @@ fn get_functions() -> Vec<FormulaFunction> {
                 util::average(span, numbers)
             }
         ),
+        formula_fn!(
+            /// Computes the arithmetic mean of values in `numbers_range`
+            /// wherever the corresponding values in every `eval_range` meet the
+            /// corresponding `criteria`.
+            ///
+            /// Each `eval_range` must be the same size as `numbers_range`.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "AVERAGEIFS(C1:C10, A1:A10, \">0\")",
+                "AVERAGEIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn AVERAGEIFS(
+                span: Span,
+                numbers_range: (Spanned<Array>),
+                eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs("AVERAGEIFS", span, eval_ranges_and_criteria)?;
+                let numbers =
+                    Criterion::iter_matching_all_coerced::<f64>(&criteria, &numbers_range)?;
+                util::average(span, numbers)
+            }
+        ),
         formula_fn!(
             /// Returns the number of numeric values.
             ///
@@ -85,6 +107,23 @@ fn get_functions() -> Vec<FormulaFunction> {
                 count as f64
             }
         ),
+        formula_fn!(
+            /// Counts how many positions meet every `criteria` in the
+            /// corresponding `eval_range`.
+            ///
+            /// All ranges must be the same size.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "COUNTIFS(A1:A10, \">0\")",
+                "COUNTIFS(A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn COUNTIFS(span: Span, eval_ranges_and_criteria: (Iter<Spanned<Value>>)) {
+                let criteria = util::criteria_pairs("COUNTIFS", span, eval_ranges_and_criteria)?;
+                // Ignore error values.
+                let count = Criterion::iter_matching_all(&criteria, None)?.count();
+                count as f64
+            }
+        ),
         formula_fn!(
             /// Counts how many values in the range are empty.
             ///
@@ -117,12 +156,966 @@ fn get_functions() -> Vec<FormulaFunction> {
                 numbers.try_fold(-f64::INFINITY, |a, b| Ok(f64::max(a, b?)))
             }
         ),
+        formula_fn!(
+            /// Returns the smallest value in `numbers_range` wherever the
+            /// corresponding values in every `eval_range` meet the
+            /// corresponding `criteria`.
+            ///
+            /// Each `eval_range` must be the same size as `numbers_range`.
+            /// Returns `0` if no values meet the criteria.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "MINIFS(C1:C10, A1:A10, \">0\")",
+                "MINIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn MINIFS(
+                span: Span,
+                numbers_range: (Spanned<Array>),
+                eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs("MINIFS", span, eval_ranges_and_criteria)?;
+                let numbers =
+                    Criterion::iter_matching_all_coerced::<f64>(&criteria, &numbers_range)?
+                        .collect::<CodeResult<Vec<f64>>>()?;
+                numbers.into_iter().reduce(f64::min).unwrap_or(0.0)
+            }
+        ),
+        formula_fn!(
+            /// Returns the largest value in `numbers_range` wherever the
+            /// corresponding values in every `eval_range` meet the
+            /// corresponding `criteria`.
+            ///
+            /// Each `eval_range` must be the same size as `numbers_range`.
+            /// Returns `0` if no values meet the criteria.
+            #[doc = see_docs_for_more_about_criteria!()]
+            #[examples(
+                "MAXIFS(C1:C10, A1:A10, \">0\")",
+                "MAXIFS(C1:C10, A1:A10, \">0\", B1:B10, \"<>INVALID\")"
+            )]
+            fn MAXIFS(
+                span: Span,
+                numbers_range: (Spanned<Array>),
+                eval_ranges_and_criteria: (Iter<Spanned<Value>>),
+            ) {
+                let criteria = util::criteria_pairs("MAXIFS", span, eval_ranges_and_criteria)?;
+                let numbers =
+                    Criterion::iter_matching_all_coerced::<f64>(&criteria, &numbers_range)?
+                        .collect::<CodeResult<Vec<f64>>>()?;
+                numbers.into_iter().reduce(f64::max).unwrap_or(0.0)
+            }
+        ),
+        formula_fn!(
+            /// Returns the middle value of the numbers, or the arithmetic mean
+            /// of the two middle values if there is an even number of them.
+            #[examples("MEDIAN(A1:A10)", "MEDIAN(1, 3, 2, 5)")]
+            fn MEDIAN(span: Span, numbers: (Iter<f64>)) {
+                let numbers = sorted(span, numbers)?;
+                let mid = numbers.len() / 2;
+                if numbers.len() % 2 == 0 {
+                    (numbers[mid - 1] + numbers[mid]) / 2.0
+                } else {
+                    numbers[mid]
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the most common value. If several values are equally
+            /// common, returns the one that appears first.
+            ///
+            /// Returns an error if no value appears more than once.
+            #[examples("MODE(A1:A10)", "MODE(1, 2, 2, 3)")]
+            fn MODE(span: Span, numbers: (Iter<f64>)) {
+                let numbers: Vec<f64> = numbers.try_collect()?;
+                let mut indices = (0..numbers.len()).collect_vec();
+                indices.sort_by(|&i, &j| numbers[i].total_cmp(&numbers[j]).then(i.cmp(&j)));
+
+                // (count, index of first occurrence)
+                let mut best: Option<(usize, usize)> = None;
+                let mut run_start = 0;
+                for run_end in 1..=indices.len() {
+                    if run_end < indices.len()
+                        && numbers[indices[run_end]] == numbers[indices[run_start]]
+                    {
+                        continue;
+                    }
+                    let count = run_end - run_start;
+                    let first = indices[run_start];
+                    let is_better = match best {
+                        Some((c, f)) => count > c || (count == c && first < f),
+                        None => true,
+                    };
+                    if count > 1 && is_better {
+                        best = Some((count, first));
+                    }
+                    run_start = run_end;
+                }
+                match best {
+                    Some((_, i)) => numbers[i],
+                    None => return Err(ErrorMsg::NotAvailable.with_span(span)),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the standard deviation of a sample of a population.
+            #[examples("STDEV.S(A1:A10)")]
+            fn "STDEV.S"(span: Span, numbers: (Iter<f64>)) {
+                variance(span, numbers.try_collect()?, true)?.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the standard deviation of an entire population.
+            #[examples("STDEV.P(A1:A10)")]
+            fn "STDEV.P"(span: Span, numbers: (Iter<f64>)) {
+                variance(span, numbers.try_collect()?, false)?.sqrt()
+            }
+        ),
+        formula_fn!(
+            /// Returns the variance of a sample of a population.
+            #[examples("VAR.S(A1:A10)")]
+            fn "VAR.S"(span: Span, numbers: (Iter<f64>)) {
+                variance(span, numbers.try_collect()?, true)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the variance of an entire population.
+            #[examples("VAR.P(A1:A10)")]
+            fn "VAR.P"(span: Span, numbers: (Iter<f64>)) {
+                variance(span, numbers.try_collect()?, false)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the `k`th percentile of the numbers in `array`, where
+            /// `k` is between 0 and 1 inclusive. Interpolates between values
+            /// if necessary.
+            #[examples("PERCENTILE(A1:A10, 0.9)", "PERCENTILE(A1:A10, 90%)")]
+            #[zip_map]
+            fn PERCENTILE(span: Span, array: (Spanned<Value>), [k]: (Spanned<f64>)) {
+                let numbers = sorted(*span, array.clone().into_iter::<f64>().without_spans())?;
+                percentile(&numbers, k)?
+            }
+        ),
+        formula_fn!(
+            /// Returns a quartile of the numbers in `array`.
+            ///
+            /// - If `quart` is 0, returns the smallest value.
+            /// - If `quart` is 1, returns the 25th percentile.
+            /// - If `quart` is 2, returns the median.
+            /// - If `quart` is 3, returns the 75th percentile.
+            /// - If `quart` is 4, returns the largest value.
+            #[examples("QUARTILE(A1:A10, 1)")]
+            #[zip_map]
+            fn QUARTILE(span: Span, array: (Spanned<Value>), [quart]: (Spanned<f64>)) {
+                let numbers = sorted(*span, array.clone().into_iter::<f64>().without_spans())?;
+                let k = quart.map(|q| q.trunc() / 4.0);
+                percentile(&numbers, k)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the rank of `number` among the numbers in `array`,
+            /// where the largest number has rank 1. If `ascending` is true,
+            /// then the smallest number has rank 1 instead.
+            ///
+            /// Equal numbers have the same rank. Returns an error if `number`
+            /// is not in `array`.
+            #[examples("RANK(A1, A1:A10)", "RANK(A1, A1:A10, TRUE)")]
+            #[zip_map]
+            fn RANK(span: Span, [number]: f64, array: (Spanned<Value>), ascending: (Option<bool>)) {
+                let numbers: Vec<f64> = array
+                    .clone()
+                    .into_iter::<f64>()
+                    .without_spans()
+                    .try_collect()?;
+                if !numbers.contains(&number) {
+                    return Err(ErrorMsg::NotAvailable.with_span(*span));
+                }
+                let ranked_higher = match ascending.unwrap_or(false) {
+                    true => numbers.iter().filter(|&&n| n < number).count(),
+                    false => numbers.iter().filter(|&&n| n > number).count(),
+                };
+                (ranked_higher + 1) as f64
+            }
+        ),
+        formula_fn!(
+            /// Returns the `k`th largest number in `array`. `LARGE(array, 1)`
+            /// is equivalent to `MAX(array)`.
+            #[examples("LARGE(A1:A10, 2)")]
+            #[zip_map]
+            fn LARGE(span: Span, array: (Spanned<Value>), [k]: (Spanned<i64>)) {
+                let numbers = sorted(*span, array.clone().into_iter::<f64>().without_spans())?;
+                let i = kth_index(k, numbers.len())?;
+                numbers[numbers.len() - 1 - i]
+            }
+        ),
+        formula_fn!(
+            /// Returns the `k`th smallest number in `array`. `SMALL(array, 1)`
+            /// is equivalent to `MIN(array)`.
+            #[examples("SMALL(A1:A10, 2)")]
+            #[zip_map]
+            fn SMALL(span: Span, array: (Spanned<Value>), [k]: (Spanned<i64>)) {
+                let numbers = sorted(*span, array.clone().into_iter::<f64>().without_spans())?;
+                numbers[kth_index(k, numbers.len())?]
+            }
+        ),
+        formula_fn!(
+            /// Returns the Pearson correlation coefficient of two arrays of
+            /// the same size.
+            ///
+            /// Positions where either array does not contain a number are
+            /// ignored.
+            #[examples("CORREL(A1:A10, B1:B10)")]
+            fn CORREL(span: Span, array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                let pairs = LinearPairs::new(&array2, &array1)?;
+                let denominator = (pairs.sum_xx() * pairs.sum_yy()).sqrt();
+                util::checked_div(span, pairs.sum_xy(), denominator)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the covariance of two arrays of the same size, treating
+            /// them as an entire population.
+            ///
+            /// Positions where either array does not contain a number are
+            /// ignored.
+            #[examples("COVARIANCE.P(A1:A10, B1:B10)")]
+            fn "COVARIANCE.P"(span: Span, array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                let pairs = LinearPairs::new(&array2, &array1)?;
+                util::checked_div(span, pairs.sum_xy(), pairs.len() as f64)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the covariance of two arrays of the same size, treating
+            /// them as a sample of a population.
+            ///
+            /// Positions where either array does not contain a number are
+            /// ignored.
+            #[examples("COVARIANCE.S(A1:A10, B1:B10)")]
+            fn "COVARIANCE.S"(span: Span, array1: (Spanned<Array>), array2: (Spanned<Array>)) {
+                let pairs = LinearPairs::new(&array2, &array1)?;
+                util::checked_div(span, pairs.sum_xy(), pairs.len() as f64 - 1.0)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the slope of the linear regression line through the
+            /// points given by `known_xs` and `known_ys`, which must be the
+            /// same size.
+            ///
+            /// Positions where either array does not contain a number are
+            /// ignored.
+            #[examples("SLOPE(B1:B10, A1:A10)")]
+            fn SLOPE(span: Span, known_ys: (Spanned<Array>), known_xs: (Spanned<Array>)) {
+                LinearPairs::new(&known_ys, &known_xs)?.slope(span)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the value at which the linear regression line through
+            /// the points given by `known_xs` and `known_ys` crosses the y-axis.
+            /// The arrays must be the same size.
+            ///
+            /// Positions where either array does not contain a number are
+            /// ignored.
+            #[examples("INTERCEPT(B1:B10, A1:A10)")]
+            fn INTERCEPT(span: Span, known_ys: (Spanned<Array>), known_xs: (Spanned<Array>)) {
+                LinearPairs::new(&known_ys, &known_xs)?.predict(span, 0.0)?
+            }
+        ),
+        formula_fn!(
+            /// Predicts the y-value at `x` using the linear regression line
+            /// through the points given by `known_xs` and `known_ys`, which
+            /// must be the same size.
+            ///
+            /// Positions where either array does not contain a number are
+            /// ignored.
+            #[examples("FORECAST.LINEAR(11, B1:B10, A1:A10)")]
+            #[zip_map]
+            fn "FORECAST.LINEAR"(
+                span: Span,
+                [x]: f64,
+                known_ys: (Spanned<Array>),
+                known_xs: (Spanned<Array>),
+            ) {
+                LinearPairs::new(known_ys, known_xs)?.predict(*span, x)?
+            }
+        ),
+        formula_fn!(
+            /// Fits a straight line through `known_ys` as a linear function of
+            /// one or more variables using least squares, and returns the
+            /// coefficients as a row in the order `m_n, ..., m_2, m_1, b`.
+            ///
+            /// If `known_ys` is a column, then each column of `known_xs` is a
+            /// variable; if it is a row, then each row of `known_xs` is a
+            /// variable. `known_xs` defaults to `{1, 2, 3, ...}`. If
+            /// `calculate_b` is false, then the line is forced through the
+            /// origin. It defaults to true.
+            ///
+            /// If `stats` is true, then four more rows are returned: the
+            /// standard errors of the coefficients; the coefficient of
+            /// determination and the standard error of the y estimate; the F
+            /// statistic and the degrees of freedom; and the regression and
+            /// residual sums of squares. It defaults to false.
+            ///
+            /// Returns an error if `known_xs` does not have one value for each
+            /// value in `known_ys` or if the variables are collinear.
+            #[examples("LINEST(B1:B10, A1:A10)", "LINEST(C1:C10, A1:B10, TRUE, TRUE)")]
+            fn LINEST(
+                span: Span,
+                known_ys: (Spanned<Array>),
+                known_xs: (Option<Spanned<Array>>),
+                calculate_b: (Option<bool>),
+                stats: (Option<bool>),
+            ) {
+                let calculate_b = calculate_b.unwrap_or(true);
+                let stats = stats.unwrap_or(false);
+                linest(span, known_ys, known_xs, calculate_b, stats)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the normal distribution with the given `mean` and
+            /// `standard_dev` evaluated at `x`.
+            ///
+            /// If `cumulative` is true, returns the cumulative distribution
+            /// function. Otherwise, returns the probability density function.
+            #[examples("NORM.DIST(42, 40, 1.5, TRUE)")]
+            #[zip_map]
+            fn "NORM.DIST"(
+                [x]: f64,
+                [mean]: f64,
+                [standard_dev]: (Spanned<f64>),
+                [cumulative]: bool,
+            ) {
+                let z = (x - mean) / util::positive(standard_dev)?;
+                match cumulative {
+                    true => norm_s_cdf(z),
+                    false => norm_s_pdf(z) / standard_dev.inner,
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the standard normal distribution (with a mean of 0 and
+            /// a standard deviation of 1) evaluated at `z`.
+            ///
+            /// If `cumulative` is true, returns the cumulative distribution
+            /// function. Otherwise, returns the probability density function.
+            #[examples("NORM.S.DIST(1.333333, TRUE)")]
+            #[zip_map]
+            fn "NORM.S.DIST"([z]: f64, [cumulative]: bool) {
+                match cumulative {
+                    true => norm_s_cdf(z),
+                    false => norm_s_pdf(z),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the cumulative normal distribution with
+            /// the given `mean` and `standard_dev`. `probability` must be
+            /// strictly between 0 and 1.
+            #[examples("NORM.INV(0.908789, 40, 1.5)")]
+            #[zip_map]
+            fn "NORM.INV"(
+                [probability]: (Spanned<f64>),
+                [mean]: f64,
+                [standard_dev]: (Spanned<f64>),
+            ) {
+                mean + util::positive(standard_dev)? * norm_s_inv(probability)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the cumulative standard normal
+            /// distribution (with a mean of 0 and a standard deviation of 1).
+            /// `probability` must be strictly between 0 and 1.
+            #[examples("NORM.S.INV(0.908789)")]
+            #[zip_map]
+            fn "NORM.S.INV"([probability]: (Spanned<f64>)) {
+                norm_s_inv(probability)?
+            }
+        ),
+        formula_fn!(
+            /// Returns Student's t-distribution with `degrees_freedom` degrees
+            /// of freedom evaluated at `x`. `degrees_freedom` must be at least
+            /// 1.
+            ///
+            /// If `cumulative` is true, returns the left-tailed cumulative
+            /// distribution function. Otherwise, returns the probability
+            /// density function.
+            #[examples("T.DIST(60, 1, TRUE)", "T.DIST(8, 3, FALSE)")]
+            #[zip_map]
+            fn "T.DIST"([x]: f64, [degrees_freedom]: (Spanned<f64>), [cumulative]: bool) {
+                let df = at_least_one(degrees_freedom)?;
+                match cumulative {
+                    true => t_cdf(x, df),
+                    false => t_pdf(x, df),
+                }
+            }
+        ),
+        formula_fn!(
+            /// Returns the right-tailed Student's t-distribution with
+            /// `degrees_freedom` degrees of freedom evaluated at `x`.
+            /// `degrees_freedom` must be at least 1.
+            #[examples("T.DIST.RT(1.959999998, 60)")]
+            #[zip_map]
+            fn "T.DIST.RT"([x]: f64, [degrees_freedom]: (Spanned<f64>)) {
+                t_cdf(-x, at_least_one(degrees_freedom)?)
+            }
+        ),
+        formula_fn!(
+            /// Returns the two-tailed Student's t-distribution with
+            /// `degrees_freedom` degrees of freedom evaluated at `x`. `x` must
+            /// not be negative, and `degrees_freedom` must be at least 1.
+            #[examples("T.DIST.2T(1.959999998, 60)")]
+            #[zip_map]
+            fn "T.DIST.2T"([x]: (Spanned<f64>), [degrees_freedom]: (Spanned<f64>)) {
+                if x.inner < 0.0 {
+                    return Err(ErrorMsg::InvalidArgument.with_span(x.span));
+                }
+                2.0 * t_cdf(-x.inner, at_least_one(degrees_freedom)?)
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the left-tailed Student's t-distribution
+            /// with `degrees_freedom` degrees of freedom. `probability` must be
+            /// strictly between 0 and 1, and `degrees_freedom` must be at least
+            /// 1.
+            #[examples("T.INV(0.75, 2)")]
+            #[zip_map]
+            fn "T.INV"([probability]: (Spanned<f64>), [degrees_freedom]: (Spanned<f64>)) {
+                t_inv(probability, at_least_one(degrees_freedom)?)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the inverse of the two-tailed Student's t-distribution
+            /// with `degrees_freedom` degrees of freedom. `probability` must be
+            /// strictly between 0 and 1, and `degrees_freedom` must be at least
+            /// 1.
+            #[examples("T.INV.2T(0.546449, 60)")]
+            #[zip_map]
+            fn "T.INV.2T"([probability]: (Spanned<f64>), [degrees_freedom]: (Spanned<f64>)) {
+                let df = at_least_one(degrees_freedom)?;
+                -t_inv(probability.map(|p| p / 2.0), df)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the probability of `successes` successes in `trials`
+            /// independent trials, each with a probability of success of
+            /// `probability`.
+            ///
+            /// If `cumulative` is true, returns the probability of at most
+            /// `successes` successes. `successes` and `trials` are truncated
+            /// to integers.
+            #[examples("BINOM.DIST(6, 10, 0.5, FALSE)", "BINOM.DIST(6, 10, 0.5, TRUE)")]
+            #[zip_map]
+            fn "BINOM.DIST"(
+                span: Span,
+                [successes]: f64,
+                [trials]: f64,
+                [probability]: (Spanned<f64>),
+                [cumulative]: bool,
+            ) {
+                let k = successes.trunc();
+                let n = trials.trunc();
+                if !(0.0..=n).contains(&k) {
+                    return Err(ErrorMsg::InvalidArgument.with_span(*span));
+                }
+                if !(0.0..=1.0).contains(&probability.inner) {
+                    return Err(ErrorMsg::InvalidArgument.with_span(probability.span));
+                }
+                let p = probability.inner;
+                match cumulative {
+                    // P(X <= k) = I_{1-p}(n - k, k + 1)
+                    true if k == n => 1.0,
+                    true => regularized_beta(1.0 - p, n - k, k + 1.0),
+                    false => binom_pmf(k, n, p),
+                }
+            }
+        ),
     ]
 }
 
+/// Collects numbers and sorts them, returning an error if there are none.
+fn sorted(span: Span, numbers: impl Iterator<Item = CodeResult<f64>>) -> CodeResult<Vec<f64>> {
+    let mut numbers: Vec<f64> = numbers.try_collect()?;
+    if numbers.is_empty() {
+        return Err(ErrorMsg::EmptyArray.with_span(span));
+    }
+    numbers.sort_by(f64::total_cmp);
+    Ok(numbers)
+}
+
+/// Returns the variance of `numbers`. If `sample` is true, the sample variance
+/// is returned; otherwise the population variance is returned.
+fn variance(span: Span, numbers: Vec<f64>, sample: bool) -> CodeResult<f64> {
+    let n = numbers.len() as f64;
+    let mean = numbers.iter().sum::<f64>() / n;
+    let sum_of_squares: f64 = numbers.iter().map(|x| (x - mean).powi(2)).sum();
+    let denominator = if sample { n - 1.0 } else { n };
+    if denominator <= 0.0 {
+        return Err(ErrorMsg::DivideByZero.with_span(span));
+    }
+    Ok(sum_of_squares / denominator)
+}
+
+/// Returns the `k`th percentile of sorted numbers, interpolating between
+/// values if necessary. `numbers` must not be empty.
+fn percentile(numbers: &[f64], k: Spanned<f64>) -> CodeResult<f64> {
+    if !(0.0..=1.0).contains(&k.inner) {
+        return Err(ErrorMsg::InvalidArgument.with_span(k.span));
+    }
+    let rank = k.inner * (numbers.len() - 1) as f64;
+    let i = rank.floor() as usize;
+    Ok(match numbers.get(i + 1) {
+        Some(next) => numbers[i] + (rank - i as f64) * (next - numbers[i]),
+        None => numbers[i],
+    })
+}
+
+/// Converts a 1-based `k` to an index into a list of length `len`.
+fn kth_index(k: Spanned<i64>, len: usize) -> CodeResult<usize> {
+    match usize::try_from(k.inner) {
+        Ok(k @ 1..) if k <= len => Ok(k - 1),
+        _ => Err(ErrorMsg::InvalidArgument.with_span(k.span)),
+    }
+}
+
+/// Returns an error if `n` is less than 1.
+fn at_least_one(n: Spanned<f64>) -> CodeResult<f64> {
+    match n.inner >= 1.0 {
+        true => Ok(n.inner),
+        false => Err(ErrorMsg::InvalidArgument.with_span(n.span)),
+    }
+}
+
+/// Pairs of numbers from two arrays of the same size, centered on their means.
+struct LinearPairs {
+    mean_x: f64,
+    mean_y: f64,
+    /// Pairs of `(x - mean_x, y - mean_y)`.
+    deviations: Vec<(f64, f64)>,
+}
+impl LinearPairs {
+    /// Collects pairs of numbers from `ys` and `xs`, ignoring positions where
+    /// either one is not a number.
+    fn new(ys: &Spanned<Array>, xs: &Spanned<Array>) -> CodeResult<Self> {
+        xs.check_array_size_exact(ys.inner.size())?;
+
+        let coerce = |array: &Spanned<Array>, value| {
+            Spanned {
+                span: array.span,
+                inner: value,
+            }
+            .coerce_or_none::<f64>()
+            .transpose()
+        };
+        let mut pairs = vec![];
+        for (y, x) in std::iter::zip(ys.inner.cell_values_slice(), xs.inner.cell_values_slice()) {
+            if let (Some(y), Some(x)) = (coerce(ys, y)?, coerce(xs, x)?) {
+                pairs.push((x.inner, y.inner));
+            }
+        }
+
+        let n = pairs.len() as f64;
+        let mean_x = pairs.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = pairs.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let deviations = pairs
+            .into_iter()
+            .map(|(x, y)| (x - mean_x, y - mean_y))
+            .collect();
+        Ok(Self {
+            mean_x,
+            mean_y,
+            deviations,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.deviations.len()
+    }
+    fn sum_xx(&self) -> f64 {
+        self.deviations.iter().map(|(x, _)| x * x).sum()
+    }
+    fn sum_yy(&self) -> f64 {
+        self.deviations.iter().map(|(_, y)| y * y).sum()
+    }
+    fn sum_xy(&self) -> f64 {
+        self.deviations.iter().map(|(x, y)| x * y).sum()
+    }
+
+    /// Returns the slope of the linear regression line.
+    fn slope(&self, span: Span) -> CodeResult<f64> {
+        util::checked_div(span, self.sum_xy(), self.sum_xx())
+    }
+    /// Returns the y-value of the linear regression line at `x`.
+    fn predict(&self, span: Span, x: f64) -> CodeResult<f64> {
+        Ok(self.mean_y + self.slope(span)? * (x - self.mean_x))
+    }
+}
+
+/// Fits `known_ys` to a linear function of the variables in `known_xs` using
+/// least squares, and returns the results in the layout used by `LINEST`.
+fn linest(
+    span: Span,
+    known_ys: Spanned<Array>,
+    known_xs: Option<Spanned<Array>>,
+    calculate_b: bool,
+    stats: bool,
+) -> CodeResult<Array> {
+    let axis = known_ys.array_linear_axis()?.unwrap_or(Axis::Y);
+    let ys = util::matrix_from_array(&known_ys)?.concat();
+    let n = ys.len();
+
+    // Each variable is a list of values with the same length as `ys`.
+    let variables = match known_xs {
+        None => vec![(1..=n).map(|i| i as f64).collect()],
+        Some(known_xs) => {
+            let len = known_xs.inner.size()[axis].get();
+            if len as usize != n {
+                return Err(ErrorMsg::ArrayAxisMismatch {
+                    axis,
+                    expected: n as u32,
+                    got: len,
+                }
+                .with_span(known_xs.span));
+            }
+            let xs = util::matrix_from_array(&known_xs)?;
+            match axis {
+                Axis::X => xs,
+                Axis::Y => util::transpose_matrix(&xs),
+            }
+        }
+    };
+    let k = variables.len();
+    if n < k + calculate_b as usize {
+        return Err(ErrorMsg::InvalidArgument.with_span(span));
+    }
+
+    // Solve the normal equations `(XᵀX)β = Xᵀy`, where `X` has a column for
+    // each variable and a column of ones for the constant.
+    let x = (0..n)
+        .map(|i| {
+            let row = variables.iter().map(|v| v[i]);
+            row.chain(calculate_b.then_some(1.0)).collect_vec()
+        })
+        .collect_vec();
+    let x_t = util::transpose_matrix(&x);
+    let x_t_x_inv = util::inverse_matrix(&util::matrix_product(&x_t, &x))
+        .ok_or_else(|| ErrorMsg::InvalidArgument.with_span(span))?;
+    let y = ys.iter().map(|&y| vec![y]).collect_vec();
+    let beta = util::matrix_product(&x_t_x_inv, &util::matrix_product(&x_t, &y)).concat();
+
+    let not_available = || CellValue::Error(Box::new(ErrorMsg::NotAvailable.with_span(span)));
+    let number = |n: f64| match n.is_finite() {
+        true => CellValue::from(n),
+        false => CellValue::Error(Box::new(ErrorMsg::DivideByZero.with_span(span))),
+    };
+    // Coefficients are listed from the last variable to the first.
+    let b = if calculate_b { beta[k] } else { 0.0 };
+    let mut rows = vec![beta[..k]
+        .iter()
+        .rev()
+        .chain([&b])
+        .map(|&c| number(c))
+        .collect_vec()];
+
+    if stats {
+        let predicted = x
+            .iter()
+            .map(|row| row.iter().zip(&beta).map(|(a, b)| a * b).sum::<f64>())
+            .collect_vec();
+        let mean = if calculate_b {
+            ys.iter().sum::<f64>() / n as f64
+        } else {
+            0.0
+        };
+        let ss_reg: f64 = predicted.iter().map(|p| (p - mean).powi(2)).sum();
+        let ss_resid: f64 = ys
+            .iter()
+            .zip(&predicted)
+            .map(|(y, p)| (y - p).powi(2))
+            .sum();
+        let df = (n - beta.len()) as f64;
+        let se_y = (ss_resid / df).sqrt();
+        let r_squared = ss_reg / (ss_reg + ss_resid);
+        let f = (ss_reg / k as f64) / (ss_resid / df);
+
+        let se = |i: usize| x_t_x_inv[i][i].sqrt() * se_y;
+        let se_b = match calculate_b {
+            true => number(se(k)),
+            false => not_available(),
+        };
+        rows.push((0..k).rev().map(|i| number(se(i))).chain([se_b]).collect());
+        rows.push(vec![number(r_squared), number(se_y)]);
+        rows.push(vec![number(f), CellValue::from(df)]);
+        rows.push(vec![number(ss_reg), number(ss_resid)]);
+    }
+
+    let size = ArraySize::new_or_err(k as u32 + 1, rows.len() as u32)?;
+    let values = rows
+        .into_iter()
+        .flat_map(|row| {
+            let padding = std::iter::repeat_with(not_available);
+            row.into_iter().chain(padding).take(k + 1).collect_vec()
+        })
+        .collect();
+    Array::new_row_major(size, values)
+}
+
+/// Returns the probability density function of the standard normal
+/// distribution.
+fn norm_s_pdf(z: f64) -> f64 {
+    (-z * z / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Returns the cumulative distribution function of the standard normal
+/// distribution.
+///
+/// This uses Hart's algorithm as described in "Better approximations to
+/// cumulative normal functions" by Graeme West, which has an absolute error
+/// of less than 1e-15.
+fn norm_s_cdf(z: f64) -> f64 {
+    let x = z.abs();
+    let tail = if x > 37.0 {
+        0.0
+    } else if x < 7.07106781186547 {
+        let numerator = [
+            3.52624965998911e-02,
+            0.700383064443688,
+            6.37396220353165,
+            33.912866078383,
+            112.079291497871,
+            221.213596169931,
+            220.206867912376,
+        ];
+        let denominator = [
+            8.83883476483184e-02,
+            1.75566716318264,
+            16.064177579207,
+            86.7807322029461,
+            296.564248779674,
+            637.333633378831,
+            793.826512519948,
+            440.413735824752,
+        ];
+        let polynomial = |coefficients: &[f64]| coefficients.iter().fold(0.0, |acc, c| acc * x + c);
+        (-x * x / 2.0).exp() * polynomial(&numerator) / polynomial(&denominator)
+    } else {
+        let continued_fraction = x + 1.0 / (x + 2.0 / (x + 3.0 / (x + 4.0 / (x + 0.65))));
+        norm_s_pdf(x) / continued_fraction
+    };
+    if z > 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+/// Returns the inverse of the cumulative distribution function of the standard
+/// normal distribution.
+///
+/// This uses Peter Acklam's rational approximation, followed by one step of
+/// Halley's method to refine it to double precision.
+fn norm_s_inv(probability: Spanned<f64>) -> CodeResult<f64> {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.38357751867269e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let p = probability.inner;
+    if !(p > 0.0 && p < 1.0) {
+        return Err(ErrorMsg::InvalidArgument.with_span(probability.span));
+    }
+
+    let polynomial =
+        |coefficients: &[f64], x: f64| coefficients.iter().fold(0.0, |acc, c| acc * x + c);
+    let tail = |p: f64| {
+        let q = (-2.0 * p.ln()).sqrt();
+        polynomial(&C, q) / (polynomial(&D, q) * q + 1.0)
+    };
+    let x = if p < P_LOW {
+        tail(p)
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        polynomial(&A, r) * q / (polynomial(&B, r) * r + 1.0)
+    } else {
+        -tail(1.0 - p)
+    };
+
+    let e = norm_s_cdf(x) - p;
+    let u = e / norm_s_pdf(x);
+    Ok(x - u / (1.0 + x * u / 2.0))
+}
+
+/// Returns the probability density function of Student's t-distribution.
+fn t_pdf(x: f64, df: f64) -> f64 {
+    let ln_coefficient =
+        ln_gamma((df + 1.0) / 2.0) - ln_gamma(df / 2.0) - 0.5 * (df * std::f64::consts::PI).ln();
+    (ln_coefficient - (df + 1.0) / 2.0 * (1.0 + x * x / df).ln()).exp()
+}
+
+/// Returns the left-tailed cumulative distribution function of Student's
+/// t-distribution.
+fn t_cdf(x: f64, df: f64) -> f64 {
+    let tail = regularized_beta(df / (df + x * x), df / 2.0, 0.5) / 2.0;
+    if x > 0.0 {
+        1.0 - tail
+    } else {
+        tail
+    }
+}
+
+/// Returns the inverse of the left-tailed cumulative distribution function of
+/// Student's t-distribution.
+fn t_inv(probability: Spanned<f64>, df: f64) -> CodeResult<f64> {
+    let p = probability.inner;
+    if !(p > 0.0 && p < 1.0) {
+        return Err(ErrorMsg::InvalidArgument.with_span(probability.span));
+    }
+
+    // Find an interval containing the result, then bisect it.
+    let mut lo = -1.0;
+    let mut hi = 1.0;
+    while t_cdf(lo, df) > p {
+        lo *= 2.0;
+    }
+    while t_cdf(hi, df) < p {
+        hi *= 2.0;
+    }
+    for _ in 0..200 {
+        let mid = (lo + hi) / 2.0;
+        if mid == lo || mid == hi {
+            break;
+        }
+        if t_cdf(mid, df) < p {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok((lo + hi) / 2.0)
+}
+
+/// Returns the probability of exactly `k` successes in `n` trials with
+/// probability of success `p`.
+fn binom_pmf(k: f64, n: f64, p: f64) -> f64 {
+    if p == 0.0 || p == 1.0 {
+        let certain_successes = if p == 0.0 { 0.0 } else { n };
+        return if k == certain_successes { 1.0 } else { 0.0 };
+    }
+    let ln_combinations = ln_gamma(n + 1.0) - ln_gamma(k + 1.0) - ln_gamma(n - k + 1.0);
+    (ln_combinations + k * p.ln() + (n - k) * (1.0 - p).ln()).exp()
+}
+
+/// Returns the natural logarithm of the gamma function for positive `x`.
+///
+/// This uses the Lanczos approximation with g=7.
+fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.5203681218851,
+        -1259.1392167224028,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507343278686905,
+        -0.13857109526572012,
+        9.984_369_578_019_572e-6,
+        1.5056327351493116e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let a = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |acc, (i, c)| {
+            acc + c / (x + i as f64 + 1.0)
+        });
+    let t = x + G + 0.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + a.ln()
+}
+
+/// Returns the regularized incomplete beta function I_x(a, b).
+///
+/// This uses the continued fraction from "Numerical Recipes", evaluated using
+/// the modified Lentz's method.
+fn regularized_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    // The continued fraction converges quickly only on one side of this
+    // point, so use the symmetry I_x(a, b) = 1 - I_{1-x}(b, a) on the other.
+    if x > (a + 1.0) / (a + b + 2.0) {
+        return 1.0 - regularized_beta(1.0 - x, b, a);
+    }
+
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    let front = ln_front.exp() / a;
+
+    const TINY: f64 = 1e-300;
+    const EPSILON: f64 = 1e-15;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+    for m in 1..1000 {
+        let m = m as f64;
+        for numerator in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + numerator / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    front * h
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::formulas::tests::*;
+    use crate::{formulas::tests::*, util::assert_f64_approx_eq};
 
     #[test]
     fn test_formula_average() {
@@ -205,6 +1198,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_averageifs() {
+        let g = Grid::new();
+        assert_eq!(
+            "3",
+            eval_to_string(&g, "AVERAGEIFS(0..10, 0..10, \">=2\", 0..10, \"<=4\")"),
+        );
+        expect_err(
+            &ErrorMsg::DivideByZero,
+            &g,
+            "AVERAGEIFS(0..10, 0..10, \">20\")",
+        );
+        assert_eq!(
+            ErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 11).unwrap(),
+                got: ArraySize::new(2, 1).unwrap(),
+            },
+            eval_to_err(&g, "AVERAGEIFS(0..10, {1, 2}, \">1\")").msg,
+        );
+    }
+
     #[test]
     fn test_count() {
         let g = Grid::new();
@@ -253,6 +1267,40 @@ mod tests {
         assert_eq!("6", eval_to_string(&g, "COUNTIF(Bn5:B10, \"<=5\")"));
     }
 
+    #[test]
+    fn test_countifs() {
+        let g = grid_with_rows(&[
+            &["apple", "3"],
+            &["avocado", "1"],
+            &["banana", "5"],
+            &["apricot", "7"],
+        ]);
+
+        assert_eq!("3", eval_to_string(&g, "COUNTIFS(A1:A4, \"a*\")"));
+        assert_eq!(
+            "2",
+            eval_to_string(&g, "COUNTIFS(A1:A4, \"a*\", B1:B4, \">2\")")
+        );
+        assert_eq!(
+            "5",
+            eval_to_string(&g, "COUNTIFS(0..10, \">2\", 0..10, \"<8\")"),
+        );
+        assert_eq!(
+            ErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(1, 4).unwrap(),
+                got: ArraySize::new(1, 3).unwrap(),
+            },
+            eval_to_err(&g, "COUNTIFS(A1:A4, \"a*\", B1:B3, \">2\")").msg,
+        );
+        assert_eq!(
+            ErrorMsg::MissingRequiredArgument {
+                func_name: "COUNTIFS".into(),
+                arg_name: "eval_range".into(),
+            },
+            eval_to_err(&g, "COUNTIFS()").msg,
+        );
+    }
+
     #[test]
     fn test_countblank() {
         let g = Grid::new();
@@ -277,4 +1325,296 @@ mod tests {
         let g = Grid::new();
         assert_eq!("3", eval_to_string(&g, "MAX(1, 3, 2)"));
     }
+
+    #[test]
+    fn test_minifs_maxifs() {
+        let g = Grid::new();
+        assert_eq!("6", eval_to_string(&g, "MINIFS(0..10, 0..10, \">5\")"));
+        assert_eq!("4", eval_to_string(&g, "MAXIFS(0..10, 0..10, \"<5\")"));
+        assert_eq!(
+            "8",
+            eval_to_string(&g, "MAXIFS(2^0..10, 0..10, \"<5\", 0..10, \"<>4\")"),
+        );
+        assert_eq!("0", eval_to_string(&g, "MINIFS(0..10, 0..10, \">20\")"));
+        assert_eq!("0", eval_to_string(&g, "MAXIFS(0..10, 0..10, \">20\")"));
+    }
+
+    #[test]
+    fn test_formula_median_mode() {
+        let g = Grid::new();
+        assert_eq!("3", eval_to_string(&g, "MEDIAN(1, 2, 3, 4, 5)"));
+        assert_eq!("3.5", eval_to_string(&g, "MEDIAN(1..6)"));
+        assert_eq!("3.5", eval_to_string(&g, "MEDIAN({6, 1, 5; 2, 4, 3})"));
+        expect_err(&ErrorMsg::EmptyArray, &g, "MEDIAN({\"a\"})");
+
+        assert_eq!("4", eval_to_string(&g, "MODE(5.6, 4, 4, 3, 2, 4)"));
+        // Ties go to the value that appears first.
+        assert_eq!("3", eval_to_string(&g, "MODE(1, 3, 2, 2, 3)"));
+        expect_err(&ErrorMsg::NotAvailable, &g, "MODE(1, 2, 3)");
+    }
+
+    #[test]
+    fn test_formula_stdev_var() {
+        let g = Grid::new();
+        let data = "{1345, 1301, 1368, 1322, 1310, 1370, 1318, 1350, 1303, 1299}";
+        let eval = |f: &str| eval_to_string(&g, &format!("{f}({data})"));
+        assert_f64_approx_eq(27.46391572, &eval("STDEV.S"));
+        assert_f64_approx_eq(26.05455814, &eval("STDEV.P"));
+        assert_f64_approx_eq(754.2666667, &eval("VAR.S"));
+        assert_f64_approx_eq(678.84, &eval("VAR.P"));
+
+        assert_eq!("0", eval_to_string(&g, "VAR.P(5)"));
+        expect_err(&ErrorMsg::DivideByZero, &g, "VAR.S(5)");
+        expect_err(&ErrorMsg::DivideByZero, &g, "STDEV.P()");
+    }
+
+    #[test]
+    fn test_formula_percentile_quartile() {
+        let g = Grid::new();
+        assert_f64_approx_eq(1.9, &eval_to_string(&g, "PERCENTILE({1, 3, 2, 4}, 0.3)"));
+        assert_eq!(
+            "{1, 4}",
+            eval_to_string(&g, "PERCENTILE({1, 3, 2, 4}, {0, 1})")
+        );
+        expect_err(&ErrorMsg::InvalidArgument, &g, "PERCENTILE({1, 2}, 1.5)");
+
+        let data = "{1, 2, 4, 7, 8, 9, 10, 12}";
+        assert_eq!(
+            "{1, 3.5, 7.5, 9.25, 12}",
+            eval_to_string(&g, &format!("QUARTILE({data}, {{0, 1, 2, 3, 4}})")),
+        );
+        expect_err(&ErrorMsg::InvalidArgument, &g, "QUARTILE({1, 2}, 5)");
+    }
+
+    #[test]
+    fn test_formula_rank_large_small() {
+        let g = Grid::new();
+        let data = "{7, 3.5, 3.5, 1, 2}";
+        assert_eq!("1", eval_to_string(&g, &format!("RANK(7, {data})")));
+        assert_eq!("3", eval_to_string(&g, &format!("RANK(3.5, {data}, 1)")));
+        assert_eq!(
+            "{1, 2, 2, 5, 4}",
+            eval_to_string(&g, &format!("RANK({data}, {data})")),
+        );
+        expect_err(&ErrorMsg::NotAvailable, &g, &format!("RANK(4, {data})"));
+
+        let data = "{3, 5, 3, 5, 4; 4, 2, 4, 6, 7}";
+        assert_eq!("5", eval_to_string(&g, &format!("LARGE({data}, 3)")));
+        assert_eq!("7", eval_to_string(&g, &format!("LARGE({data}, 1)")));
+        assert_eq!("4", eval_to_string(&g, &format!("SMALL({data}, 5)")));
+        assert_eq!(
+            "{2, 3}",
+            eval_to_string(&g, &format!("SMALL({data}, {{1, 2}})"))
+        );
+        expect_err(&ErrorMsg::InvalidArgument, &g, &format!("LARGE({data}, 0)"));
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            &format!("SMALL({data}, 11)"),
+        );
+    }
+
+    #[test]
+    fn test_formula_correl_covariance() {
+        let g = Grid::new();
+        let data = "{3, 2, 4, 5, 6}, {9, 7, 12, 15, 17}";
+        assert_f64_approx_eq(0.997054486, &eval_to_string(&g, &format!("CORREL({data})")));
+        assert_f64_approx_eq(5.2, &eval_to_string(&g, &format!("COVARIANCE.P({data})")));
+        assert_f64_approx_eq(
+            9.666666667,
+            &eval_to_string(&g, "COVARIANCE.S({2, 4, 8}, {5, 11, 12})"),
+        );
+
+        // Positions that are not numbers in both arrays are ignored.
+        assert_f64_approx_eq(
+            5.2,
+            &eval_to_string(
+                &g,
+                "COVARIANCE.P({3, 2, \"a\", 4, 5, 6}, {9, 7, 1, 12, 15, 17})",
+            ),
+        );
+
+        expect_err(&ErrorMsg::DivideByZero, &g, "CORREL({1, 1}, {2, 3})");
+        expect_err(&ErrorMsg::DivideByZero, &g, "COVARIANCE.S({1}, {2})");
+        assert_eq!(
+            ErrorMsg::ExactArraySizeMismatch {
+                expected: ArraySize::new(2, 1).unwrap(),
+                got: ArraySize::new(3, 1).unwrap(),
+            },
+            eval_to_err(&g, "CORREL({1, 2, 3}, {1, 2})").msg,
+        );
+    }
+
+    #[test]
+    fn test_formula_linear_regression() {
+        let g = Grid::new();
+        assert_f64_approx_eq(
+            0.305556,
+            &eval_to_string(&g, "SLOPE({2, 3, 9, 1, 8, 7, 5}, {6, 5, 11, 7, 5, 4, 4})"),
+        );
+        assert_f64_approx_eq(
+            0.0483871,
+            &eval_to_string(&g, "INTERCEPT({2, 3, 9, 1, 8}, {6, 5, 11, 7, 5})"),
+        );
+        assert_f64_approx_eq(
+            10.607253,
+            &eval_to_string(
+                &g,
+                "FORECAST.LINEAR(30, {6, 7, 9, 15, 21}, {20, 28, 31, 38, 40})",
+            ),
+        );
+        assert_eq!(
+            "{3, 5}",
+            eval_to_string(&g, "FORECAST.LINEAR({1, 2}, {3, 5, 7}, {1, 2, 3})"),
+        );
+        expect_err(&ErrorMsg::DivideByZero, &g, "SLOPE({1, 2}, {3, 3})");
+    }
+
+    #[test]
+    fn test_formula_normal_distribution() {
+        let g = Grid::new();
+        assert_f64_approx_eq(
+            0.9087888,
+            &eval_to_string(&g, "NORM.DIST(42, 40, 1.5, TRUE)"),
+        );
+        assert_f64_approx_eq(
+            0.10934005,
+            &eval_to_string(&g, "NORM.DIST(42, 40, 1.5, FALSE)"),
+        );
+        assert_f64_approx_eq(
+            0.908788726,
+            &eval_to_string(&g, "NORM.S.DIST(1.333333, TRUE)"),
+        );
+        assert_f64_approx_eq(
+            0.164010148,
+            &eval_to_string(&g, "NORM.S.DIST(1.333333, FALSE)"),
+        );
+        assert_eq!("0.5", eval_to_string(&g, "NORM.S.DIST(0, TRUE)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "NORM.DIST(42, 40, 0, TRUE)");
+
+        assert_f64_approx_eq(
+            42.000002,
+            &eval_to_string(&g, "NORM.INV(0.908789, 40, 1.5)"),
+        );
+        assert_f64_approx_eq(1.333334673, &eval_to_string(&g, "NORM.S.INV(0.908789)"));
+        assert_f64_approx_eq(-3.090232306, &eval_to_string(&g, "NORM.S.INV(0.001)"));
+        assert_f64_approx_eq(4.753424309, &eval_to_string(&g, "NORM.S.INV(0.999999)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "NORM.S.INV(0)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "NORM.INV(1, 40, 1.5)");
+    }
+
+    #[test]
+    fn test_formula_t_distribution() {
+        let g = Grid::new();
+        assert_f64_approx_eq(0.99469533, &eval_to_string(&g, "T.DIST(60, 1, TRUE)"));
+        assert_f64_approx_eq(0.00073691, &eval_to_string(&g, "T.DIST(8, 3, FALSE)"));
+        assert_f64_approx_eq(0.020496109, &eval_to_string(&g, "T.DIST(-2.5, 7, TRUE)"));
+        assert_f64_approx_eq(
+            0.054644930,
+            &eval_to_string(&g, "T.DIST.2T(1.959999998, 60)"),
+        );
+        assert_f64_approx_eq(
+            0.027322465,
+            &eval_to_string(&g, "T.DIST.RT(1.959999998, 60)"),
+        );
+        expect_err(&ErrorMsg::InvalidArgument, &g, "T.DIST(1, 0.5, TRUE)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "T.DIST.2T(-1, 60)");
+
+        assert_f64_approx_eq(0.8164966, &eval_to_string(&g, "T.INV(0.75, 2)"));
+        assert_f64_approx_eq(-3.36493, &eval_to_string(&g, "T.INV(0.01, 5)"));
+        assert_f64_approx_eq(0.606533076, &eval_to_string(&g, "T.INV.2T(0.546449, 60)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "T.INV(1, 2)");
+    }
+
+    #[test]
+    fn test_formula_binom_dist() {
+        let g = Grid::new();
+        assert_f64_approx_eq(
+            0.2050781,
+            &eval_to_string(&g, "BINOM.DIST(6, 10, 0.5, FALSE)"),
+        );
+        assert_f64_approx_eq(
+            0.828125,
+            &eval_to_string(&g, "BINOM.DIST(6, 10, 0.5, TRUE)"),
+        );
+        assert_f64_approx_eq(
+            0.2050781,
+            &eval_to_string(&g, "BINOM.DIST(6.9, 10, 0.5, FALSE)"),
+        );
+        assert_f64_approx_eq(
+            0.105574515,
+            &eval_to_string(&g, "BINOM.DIST(400, 1000, 0.42, TRUE)"),
+        );
+        assert_eq!("1", eval_to_string(&g, "BINOM.DIST(0, 10, 0, FALSE)"));
+        assert_eq!("1", eval_to_string(&g, "BINOM.DIST(10, 10, 0.3, TRUE)"));
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "BINOM.DIST(11, 10, 0.5, TRUE)",
+        );
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "BINOM.DIST(5, 10, 1.5, TRUE)",
+        );
+    }
+
+    #[test]
+    fn test_linest() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "{2, -1}",
+            eval_to_string(&g, "ROUND(LINEST({1; 3; 5; 7}, {1; 2; 3; 4}), 9)")
+        );
+        assert_eq!(
+            "{2, -1}",
+            eval_to_string(&g, "ROUND(LINEST({1, 3, 5, 7}), 9)")
+        );
+        assert_eq!(
+            "{0.6, 2.2; 0.282843, 0.938083; 0.6, 0.894427; 4.5, 3; 3.6, 2.4}",
+            eval_to_string(
+                &g,
+                "ROUND(LINEST({2; 4; 5; 4; 5}, {1; 2; 3; 4; 5}, TRUE, TRUE), 6)"
+            ),
+        );
+
+        // Multiple variables are returned from last to first.
+        assert_eq!(
+            "{-0.833333, 2.833333, -0.666667; \
+              0.263523, 0.345607, 0.749074; \
+              0.978261, 0.707107, Value not available; \
+              45, 2, Value not available; \
+              45, 1, Value not available}",
+            eval_to_string(
+                &g,
+                "ROUND(LINEST({1; 4; 3; 8; 9}, {1, 2; 2, 1; 3, 5; 4, 3; 5, 6}, , TRUE), 6)",
+            ),
+        );
+
+        // Without a constant, the line passes through the origin.
+        assert_eq!(
+            "{1.2, 0; 0.17581, Value not available; 0.92093, 1.30384; 46.588235, 4; 79.2, 6.8}",
+            eval_to_string(
+                &g,
+                "ROUND(LINEST({2; 4; 5; 4; 5}, {1; 2; 3; 4; 5}, FALSE, TRUE), 6)"
+            ),
+        );
+
+        assert_eq!(
+            ErrorMsg::ArrayAxisMismatch {
+                axis: crate::Axis::Y,
+                expected: 4,
+                got: 3,
+            },
+            eval_to_err(&g, "LINEST({1; 2; 3; 4}, {1; 2; 3})").msg,
+        );
+        expect_err(&ErrorMsg::NonLinearArray, &g, "LINEST({1, 2; 3, 4})");
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "LINEST({1; 2; 3}, {1; 1; 1})",
+        );
+        expect_err(&ErrorMsg::InvalidArgument, &g, "LINEST(5)");
+    }
 }
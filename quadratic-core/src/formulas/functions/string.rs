@@ -4,19 +4,705 @@ pub const CATEGORY: FormulaFunctionCategory = FormulaFunctionCategory {
     include_in_docs: true,
     include_in_completions: true,
     name: "String functions",
-    docs: "",
+    docs: "Positions within a string are counted in characters, starting \
+           from `1` for the first character.\
+           \n\n\
+           Because `\\` escapes the next character in a string literal, \
+           backslashes in a regular expression must be doubled, such as \
+           `\"\\\\d+\"` to match digits.",
     get_functions,
 };
 
 fn get_functions() -> Vec<FormulaFunction> {
-    vec![formula_fn!(
-        /// [Concatenates](https://en.wikipedia.org/wiki/Concatenation) all
-        /// values as strings.
-        #[examples("CONCAT(\"Hello, \", C0, \"!\")")]
-        fn CONCAT(strings: (Iter<String>)) {
-            strings.try_fold(String::new(), |a, b| Ok(a + &b?))
+    vec![
+        formula_fn!(
+            /// [Concatenates](https://en.wikipedia.org/wiki/Concatenation) all
+            /// values as strings.
+            #[examples("CONCAT(\"Hello, \", C0, \"!\")")]
+            fn CONCAT(strings: (Iter<String>)) {
+                strings.try_fold(String::new(), |a, b| Ok(a + &b?))
+            }
+        ),
+        formula_fn!(
+            /// Returns the number of characters in a string.
+            #[examples("LEN(\"abc\") = 3", "LEN(A1)")]
+            #[zip_map]
+            fn LEN([s]: String) {
+                s.chars().count() as i64
+            }
+        ),
+        formula_fn!(
+            /// Returns the first `char_count` characters from the beginning
+            /// of the string `s`.
+            ///
+            /// If `char_count` is omitted, it is assumed to be 1. If
+            /// `char_count` is greater than the number of characters in `s`,
+            /// then the entire string is returned. Returns an error if
+            /// `char_count` is less than 0.
+            #[examples(
+                "LEFT(\"Hello, world!\") = \"H\"",
+                "LEFT(\"Hello, world!\", 6) = \"Hello,\""
+            )]
+            #[zip_map]
+            fn LEFT([s]: String, [char_count]: (Option<Spanned<i64>>)) {
+                let char_count = char_count.map_or(Ok(1), count_to_usize)?;
+                s.chars().take(char_count).collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the last `char_count` characters from the end of the
+            /// string `s`.
+            ///
+            /// If `char_count` is omitted, it is assumed to be 1. If
+            /// `char_count` is greater than the number of characters in `s`,
+            /// then the entire string is returned. Returns an error if
+            /// `char_count` is less than 0.
+            #[examples(
+                "RIGHT(\"Hello, world!\") = \"!\"",
+                "RIGHT(\"Hello, world!\", 6) = \"world!\""
+            )]
+            #[zip_map]
+            fn RIGHT([s]: String, [char_count]: (Option<Spanned<i64>>)) {
+                let char_count = char_count.map_or(Ok(1), count_to_usize)?;
+                let skip = s.chars().count().saturating_sub(char_count);
+                s.chars().skip(skip).collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the substring of `s` that is `char_count` characters
+            /// long and starts at `start_char`.
+            ///
+            /// If `start_char` is past the end of `s`, then an empty string is
+            /// returned. Returns an error if `start_char` is less than 1 or if
+            /// `char_count` is less than 0.
+            #[examples("MID(\"Hello, world!\", 4, 6) = \"lo, wo\"", "MID(A1, 2, 99)")]
+            #[zip_map]
+            fn MID([s]: String, [start_char]: (Spanned<i64>), [char_count]: (Spanned<i64>)) {
+                let start = position_to_index(start_char)?;
+                let char_count = count_to_usize(char_count)?;
+                s.chars().skip(start).take(char_count).collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the string `s` converted to uppercase.
+            #[examples("UPPER(\"Hello, world!\") = \"HELLO, WORLD!\"")]
+            #[zip_map]
+            fn UPPER([s]: String) {
+                s.to_uppercase()
+            }
+        ),
+        formula_fn!(
+            /// Returns the string `s` converted to lowercase.
+            #[examples("LOWER(\"Hello, world!\") = \"hello, world!\"")]
+            #[zip_map]
+            fn LOWER([s]: String) {
+                s.to_lowercase()
+            }
+        ),
+        formula_fn!(
+            /// Capitalizes the first letter of each word in `s` and converts
+            /// all other letters to lowercase. Any character that is not a
+            /// letter starts a new word.
+            #[examples("PROPER(\"hELLO, wORLD!\") = \"Hello, World!\"")]
+            #[zip_map]
+            fn PROPER([s]: String) {
+                let mut ret = String::with_capacity(s.len());
+                let mut is_start_of_word = true;
+                for c in s.chars() {
+                    if is_start_of_word {
+                        ret.extend(c.to_uppercase());
+                    } else {
+                        ret.extend(c.to_lowercase());
+                    }
+                    is_start_of_word = !c.is_alphabetic();
+                }
+                ret
+            }
+        ),
+        formula_fn!(
+            /// Removes spaces from the beginning and end of `s`, and replaces
+            /// each run of spaces between words with a single space.
+            #[examples("TRIM(\"   a   b    c   \") = \"a b c\"")]
+            #[zip_map]
+            fn TRIM([s]: String) {
+                s.split(' ').filter(|word| !word.is_empty()).join(" ")
+            }
+        ),
+        formula_fn!(
+            /// Replaces `old_text` with `new_text` in `s`.
+            ///
+            /// If `instance_num` is given, then only that occurrence of
+            /// `old_text` is replaced, counting from 1. Otherwise every
+            /// occurrence is replaced. Returns an error if `instance_num` is
+            /// less than 1.
+            #[examples(
+                "SUBSTITUTE(\"a-b-c\", \"-\", \"+\") = \"a+b+c\"",
+                "SUBSTITUTE(\"a-b-c\", \"-\", \"+\", 2) = \"a-b+c\""
+            )]
+            #[zip_map]
+            fn SUBSTITUTE(
+                [s]: String,
+                [old_text]: String,
+                [new_text]: String,
+                [instance_num]: (Option<Spanned<i64>>),
+            ) {
+                let instance_index = instance_num.map(position_to_index).transpose()?;
+                if old_text.is_empty() {
+                    return Ok(CellValue::from(s.clone()));
+                }
+                match instance_index {
+                    None => s.replace(old_text.as_str(), &new_text),
+                    Some(i) => match s.match_indices(old_text.as_str()).nth(i) {
+                        Some((byte_index, _)) => {
+                            let rest = &s[byte_index + old_text.len()..];
+                            format!("{}{new_text}{rest}", &s[..byte_index])
+                        }
+                        None => s.clone(),
+                    },
+                }
+            }
+        ),
+        formula_fn!(
+            /// Replaces `char_count` characters in `old_text`, starting at
+            /// `start_char`, with `new_text`.
+            ///
+            /// Returns an error if `start_char` is less than 1 or if
+            /// `char_count` is less than 0.
+            #[examples("REPLACE(\"Hello, world!\", 8, 5, \"there\") = \"Hello, there!\"")]
+            #[zip_map]
+            fn REPLACE(
+                [old_text]: String,
+                [start_char]: (Spanned<i64>),
+                [char_count]: (Spanned<i64>),
+                [new_text]: String,
+            ) {
+                let start = position_to_index(start_char)?;
+                let char_count = count_to_usize(char_count)?;
+                let before = old_text.chars().take(start);
+                let after = old_text.chars().skip(start.saturating_add(char_count));
+                before
+                    .chain(new_text.chars())
+                    .chain(after)
+                    .collect::<String>()
+            }
+        ),
+        formula_fn!(
+            /// Returns the position of the first occurrence of `search_for` in
+            /// `text_to_search`, starting at `start_char`. The search is
+            /// case-sensitive and does not support wildcards.
+            ///
+            /// If `start_char` is omitted, it is assumed to be 1. Returns an
+            /// error if `search_for` is not found or if `start_char` is less
+            /// than 1.
+            #[examples(
+                "FIND(\"o\", \"Hello, world!\") = 5",
+                "FIND(\"o\", \"Hello, world!\", 6) = 9"
+            )]
+            #[zip_map]
+            fn FIND(
+                span: Span,
+                [search_for]: String,
+                [text_to_search]: String,
+                [start_char]: (Option<Spanned<i64>>),
+            ) {
+                let start = start_char.map_or(Ok(0), position_to_index)?;
+                let byte_start = char_to_byte_index(&text_to_search, start)
+                    .ok_or_else(|| ErrorMsg::NoMatch.with_span(*span))?;
+                let byte_index = text_to_search[byte_start..]
+                    .find(search_for.as_str())
+                    .ok_or_else(|| ErrorMsg::NoMatch.with_span(*span))?;
+                byte_to_position(&text_to_search, byte_start + byte_index)
+            }
+        ),
+        formula_fn!(
+            /// Returns the position of the first occurrence of `search_for` in
+            /// `text_to_search`, starting at `start_char`. The search is
+            /// case-insensitive and `search_for` may contain wildcards.
+            #[doc = see_docs_for_more_about_wildcards!()]
+            ///
+            /// If `start_char` is omitted, it is assumed to be 1. Returns an
+            /// error if `search_for` is not found or if `start_char` is less
+            /// than 1.
+            #[examples(
+                "SEARCH(\"O\", \"Hello, world!\") = 5",
+                "SEARCH(\"w?r\", \"Hello, world!\") = 8"
+            )]
+            #[zip_map]
+            fn SEARCH(
+                span: Span,
+                [search_for]: String,
+                [text_to_search]: String,
+                [start_char]: (Option<Spanned<i64>>),
+            ) {
+                let start = start_char.map_or(Ok(0), position_to_index)?;
+                let byte_start = char_to_byte_index(&text_to_search, start)
+                    .ok_or_else(|| ErrorMsg::NoMatch.with_span(*span))?;
+                let regex = crate::formulas::wildcard_pattern_to_search_regex(&search_for)?;
+                let m = regex
+                    .find_at(&text_to_search, byte_start)
+                    .ok_or_else(|| ErrorMsg::NoMatch.with_span(*span))?;
+                byte_to_position(&text_to_search, m.start())
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if any part of `text` matches the [regular
+            /// expression](https://docs.rs/regex/latest/regex/#syntax)
+            /// `pattern` and `FALSE` otherwise. The match is case-sensitive
+            /// unless `pattern` starts with `(?i)`.
+            ///
+            /// Returns an error if `pattern` is not a valid regular
+            /// expression.
+            #[examples(
+                "REGEXMATCH(\"Hello, world!\", \"w.r\") = TRUE",
+                "REGEXMATCH(A1, \"^[0-9]+$\")"
+            )]
+            #[zip_map]
+            fn REGEXMATCH(ctx: Ctx, [text]: String, [pattern]: (Spanned<String>)) {
+                ctx.get_regex(&pattern, false)?.is_match(&text)
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if any part of `text` matches the [regular
+            /// expression](https://docs.rs/regex/latest/regex/#syntax)
+            /// `pattern` and `FALSE` otherwise.
+            ///
+            /// If `case_sensitivity` is 1, then the match is
+            /// case-insensitive; if it is 0 or omitted, then the match is
+            /// case-sensitive. Returns an error if `pattern` is not a valid
+            /// regular expression.
+            #[examples(
+                "REGEXTEST(\"Hello, world!\", \"W.R\", 1) = TRUE",
+                "REGEXTEST(A1, \"^[0-9]+$\")"
+            )]
+            #[zip_map]
+            fn REGEXTEST(
+                ctx: Ctx,
+                [text]: String,
+                [pattern]: (Spanned<String>),
+                [case_sensitivity]: (Option<Spanned<i64>>),
+            ) {
+                let case_insensitive = is_case_insensitive(case_sensitivity)?;
+                ctx.get_regex(&pattern, case_insensitive)?.is_match(&text)
+            }
+        ),
+        formula_fn!(
+            /// Returns the part of `text` that matches the [regular
+            /// expression](https://docs.rs/regex/latest/regex/#syntax)
+            /// `pattern`.
+            ///
+            /// If `return_mode` is 0 or omitted, then the first match is
+            /// returned. If it is 1, then all matches are returned as a
+            /// column. If it is 2, then the capture groups of the first match
+            /// are returned as a row; unmatched groups are empty.
+            ///
+            /// If `case_sensitivity` is 1, then the match is
+            /// case-insensitive; if it is 0 or omitted, then the match is
+            /// case-sensitive. Returns an error if `pattern` is not a valid
+            /// regular expression or if there is no match.
+            #[examples(
+                "REGEXEXTRACT(\"Order #1234\", \"[0-9]+\") = \"1234\"",
+                "REGEXEXTRACT(\"a1 b2 c3\", \"[a-z][0-9]\", 1)",
+                "REGEXEXTRACT(\"2024-06-15\", \"([0-9]+)-([0-9]+)-([0-9]+)\", 2)"
+            )]
+            fn REGEXEXTRACT(
+                ctx: Ctx,
+                span: Span,
+                text: String,
+                pattern: (Spanned<String>),
+                return_mode: (Option<Spanned<i64>>),
+                case_sensitivity: (Option<Spanned<i64>>),
+            ) {
+                let case_insensitive = is_case_insensitive(case_sensitivity)?;
+                let regex = ctx.get_regex(&pattern, case_insensitive)?;
+                let no_match = || ErrorMsg::NotAvailable.with_span(span);
+                match return_mode {
+                    None | Some(Spanned { inner: 0, .. }) => {
+                        Value::from(regex.find(&text).ok_or_else(no_match)?.as_str())
+                    }
+                    Some(Spanned { inner: 1, .. }) => {
+                        let matches = regex
+                            .find_iter(&text)
+                            .map(|m| vec![m.as_str().to_string()])
+                            .collect_vec();
+                        if matches.is_empty() {
+                            return Err(no_match());
+                        }
+                        Value::from(strings_to_array(matches, CellValue::Blank)?)
+                    }
+                    Some(Spanned { inner: 2, .. }) => {
+                        let captures = regex.captures(&text).ok_or_else(no_match)?;
+                        let groups = captures
+                            .iter()
+                            .skip(1)
+                            .map(|group| group.map_or("", |m| m.as_str()).to_string())
+                            .collect_vec();
+                        Value::from(strings_to_array(vec![groups], CellValue::Blank)?)
+                    }
+                    Some(Spanned { span, .. }) => {
+                        return Err(ErrorMsg::InvalidArgument.with_span(span));
+                    }
+                }
+            }
+        ),
+        formula_fn!(
+            /// Replaces the parts of `text` that match the [regular
+            /// expression](https://docs.rs/regex/latest/regex/#syntax)
+            /// `pattern` with `replacement`. In `replacement`, `$1` or `${1}`
+            /// is replaced with the first capture group, `${name}` with the
+            /// group named `name`, and `$$` with a literal `$`.
+            ///
+            /// If `occurrence` is 0 or omitted, then every match is replaced.
+            /// Otherwise only that match is replaced, counting from 1, or
+            /// from the end if `occurrence` is negative. If `case_sensitivity`
+            /// is 1, then the match is case-insensitive; if it is 0 or
+            /// omitted, then the match is case-sensitive. Returns an error if
+            /// `pattern` is not a valid regular expression.
+            #[examples(
+                "REGEXREPLACE(\"a1b22c333\", \"[0-9]+\", \"#\") = \"a#b#c#\"",
+                "REGEXREPLACE(\"Smith, John\", \"([a-z]+), ([a-z]+)\", \"$2 $1\", 0, 1) = \"John Smith\""
+            )]
+            #[zip_map]
+            fn REGEXREPLACE(
+                ctx: Ctx,
+                [text]: String,
+                [pattern]: (Spanned<String>),
+                [replacement]: String,
+                [occurrence]: (Option<i64>),
+                [case_sensitivity]: (Option<Spanned<i64>>),
+            ) {
+                let case_insensitive = is_case_insensitive(case_sensitivity)?;
+                let regex = ctx.get_regex(&pattern, case_insensitive)?;
+                match occurrence.unwrap_or(0) {
+                    0 => replace_all_matches(&regex, &text, &replacement),
+                    n => replace_nth_match(&regex, &text, &replacement, n),
+                }
+                .ok_or_else(|| ErrorMsg::Overflow.with_span(pattern.span))?
+            }
+        ),
+        formula_fn!(
+            /// Concatenates `strings` as strings, with `delimiter` between
+            /// each one. If `ignore_empty` is true, then empty strings and
+            /// blank cells are skipped.
+            #[examples(
+                "TEXTJOIN(\", \", TRUE, A1:A10)",
+                "TEXTJOIN(\"-\", FALSE, \"a\", \"\", \"c\") = \"a--c\""
+            )]
+            #[zip_map]
+            fn TEXTJOIN([delimiter]: String, [ignore_empty]: bool, strings: (Iter<String>)) {
+                strings
+                    .iter()
+                    .filter(|s| !(ignore_empty && s.is_empty()))
+                    .join(&delimiter)
+            }
+        ),
+        formula_fn!(
+            /// Splits `text` at each occurrence of `delimiter` and returns the
+            /// pieces as a single row.
+            ///
+            /// If `split_by_each` is true or omitted, then `text` is split at
+            /// each character of `delimiter`; otherwise it is split only at
+            /// the whole `delimiter`. If `remove_empty_text` is true or
+            /// omitted, then empty pieces are removed. Returns an error if
+            /// `delimiter` is empty.
+            #[examples("SPLIT(\"a,b;c\", \",;\")", "SPLIT(\"a--b\", \"--\", FALSE, FALSE)")]
+            fn SPLIT(
+                text: String,
+                delimiter: (Spanned<String>),
+                split_by_each: (Option<bool>),
+                remove_empty_text: (Option<bool>),
+            ) {
+                if delimiter.inner.is_empty() {
+                    return Err(ErrorMsg::InvalidArgument.with_span(delimiter.span));
+                }
+                let delimiters = match split_by_each.unwrap_or(true) {
+                    true => delimiter.inner.chars().map(String::from).collect(),
+                    false => vec![delimiter.inner],
+                };
+                let pieces = split_at_any(&text, &delimiters, false);
+                let remove_empty_text = remove_empty_text.unwrap_or(true);
+                let pieces = pieces
+                    .into_iter()
+                    .filter(|piece| !(remove_empty_text && piece.is_empty()))
+                    .collect_vec();
+                strings_to_array(vec![pieces], CellValue::Blank)?
+            }
+        ),
+        formula_fn!(
+            /// Splits `text` into columns at each occurrence of
+            /// `col_delimiter` and into rows at each occurrence of
+            /// `row_delimiter`.
+            ///
+            /// Each delimiter may be a single string or an array of strings,
+            /// any of which splits the text. If `ignore_empty` is true, then
+            /// empty pieces are removed. If `match_mode` is 1, then matching
+            /// delimiters is case-insensitive; otherwise it is case-sensitive.
+            /// Rows with fewer pieces than the longest row are padded with
+            /// `pad_with`, or blank cells if `pad_with` is omitted.
+            #[examples(
+                "TEXTSPLIT(\"a,b,c\", \",\")",
+                "TEXTSPLIT(\"a=1;b=2\", \"=\", \";\")",
+                "TEXTSPLIT(A1, {\",\", \";\"}, , TRUE)"
+            )]
+            fn TEXTSPLIT(
+                text: String,
+                col_delimiter: (Spanned<Array>),
+                row_delimiter: (Option<Spanned<Array>>),
+                ignore_empty: (Option<bool>),
+                match_mode: (Option<Spanned<i64>>),
+                pad_with: (Option<CellValue>),
+            ) {
+                let ignore_case = is_case_insensitive(match_mode)?;
+                let col_delimiters = delimiter_strings(col_delimiter)?;
+                let row_delimiters = match row_delimiter {
+                    Some(row_delimiter) => delimiter_strings(row_delimiter)?,
+                    None => vec![],
+                };
+                let ignore_empty = ignore_empty.unwrap_or(false);
+
+                let rows = split_at_any(&text, &row_delimiters, ignore_case)
+                    .into_iter()
+                    .filter(|row| !(ignore_empty && row.is_empty()))
+                    .map(|row| {
+                        split_at_any(&row, &col_delimiters, ignore_case)
+                            .into_iter()
+                            .filter(|piece| !(ignore_empty && piece.is_empty()))
+                            .collect_vec()
+                    })
+                    .collect_vec();
+                strings_to_array(rows, pad_with.unwrap_or(CellValue::Blank))?
+            }
+        ),
+        formula_fn!(
+            /// Returns the string `s` repeated `count` times.
+            ///
+            /// Returns an error if `count` is less than 0.
+            #[examples("REPT(\"ab\", 3) = \"ababab\"")]
+            #[zip_map]
+            fn REPT([s]: String, [count]: (Spanned<i64>)) {
+                let count_span = count.span;
+                let count = count_to_usize(count)?;
+                if s.len().saturating_mul(count) > MAX_STRING_LEN {
+                    return Err(ErrorMsg::Overflow.with_span(count_span));
+                }
+                s.repeat(count)
+            }
+        ),
+        formula_fn!(
+            /// Returns `TRUE` if `s1` and `s2` are exactly the same, including
+            /// case, and `FALSE` otherwise.
+            #[examples("EXACT(\"abc\", \"abc\") = TRUE", "EXACT(\"abc\", \"ABC\") = FALSE")]
+            #[zip_map]
+            fn EXACT([s1]: String, [s2]: String) {
+                s1 == s2
+            }
+        ),
+        formula_fn!(
+            /// Returns the character with the Unicode code point `code`.
+            ///
+            /// Returns an error if `code` is not a valid code point.
+            #[examples("CHAR(65) = \"A\"", "CHAR(9731) = \"☃\"")]
+            #[zip_map]
+            fn CHAR([code]: (Spanned<i64>)) {
+                u32::try_from(code.inner)
+                    .ok()
+                    .filter(|&c| c != 0)
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| ErrorMsg::InvalidArgument.with_span(code.span))?
+                    .to_string()
+            }
+        ),
+        formula_fn!(
+            /// Returns the Unicode code point of the first character in `s`.
+            /// This is the same as `UNICODE`.
+            ///
+            /// Returns an error if `s` is empty.
+            #[examples("CODE(\"A\") = 65", "CODE(\"☃\") = 9731")]
+            #[zip_map]
+            fn CODE([s]: (Spanned<String>)) {
+                first_char_code(&s)?
+            }
+        ),
+        formula_fn!(
+            /// Returns the Unicode code point of the first character in `s`.
+            ///
+            /// Returns an error if `s` is empty.
+            #[examples("UNICODE(\"A\") = 65", "UNICODE(\"☃\") = 9731")]
+            #[zip_map]
+            fn UNICODE([s]: (Spanned<String>)) {
+                first_char_code(&s)?
+            }
+        ),
+    ]
+}
+
+/// Maximum length in bytes of a string produced by a formula function.
+const MAX_STRING_LEN: usize = 1 << 24;
+
+/// Converts a number of characters to a `usize`, returning an error if it is
+/// negative.
+fn count_to_usize(count: Spanned<i64>) -> CodeResult<usize> {
+    usize::try_from(count.inner).map_err(|_| ErrorMsg::InvalidArgument.with_span(count.span))
+}
+
+/// Converts a 1-based character position to a 0-based index, returning an
+/// error if it is less than 1.
+fn position_to_index(position: Spanned<i64>) -> CodeResult<usize> {
+    position
+        .inner
+        .checked_sub(1)
+        .and_then(|i| usize::try_from(i).ok())
+        .ok_or_else(|| ErrorMsg::InvalidArgument.with_span(position.span))
+}
+
+/// Returns the byte index of the character at `char_index` in `s`, or `None`
+/// if it is past the end of `s`. The end of `s` is a valid index.
+fn char_to_byte_index(s: &str, char_index: usize) -> Option<usize> {
+    s.char_indices()
+        .map(|(i, _)| i)
+        .chain([s.len()])
+        .nth(char_index)
+}
+
+/// Returns the 1-based character position of the character at `byte_index` in
+/// `s`.
+fn byte_to_position(s: &str, byte_index: usize) -> i64 {
+    s[..byte_index].chars().count() as i64 + 1
+}
+
+/// Returns the Unicode code point of the first character in `s`.
+fn first_char_code(s: &Spanned<String>) -> CodeResult<u32> {
+    s.inner
+        .chars()
+        .next()
+        .map(u32::from)
+        .ok_or_else(|| ErrorMsg::InvalidArgument.with_span(s.span))
+}
+
+/// Returns the strings in a delimiter argument, ignoring empty strings.
+fn delimiter_strings(delimiters: Spanned<Array>) -> CodeResult<Vec<String>> {
+    delimiters
+        .inner
+        .cell_values_slice()
+        .iter()
+        .map(|v| String::try_from(v).map_err(|e| e.with_span(delimiters.span)))
+        .filter_ok(|s| !s.is_empty())
+        .collect()
+}
+
+/// Splits `s` at every occurrence of any of `delimiters`, preferring the
+/// longest delimiter that matches at each position.
+fn split_at_any(s: &str, delimiters: &[String], ignore_case: bool) -> Vec<String> {
+    if delimiters.is_empty() {
+        return vec![s.to_string()];
+    }
+    let pattern = delimiters
+        .iter()
+        .sorted_by_key(|d| std::cmp::Reverse(d.len()))
+        .map(|d| regex::escape(d))
+        .join("|");
+    match regex::RegexBuilder::new(&pattern)
+        .case_insensitive(ignore_case)
+        .build()
+    {
+        Ok(regex) => regex.split(s).map(String::from).collect(),
+        Err(_) => vec![s.to_string()],
+    }
+}
+
+/// Returns whether matching is case-insensitive, given an argument that is 0
+/// or omitted for case-sensitive and 1 for case-insensitive.
+fn is_case_insensitive(case_sensitivity: Option<Spanned<i64>>) -> CodeResult<bool> {
+    match case_sensitivity {
+        None | Some(Spanned { inner: 0, .. }) => Ok(false),
+        Some(Spanned { inner: 1, .. }) => Ok(true),
+        Some(Spanned { span, .. }) => Err(ErrorMsg::InvalidArgument.with_span(span)),
+    }
+}
+
+/// Replaces every match of `regex` in `text` with `replacement`. Returns
+/// `None` if the result would be longer than `MAX_STRING_LEN`, without building
+/// it.
+fn replace_all_matches(regex: &regex::Regex, text: &str, replacement: &str) -> Option<String> {
+    let mut result = String::new();
+    let mut last_end = 0;
+    for captures in regex.captures_iter(text) {
+        let m = captures.get(0)?;
+        result.push_str(&text[last_end..m.start()]);
+        expand_replacement(&captures, replacement, &mut result)?;
+        last_end = m.end();
+    }
+    result.push_str(&text[last_end..]);
+    (result.len() <= MAX_STRING_LEN).then_some(result)
+}
+
+/// Replaces the `n`th match of `regex` in `text` with `replacement`, counting
+/// from 1, or from the end if `n` is negative. Returns `text` unchanged if
+/// there is no such match, or `None` if the result would be longer than
+/// `MAX_STRING_LEN`.
+fn replace_nth_match(
+    regex: &regex::Regex,
+    text: &str,
+    replacement: &str,
+    n: i64,
+) -> Option<String> {
+    let all_captures = regex.captures_iter(text).collect_vec();
+    let index = match n > 0 {
+        true => usize::try_from(n - 1).ok(),
+        false => usize::try_from(n.unsigned_abs())
+            .ok()
+            .and_then(|k| all_captures.len().checked_sub(k)),
+    };
+    let Some((captures, m)) = index
+        .and_then(|i| all_captures.get(i))
+        .and_then(|captures| Some((captures, captures.get(0)?)))
+    else {
+        return Some(text.to_string());
+    };
+    let mut result = text[..m.start()].to_string();
+    expand_replacement(captures, replacement, &mut result)?;
+    result.push_str(&text[m.end()..]);
+    (result.len() <= MAX_STRING_LEN).then_some(result)
+}
+
+/// Appends `replacement` to `result`, with capture groups expanded. Returns
+/// `None` without appending anything if the result might become longer than
+/// `MAX_STRING_LEN`.
+fn expand_replacement(
+    captures: &regex::Captures<'_>,
+    replacement: &str,
+    result: &mut String,
+) -> Option<()> {
+    // Each `$` expands to at most the whole match.
+    let match_len = captures.get(0).map_or(0, |m| m.len());
+    let max_len = replacement
+        .matches('$')
+        .count()
+        .saturating_mul(match_len)
+        .saturating_add(replacement.len());
+    if result.len().saturating_add(max_len) > MAX_STRING_LEN {
+        return None;
+    }
+    captures.expand(replacement, result);
+    Some(())
+}
+
+/// Constructs an array from rows of strings, padding short rows with `pad`.
+fn strings_to_array(rows: Vec<Vec<String>>, pad: CellValue) -> CodeResult<Array> {
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(0).max(1);
+    let height = rows.len().max(1);
+    let size = crate::ArraySize::new_or_err(width as u32, height as u32)?;
+    let mut values = smallvec::SmallVec::with_capacity(size.len());
+    for y in 0..height {
+        let row = rows.get(y).map(|row| row.as_slice()).unwrap_or(&[]);
+        for x in 0..width {
+            values.push(match row.get(x) {
+                Some(s) => CellValue::Text(s.clone()),
+                None if row.is_empty() && x == 0 => CellValue::Text(String::new()),
+                None => pad.clone(),
+            });
         }
-    )]
+    }
+    Array::new_row_major(size, values)
 }
 
 #[cfg(test)]
@@ -31,4 +717,271 @@ mod tests {
             eval_to_string(&g, "'Hello, ' & 14000605 & ' worlds!'"),
         );
     }
+
+    #[test]
+    fn test_formula_len_left_right_mid() {
+        let g = Grid::new();
+        assert_eq!("5", eval_to_string(&g, "LEN(\"héllo\")"));
+        assert_eq!("{3, 0}", eval_to_string(&g, "LEN({\"abc\", \"\"})"));
+        assert_eq!("H", eval_to_string(&g, "LEFT(\"Hello\")"));
+        assert_eq!("Hel", eval_to_string(&g, "LEFT(\"Hello\", 3)"));
+        assert_eq!("Hello", eval_to_string(&g, "LEFT(\"Hello\", 99)"));
+        assert_eq!(
+            "{H, He, Hel}",
+            eval_to_string(&g, "LEFT(\"Hello\", {1, 2, 3})")
+        );
+        assert_eq!("o", eval_to_string(&g, "RIGHT(\"Hello\")"));
+        assert_eq!("llo", eval_to_string(&g, "RIGHT(\"Hello\", 3)"));
+        assert_eq!("", eval_to_string(&g, "RIGHT(\"Hello\", 0)"));
+        assert_eq!("ell", eval_to_string(&g, "MID(\"Hello\", 2, 3)"));
+        assert_eq!("", eval_to_string(&g, "MID(\"Hello\", 9, 3)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "LEFT(\"Hello\", -1)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "MID(\"Hello\", 0, 1)");
+    }
+
+    #[test]
+    fn test_formula_case_and_trim() {
+        let g = Grid::new();
+        assert_eq!("HÉLLO", eval_to_string(&g, "UPPER(\"héllo\")"));
+        assert_eq!("héllo", eval_to_string(&g, "LOWER(\"HÉLLO\")"));
+        assert_eq!(
+            "Hello, World-Wide 3D Web",
+            eval_to_string(&g, "PROPER(\"hELLO, world-wIDE 3D wEB\")"),
+        );
+        assert_eq!("a b c", eval_to_string(&g, "TRIM(\"   a   b    c   \")"));
+        assert_eq!("TRUE", eval_to_string(&g, "EXACT(\"abc\", \"abc\")"));
+        assert_eq!("FALSE", eval_to_string(&g, "EXACT(\"abc\", \"ABC\")"));
+        assert_eq!("ababab", eval_to_string(&g, "REPT(\"ab\", 3)"));
+        assert_eq!("", eval_to_string(&g, "REPT(\"ab\", 0)"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "REPT(\"ab\", -1)");
+    }
+
+    #[test]
+    fn test_formula_substitute_replace() {
+        let g = Grid::new();
+        assert_eq!(
+            "a+b+c",
+            eval_to_string(&g, "SUBSTITUTE(\"a-b-c\", \"-\", \"+\")")
+        );
+        assert_eq!(
+            "a-b+c",
+            eval_to_string(&g, "SUBSTITUTE(\"a-b-c\", \"-\", \"+\", 2)"),
+        );
+        assert_eq!(
+            "a-b-c",
+            eval_to_string(&g, "SUBSTITUTE(\"a-b-c\", \"-\", \"+\", 3)"),
+        );
+        assert_eq!(
+            "abc",
+            eval_to_string(&g, "SUBSTITUTE(\"abc\", \"\", \"+\")")
+        );
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "SUBSTITUTE(\"abc\", \"b\", \"+\", 0)",
+        );
+        assert_eq!(
+            "Hello, there!",
+            eval_to_string(&g, "REPLACE(\"Hello, world!\", 8, 5, \"there\")"),
+        );
+        assert_eq!(
+            "abcXY",
+            eval_to_string(&g, "REPLACE(\"abc\", 9, 1, \"XY\")")
+        );
+    }
+
+    #[test]
+    fn test_formula_find_search() {
+        let g = Grid::new();
+        assert_eq!("5", eval_to_string(&g, "FIND(\"o\", \"Hello, world!\")"));
+        assert_eq!("9", eval_to_string(&g, "FIND(\"o\", \"Hello, world!\", 6)"));
+        assert_eq!("3", eval_to_string(&g, "FIND(\"l\", \"héllo\")"));
+        expect_err(&ErrorMsg::NoMatch, &g, "FIND(\"O\", \"Hello, world!\")");
+        expect_err(&ErrorMsg::NoMatch, &g, "FIND(\"o\", \"Hello\", 99)");
+
+        assert_eq!("5", eval_to_string(&g, "SEARCH(\"O\", \"Hello, world!\")"));
+        assert_eq!(
+            "9",
+            eval_to_string(&g, "SEARCH(\"O\", \"Hello, world!\", 6)")
+        );
+        assert_eq!(
+            "8",
+            eval_to_string(&g, "SEARCH(\"w?r\", \"Hello, world!\")")
+        );
+        assert_eq!(
+            "2",
+            eval_to_string(&g, "SEARCH(\"e*o\", \"Hello, world!\")")
+        );
+        assert_eq!("3", eval_to_string(&g, "SEARCH(\"~?\", \"ab?\")"));
+        expect_err(&ErrorMsg::NoMatch, &g, "SEARCH(\"z\", \"Hello\")");
+    }
+
+    #[test]
+    fn test_formula_textjoin_split() {
+        let g = Grid::new();
+        assert_eq!(
+            "a, c",
+            eval_to_string(&g, "TEXTJOIN(\", \", TRUE, \"a\", \"\", \"c\")"),
+        );
+        assert_eq!(
+            "a--c",
+            eval_to_string(&g, "TEXTJOIN(\"-\", FALSE, {\"a\", \"\", \"c\"})"),
+        );
+
+        assert_eq!("{a, b, c}", eval_to_string(&g, "SPLIT(\"a,b;;c\", \",;\")"));
+        assert_eq!(
+            "{a, b, , c}",
+            eval_to_string(&g, "SPLIT(\"a,b;;c\", \",;\", TRUE, FALSE)"),
+        );
+        assert_eq!(
+            "{a, b-c}",
+            eval_to_string(&g, "SPLIT(\"a--b-c\", \"--\", FALSE)"),
+        );
+        expect_err(&ErrorMsg::InvalidArgument, &g, "SPLIT(\"abc\", \"\")");
+
+        assert_eq!(
+            "{a, b, c}",
+            eval_to_string(&g, "TEXTSPLIT(\"a,b,c\", \",\")")
+        );
+        assert_eq!(
+            "{a, 1; b, 2; c, }",
+            eval_to_string(&g, "TEXTSPLIT(\"a=1;b=2;c\", \"=\", \";\")"),
+        );
+        assert_eq!(
+            "{a, 1; c, -}",
+            eval_to_string(&g, "TEXTSPLIT(\"a=1;;c\", \"=\", \";\", TRUE, 0, \"-\")"),
+        );
+        assert_eq!(
+            "{a, b, c}",
+            eval_to_string(&g, "TEXTSPLIT(\"aXbxc\", \"x\", , , 1)"),
+        );
+        assert_eq!(
+            "{a, b, c}",
+            eval_to_string(&g, "TEXTSPLIT(\"a,b;c\", {\",\", \";\"})"),
+        );
+    }
+
+    #[test]
+    fn test_formula_char_code() {
+        let g = Grid::new();
+        assert_eq!("A", eval_to_string(&g, "CHAR(65)"));
+        assert_eq!("☃", eval_to_string(&g, "CHAR(9731)"));
+        assert_eq!("{a, b}", eval_to_string(&g, "CHAR({97, 98})"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "CHAR(0)");
+        expect_err(&ErrorMsg::InvalidArgument, &g, "CHAR(-5)");
+        assert_eq!("65", eval_to_string(&g, "CODE(\"ABC\")"));
+        assert_eq!("9731", eval_to_string(&g, "UNICODE(\"☃\")"));
+        expect_err(&ErrorMsg::InvalidArgument, &g, "UNICODE(\"\")");
+    }
+
+    #[test]
+    fn test_formula_regex() {
+        let g = Grid::new();
+
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, "REGEXMATCH(\"Hello, world!\", \"w.r\")")
+        );
+        assert_eq!(
+            "FALSE",
+            eval_to_string(&g, "REGEXMATCH(\"Hello, world!\", \"W.R\")")
+        );
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, "REGEXMATCH(\"Hello\", \"(?i)^h\")")
+        );
+        assert_eq!(
+            "{TRUE, FALSE}",
+            eval_to_string(&g, "REGEXMATCH({\"123\", \"12a\"}, \"^\\\\d+$\")"),
+        );
+        assert_eq!("FALSE", eval_to_string(&g, "REGEXTEST(\"Hello\", \"^h\")"));
+        assert_eq!(
+            "TRUE",
+            eval_to_string(&g, "REGEXTEST(\"Hello\", \"^h\", 1)")
+        );
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "REGEXTEST(\"Hello\", \"^h\", 2)",
+        );
+
+        assert_eq!(
+            "1234",
+            eval_to_string(&g, "REGEXEXTRACT(\"Order #1234\", \"[0-9]+\")")
+        );
+        assert_eq!(
+            "{a1; b2; c3}",
+            eval_to_string(&g, "REGEXEXTRACT(\"a1 b2 c3\", \"[a-z][0-9]\", 1)"),
+        );
+        assert_eq!(
+            "{2024, 06, 15}",
+            eval_to_string(
+                &g,
+                "REGEXEXTRACT(\"2024-06-15\", \"(\\\\d+)-(\\\\d+)-(\\\\d+)\", 2)"
+            ),
+        );
+        assert_eq!(
+            "{a, , c}",
+            eval_to_string(&g, "REGEXEXTRACT(\"ac\", \"(a)(b)?(c)\", 2)"),
+        );
+        assert_eq!(
+            "B",
+            eval_to_string(&g, "REGEXEXTRACT(\"aBc\", \"b\", 0, 1)")
+        );
+        expect_err(
+            &ErrorMsg::NotAvailable,
+            &g,
+            "REGEXEXTRACT(\"abc\", \"[0-9]\")",
+        );
+        expect_err(
+            &ErrorMsg::InvalidArgument,
+            &g,
+            "REGEXEXTRACT(\"abc\", \"b\", 3)",
+        );
+
+        assert_eq!(
+            "a#b#c#",
+            eval_to_string(&g, "REGEXREPLACE(\"a1b22c333\", \"[0-9]+\", \"#\")"),
+        );
+        assert_eq!(
+            "a1b#c333",
+            eval_to_string(&g, "REGEXREPLACE(\"a1b22c333\", \"[0-9]+\", \"#\", 2)"),
+        );
+        assert_eq!(
+            "a1b22c#",
+            eval_to_string(&g, "REGEXREPLACE(\"a1b22c333\", \"[0-9]+\", \"#\", -1)"),
+        );
+        assert_eq!(
+            "a1b22c333",
+            eval_to_string(&g, "REGEXREPLACE(\"a1b22c333\", \"[0-9]+\", \"#\", 4)"),
+        );
+        assert_eq!(
+            "John Smith",
+            eval_to_string(
+                &g,
+                "REGEXREPLACE(\"Smith, John\", \"(\\\\w+), (\\\\w+)\", \"$2 $1\")"
+            ),
+        );
+        assert_eq!(
+            "x-x",
+            eval_to_string(&g, "REGEXREPLACE(\"A-a\", \"a\", \"x\", 0, 1)"),
+        );
+
+        // Results that are too long fail before they are built.
+        expect_err(
+            &ErrorMsg::Overflow,
+            &g,
+            "REGEXREPLACE(REPT(\"a\", 1e7), \"\", REPT(\"b\", 1e7))",
+        );
+        expect_err(
+            &ErrorMsg::Overflow,
+            &g,
+            "REGEXREPLACE(REPT(\"a\", 1e7), \"a*\", REPT(\"$0\", 1e6), 1)",
+        );
+
+        // Malformed patterns are reported at the pattern argument.
+        let err = eval_to_err(&g, "REGEXMATCH(\"abc\", \"a(b\")");
+        assert_eq!(ErrorMsg::InvalidArgument, err.msg);
+        assert_eq!(Some(crate::Span { start: 18, end: 23 }), err.span);
+    }
 }
@@ -1,3 +1,5 @@
+use bigdecimal::{BigDecimal, RoundingMode, Zero};
+
 use super::*;
 
 /// Divides one number by another, handling the error case of division by zero.
@@ -9,6 +11,31 @@ pub fn checked_div(span: impl Into<Span>, dividend: f64, divisor: f64) -> CodeRe
     }
 }
 
+/// Returns an error if the result of a calculation is NaN or too large to
+/// represent.
+pub fn finite(span: impl Into<Span>, n: f64) -> CodeResult<f64> {
+    if n.is_nan() {
+        Err(ErrorMsg::NotANumber.with_span(span))
+    } else if n.is_infinite() {
+        Err(ErrorMsg::Overflow.with_span(span))
+    } else {
+        Ok(n)
+    }
+}
+
+/// Returns an error saying that a required argument to a function is missing.
+pub fn missing_arg_error(
+    func_name: &'static str,
+    arg_name: impl Into<Cow<'static, str>>,
+    span: impl Into<Span>,
+) -> Error {
+    ErrorMsg::MissingRequiredArgument {
+        func_name: func_name.into(),
+        arg_name: arg_name.into(),
+    }
+    .with_span(span)
+}
+
 pub fn average(
     span: impl Into<Span>,
     numbers: impl IntoIterator<Item = CodeResult<f64>>,
@@ -21,3 +48,276 @@ pub fn average(
     }
     util::checked_div(span, sum, count as f64)
 }
+
+/// Parses repeating pairs of ranges and criteria, such as the arguments to
+/// `SUMIFS`. Returns an error if there are no pairs or the last criterion is
+/// missing.
+pub fn criteria_pairs(
+    func_name: &'static str,
+    span: Span,
+    args: impl IntoIterator<Item = CodeResult<Spanned<Value>>>,
+) -> CodeResult<Vec<(Spanned<Array>, Criterion)>> {
+    let mut args = args.into_iter();
+    let mut pairs = vec![];
+    while let Some(eval_range) = args.next() {
+        let eval_range = eval_range?.map(Array::from);
+        let criteria = args
+            .next()
+            .ok_or_else(|| missing_arg_error(func_name, "criteria", span))??;
+        pairs.push((eval_range, Criterion::try_from(criteria.cell_value()?)?));
+    }
+    if pairs.is_empty() {
+        return Err(missing_arg_error(func_name, "eval_range", span));
+    }
+    Ok(pairs)
+}
+
+/// Converts a length to a `u32`, returning an error if it is less than 1.
+pub fn positive_len(len: Spanned<i64>) -> CodeResult<u32> {
+    match u32::try_from(len.inner) {
+        Ok(n @ 1..) => Ok(n),
+        _ => Err(ErrorMsg::InvalidArgument.with_span(len.span)),
+    }
+}
+
+/// Returns the size of an array with `columns` columns and `rows` rows, or an
+/// error if it would have too many elements.
+pub fn array_size(span: Span, columns: u32, rows: u32) -> CodeResult<ArraySize> {
+    if columns as u64 * rows as u64 > crate::limits::CELL_RANGE_LIMIT as u64 {
+        return Err(ErrorMsg::ArrayTooBig.with_span(span));
+    }
+    Ok(ArraySize::new_or_err(columns, rows)?)
+}
+
+/// Returns an error if `n` is not positive.
+pub fn positive(n: Spanned<f64>) -> CodeResult<f64> {
+    match n.inner > 0.0 {
+        true => Ok(n.inner),
+        false => Err(ErrorMsg::InvalidArgument.with_span(n.span)),
+    }
+}
+
+/// Converts a number to a decimal using the shortest representation that
+/// round-trips, so that `2.675` is treated as exactly `2.675` rather than the
+/// nearest `f64`, which is slightly less.
+pub fn to_decimal(span: Span, n: f64) -> CodeResult<BigDecimal> {
+    if n.is_nan() {
+        return Err(ErrorMsg::NotANumber.with_span(span));
+    }
+    if n.is_infinite() {
+        return Err(ErrorMsg::Infinity.with_span(span));
+    }
+    n.to_string()
+        .parse()
+        .map_err(|_| ErrorMsg::NotANumber.with_span(span))
+}
+
+/// Number of digits to the left of the decimal point in the largest `f64`.
+const MAX_INTEGER_DIGITS: i64 = 309;
+
+/// Rounds a number to `digits` decimal places using `mode`. If `digits` is
+/// negative, then the number is rounded to the left of the decimal point.
+pub fn round_to_digits(
+    span: Span,
+    number: f64,
+    digits: i64,
+    mode: RoundingMode,
+) -> CodeResult<CellValue> {
+    round_decimal_to_digits(span, to_decimal(span, number)?, digits, mode)
+}
+
+/// Rounds a decimal number to `digits` decimal places using `mode`. If
+/// `digits` is negative, then the number is rounded to the left of the decimal
+/// point.
+pub fn round_decimal_to_digits(
+    span: Span,
+    number: BigDecimal,
+    digits: i64,
+    mode: RoundingMode,
+) -> CodeResult<CellValue> {
+    let (_, scale) = number.as_bigint_and_exponent();
+    // Rounding past the last decimal place leaves the number unchanged, which
+    // also keeps huge `digits` from doing any work.
+    if digits >= scale {
+        return Ok(CellValue::Number(number));
+    }
+    // Rounding to the left of every digit gives zero, or a power of ten when
+    // rounding away from zero. Return early rather than build a huge power of
+    // ten.
+    if digits < -MAX_INTEGER_DIGITS {
+        let away_from_zero = match mode {
+            RoundingMode::Up => true,
+            RoundingMode::Ceiling => number > BigDecimal::zero(),
+            RoundingMode::Floor => number < BigDecimal::zero(),
+            _ => false,
+        };
+        return match away_from_zero && !number.is_zero() {
+            true => Err(ErrorMsg::Overflow.with_span(span)),
+            false => Ok(CellValue::Number(BigDecimal::zero())),
+        };
+    }
+    // Drop trailing zeros, but keep numbers rounded to the left of the
+    // decimal point out of exponential notation.
+    let rounded = number.with_scale_round(digits, mode).normalized();
+    let (_, scale) = rounded.as_bigint_and_exponent();
+    Ok(CellValue::Number(rounded.with_scale(scale.max(0))))
+}
+
+/// Rounds a number to a multiple of `multiple` using `mode`. Returns zero if
+/// `multiple` is zero.
+pub fn round_to_multiple(
+    span: Span,
+    number: f64,
+    multiple: f64,
+    mode: RoundingMode,
+) -> CodeResult<CellValue> {
+    let number = to_decimal(span, number)?;
+    let multiple = to_decimal(span, multiple)?;
+    if multiple.is_zero() {
+        return Ok(CellValue::Number(multiple));
+    }
+    let quotient = (number / &multiple).with_scale_round(0, mode);
+    Ok(CellValue::Number((quotient * multiple).normalized()))
+}
+
+/// Returns the numbers in `array` as a list of rows, or an error if any value
+/// is not a number.
+pub fn matrix_from_array(array: &Spanned<Array>) -> CodeResult<Vec<Vec<f64>>> {
+    array
+        .inner
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|value| {
+                    let value = Spanned {
+                        span: array.span,
+                        inner: value,
+                    };
+                    Ok(value.try_coerce::<f64>()?.inner)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns an array containing a matrix given as a list of rows, which must
+/// all have the same length. Elements that are NaN or too large to represent
+/// become errors.
+pub fn matrix_to_array(span: Span, matrix: &[Vec<f64>]) -> CodeResult<Array> {
+    let width = matrix.first().map_or(0, |row| row.len());
+    let size = ArraySize::new_or_err(width as u32, matrix.len() as u32)?;
+    let values = matrix.iter().flatten().map(|&n| match finite(span, n) {
+        Ok(n) => CellValue::from(n),
+        Err(e) => CellValue::Error(Box::new(e)),
+    });
+    Array::new_row_major(size, values.collect())
+}
+
+/// Returns the width of `array`, or an error if it is not square.
+pub fn square_matrix_size(array: &Spanned<Array>) -> CodeResult<usize> {
+    let (width, height) = (array.inner.width(), array.inner.height());
+    if width != height {
+        return Err(ErrorMsg::ArrayAxisMismatch {
+            axis: Axis::X,
+            expected: height,
+            got: width,
+        }
+        .with_span(array.span));
+    }
+    Ok(width as usize)
+}
+
+/// Returns the `n`x`n` identity matrix.
+pub fn identity_matrix(n: usize) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect()
+}
+
+/// Returns the transpose of a matrix.
+pub fn transpose_matrix(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let width = matrix.first().map_or(0, |row| row.len());
+    (0..width)
+        .map(|j| matrix.iter().map(|row| row[j]).collect())
+        .collect()
+}
+
+/// Returns the product of two matrices. The width of `a` must equal the
+/// height of `b`.
+pub fn matrix_product(a: &[Vec<f64>], b: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let width = b.first().map_or(0, |row| row.len());
+    a.iter()
+        .map(|a_row| {
+            (0..width)
+                .map(|j| a_row.iter().zip(b).map(|(x, b_row)| x * b_row[j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the determinant of a square matrix, using Gaussian elimination
+/// with partial pivoting.
+pub fn determinant(mut matrix: Vec<Vec<f64>>) -> f64 {
+    let n = matrix.len();
+    let mut det = 1.0;
+    for col in 0..n {
+        let pivot = pivot_row(&matrix, col);
+        if matrix[pivot][col] == 0.0 {
+            return 0.0;
+        }
+        if pivot != col {
+            matrix.swap(pivot, col);
+            det = -det;
+        }
+        det *= matrix[col][col];
+
+        let (above, below) = matrix.split_at_mut(col + 1);
+        let pivot_row = &above[col];
+        for row in below {
+            let factor = row[col] / pivot_row[col];
+            for (x, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= factor * p;
+            }
+        }
+    }
+    det
+}
+
+/// Returns the inverse of a square matrix, using Gauss-Jordan elimination
+/// with partial pivoting, or `None` if the matrix is singular.
+pub fn inverse_matrix(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let mut augmented = matrix
+        .iter()
+        .zip(identity_matrix(n))
+        .map(|(row, identity_row)| [row.as_slice(), &identity_row].concat())
+        .collect_vec();
+    for col in 0..n {
+        let pivot = pivot_row(&augmented, col);
+        if augmented[pivot][col] == 0.0 {
+            return None;
+        }
+        augmented.swap(pivot, col);
+
+        let divisor = augmented[col][col];
+        augmented[col].iter_mut().for_each(|x| *x /= divisor);
+        let pivot_row = augmented[col].clone();
+        for (i, row) in augmented.iter_mut().enumerate() {
+            let factor = row[col];
+            if i != col && factor != 0.0 {
+                for (x, p) in row.iter_mut().zip(&pivot_row) {
+                    *x -= factor * p;
+                }
+            }
+        }
+    }
+    Some(augmented.into_iter().map(|row| row[n..].to_vec()).collect())
+}
+
+/// Returns the index of the row at or below `col` with the largest absolute
+/// value in column `col`.
+fn pivot_row(matrix: &[Vec<f64>], col: usize) -> usize {
+    (col..matrix.len())
+        .max_by(|&i, &j| matrix[i][col].abs().total_cmp(&matrix[j][col].abs()))
+        .unwrap_or(col)
+}
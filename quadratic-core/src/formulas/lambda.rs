@@ -0,0 +1,62 @@
+use super::*;
+use crate::{CodeResult, ErrorMsg, Span, Spanned, Value};
+
+/// Function defined in a formula using `LAMBDA`.
+#[derive(Debug, Clone)]
+pub struct Lambda {
+    /// Names of the parameters.
+    pub params: Vec<Spanned<String>>,
+    /// Expression that is evaluated when the function is called.
+    pub body: AstNode,
+    /// Names that were in scope where the function was defined.
+    pub scope: Vec<(String, ArgValue)>,
+}
+impl Lambda {
+    /// Calls the function with `args` and returns the result, which may be a
+    /// cell reference or another function.
+    pub fn call(
+        &self,
+        ctx: &mut Ctx<'_>,
+        args: Vec<Spanned<ArgValue>>,
+        span: Span,
+    ) -> CodeResult<Spanned<ArgValue>> {
+        if let Some(extra_arg) = args.get(self.params.len()) {
+            return Err(ErrorMsg::TooManyArguments {
+                func_name: "LAMBDA".into(),
+                max_arg_count: self.params.len(),
+            }
+            .with_span(extra_arg.span));
+        }
+        if let Some(missing_param) = self.params.get(args.len()) {
+            return Err(functions::missing_arg_error(
+                "LAMBDA",
+                missing_param.inner.clone(),
+                span,
+            ));
+        }
+
+        // Names are lexically scoped, so the body only sees the names that
+        // were in scope where the function was defined, plus its parameters.
+        let mut scope = self.scope.clone();
+        scope.extend(
+            std::iter::zip(&self.params, args).map(|(param, arg)| (param.inner.clone(), arg.inner)),
+        );
+        ctx.enter_call(span)?;
+        let outer_scope = ctx.replace_scope(scope);
+        let result = self.body.eval_arg(ctx);
+        ctx.replace_scope(outer_scope);
+        ctx.exit_call();
+        result
+    }
+
+    /// Calls the function with `args` and returns the resulting value.
+    pub fn call_with_values(
+        &self,
+        ctx: &mut Ctx<'_>,
+        args: impl IntoIterator<Item = Spanned<Value>>,
+        span: Span,
+    ) -> CodeResult<Spanned<Value>> {
+        let args = args.into_iter().map(|v| v.map(ArgValue::Value)).collect();
+        self.call(ctx, args, span)?.into_value(ctx)
+    }
+}
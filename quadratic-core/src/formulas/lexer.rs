@@ -25,8 +25,12 @@ fn new_fullmatch_regex(s: &str) -> Regex {
 }
 
 /// Function call consisting of a letter or underscore followed by any letters,
-/// digits, and/or underscores terminated with a `(`.
-const FUNCTION_CALL_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d]*\(";
+/// digits, underscores, and/or periods terminated with a `(`.
+const FUNCTION_CALL_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d\.]*\(";
+
+/// Name consisting of a letter or underscore followed by any letters, digits,
+/// and/or underscores, such as a variable defined using `LET`.
+const NAME_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d]*";
 
 /// A1-style cell reference.
 ///
@@ -37,6 +41,24 @@ const FUNCTION_CALL_PATTERN: &str = r"[A-Za-z_][A-Za-z_\d]*\(";
 ///                 \d+       digits
 const A1_CELL_REFERENCE_PATTERN: &str = r"\$?n?[A-Z]+\$?n?\d+";
 
+/// A1-style reference to whole columns, such as `A:C`.
+///
+/// \$?n?[A-Z]+:\$?n?[A-Z]+
+/// \$?         \$?             optional `$`s
+///    n?          n?           optional `n`s
+///      [A-Z]+      [A-Z]+     letters
+///            :                separated by a colon
+const A1_COLUMN_RANGE_REFERENCE_PATTERN: &str = r"\$?n?[A-Z]+:\$?n?[A-Z]+";
+
+/// A1-style reference to whole rows, such as `3:5`.
+///
+/// \$?n?\d+:\$?n?\d+
+/// \$?      \$?          optional `$`s
+///    n?       n?        optional `n`s
+///      \d+      \d+     digits
+///         :             separated by a colon
+const A1_ROW_RANGE_REFERENCE_PATTERN: &str = r"\$?n?\d+:\$?n?\d+";
+
 /// Floating-point or integer number, without leading sign.
 ///
 /// (\d+(\.\d*)?|\.\d+)([eE][+-]?\d+)?
@@ -62,6 +84,9 @@ const DOUBLE_QUOTE_STRING_LITERAL_PATTERN: &str = r#""([^"\\]|\\[\s\S])*""#;
 const UNQUOTED_SHEET_REFERENCE_PATTERN: &str = r"[A-Za-z_][A-Za-z0-9_\.]*\s*!";
 /// Unterminated string literal.
 const UNTERMINATED_STRING_LITERAL_PATTERN: &str = r#"["']"#;
+/// Reference to cells that have been deleted, which replaces the reference
+/// when its cells are deleted.
+const DELETED_REFERENCE_PATTERN: &str = r"#REF!";
 
 /// List of token patterns, arranged roughly from least to most general.
 const TOKEN_PATTERNS: &[&str] = &[
@@ -75,18 +100,25 @@ const TOKEN_PATTERNS: &[&str] = &[
     r"/\*",
     // Sheet reference.
     UNQUOTED_SHEET_REFERENCE_PATTERN,
+    // Deleted reference.
+    DELETED_REFERENCE_PATTERN,
     // String literal.
     SINGLE_QUOTE_STRING_LITERAL_PATTERN,
     DOUBLE_QUOTE_STRING_LITERAL_PATTERN,
     UNTERMINATED_STRING_LITERAL_PATTERN,
+    // Reference to whole columns or rows (must come before numeric literal).
+    A1_COLUMN_RANGE_REFERENCE_PATTERN,
+    A1_ROW_RANGE_REFERENCE_PATTERN,
     // Numeric literal.
     NUMERIC_LITERAL_PATTERN,
     // Function call.
     FUNCTION_CALL_PATTERN,
-    // Boolean literal (case-insensitive).
-    r#"false|true"#,
+    // Boolean literal (case-insensitive), but not the start of a name.
+    r#"(false|true)\b"#,
     // Reference to a cell.
     A1_CELL_REFERENCE_PATTERN,
+    // Name (must come after cell reference).
+    NAME_PATTERN,
     // Whitespace.
     r"\s+",
     // Any other single Unicode character.
@@ -111,6 +143,18 @@ lazy_static! {
     pub static ref A1_CELL_REFERENCE_REGEX: Regex =
         new_fullmatch_regex(A1_CELL_REFERENCE_PATTERN);
 
+    /// Regex that matches a valid name.
+    pub static ref NAME_REGEX: Regex =
+        new_fullmatch_regex(NAME_PATTERN);
+
+    /// Regex that matches a valid A1-style reference to whole columns.
+    pub static ref A1_COLUMN_RANGE_REFERENCE_REGEX: Regex =
+        new_fullmatch_regex(A1_COLUMN_RANGE_REFERENCE_PATTERN);
+
+    /// Regex that matches a valid A1-style reference to whole rows.
+    pub static ref A1_ROW_RANGE_REFERENCE_REGEX: Regex =
+        new_fullmatch_regex(A1_ROW_RANGE_REFERENCE_PATTERN);
+
     /// Regex that matches all valid numeric literals and some invalid ones.
     pub static ref NUMERIC_LITERAL_REGEX: Regex =
         new_fullmatch_regex(NUMERIC_LITERAL_PATTERN);
@@ -214,6 +258,14 @@ pub enum Token {
     NumericLiteral,
     #[strum(to_string = "cell reference")]
     CellRef,
+    #[strum(to_string = "deleted reference")]
+    DeletedRef,
+    #[strum(to_string = "column range reference")]
+    ColRangeRef,
+    #[strum(to_string = "row range reference")]
+    RowRangeRef,
+    #[strum(to_string = "name")]
+    Name,
     #[strum(to_string = "whitespace")]
     Whitespace,
     #[strum(to_string = "unknown symbol")]
@@ -256,6 +308,7 @@ impl Token {
             "..." => Self::Ellipsis,
             s if s.eq_ignore_ascii_case("false") => Self::False,
             s if s.eq_ignore_ascii_case("true") => Self::True,
+            s if s.eq_ignore_ascii_case("#REF!") => Self::DeletedRef,
 
             // Match a line comment.
             s if s.starts_with("//") => Self::Comment,
@@ -293,8 +346,11 @@ impl Token {
             s if UNTERMINATED_STRING_LITERAL_REGEX.is_match(s) => Self::UnterminatedStringLiteral,
             s if s.eq_ignore_ascii_case("false") => Self::False,
             s if s.eq_ignore_ascii_case("true") => Self::True,
+            s if A1_COLUMN_RANGE_REFERENCE_REGEX.is_match(s) => Self::ColRangeRef,
+            s if A1_ROW_RANGE_REFERENCE_REGEX.is_match(s) => Self::RowRangeRef,
             s if NUMERIC_LITERAL_REGEX.is_match(s) => Self::NumericLiteral,
             s if A1_CELL_REFERENCE_REGEX.is_match(s) => Self::CellRef,
+            s if NAME_REGEX.is_match(s) => Self::Name,
             s if s.trim().is_empty() => Self::Whitespace,
 
             // Give up.
@@ -1,28 +1,35 @@
 #[cfg(test)]
 #[macro_use]
-mod tests;
+pub(crate) mod tests;
 
+mod adjust;
 mod ast;
 mod cell_ref;
 mod criteria;
 mod ctx;
 #[allow(clippy::vec_init_then_push)]
 pub mod functions;
+mod lambda;
 mod lexer;
 pub mod lsp;
 mod params;
 mod parser;
 mod wildcards;
 
+pub use adjust::{
+    adjust_cell_references, adjust_sql_cell_references, move_cell_references,
+    move_sql_cell_references, RefAdjust, DELETED_REFERENCE,
+};
 use ast::AstNode;
 pub use ast::Formula;
 pub use cell_ref::*;
 pub use criteria::Criterion;
 pub use ctx::Ctx;
-use functions::FormulaFnArgs;
+use functions::{ArgValue, FormulaFnArgs};
+pub use lambda::Lambda;
 use params::{Param, ParamKind};
 pub use parser::{find_cell_references, parse_formula};
-use wildcards::wildcard_pattern_to_regex;
+use wildcards::{wildcard_pattern_to_regex, wildcard_pattern_to_search_regex};
 
 /// Escapes a formula string.
 pub fn escape_string(s: &str) -> String {
@@ -111,6 +111,49 @@ impl SyntaxRule for CellReference {
     }
 }
 
+/// Matches a reference to whole columns or rows.
+#[derive(Debug, Copy, Clone)]
+pub struct ColRowRangeReference;
+impl_display!(for ColRowRangeReference, "column or row range reference, such as 'A:C' or '3:5'");
+impl SyntaxRule for ColRowRangeReference {
+    type Output = Spanned<RangeRef>;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        let is_col_or_row_range = |t| matches!(t, Some(Token::ColRangeRef | Token::RowRangeRef));
+        match p.next() {
+            Some(Token::UnquotedSheetReference) => is_col_or_row_range(p.next()),
+            Some(Token::StringLiteral) => {
+                p.next() == Some(Token::SheetRefOp) && is_col_or_row_range(p.next())
+            }
+            t => is_col_or_row_range(t),
+        }
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        let start_span = p.peek_next_span();
+
+        let sheet_name = p.try_parse(SheetRefPrefix).transpose()?;
+
+        p.next();
+        let range_ref = match RangeRef::parse_a1_col_or_row_range(p.token_str(), p.pos) {
+            Some(RangeRef::ColRange { start, end, .. }) => RangeRef::ColRange {
+                start,
+                end,
+                sheet: sheet_name,
+            },
+            Some(RangeRef::RowRange { start, end, .. }) => RangeRef::RowRange {
+                start,
+                end,
+                sheet: sheet_name,
+            },
+            _ => return Err(ErrorMsg::BadCellReference.with_span(p.span())),
+        };
+        Ok(Spanned {
+            span: Span::merge(start_span, p.span()),
+            inner: range_ref,
+        })
+    }
+}
+
 /// Matches a single cell reference or a cell range reference on its own, not as
 /// part of an expression.
 #[derive(Debug, Copy, Clone)]
@@ -120,9 +163,13 @@ impl SyntaxRule for CellRangeReference {
     type Output = Spanned<RangeRef>;
 
     fn prefix_matches(&self, p: Parser<'_>) -> bool {
-        CellReference.prefix_matches(p)
+        ColRowRangeReference.prefix_matches(p) || CellReference.prefix_matches(p)
     }
     fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        if ColRowRangeReference.prefix_matches(*p) {
+            return p.parse(ColRowRangeReference);
+        }
+
         let pos1 = p.parse(CellReference)?;
 
         // Check for a range reference.
@@ -159,3 +206,22 @@ impl SyntaxRule for BoolExpression {
         })
     }
 }
+
+/// Matches a reference to cells that have been deleted.
+#[derive(Debug, Copy, Clone)]
+pub struct DeletedReference;
+impl_display!(for DeletedReference, "deleted reference '#REF!'");
+impl SyntaxRule for DeletedReference {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        p.next() == Some(Token::DeletedRef)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        p.parse(Token::DeletedRef)?;
+        Ok(AstNode {
+            span: p.span(),
+            inner: ast::AstNodeContents::DeletedRef,
+        })
+    }
+}
@@ -145,7 +145,11 @@ impl SyntaxRule for ExpressionWithPrecedence {
                 | Token::StringLiteral
                 | Token::UnterminatedStringLiteral
                 | Token::NumericLiteral
-                | Token::CellRef => true,
+                | Token::CellRef
+                | Token::DeletedRef
+                | Token::ColRangeRef
+                | Token::RowRangeRef
+                | Token::Name => true,
 
                 Token::Whitespace => false,
                 Token::Unknown => false,
@@ -162,11 +166,14 @@ impl SyntaxRule for ExpressionWithPrecedence {
                 p,
                 [
                     FunctionCall.map(Some),
+                    ColRowRangeReferenceExpression.map(Some),
                     CellReferenceExpression.map(Some),
                     StringLiteralExpression.map(Some),
+                    NameExpression.map(Some),
                     NumericLiteral.map(Some),
                     ArrayLiteral.map(Some),
                     BoolExpression.map(Some),
+                    DeletedReference.map(Some),
                     ParenExpression.map(Some),
                     EmptyExpression.map(Some),
                 ],
@@ -358,6 +365,22 @@ impl SyntaxRule for CellReferenceExpression {
     }
 }
 
+/// Matches a reference to whole columns or rows.
+#[derive(Debug, Copy, Clone)]
+pub struct ColRowRangeReferenceExpression;
+impl_display!(for ColRowRangeReferenceExpression, "{}", ColRowRangeReference);
+impl SyntaxRule for ColRowRangeReferenceExpression {
+    type Output = AstNode;
+
+    fn prefix_matches(&self, p: Parser<'_>) -> bool {
+        ColRowRangeReference.prefix_matches(p)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        Ok(p.parse(ColRowRangeReference)?
+            .map(ast::AstNodeContents::RangeRef))
+    }
+}
+
 /// Matches a pair of parentheses containing an expression.
 #[derive(Debug, Copy, Clone)]
 pub struct ParenExpression;
@@ -443,6 +466,25 @@ impl SyntaxRule for StringLiteralExpression {
     }
 }
 
+/// Matches a name, such as a variable defined using `LET`.
+#[derive(Debug, Copy, Clone)]
+pub struct NameExpression;
+impl_display!(for NameExpression, "name");
+impl SyntaxRule for NameExpression {
+    type Output = ast::AstNode;
+
+    fn prefix_matches(&self, mut p: Parser<'_>) -> bool {
+        p.next() == Some(Token::Name)
+    }
+    fn consume_match(&self, p: &mut Parser<'_>) -> CodeResult<Self::Output> {
+        p.parse(Token::Name)?;
+        Ok(Spanned {
+            span: p.span(),
+            inner: ast::AstNodeContents::Name(p.token_str().to_string()),
+        })
+    }
+}
+
 /// Matches an empty expression that is followed by something that should
 /// normally follow an expression (such as a comma or semicolon or right paren).
 #[derive(Debug, Copy, Clone)]
@@ -5,6 +5,25 @@ pub(crate) use crate::grid::Grid;
 pub(crate) use crate::values::*;
 pub(crate) use crate::{array, CodeResult, Error, ErrorMsg, Pos, SheetPos, Spanned};
 
+/// Returns a grid whose first sheet has `rows` starting at A1. Empty strings
+/// leave the cell blank; other strings are parsed like user input.
+pub(crate) fn grid_with_rows(rows: &[&[&str]]) -> Grid {
+    let mut g = Grid::new();
+    let sheet = &mut g.sheets_mut()[0];
+    for (y, row) in rows.iter().enumerate() {
+        for (x, value) in row.iter().enumerate() {
+            if !value.is_empty() {
+                let pos = Pos {
+                    x: x as i64,
+                    y: y as i64 + 1,
+                };
+                sheet.set_cell_value(pos, CellValue::to_cell_value(value));
+            }
+        }
+    }
+    g
+}
+
 pub(crate) fn try_eval_at(grid: &Grid, pos: SheetPos, s: &str) -> CodeResult<Value> {
     println!("Evaluating formula {s:?} at {pos:?}");
     let mut ctx = Ctx::new(grid, pos);
@@ -307,6 +326,24 @@ fn test_find_cell_references() {
                 pos: a1("\"plum\"!$A1"),
             },
         ),
+        // Column range
+        (
+            "B:$C",
+            RangeRef::ColRange {
+                start: CellRefCoord::Relative(1),
+                end: CellRefCoord::Absolute(2),
+                sheet: None,
+            },
+        ),
+        // Row range with quoted sheet reference
+        (
+            "'kiwi'!3:$n5",
+            RangeRef::RowRange {
+                start: CellRefCoord::Relative(3),
+                end: CellRefCoord::Absolute(-5),
+                sheet: Some("kiwi".to_string()),
+            },
+        ),
     ];
     let formula_string = test_cases.iter().map(|(string, _)| string).join(" + ");
     let cell_references_found = find_cell_references(&formula_string, Pos::ORIGIN)
@@ -357,9 +394,68 @@ fn test_sheet_references() {
     );
 }
 
+#[test]
+fn test_col_and_row_range_references() {
+    let mut g = Grid::new();
+    let id1 = g.sheets()[0].id;
+    let id2 = g.add_sheet(None).unwrap();
+    g.sheets_mut()[1].name = "Data".to_string();
+
+    let sheet = g.sheet_mut_from_id(id1);
+    sheet.set_cell_value(pos![B1], 1);
+    sheet.set_cell_value(pos![B2], 2);
+    sheet.set_cell_value(pos![C4], 30);
+    sheet.set_cell_value(pos![D2], 400);
+    sheet.recalculate_bounds();
+    g.sheet_mut_from_id(id2).set_cell_value(pos![A10], 5000);
+    g.sheet_mut_from_id(id2).recalculate_bounds();
+
+    let pos = Pos::ORIGIN.with_sheet(id1);
+    assert_eq!("3", eval_to_string_at(&g, pos, "SUM(B:B)"));
+    assert_eq!("33", eval_to_string_at(&g, pos, "SUM(B:C)"));
+    assert_eq!("{1; 2}", eval_to_string_at(&g, pos, "B:B"));
+    assert_eq!("402", eval_to_string_at(&g, pos, "SUM(2:2)"));
+    assert_eq!("{2, , 400}", eval_to_string_at(&g, pos, "2:$2"));
+    assert_eq!("5000", eval_to_string_at(&g, pos, "SUM(Data!A:A)"));
+    assert_eq!("0", eval_to_string_at(&g, pos, "SUM(E:E)"));
+
+    // A range that contains the formula is a circular reference.
+    assert_eq!(
+        ErrorMsg::CircularReference,
+        try_eval_at(&g, pos, "SUM(A:B)").unwrap_err().msg,
+    );
+    assert_eq!(
+        ErrorMsg::CircularReference,
+        try_eval_at(&g, pos, "SUM(n1:1)").unwrap_err().msg,
+    );
+}
+
+#[test]
+fn test_deleted_reference() {
+    let g = Grid::new();
+    expect_err(&ErrorMsg::BadCellReference, &g, "#REF!");
+    expect_err(&ErrorMsg::BadCellReference, &g, "SUM(A1:A3) + #ref!");
+    assert_eq!("4", eval_to_string(&g, "ERROR.TYPE(#REF!)"));
+    assert_eq!("1", eval_to_string(&g, "IFERROR(#REF!, 1)"));
+    assert_eq!("{FALSE, TRUE}", eval_to_string(&g, "ISERROR({1, #REF!})"));
+    assert_eq!("#REF!", eval_to_string(&g, "\"#REF!\""));
+}
+
 /// Regression test for quadratic#410
 #[test]
 fn test_currency_string() {
     let g = Grid::new();
     assert_eq!("30", eval_to_string(&g, "\"$10\" + 20"));
 }
+
+#[test]
+fn test_is_volatile() {
+    let is_volatile = |s| parse_formula(s, Pos::ORIGIN).unwrap().is_volatile();
+
+    assert!(is_volatile("RAND()"));
+    assert!(is_volatile("1 + SUM(A1, (RANDBETWEEN(1, 6)))"));
+    assert!(is_volatile("{1, RANDARRAY(2)}"));
+    assert!(is_volatile("NOW() - TODAY()"));
+    assert!(!is_volatile("SUM(A1:A10) + PI()"));
+    assert!(!is_volatile("\"RAND()\""));
+}
@@ -9,8 +9,8 @@ use crate::grid::block::SameValue;
 use crate::grid::borders::cell::{CellBorders, CellSide};
 use crate::grid::borders::compute_indices;
 use crate::grid::borders::style::{BorderSelection, BorderStyle};
-use crate::grid::{ColumnData, ColumnId, IdMap, RegionRef, RowId, Sheet};
-use crate::{grid, Rect};
+use crate::grid::{ColumnData, ColumnId, GridBounds, IdMap, RegionRef, RowId, Sheet};
+use crate::{grid, Pos, Rect};
 
 pub fn generate_borders(
     sheet: &Sheet,
@@ -129,6 +129,16 @@ pub fn get_cell_borders_in_rect(sheet: &Sheet, rect: Rect) -> Vec<(i64, i64, Opt
     borders
 }
 
+/// Moves every entry with a key at or after `start` by `delta`.
+fn shift_keys<V>(map: &mut HashMap<i64, V>, start: i64, delta: i64) {
+    let moved = map.keys().copied().filter(|&k| k >= start).collect_vec();
+    let moved = moved
+        .into_iter()
+        .filter_map(|k| Some((k + delta, map.remove(&k)?)))
+        .collect_vec();
+    map.extend(moved);
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct SheetBorders {
     pub per_cell: IdSpaceBorders,
@@ -168,6 +178,82 @@ impl SheetBorders {
         previous_borders
     }
 
+    /// Returns the bounds of all cells with borders.
+    pub fn bounds(&self) -> GridBounds {
+        let mut bounds = GridBounds::Empty;
+        // a border on edge `i` is shared by the cells at `i - 1` and `i`
+        for (&x, column) in &self.render_lookup.vertical {
+            if let Some(y_range) = column.range() {
+                bounds.add(Pos {
+                    x: x - 1,
+                    y: y_range.start,
+                });
+                bounds.add(Pos {
+                    x,
+                    y: y_range.end - 1,
+                });
+            }
+        }
+        for (&y, row) in &self.render_lookup.horizontal {
+            if let Some(x_range) = row.range() {
+                bounds.add(Pos {
+                    x: x_range.start,
+                    y: y - 1,
+                });
+                bounds.add(Pos {
+                    x: x_range.end - 1,
+                    y,
+                });
+            }
+        }
+        bounds
+    }
+
+    /// Inserts `count` columns without borders at `x`.
+    pub fn insert_columns(&mut self, x: i64, count: i64) {
+        shift_keys(&mut self.render_lookup.vertical, x, count);
+        for row in self.render_lookup.horizontal.values_mut() {
+            row.insert_rows(x, count);
+        }
+    }
+    /// Deletes the borders of `count` columns starting at `x`, which have
+    /// the IDs `column_ids`.
+    pub fn delete_columns(&mut self, x: i64, count: i64, column_ids: &[ColumnId]) {
+        for column_id in column_ids {
+            self.per_cell.borders.remove(column_id);
+        }
+        self.render_lookup
+            .vertical
+            .retain(|&k, _| !(x..x + count).contains(&k));
+        shift_keys(&mut self.render_lookup.vertical, x + count, -count);
+        for row in self.render_lookup.horizontal.values_mut() {
+            row.delete_rows(x, count);
+        }
+    }
+    /// Inserts `count` rows without borders at `y`.
+    pub fn insert_rows(&mut self, y: i64, count: i64) {
+        for column in self.per_cell.borders.values_mut() {
+            column.insert_rows(y, count);
+        }
+        shift_keys(&mut self.render_lookup.horizontal, y, count);
+        for column in self.render_lookup.vertical.values_mut() {
+            column.insert_rows(y, count);
+        }
+    }
+    /// Deletes the borders of `count` rows starting at `y`.
+    pub fn delete_rows(&mut self, y: i64, count: i64) {
+        for column in self.per_cell.borders.values_mut() {
+            column.delete_rows(y, count);
+        }
+        self.render_lookup
+            .horizontal
+            .retain(|&k, _| !(y..y + count).contains(&k));
+        shift_keys(&mut self.render_lookup.horizontal, y + count, -count);
+        for column in self.render_lookup.vertical.values_mut() {
+            column.delete_rows(y, count);
+        }
+    }
+
     fn get_regions(&self, row_ids: &IdMap<RowId, i64>, regions: Vec<RegionRef>) -> SheetBorders {
         let mut sheet_borders = SheetBorders::default();
 
@@ -71,6 +71,36 @@ impl Column {
         }
     }
 
+    /// Inserts `count` empty rows at `y`, moving the rows at and below `y`
+    /// down.
+    pub fn insert_rows(&mut self, y: i64, count: i64) {
+        self.values.insert_rows(y, count);
+        self.spills.insert_rows(y, count);
+        self.align.insert_rows(y, count);
+        self.wrap.insert_rows(y, count);
+        self.numeric_format.insert_rows(y, count);
+        self.numeric_decimals.insert_rows(y, count);
+        self.numeric_commas.insert_rows(y, count);
+        self.bold.insert_rows(y, count);
+        self.italic.insert_rows(y, count);
+        self.text_color.insert_rows(y, count);
+        self.fill_color.insert_rows(y, count);
+    }
+    /// Deletes `count` rows starting at `y`, moving the rows below them up.
+    pub fn delete_rows(&mut self, y: i64, count: i64) {
+        self.values.delete_rows(y, count);
+        self.spills.delete_rows(y, count);
+        self.align.delete_rows(y, count);
+        self.wrap.delete_rows(y, count);
+        self.numeric_format.delete_rows(y, count);
+        self.numeric_decimals.delete_rows(y, count);
+        self.numeric_commas.delete_rows(y, count);
+        self.bold.delete_rows(y, count);
+        self.italic.delete_rows(y, count);
+        self.text_color.delete_rows(y, count);
+        self.fill_color.delete_rows(y, count);
+    }
+
     pub fn has_data_in_row(&self, y: i64) -> bool {
         self.values.get(y).is_some_and(|v| !v.is_blank()) || self.spills.get(y).is_some()
     }
@@ -277,6 +307,33 @@ impl<B: BlockContent> ColumnData<B> {
         to_return
     }
 
+    /// Moves every value at or below `y` by `delta`. If `delta` is negative,
+    /// the rows that values move into must already be empty.
+    fn shift_rows(&mut self, y: i64, delta: i64) {
+        if let Some(block) = self.remove_block_containing(y) {
+            let [above, below] = block.split(y);
+            self.add_blocks(above.into_iter().chain(below));
+        }
+        let moved = self.0.split_off(&y);
+        for (_, mut block) in moved {
+            block.y += delta;
+            self.add_block(block);
+        }
+    }
+    /// Inserts `count` empty rows at `y`, moving the values at and below `y`
+    /// down.
+    pub fn insert_rows(&mut self, y: i64, count: i64) {
+        self.shift_rows(y, count);
+    }
+    /// Deletes `count` rows starting at `y`, moving the values below them up.
+    /// Returns the removed blocks.
+    pub fn delete_rows(&mut self, y: i64, count: i64) -> Vec<Block<B>> {
+        let removed = self.remove_range(y..y + count);
+        self.shift_rows(y + count, -count);
+        self.try_merge_at(y);
+        removed
+    }
+
     pub fn range(&self) -> Option<Range<i64>> {
         let min = *self.0.first_key_value()?.0;
         let max = self.0.last_key_value()?.1.end();
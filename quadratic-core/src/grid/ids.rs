@@ -2,6 +2,7 @@ use core::fmt;
 use core::fmt::Display;
 use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
+use std::ops::Range;
 use std::str::FromStr;
 
 use anyhow::Result;
@@ -198,6 +199,28 @@ impl<Id: Copy + Hash + Eq, Idx: Copy + Ord> IdMap<Id, Idx> {
         self.index_to_id.iter().map(|(&index, &id)| (index, id))
     }
 }
+impl<Id: Copy + Hash + Eq> IdMap<Id, i64> {
+    /// Removes the IDs at indices in `range` and returns them in order.
+    pub fn remove_range(&mut self, range: Range<i64>) -> Vec<Id> {
+        let mut removed = self.index_to_id.split_off(&range.start);
+        let mut rest = removed.split_off(&range.end);
+        self.index_to_id.append(&mut rest);
+        removed
+            .into_values()
+            .inspect(|id| {
+                self.id_to_index.remove(id);
+            })
+            .collect()
+    }
+    /// Moves every ID at or after index `start` by `delta`. If `delta` is
+    /// negative, the indices that IDs move into must already be empty.
+    pub fn shift(&mut self, start: i64, delta: i64) {
+        let moved = self.index_to_id.split_off(&start);
+        for (index, id) in moved {
+            self.add(id, index + delta);
+        }
+    }
+}
 impl<Id: Copy + Hash + Eq, Idx: Copy + Ord> FromIterator<(Idx, Id)> for IdMap<Id, Idx> {
     fn from_iter<T: IntoIterator<Item = (Idx, Id)>>(iter: T) -> Self {
         let mut ret = Self::new();
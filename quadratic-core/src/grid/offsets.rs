@@ -52,6 +52,23 @@ impl Offsets {
         }
     }
 
+    /// Inserts `count` columns/rows with the default size at `index`, moving
+    /// the ones at and after `index`.
+    pub fn insert(&mut self, index: i64, count: i64) {
+        let moved = self.sizes.split_off(&index);
+        self.sizes
+            .extend(moved.into_iter().map(|(k, v)| (k + count, v)));
+    }
+    /// Deletes `count` columns/rows starting at `index`, moving the ones after
+    /// them. Returns the sizes that were removed.
+    pub fn delete(&mut self, index: i64, count: i64) -> Vec<(i64, f64)> {
+        let mut removed = self.sizes.split_off(&index);
+        let moved = removed.split_off(&(index + count));
+        self.sizes
+            .extend(moved.into_iter().map(|(k, v)| (k - count, v)));
+        removed.into_iter().collect()
+    }
+
     /// Returns the width/height of a column/row.
     pub fn get_size(&self, index: i64) -> f64 {
         *self.sizes.get(&index).unwrap_or(&self.default)
@@ -23,6 +23,7 @@ pub mod bounds;
 pub mod cells;
 pub mod code;
 pub mod rendering;
+pub mod rows_columns;
 pub mod sheet_offsets;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
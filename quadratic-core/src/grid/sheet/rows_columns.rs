@@ -0,0 +1,194 @@
+use itertools::Itertools;
+
+use super::Sheet;
+use crate::grid::{Column, ColumnData, ColumnId, RowId};
+
+impl Sheet {
+    /// Inserts empty columns at `x` with the IDs `column_ids`, moving the
+    /// columns at and after `x` to the right.
+    pub fn insert_columns(&mut self, x: i64, column_ids: &[ColumnId]) {
+        let count = column_ids.len() as i64;
+        let moved = self.columns.split_off(&x);
+        self.columns
+            .extend(moved.into_iter().map(|(x, column)| (x + count, column)));
+        self.column_ids.shift(x, count);
+        for (i, &id) in column_ids.iter().enumerate() {
+            let index = x + i as i64;
+            self.columns.insert(index, Column::with_id(id));
+            self.column_ids.add(id, index);
+        }
+
+        self.offsets.insert_columns(x, count);
+        self.borders.insert_columns(x, count);
+        self.rebuild_spills();
+        self.recalculate_bounds();
+    }
+
+    /// Deletes `count` columns starting at `x`, moving the columns after them
+    /// to the left. Returns the IDs of the deleted columns.
+    pub fn delete_columns(&mut self, x: i64, count: i64) -> Vec<ColumnId> {
+        let mut deleted = self.columns.split_off(&x);
+        let moved = deleted.split_off(&(x + count));
+        self.columns
+            .extend(moved.into_iter().map(|(x, column)| (x - count, column)));
+        let column_ids = self.column_ids.remove_range(x..x + count);
+        self.column_ids.shift(x + count, -count);
+        self.code_cells
+            .retain(|cell_ref, _| !column_ids.contains(&cell_ref.column));
+
+        self.offsets.delete_columns(x, count);
+        self.borders.delete_columns(x, count, &column_ids);
+        self.rebuild_spills();
+        self.recalculate_bounds();
+        column_ids
+    }
+
+    /// Inserts empty rows at `y` with the IDs `row_ids`, moving the rows at and
+    /// below `y` down.
+    pub fn insert_rows(&mut self, y: i64, row_ids: &[RowId]) {
+        let count = row_ids.len() as i64;
+        for column in self.columns.values_mut() {
+            column.insert_rows(y, count);
+        }
+        self.row_ids.shift(y, count);
+        for (i, &id) in row_ids.iter().enumerate() {
+            self.row_ids.add(id, y + i as i64);
+        }
+
+        self.offsets.insert_rows(y, count);
+        self.borders.insert_rows(y, count);
+        self.rebuild_spills();
+        self.recalculate_bounds();
+    }
+
+    /// Deletes `count` rows starting at `y`, moving the rows below them up.
+    /// Returns the IDs of the deleted rows.
+    pub fn delete_rows(&mut self, y: i64, count: i64) -> Vec<RowId> {
+        for column in self.columns.values_mut() {
+            column.delete_rows(y, count);
+        }
+        let row_ids = self.row_ids.remove_range(y..y + count);
+        self.row_ids.shift(y + count, -count);
+        self.code_cells
+            .retain(|cell_ref, _| !row_ids.contains(&cell_ref.row));
+
+        self.offsets.delete_rows(y, count);
+        self.borders.delete_rows(y, count);
+        self.rebuild_spills();
+        self.recalculate_bounds();
+        row_ids
+    }
+
+    /// Recreates the spills of every code cell from its output, after code
+    /// cells have moved relative to each other.
+    fn rebuild_spills(&mut self) {
+        for column in self.columns.values_mut() {
+            column.spills = ColumnData::new();
+        }
+        let code_cells = self
+            .code_cells
+            .drain()
+            .sorted_by(|(_, a), (_, b)| a.last_modified.cmp(&b.last_modified))
+            .collect_vec();
+        for (cell_ref, code_cell) in code_cells {
+            if let Some(pos) = self.cell_ref_to_pos(cell_ref) {
+                self.set_code_cell_value(pos, Some(code_cell));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        grid::{Bold, ColumnId, GridBounds, RowId, Sheet},
+        CellValue, Pos, Rect,
+    };
+
+    #[test]
+    fn test_insert_and_delete_columns() {
+        let mut sheet = Sheet::test();
+        sheet.set_cell_value(Pos { x: 0, y: 0 }, CellValue::Number(1.into()));
+        sheet.set_cell_value(Pos { x: 1, y: 0 }, CellValue::Number(2.into()));
+        sheet.set_formatting_value::<Bold>(Pos { x: 1, y: 0 }, Some(true));
+        sheet.offsets.set_column_width(1, 200.0);
+        let cell_ref = sheet.try_get_cell_ref(Pos { x: 1, y: 0 }).unwrap();
+        let first_column_id = sheet.get_column(0).unwrap().id;
+
+        let column_ids = [ColumnId::new(), ColumnId::new()];
+        sheet.insert_columns(1, &column_ids);
+        assert_eq!(sheet.get_cell_value(Pos { x: 1, y: 0 }), None);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 3, y: 0 }),
+            Some(CellValue::Number(2.into()))
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 3, y: 0 }),
+            Some(true)
+        );
+        assert_eq!(sheet.offsets.column_width(3), 200.0);
+        assert_eq!(sheet.cell_ref_to_pos(cell_ref), Some(Pos { x: 3, y: 0 }));
+        assert_eq!(sheet.get_column_index(column_ids[1]), Some(2));
+
+        assert_eq!(
+            sheet.delete_columns(0, 2),
+            vec![first_column_id, column_ids[0]]
+        );
+        assert_eq!(sheet.get_cell_value(Pos { x: 0, y: 0 }), None);
+        assert_eq!(
+            sheet.get_cell_value(Pos { x: 1, y: 0 }),
+            Some(CellValue::Number(2.into()))
+        );
+        assert_eq!(sheet.cell_ref_to_pos(cell_ref), Some(Pos { x: 1, y: 0 }));
+        assert_eq!(sheet.offsets.column_width(1), 200.0);
+    }
+
+    #[test]
+    fn test_insert_and_delete_rows() {
+        let mut sheet = Sheet::test();
+        for y in 0..4 {
+            sheet.set_cell_value(Pos { x: 0, y }, CellValue::Number(y.into()));
+        }
+        sheet.set_formatting_value::<Bold>(Pos { x: 0, y: 2 }, Some(true));
+        let cell_ref = sheet.try_get_cell_ref(Pos { x: 0, y: 2 }).unwrap();
+
+        let row_ids = [RowId::new()];
+        sheet.insert_rows(1, &row_ids);
+        let values = (0..5)
+            .map(|y| sheet.get_cell_value(Pos { x: 0, y }))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                Some(CellValue::Number(0.into())),
+                None,
+                Some(CellValue::Number(1.into())),
+                Some(CellValue::Number(2.into())),
+                Some(CellValue::Number(3.into())),
+            ]
+        );
+        assert_eq!(
+            sheet.get_formatting_value::<Bold>(Pos { x: 0, y: 3 }),
+            Some(true)
+        );
+        assert_eq!(sheet.cell_ref_to_pos(cell_ref), Some(Pos { x: 0, y: 3 }));
+
+        assert_eq!(sheet.delete_rows(1, 2).len(), 2);
+        let values = (0..3)
+            .map(|y| sheet.get_cell_value(Pos { x: 0, y }))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                Some(CellValue::Number(0.into())),
+                Some(CellValue::Number(2.into())),
+                Some(CellValue::Number(3.into())),
+            ]
+        );
+        assert_eq!(sheet.cell_ref_to_pos(cell_ref), Some(Pos { x: 0, y: 1 }));
+        assert_eq!(
+            sheet.bounds(true),
+            GridBounds::NonEmpty(Rect::new_span(Pos { x: 0, y: 0 }, Pos { x: 0, y: 2 }))
+        );
+    }
+}
@@ -99,6 +99,23 @@ impl SheetOffsets {
         self.row_heights.reset(y)
     }
 
+    /// Inserts `count` columns with the default width at `x`.
+    pub fn insert_columns(&mut self, x: i64, count: i64) {
+        self.column_widths.insert(x, count);
+    }
+    /// Deletes `count` columns starting at `x` and returns their old widths.
+    pub fn delete_columns(&mut self, x: i64, count: i64) -> Vec<(i64, f64)> {
+        self.column_widths.delete(x, count)
+    }
+    /// Inserts `count` rows with the default height at `y`.
+    pub fn insert_rows(&mut self, y: i64, count: i64) {
+        self.row_heights.insert(y, count);
+    }
+    /// Deletes `count` rows starting at `y` and returns their old heights.
+    pub fn delete_rows(&mut self, y: i64, count: i64) -> Vec<(i64, f64)> {
+        self.row_heights.delete(y, count)
+    }
+
     pub fn column_width(&self, x: i64) -> f64 {
         self.column_widths.get_size(x)
     }
@@ -14,6 +14,7 @@ pub mod grid;
 mod position;
 mod rle;
 mod span;
+pub mod sql;
 #[cfg(test)]
 mod test_util;
 mod values;
@@ -36,6 +37,11 @@ pub mod limits {
 
     /// Maximum cell range size allowed. Must be strictly less than `u32::MAX`.
     pub const CELL_RANGE_LIMIT: u32 = 1_000_000;
+
+    /// Maximum number of nested calls to functions defined using `LAMBDA`.
+    /// Each call uses several stack frames, so this must be small enough that
+    /// the deepest recursion fits in the stack of a web worker.
+    pub const MAX_CALL_DEPTH: usize = 32;
 }
 
 pub const DEFAULT_COLUMN_WIDTH: f64 = 100.0;
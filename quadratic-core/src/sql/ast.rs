@@ -0,0 +1,180 @@
+use crate::{formulas::CellRef, CellValue, Span, Spanned};
+
+/// Parsed `SELECT` query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub distinct: bool,
+    pub columns: Vec<SelectItem>,
+    pub from: Option<TableRef>,
+    pub where_clause: Option<Expr>,
+    pub group_by: Vec<Expr>,
+    pub having: Option<Expr>,
+    pub order_by: Vec<OrderBy>,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+/// Item in the list of columns after `SELECT`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectItem {
+    /// `*`, which selects every column of the table.
+    Wildcard(Span),
+    /// Expression, with the name of the output column.
+    Expr { expr: Expr, name: String },
+}
+
+/// Range of cells used as a table. The first row of the range provides the
+/// column names.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRef {
+    pub start: CellRef,
+    pub end: CellRef,
+    pub alias: Option<String>,
+    pub span: Span,
+}
+
+/// Item in an `ORDER BY` clause.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderBy {
+    pub expr: Expr,
+    pub descending: bool,
+}
+
+pub type Expr = Spanned<ExprKind>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprKind {
+    Literal(CellValue),
+    Column {
+        table: Option<String>,
+        name: String,
+    },
+    Unary {
+        op: UnaryOp,
+        expr: Box<Expr>,
+    },
+    Binary {
+        op: BinaryOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    IsNull {
+        expr: Box<Expr>,
+        negated: bool,
+    },
+    InList {
+        expr: Box<Expr>,
+        list: Vec<Expr>,
+        negated: bool,
+    },
+    Between {
+        expr: Box<Expr>,
+        low: Box<Expr>,
+        high: Box<Expr>,
+        negated: bool,
+    },
+    Like {
+        expr: Box<Expr>,
+        pattern: Box<Expr>,
+        negated: bool,
+    },
+    Case {
+        operand: Option<Box<Expr>>,
+        branches: Vec<(Expr, Expr)>,
+        else_result: Option<Box<Expr>>,
+    },
+    Function {
+        name: Spanned<String>,
+        args: FunctionArgs,
+    },
+}
+
+/// Arguments to a function call.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FunctionArgs {
+    /// `*`, as in `COUNT(*)`.
+    Star,
+    /// List of expressions, optionally preceded by `DISTINCT`.
+    List { distinct: bool, args: Vec<Expr> },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Concat,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+/// Names of the aggregate functions, which evaluate an expression over every
+/// row of a group.
+pub const AGGREGATE_FUNCTIONS: &[&str] = &["AVG", "COUNT", "MAX", "MIN", "SUM"];
+
+impl ExprKind {
+    /// Returns whether the expression contains an aggregate function.
+    pub fn contains_aggregate(&self) -> bool {
+        match self {
+            ExprKind::Literal(_) | ExprKind::Column { .. } => false,
+            ExprKind::Unary { expr, .. } | ExprKind::IsNull { expr, .. } => {
+                expr.inner.contains_aggregate()
+            }
+            ExprKind::Binary { left, right, .. } => {
+                left.inner.contains_aggregate() || right.inner.contains_aggregate()
+            }
+            ExprKind::InList { expr, list, .. } => {
+                expr.inner.contains_aggregate() || list.iter().any(|e| e.inner.contains_aggregate())
+            }
+            ExprKind::Between {
+                expr, low, high, ..
+            } => {
+                expr.inner.contains_aggregate()
+                    || low.inner.contains_aggregate()
+                    || high.inner.contains_aggregate()
+            }
+            ExprKind::Like { expr, pattern, .. } => {
+                expr.inner.contains_aggregate() || pattern.inner.contains_aggregate()
+            }
+            ExprKind::Case {
+                operand,
+                branches,
+                else_result,
+            } => {
+                operand.iter().any(|e| e.inner.contains_aggregate())
+                    || branches.iter().any(|(condition, result)| {
+                        condition.inner.contains_aggregate() || result.inner.contains_aggregate()
+                    })
+                    || else_result.iter().any(|e| e.inner.contains_aggregate())
+            }
+            ExprKind::Function { name, args } => {
+                is_aggregate(&name.inner)
+                    || match args {
+                        FunctionArgs::Star => false,
+                        FunctionArgs::List { args, .. } => {
+                            args.iter().any(|e| e.inner.contains_aggregate())
+                        }
+                    }
+            }
+        }
+    }
+}
+
+/// Returns whether `name` is the name of an aggregate function.
+pub fn is_aggregate(name: &str) -> bool {
+    AGGREGATE_FUNCTIONS.contains(&name)
+}
@@ -0,0 +1,803 @@
+//! Evaluation of SQL queries over ranges of cells.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use bigdecimal::{BigDecimal, ToPrimitive, Zero};
+use indexmap::IndexMap;
+use regex::Regex;
+
+use super::ast::*;
+use crate::{
+    formulas::{functions::round_decimal_to_digits, CellRef, Ctx},
+    Array, ArraySize, CellValue, CodeResult, ErrorMsg, IsBlank, Pos, Span, Spanned, Value,
+};
+
+/// Table of values read from a range of cells.
+struct Table {
+    alias: Option<String>,
+    columns: Vec<String>,
+    rows: Vec<Vec<CellValue>>,
+}
+
+/// Rows visible to an expression while it is evaluated.
+#[derive(Debug, Copy, Clone)]
+struct Scope<'a> {
+    /// Rows in the current group, used by aggregate functions.
+    rows: &'a [usize],
+    /// Row used for column references.
+    row: Option<usize>,
+    /// Whether aggregate functions may be used.
+    allow_aggregates: bool,
+}
+
+impl Query {
+    /// Evaluates the query, returning the result as an array whose first row
+    /// contains the column names.
+    pub fn eval(&self, ctx: &mut Ctx<'_>) -> CodeResult<Value> {
+        let table = match &self.from {
+            Some(table_ref) => read_table(ctx, table_ref)?,
+            None => Table {
+                alias: None,
+                columns: vec![],
+                rows: vec![vec![]],
+            },
+        };
+        let eval = Eval { table: &table };
+
+        // filter rows
+        let mut rows = vec![];
+        for i in 0..table.rows.len() {
+            if let Some(condition) = &self.where_clause {
+                let scope = Scope {
+                    rows: &[],
+                    row: Some(i),
+                    allow_aggregates: false,
+                };
+                if eval.eval_condition(condition, scope, "WHERE")? != Some(true) {
+                    continue;
+                }
+            }
+            rows.push(i);
+        }
+
+        let is_aggregate_query = !self.group_by.is_empty()
+            || self.having.is_some()
+            || self.columns.iter().any(|item| match item {
+                SelectItem::Wildcard(_) => false,
+                SelectItem::Expr { expr, .. } => expr.inner.contains_aggregate(),
+            })
+            || self
+                .order_by
+                .iter()
+                .any(|item| item.expr.inner.contains_aggregate());
+
+        // each group of rows produces one row of output
+        let groups: Vec<Vec<usize>> = if is_aggregate_query {
+            let mut groups: IndexMap<Vec<String>, Vec<usize>> = IndexMap::new();
+            if self.group_by.is_empty() {
+                groups.insert(vec![], vec![]);
+            }
+            for &i in &rows {
+                let scope = Scope {
+                    rows: &[],
+                    row: Some(i),
+                    allow_aggregates: false,
+                };
+                let mut key = vec![];
+                for expr in &self.group_by {
+                    key.push(value_key(&eval.eval_expr(expr, scope)?));
+                }
+                groups.entry(key).or_default().push(i);
+            }
+            groups.into_values().collect()
+        } else {
+            rows.iter().map(|&i| vec![i]).collect()
+        };
+
+        let column_names = self.column_names(&table)?;
+        let mut output: Vec<(Vec<CellValue>, Vec<CellValue>)> = vec![];
+        let mut seen = HashSet::new();
+        for group in &groups {
+            let scope = Scope {
+                rows: group,
+                row: group.first().copied(),
+                allow_aggregates: is_aggregate_query,
+            };
+
+            if let Some(condition) = &self.having {
+                if eval.eval_condition(condition, scope, "HAVING")? != Some(true) {
+                    continue;
+                }
+            }
+
+            let mut values = vec![];
+            for item in &self.columns {
+                match item {
+                    SelectItem::Wildcard(_) => match scope.row {
+                        Some(row) => values.extend(table.rows[row].iter().cloned()),
+                        None => values.extend(table.columns.iter().map(|_| CellValue::Blank)),
+                    },
+                    SelectItem::Expr { expr, .. } => values.push(eval.eval_expr(expr, scope)?),
+                }
+            }
+
+            if self.distinct && !seen.insert(values.iter().map(value_key).collect::<Vec<_>>()) {
+                continue;
+            }
+
+            let mut sort_keys = vec![];
+            for item in &self.order_by {
+                sort_keys.push(eval.eval_order_key(&item.expr, scope, &column_names, &values)?);
+            }
+            output.push((values, sort_keys));
+        }
+
+        if !self.order_by.is_empty() {
+            let mut error = None;
+            output.sort_by(|(_, a), (_, b)| {
+                for (item, (a, b)) in self.order_by.iter().zip(a.iter().zip(b)) {
+                    let ordering = match compare_for_sort(a, b) {
+                        Ok(ordering) => ordering,
+                        Err(e) => {
+                            error.get_or_insert(e.with_span(item.expr.span));
+                            Ordering::Equal
+                        }
+                    };
+                    let ordering = if item.descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    };
+                    if ordering != Ordering::Equal {
+                        return ordering;
+                    }
+                }
+                Ordering::Equal
+            });
+            if let Some(e) = error {
+                return Err(e);
+            }
+        }
+
+        let output_rows = output
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .map(|(values, _)| values);
+
+        let width = column_names.len() as u32;
+        let mut values = column_names
+            .into_iter()
+            .map(CellValue::Text)
+            .collect::<smallvec::SmallVec<[CellValue; 1]>>();
+        for row in output_rows {
+            values.extend(row);
+        }
+        let height = values.len() as u32 / width;
+        let size = ArraySize::new_or_err(width, height)?;
+        Ok(Value::Array(Array::new_row_major(size, values)?))
+    }
+
+    /// Returns the names of the output columns.
+    fn column_names(&self, table: &Table) -> CodeResult<Vec<String>> {
+        let mut names = vec![];
+        for item in &self.columns {
+            match item {
+                SelectItem::Wildcard(span) => {
+                    if self.from.is_none() {
+                        return Err(
+                            ErrorMsg::Unexpected("`*` without `FROM`".into()).with_span(*span)
+                        );
+                    }
+                    names.extend(table.columns.iter().cloned());
+                }
+                SelectItem::Expr { name, .. } => names.push(name.clone()),
+            }
+        }
+        Ok(names)
+    }
+}
+
+/// Reads the cells of a range, recording them as accessed. The first row
+/// provides the column names and rows that are entirely blank are skipped.
+fn read_table(ctx: &mut Ctx<'_>, table_ref: &TableRef) -> CodeResult<Table> {
+    let span = table_ref.span;
+    let start = table_ref.start.resolve_from(Pos { x: 0, y: 0 });
+    let end = table_ref.end.resolve_from(Pos { x: 0, y: 0 });
+    let (x_min, x_max) = (start.x.min(end.x), start.x.max(end.x));
+    let (y_min, y_max) = (start.y.min(end.y), start.y.max(end.y));
+
+    let cell_count = (x_max - x_min + 1) as f64 * (y_max - y_min + 1) as f64;
+    if cell_count > crate::limits::CELL_RANGE_LIMIT as f64 {
+        return Err(ErrorMsg::ArrayTooBig.with_span(span));
+    }
+
+    let sheet = table_ref.start.sheet.clone();
+    let mut get_cell = |x: i64, y: i64| -> CodeResult<CellValue> {
+        let cell_ref = CellRef::absolute(sheet.clone(), Pos { x, y });
+        Ok(ctx.get_cell(&cell_ref, span)?.inner)
+    };
+
+    let mut columns = vec![];
+    for x in x_min..=x_max {
+        let name = get_cell(x, y_min)?.to_string().trim().to_string();
+        columns.push(match name.is_empty() {
+            true => crate::util::column_name(x),
+            false => name,
+        });
+    }
+
+    let mut rows = vec![];
+    for y in y_min + 1..=y_max {
+        let mut row = vec![];
+        for x in x_min..=x_max {
+            row.push(get_cell(x, y)?);
+        }
+        if !row.iter().all(|value| value.is_blank()) {
+            rows.push(row);
+        }
+    }
+
+    Ok(Table {
+        alias: table_ref.alias.clone(),
+        columns,
+        rows,
+    })
+}
+
+struct Eval<'a> {
+    table: &'a Table,
+}
+impl Eval<'_> {
+    fn eval_expr(&self, expr: &Expr, scope: Scope<'_>) -> CodeResult<CellValue> {
+        let span = expr.span;
+        match &expr.inner {
+            ExprKind::Literal(value) => Ok(value.clone()),
+
+            ExprKind::Column { table, name } => {
+                let column = self.column_index(table.as_deref(), name, span)?;
+                Ok(match scope.row {
+                    Some(row) => self.table.rows[row][column].clone(),
+                    None => CellValue::Blank,
+                })
+            }
+
+            ExprKind::Unary { op, expr } => {
+                let value = self.eval_expr(expr, scope)?;
+                match op {
+                    UnaryOp::Neg => match value {
+                        CellValue::Blank => Ok(CellValue::Blank),
+                        _ => Ok(CellValue::Number(-to_number(&value, expr.span)?)),
+                    },
+                    UnaryOp::Not => {
+                        Ok(CellValue::from(truth(&value, expr.span)?.map(|b: bool| !b)))
+                    }
+                }
+            }
+
+            ExprKind::Binary { op, left, right } => match op {
+                BinaryOp::And | BinaryOp::Or => {
+                    let l = truth(&self.eval_expr(left, scope)?, left.span)?;
+                    let r = truth(&self.eval_expr(right, scope)?, right.span)?;
+                    Ok(CellValue::from(match op {
+                        BinaryOp::And => match (l, r) {
+                            (Some(false), _) | (_, Some(false)) => Some(false),
+                            (Some(true), Some(true)) => Some(true),
+                            _ => None,
+                        },
+                        _ => match (l, r) {
+                            (Some(true), _) | (_, Some(true)) => Some(true),
+                            (Some(false), Some(false)) => Some(false),
+                            _ => None,
+                        },
+                    }))
+                }
+                _ => {
+                    let l = self.eval_expr(left, scope)?;
+                    let r = self.eval_expr(right, scope)?;
+                    binary_op(*op, &l, &r, span)
+                }
+            },
+
+            ExprKind::IsNull { expr, negated } => {
+                let is_null = self.eval_expr(expr, scope)?.is_blank();
+                Ok(CellValue::Logical(is_null != *negated))
+            }
+
+            ExprKind::InList {
+                expr,
+                list,
+                negated,
+            } => {
+                let value = self.eval_expr(expr, scope)?;
+                if value.is_blank() {
+                    return Ok(CellValue::Blank);
+                }
+                let mut found = Some(false);
+                for item in list {
+                    let item = self.eval_expr(item, scope)?;
+                    match compare(&value, &item).map_err(|e| e.with_span(span))? {
+                        Some(Ordering::Equal) => {
+                            found = Some(true);
+                            break;
+                        }
+                        Some(_) => (),
+                        None => found = None,
+                    }
+                }
+                Ok(CellValue::from(found.map(|found| found != *negated)))
+            }
+
+            ExprKind::Between {
+                expr,
+                low,
+                high,
+                negated,
+            } => {
+                let value = self.eval_expr(expr, scope)?;
+                let low = self.eval_expr(low, scope)?;
+                let high = self.eval_expr(high, scope)?;
+                let above = compare(&value, &low).map_err(|e| e.with_span(span))?;
+                let below = compare(&value, &high).map_err(|e| e.with_span(span))?;
+                Ok(CellValue::from(above.zip(below).map(|(above, below)| {
+                    (above != Ordering::Less && below != Ordering::Greater) != *negated
+                })))
+            }
+
+            ExprKind::Like {
+                expr,
+                pattern,
+                negated,
+            } => {
+                let value = self.eval_expr(expr, scope)?;
+                let pattern_value = self.eval_expr(pattern, scope)?;
+                if value.is_blank() || pattern_value.is_blank() {
+                    return Ok(CellValue::Blank);
+                }
+                let s = to_string(&value, expr.span)?;
+                let pattern = to_string(&pattern_value, pattern.span)?;
+                Ok(CellValue::Logical(
+                    like_regex(&pattern).is_match(&s) != *negated,
+                ))
+            }
+
+            ExprKind::Case {
+                operand,
+                branches,
+                else_result,
+            } => {
+                let operand = match operand {
+                    Some(operand) => Some(self.eval_expr(operand, scope)?),
+                    None => None,
+                };
+                for (condition, result) in branches {
+                    let is_match = match &operand {
+                        Some(operand) => {
+                            let value = self.eval_expr(condition, scope)?;
+                            compare(operand, &value).map_err(|e| e.with_span(condition.span))?
+                                == Some(Ordering::Equal)
+                        }
+                        None => self.eval_condition(condition, scope, "CASE")? == Some(true),
+                    };
+                    if is_match {
+                        return self.eval_expr(result, scope);
+                    }
+                }
+                match else_result {
+                    Some(else_result) => self.eval_expr(else_result, scope),
+                    None => Ok(CellValue::Blank),
+                }
+            }
+
+            ExprKind::Function { name, args } => {
+                if is_aggregate(&name.inner) {
+                    self.eval_aggregate(name, args, scope, span)
+                } else {
+                    let args = match args {
+                        FunctionArgs::Star => {
+                            return Err(ErrorMsg::Unexpected("`*`".into()).with_span(span))
+                        }
+                        FunctionArgs::List { args, .. } => args,
+                    };
+                    let mut values = vec![];
+                    for arg in args {
+                        values.push(Spanned {
+                            span: arg.span,
+                            inner: self.eval_expr(arg, scope)?,
+                        });
+                    }
+                    eval_scalar_function(name, values, span)
+                }
+            }
+        }
+    }
+
+    /// Evaluates a condition, returning `None` if it is NULL.
+    fn eval_condition(
+        &self,
+        expr: &Expr,
+        scope: Scope<'_>,
+        clause: &'static str,
+    ) -> CodeResult<Option<bool>> {
+        if !scope.allow_aggregates && expr.inner.contains_aggregate() {
+            return Err(ErrorMsg::Unexpected(
+                format!("aggregate function in {clause} clause").into(),
+            )
+            .with_span(expr.span));
+        }
+        truth(&self.eval_expr(expr, scope)?, expr.span)
+    }
+
+    /// Evaluates an `ORDER BY` expression, which may also refer to an output
+    /// column by name or by 1-based position.
+    fn eval_order_key(
+        &self,
+        expr: &Expr,
+        scope: Scope<'_>,
+        column_names: &[String],
+        values: &[CellValue],
+    ) -> CodeResult<CellValue> {
+        match &expr.inner {
+            ExprKind::Literal(CellValue::Number(n)) => {
+                let index = n
+                    .to_usize()
+                    .filter(|&i| i >= 1 && i <= values.len() && n.is_integer())
+                    .ok_or_else(|| ErrorMsg::IndexOutOfBounds.with_span(expr.span))?;
+                Ok(values[index - 1].clone())
+            }
+            ExprKind::Column { table: None, name } => {
+                match column_names
+                    .iter()
+                    .position(|column| column.eq_ignore_ascii_case(name))
+                {
+                    Some(i) => Ok(values[i].clone()),
+                    None => self.eval_expr(expr, scope),
+                }
+            }
+            _ => self.eval_expr(expr, scope),
+        }
+    }
+
+    fn eval_aggregate(
+        &self,
+        name: &Spanned<String>,
+        args: &FunctionArgs,
+        scope: Scope<'_>,
+        span: Span,
+    ) -> CodeResult<CellValue> {
+        if !scope.allow_aggregates {
+            return Err(ErrorMsg::Unexpected("aggregate function".into()).with_span(span));
+        }
+        let (distinct, arg) = match args {
+            FunctionArgs::Star if name.inner == "COUNT" => {
+                return Ok(CellValue::from(scope.rows.len() as u32));
+            }
+            FunctionArgs::Star => return Err(ErrorMsg::Unexpected("`*`".into()).with_span(span)),
+            FunctionArgs::List { distinct, args } => {
+                check_arg_count(name, args.len(), &["value"], 1, span)?;
+                (*distinct, &args[0])
+            }
+        };
+
+        // evaluate the argument for each row in the group, skipping NULLs
+        let mut values = vec![];
+        let mut seen = HashSet::new();
+        for &row in scope.rows {
+            let row_scope = Scope {
+                rows: &[],
+                row: Some(row),
+                allow_aggregates: false,
+            };
+            let value = self.eval_expr(arg, row_scope)?;
+            if value.is_blank() || (distinct && !seen.insert(value_key(&value))) {
+                continue;
+            }
+            values.push(value);
+        }
+
+        match name.inner.as_str() {
+            "COUNT" => Ok(CellValue::from(values.len() as u32)),
+            "SUM" | "AVG" => {
+                if values.is_empty() {
+                    return Ok(CellValue::Blank);
+                }
+                let mut sum = BigDecimal::zero();
+                for value in &values {
+                    sum += to_number(value, arg.span)?;
+                }
+                if name.inner == "SUM" {
+                    Ok(CellValue::Number(sum))
+                } else {
+                    let sum = sum.to_f64().unwrap_or(f64::NAN);
+                    from_f64(sum / values.len() as f64, arg.span)
+                }
+            }
+            _ => {
+                let want = match name.inner.as_str() {
+                    "MIN" => Ordering::Less,
+                    _ => Ordering::Greater,
+                };
+                let mut best: Option<CellValue> = None;
+                for value in values {
+                    let is_better = match &best {
+                        None => true,
+                        Some(best) => value.cmp(best).map_err(|e| e.with_span(arg.span))? == want,
+                    };
+                    if is_better {
+                        best = Some(value);
+                    }
+                }
+                Ok(best.unwrap_or(CellValue::Blank))
+            }
+        }
+    }
+
+    fn column_index(&self, table: Option<&str>, name: &str, span: Span) -> CodeResult<usize> {
+        let unknown = || {
+            let full_name = match table {
+                Some(table) => format!("{table}.{name}"),
+                None => name.to_string(),
+            };
+            ErrorMsg::UnknownColumn(full_name.into()).with_span(span)
+        };
+        if let Some(table) = table {
+            if !self
+                .table
+                .alias
+                .as_ref()
+                .is_some_and(|alias| alias.eq_ignore_ascii_case(table))
+            {
+                return Err(unknown());
+            }
+        }
+        // prefer an exact match, then a case-insensitive one
+        self.table
+            .columns
+            .iter()
+            .position(|column| column == name)
+            .or_else(|| {
+                self.table
+                    .columns
+                    .iter()
+                    .position(|column| column.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(unknown)
+    }
+}
+
+fn eval_scalar_function(
+    name: &Spanned<String>,
+    args: Vec<Spanned<CellValue>>,
+    span: Span,
+) -> CodeResult<CellValue> {
+    let arg_count = args.len();
+    let propagate_null = |args: &[Spanned<CellValue>]| args.iter().any(|arg| arg.inner.is_blank());
+    match name.inner.as_str() {
+        "COALESCE" | "IFNULL" => Ok(args
+            .into_iter()
+            .map(|arg| arg.inner)
+            .find(|value| !value.is_blank())
+            .unwrap_or(CellValue::Blank)),
+
+        "UPPER" | "LOWER" | "TRIM" | "LENGTH" | "LEN" => {
+            check_arg_count(name, arg_count, &["text"], 1, span)?;
+            if propagate_null(&args) {
+                return Ok(CellValue::Blank);
+            }
+            let s = to_string(&args[0].inner, args[0].span)?;
+            Ok(match name.inner.as_str() {
+                "UPPER" => CellValue::Text(s.to_uppercase()),
+                "LOWER" => CellValue::Text(s.to_lowercase()),
+                "TRIM" => CellValue::Text(s.trim().to_string()),
+                _ => CellValue::from(s.chars().count() as u32),
+            })
+        }
+
+        "SUBSTR" | "SUBSTRING" => {
+            check_arg_count(name, arg_count, &["text", "start", "length"], 2, span)?;
+            if propagate_null(&args) {
+                return Ok(CellValue::Blank);
+            }
+            let s = to_string(&args[0].inner, args[0].span)?;
+            let start = to_number(&args[1].inner, args[1].span)?
+                .to_i64()
+                .unwrap_or(1)
+                .max(1) as usize;
+            let chars = s.chars().skip(start - 1);
+            Ok(CellValue::Text(match args.get(2) {
+                Some(len) => {
+                    let len = to_number(&len.inner, len.span)?
+                        .to_i64()
+                        .unwrap_or(0)
+                        .max(0);
+                    chars.take(len as usize).collect()
+                }
+                None => chars.collect(),
+            }))
+        }
+
+        "ABS" => {
+            check_arg_count(name, arg_count, &["number"], 1, span)?;
+            if propagate_null(&args) {
+                return Ok(CellValue::Blank);
+            }
+            Ok(CellValue::Number(
+                to_number(&args[0].inner, args[0].span)?.abs(),
+            ))
+        }
+
+        "ROUND" => {
+            check_arg_count(name, arg_count, &["number", "digits"], 1, span)?;
+            if propagate_null(&args) {
+                return Ok(CellValue::Blank);
+            }
+            let number = to_number(&args[0].inner, args[0].span)?;
+            let digits = match args.get(1) {
+                Some(digits) => to_integer(&digits.inner, digits.span)?,
+                None => 0,
+            };
+            round_decimal_to_digits(span, number, digits, bigdecimal::RoundingMode::HalfUp)
+        }
+
+        _ => Err(ErrorMsg::BadFunctionName.with_span(name.span)),
+    }
+}
+
+/// Checks the number of arguments to a function. `arg_names` lists every
+/// argument, of which the first `required` must be present.
+fn check_arg_count(
+    name: &Spanned<String>,
+    arg_count: usize,
+    arg_names: &[&'static str],
+    required: usize,
+    span: Span,
+) -> CodeResult<()> {
+    if arg_count > arg_names.len() {
+        return Err(ErrorMsg::TooManyArguments {
+            func_name: name.inner.clone().into(),
+            max_arg_count: arg_names.len(),
+        }
+        .with_span(span));
+    }
+    if arg_count < required {
+        return Err(ErrorMsg::MissingRequiredArgument {
+            func_name: name.inner.clone().into(),
+            arg_name: arg_names[arg_count].into(),
+        }
+        .with_span(span));
+    }
+    Ok(())
+}
+
+fn binary_op(op: BinaryOp, l: &CellValue, r: &CellValue, span: Span) -> CodeResult<CellValue> {
+    if l.is_blank() || r.is_blank() {
+        return Ok(CellValue::Blank);
+    }
+    let comparison = |expected: &[Ordering]| -> CodeResult<CellValue> {
+        let ordering = compare(l, r).map_err(|e| e.with_span(span))?;
+        Ok(CellValue::from(ordering.map(|o| expected.contains(&o))))
+    };
+    match op {
+        BinaryOp::Eq => comparison(&[Ordering::Equal]),
+        BinaryOp::Ne => comparison(&[Ordering::Less, Ordering::Greater]),
+        BinaryOp::Lt => comparison(&[Ordering::Less]),
+        BinaryOp::Gt => comparison(&[Ordering::Greater]),
+        BinaryOp::Le => comparison(&[Ordering::Less, Ordering::Equal]),
+        BinaryOp::Ge => comparison(&[Ordering::Greater, Ordering::Equal]),
+        BinaryOp::Concat => Ok(CellValue::Text(to_string(l, span)? + &to_string(r, span)?)),
+        BinaryOp::Add => Ok(CellValue::Number(to_number(l, span)? + to_number(r, span)?)),
+        BinaryOp::Sub => Ok(CellValue::Number(to_number(l, span)? - to_number(r, span)?)),
+        BinaryOp::Mul => Ok(CellValue::Number(to_number(l, span)? * to_number(r, span)?)),
+        BinaryOp::Div | BinaryOp::Mod => {
+            let dividend = to_number(l, span)?;
+            let divisor = to_number(r, span)?;
+            if divisor.is_zero() {
+                return Err(ErrorMsg::DivideByZero.with_span(span));
+            }
+            if op == BinaryOp::Mod {
+                return Ok(CellValue::Number(dividend % divisor));
+            }
+            let quotient =
+                dividend.to_f64().unwrap_or(f64::NAN) / divisor.to_f64().unwrap_or(f64::NAN);
+            from_f64(quotient, span)
+        }
+        BinaryOp::And | BinaryOp::Or => unreachable!("logical operators are lazy"),
+    }
+}
+
+/// Compares two values, returning `None` if either is NULL.
+fn compare(a: &CellValue, b: &CellValue) -> CodeResult<Option<Ordering>> {
+    if a.is_blank() || b.is_blank() {
+        return Ok(None);
+    }
+    a.cmp(b).map(Some)
+}
+
+/// Compares two values for sorting, with NULLs before all other values.
+fn compare_for_sort(a: &CellValue, b: &CellValue) -> CodeResult<Ordering> {
+    match (a.is_blank(), b.is_blank()) {
+        (true, true) => Ok(Ordering::Equal),
+        (true, false) => Ok(Ordering::Less),
+        (false, true) => Ok(Ordering::Greater),
+        (false, false) => a.cmp(b),
+    }
+}
+
+/// Returns the truth value of a condition, or `None` if it is NULL.
+fn truth(value: &CellValue, span: Span) -> CodeResult<Option<bool>> {
+    match value {
+        CellValue::Blank => Ok(None),
+        _ => bool::try_from(value)
+            .map(Some)
+            .map_err(|msg| msg.with_span(span)),
+    }
+}
+
+fn to_number(value: &CellValue, span: Span) -> CodeResult<BigDecimal> {
+    match value {
+        CellValue::Number(n) => Ok(n.clone()),
+        _ => {
+            let n = f64::try_from(value).map_err(|msg| msg.with_span(span))?;
+            BigDecimal::try_from(n).map_err(|_| ErrorMsg::NotANumber.with_span(span))
+        }
+    }
+}
+
+/// Converts the result of a floating-point calculation to a number, using
+/// the shortest decimal that round-trips (so that `8.7` stays `8.7`).
+fn from_f64(n: f64, span: Span) -> CodeResult<CellValue> {
+    if n.is_nan() {
+        return Err(ErrorMsg::NotANumber.with_span(span));
+    }
+    if n.is_infinite() {
+        return Err(ErrorMsg::Infinity.with_span(span));
+    }
+    n.to_string()
+        .parse()
+        .map(CellValue::Number)
+        .map_err(|_| ErrorMsg::NotANumber.with_span(span))
+}
+
+/// Converts a value to the nearest integer, saturating at the bounds of
+/// `i64`.
+fn to_integer(value: &CellValue, span: Span) -> CodeResult<i64> {
+    let n = to_number(value, span)?.with_scale_round(0, bigdecimal::RoundingMode::HalfUp);
+    Ok(n.to_i64().unwrap_or(match n < BigDecimal::zero() {
+        true => i64::MIN,
+        false => i64::MAX,
+    }))
+}
+
+fn to_string(value: &CellValue, span: Span) -> CodeResult<String> {
+    String::try_from(value).map_err(|msg| msg.with_span(span))
+}
+
+/// Returns a key that is equal for values that SQL considers identical, used
+/// for `GROUP BY` and `DISTINCT`.
+fn value_key(value: &CellValue) -> String {
+    match value {
+        CellValue::Blank => String::new(),
+        CellValue::Text(s) => format!("t{s}"),
+        CellValue::Number(n) => format!("n{}", n.normalized()),
+        CellValue::Logical(b) => format!("b{b}"),
+        CellValue::Instant(i) => format!("i{i}"),
+        CellValue::Duration(d) => format!("d{d}"),
+        CellValue::Error(e) => format!("e{}", e.msg),
+    }
+}
+
+/// Converts a `LIKE` pattern to a case-insensitive regex, where `%` matches
+/// any sequence of characters and `_` matches a single character.
+fn like_regex(pattern: &str) -> Regex {
+    let mut regex = String::from("(?si)^");
+    for c in pattern.chars() {
+        match c {
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    Regex::new(&regex).expect("escaped LIKE pattern should be a valid regex")
+}
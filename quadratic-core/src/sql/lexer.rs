@@ -0,0 +1,166 @@
+//! Tokenizer for SQL queries.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use strum_macros::Display;
+
+use crate::{Span, Spanned};
+
+pub fn tokenize(input_str: &str) -> impl '_ + Iterator<Item = Spanned<Token>> {
+    let mut token_start = 0;
+    std::iter::from_fn(move || {
+        Token::consume_from_input(input_str, token_start).map(|(token, token_end)| {
+            let span = Span {
+                start: token_start as u32,
+                end: token_end as u32,
+            };
+            token_start = token_end;
+            Spanned { span, inner: token }
+        })
+    })
+}
+
+fn new_fullmatch_regex(s: &str) -> Regex {
+    Regex::new(&("^(".to_owned() + s + ")")).unwrap()
+}
+
+/// A1-style cell reference, using the same syntax as formulas.
+const A1_CELL_REFERENCE_PATTERN: &str = r"\$?n?[A-Z]+\$?n?\d+";
+
+/// Sheet name before a `!`, either unquoted or quoted with `'` or `"`.
+const SHEET_NAME_PATTERN: &str = r#"(?:'(?:[^']|'')*'|"(?:[^"]|"")*"|[A-Za-z_][A-Za-z0-9_\.]*)"#;
+
+/// Floating-point or integer number, without leading sign.
+const NUMERIC_LITERAL_PATTERN: &str = r"(\d+(\.\d*)?|\.\d+)([eE][+-]?\d+)?";
+
+lazy_static! {
+    /// Range of cells used as a table, such as `Sheet1!A1:D500` or `A1:D500`.
+    /// A single cell is only allowed when it has a sheet name, so that column
+    /// names such as `q1` are not mistaken for cell references. `#REF!`
+    /// replaces a range whose cells were deleted, and fails to parse.
+    static ref RANGE_REGEX: Regex = new_fullmatch_regex(&format!(
+        r"{SHEET_NAME_PATTERN}\s*!\s*{A1_CELL_REFERENCE_PATTERN}(\s*:\s*{A1_CELL_REFERENCE_PATTERN})?|{A1_CELL_REFERENCE_PATTERN}\s*:\s*{A1_CELL_REFERENCE_PATTERN}|#REF!"
+    ));
+
+    /// List of token patterns, arranged roughly from least to most general.
+    static ref TOKEN_REGEXES: Vec<(Regex, Token)> = [
+        (r"\s+", Token::Whitespace),
+        (r"--[^\n]*", Token::Comment),
+        (r"/\*([^*]|\*+[^*/])*\*+/", Token::Comment),
+        (r"'([^']|'')*'", Token::StringLiteral),
+        (r#""([^"]|"")*""#, Token::QuotedIdent),
+        (r"`[^`]*`", Token::QuotedIdent),
+        (r#"['"`]|/\*"#, Token::Unterminated),
+        (NUMERIC_LITERAL_PATTERN, Token::NumericLiteral),
+        (r"[A-Za-z_][A-Za-z0-9_]*", Token::Ident),
+        (r"<>|!=", Token::Ne),
+        (r"<=", Token::Le),
+        (r">=", Token::Ge),
+        (r"\|\|", Token::Concat),
+        (r"==?", Token::Eq),
+        (r"<", Token::Lt),
+        (r">", Token::Gt),
+        (r"\(", Token::LParen),
+        (r"\)", Token::RParen),
+        (r",", Token::Comma),
+        (r"\.", Token::Period),
+        (r";", Token::Semicolon),
+        (r"\*", Token::Star),
+        (r"\+", Token::Plus),
+        (r"-", Token::Minus),
+        (r"/", Token::Slash),
+        (r"%", Token::Percent),
+    ]
+    .into_iter()
+    .map(|(pattern, token)| (new_fullmatch_regex(pattern), token))
+    .collect();
+}
+
+#[derive(Debug, Display, Copy, Clone, PartialEq, Eq)]
+pub enum Token {
+    #[strum(to_string = "cell range")]
+    Range,
+    #[strum(to_string = "identifier")]
+    Ident,
+    #[strum(to_string = "quoted identifier")]
+    QuotedIdent,
+    #[strum(to_string = "string literal")]
+    StringLiteral,
+    #[strum(to_string = "numeric literal")]
+    NumericLiteral,
+
+    #[strum(to_string = "left paren")]
+    LParen,
+    #[strum(to_string = "right paren")]
+    RParen,
+    #[strum(to_string = "comma")]
+    Comma,
+    #[strum(to_string = "period")]
+    Period,
+    #[strum(to_string = "semicolon")]
+    Semicolon,
+
+    #[strum(to_string = "equals comparison")]
+    Eq,
+    #[strum(to_string = "not-equals comparison")]
+    Ne,
+    #[strum(to_string = "less-than comparison")]
+    Lt,
+    #[strum(to_string = "greater-than comparison")]
+    Gt,
+    #[strum(to_string = "less-than-or-equal comparison")]
+    Le,
+    #[strum(to_string = "greater-than-or-equal comparison")]
+    Ge,
+
+    #[strum(to_string = "star")]
+    Star,
+    #[strum(to_string = "plus")]
+    Plus,
+    #[strum(to_string = "minus")]
+    Minus,
+    #[strum(to_string = "slash")]
+    Slash,
+    #[strum(to_string = "percent")]
+    Percent,
+    #[strum(to_string = "concatenation operator")]
+    Concat,
+
+    #[strum(to_string = "whitespace")]
+    Whitespace,
+    #[strum(to_string = "comment")]
+    Comment,
+    #[strum(to_string = "unterminated string or comment")]
+    Unterminated,
+    #[strum(to_string = "unknown symbol")]
+    Unknown,
+}
+impl Token {
+    /// Consumes a token from the input at the given index, returning the token
+    /// and the index after it. Returns `None` at the end of the input.
+    fn consume_from_input(input_str: &str, start: usize) -> Option<(Self, usize)> {
+        let rest = &input_str[start..];
+        let first_char = rest.chars().next()?;
+
+        if let Some(m) = RANGE_REGEX.find(rest) {
+            return Some((Token::Range, start + m.end()));
+        }
+        for (regex, token) in TOKEN_REGEXES.iter() {
+            if let Some(m) = regex.find(rest) {
+                let end = match token {
+                    // An unterminated string or comment consumes the rest of
+                    // the input.
+                    Token::Unterminated => input_str.len(),
+                    _ => start + m.end(),
+                };
+                return Some((*token, end));
+            }
+        }
+        Some((Token::Unknown, start + first_char.len_utf8()))
+    }
+
+    /// Returns whether the token should be ignored by the parser.
+    pub fn is_skip(self) -> bool {
+        matches!(self, Token::Whitespace | Token::Comment)
+    }
+}
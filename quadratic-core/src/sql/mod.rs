@@ -0,0 +1,16 @@
+//! SQL queries over ranges of cells.
+//!
+//! A query reads a range such as `Sheet1!A1:D500` as a table, using the first
+//! row of the range for column names. Queries run entirely in-core and return
+//! an array, with a header row, that spills like any other code cell output.
+
+#[cfg(test)]
+mod tests;
+
+mod ast;
+mod eval;
+mod lexer;
+mod parser;
+
+pub use ast::Query;
+pub use parser::{find_table_references, parse_sql};
@@ -0,0 +1,665 @@
+//! Recursive descent parser that turns a list of tokens into a [`Query`].
+
+use itertools::Itertools;
+
+use super::ast::*;
+use super::lexer::{self, Token};
+use crate::{
+    formulas::{self, CellRef},
+    CellValue, CodeResult, ErrorMsg, Pos, Span, Spanned,
+};
+
+/// Keywords that cannot be used as an alias without `AS`.
+const RESERVED_KEYWORDS: &[&str] = &[
+    "ALL", "AND", "AS", "ASC", "BETWEEN", "BY", "CASE", "DESC", "DISTINCT", "ELSE", "END", "FALSE",
+    "FROM", "GROUP", "HAVING", "IN", "IS", "LIKE", "LIMIT", "NOT", "NULL", "OFFSET", "OR", "ORDER",
+    "SELECT", "THEN", "TRUE", "WHEN", "WHERE",
+];
+
+/// Parses a SQL `SELECT` query.
+pub fn parse_sql(source: &str) -> CodeResult<Query> {
+    let tokens = lexer::tokenize(source)
+        .filter(|t| !t.inner.is_skip())
+        .collect_vec();
+    let mut p = Parser {
+        source,
+        tokens: &tokens,
+        cursor: 0,
+    };
+    let query = p.parse_query()?;
+    p.eat(Token::Semicolon);
+    match p.peek() {
+        None => Ok(query),
+        Some(token) => Err(p.unexpected(token)),
+    }
+}
+
+/// Returns the ranges of cells used as tables in a query, without parsing the
+/// rest of the query.
+pub fn find_table_references(source: &str) -> Vec<Spanned<(CellRef, CellRef)>> {
+    lexer::tokenize(source)
+        .filter(|t| t.inner == Token::Range)
+        .filter_map(|t| {
+            Some(Spanned {
+                span: t.span,
+                inner: parse_range(t.span.of_str(source))?,
+            })
+        })
+        .collect()
+}
+
+/// Token parser used to assemble a [`Query`].
+struct Parser<'a> {
+    /// Source string.
+    source: &'a str,
+    /// Tokens to feed, without whitespace and comments.
+    tokens: &'a [Spanned<Token>],
+    /// Index of the next token.
+    cursor: usize,
+}
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Spanned<Token>> {
+        self.tokens.get(self.cursor).copied()
+    }
+    fn next(&mut self) -> Option<Spanned<Token>> {
+        let token = self.peek();
+        if token.is_some() {
+            self.cursor += 1;
+        }
+        token
+    }
+    fn token_str(&self, token: Spanned<Token>) -> &'a str {
+        token.span.of_str(self.source)
+    }
+    /// Returns the span of the previous token.
+    fn prev_span(&self) -> Span {
+        match self.cursor.checked_sub(1) {
+            Some(i) => self.tokens[i].span,
+            None => Span::empty(0),
+        }
+    }
+    /// Returns the span of the next token, or an empty span at the end of the
+    /// input.
+    fn next_span(&self) -> Span {
+        match self.peek() {
+            Some(token) => token.span,
+            None => Span::empty(self.source.len() as u32),
+        }
+    }
+
+    /// Consumes the next token if it has the given type.
+    fn eat(&mut self, token: Token) -> bool {
+        let matches = self.peek().is_some_and(|t| t.inner == token);
+        if matches {
+            self.cursor += 1;
+        }
+        matches
+    }
+    /// Returns whether the next token is the given keyword.
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|t| {
+            t.inner == Token::Ident && self.token_str(t).eq_ignore_ascii_case(keyword)
+        })
+    }
+    /// Consumes the next token if it is the given keyword.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let matches = self.peek_keyword(keyword);
+        if matches {
+            self.cursor += 1;
+        }
+        matches
+    }
+    fn expect(&mut self, token: Token) -> CodeResult<Span> {
+        match self.next() {
+            Some(t) if t.inner == token => Ok(t.span),
+            other => Err(self.expected(token.to_string(), other)),
+        }
+    }
+    fn expect_keyword(&mut self, keyword: &str) -> CodeResult<Span> {
+        if self.eat_keyword(keyword) {
+            Ok(self.prev_span())
+        } else {
+            let next = self.peek();
+            Err(self.expected(keyword.to_string(), next))
+        }
+    }
+
+    fn expected(&self, expected: String, got: Option<Spanned<Token>>) -> crate::Error {
+        match got {
+            Some(token) => ErrorMsg::Expected {
+                expected: expected.into(),
+                got: Some(self.describe(token).into()),
+            }
+            .with_span(token.span),
+            None => ErrorMsg::Expected {
+                expected: expected.into(),
+                got: Some("end of query".into()),
+            }
+            .with_span(self.next_span()),
+        }
+    }
+    fn unexpected(&self, token: Spanned<Token>) -> crate::Error {
+        ErrorMsg::Unexpected(self.describe(token).into()).with_span(token.span)
+    }
+    fn describe(&self, token: Spanned<Token>) -> String {
+        match token.inner {
+            Token::Ident => format!("`{}`", self.token_str(token)),
+            other => other.to_string(),
+        }
+    }
+
+    fn parse_query(&mut self) -> CodeResult<Query> {
+        self.expect_keyword("SELECT")?;
+        let distinct = self.eat_keyword("DISTINCT");
+        if !distinct {
+            self.eat_keyword("ALL");
+        }
+
+        let mut columns = vec![self.parse_select_item()?];
+        while self.eat(Token::Comma) {
+            columns.push(self.parse_select_item()?);
+        }
+
+        let from = if self.eat_keyword("FROM") {
+            Some(self.parse_table_ref()?)
+        } else {
+            None
+        };
+
+        let where_clause = if self.eat_keyword("WHERE") {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        let mut group_by = vec![];
+        if self.eat_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            group_by.push(self.parse_expr()?);
+            while self.eat(Token::Comma) {
+                group_by.push(self.parse_expr()?);
+            }
+        }
+
+        let having = if self.eat_keyword("HAVING") {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        let mut order_by = vec![];
+        if self.eat_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            loop {
+                let expr = self.parse_expr()?;
+                let descending = if self.eat_keyword("DESC") {
+                    true
+                } else {
+                    self.eat_keyword("ASC");
+                    false
+                };
+                order_by.push(OrderBy { expr, descending });
+                if !self.eat(Token::Comma) {
+                    break;
+                }
+            }
+        }
+
+        let mut limit = None;
+        let mut offset = 0;
+        if self.eat_keyword("LIMIT") {
+            limit = Some(self.parse_count()?);
+            if self.eat_keyword("OFFSET") {
+                offset = self.parse_count()?;
+            } else if self.eat(Token::Comma) {
+                // `LIMIT offset, count`
+                offset = limit.unwrap_or(0);
+                limit = Some(self.parse_count()?);
+            }
+        } else if self.eat_keyword("OFFSET") {
+            offset = self.parse_count()?;
+        }
+
+        Ok(Query {
+            distinct,
+            columns,
+            from,
+            where_clause,
+            group_by,
+            having,
+            order_by,
+            limit,
+            offset,
+        })
+    }
+
+    fn parse_select_item(&mut self) -> CodeResult<SelectItem> {
+        if self.eat(Token::Star) {
+            return Ok(SelectItem::Wildcard(self.prev_span()));
+        }
+        let expr = self.parse_expr()?;
+        let name = match self.parse_alias()? {
+            Some(alias) => alias,
+            None => match &expr.inner {
+                ExprKind::Column { name, .. } => name.clone(),
+                _ => expr.span.of_str(self.source).to_string(),
+            },
+        };
+        Ok(SelectItem::Expr { expr, name })
+    }
+
+    /// Parses an optional `AS alias` or bare alias.
+    fn parse_alias(&mut self) -> CodeResult<Option<String>> {
+        if self.eat_keyword("AS") {
+            return match self.next() {
+                Some(t) if matches!(t.inner, Token::Ident | Token::QuotedIdent) => {
+                    Ok(Some(self.ident_name(t)))
+                }
+                other => Err(self.expected("alias".to_string(), other)),
+            };
+        }
+        match self.peek() {
+            Some(t) if t.inner == Token::QuotedIdent => {
+                self.cursor += 1;
+                Ok(Some(self.ident_name(t)))
+            }
+            Some(t) if t.inner == Token::Ident && !self.is_reserved(t) => {
+                self.cursor += 1;
+                Ok(Some(self.ident_name(t)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn parse_table_ref(&mut self) -> CodeResult<TableRef> {
+        let token = match self.next() {
+            Some(t) if t.inner == Token::Range => t,
+            other => return Err(self.expected("cell range".to_string(), other)),
+        };
+        let (start, end) = parse_range(self.token_str(token))
+            .ok_or_else(|| ErrorMsg::BadCellReference.with_span(token.span))?;
+        let alias = self.parse_alias()?;
+        Ok(TableRef {
+            start,
+            end,
+            alias,
+            span: token.span,
+        })
+    }
+
+    /// Parses a non-negative integer, as used by `LIMIT` and `OFFSET`.
+    fn parse_count(&mut self) -> CodeResult<usize> {
+        match self.next() {
+            Some(t) if t.inner == Token::NumericLiteral => self
+                .token_str(t)
+                .parse()
+                .map_err(|_| ErrorMsg::BadNumber.with_span(t.span)),
+            other => Err(self.expected("row count".to_string(), other)),
+        }
+    }
+
+    fn parse_expr(&mut self) -> CodeResult<Expr> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> CodeResult<Expr> {
+        let mut left = self.parse_and()?;
+        while self.eat_keyword("OR") {
+            let right = self.parse_and()?;
+            left = binary(BinaryOp::Or, left, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> CodeResult<Expr> {
+        let mut left = self.parse_not()?;
+        while self.eat_keyword("AND") {
+            let right = self.parse_not()?;
+            left = binary(BinaryOp::And, left, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_not(&mut self) -> CodeResult<Expr> {
+        if self.eat_keyword("NOT") {
+            let start = self.prev_span();
+            let expr = self.parse_not()?;
+            return Ok(Spanned {
+                span: Span::merge(start, expr.span),
+                inner: ExprKind::Unary {
+                    op: UnaryOp::Not,
+                    expr: Box::new(expr),
+                },
+            });
+        }
+        self.parse_comparison()
+    }
+
+    fn parse_comparison(&mut self) -> CodeResult<Expr> {
+        let left = self.parse_concat()?;
+
+        let op = match self.peek().map(|t| t.inner) {
+            Some(Token::Eq) => Some(BinaryOp::Eq),
+            Some(Token::Ne) => Some(BinaryOp::Ne),
+            Some(Token::Lt) => Some(BinaryOp::Lt),
+            Some(Token::Gt) => Some(BinaryOp::Gt),
+            Some(Token::Le) => Some(BinaryOp::Le),
+            Some(Token::Ge) => Some(BinaryOp::Ge),
+            _ => None,
+        };
+        if let Some(op) = op {
+            self.cursor += 1;
+            let right = self.parse_concat()?;
+            return Ok(binary(op, left, right));
+        }
+
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(Spanned {
+                span: Span::merge(left.span, self.prev_span()),
+                inner: ExprKind::IsNull {
+                    expr: Box::new(left),
+                    negated,
+                },
+            });
+        }
+
+        // `NOT` may only be followed by `IN`, `BETWEEN`, or `LIKE` here
+        let cursor = self.cursor;
+        let negated = self.eat_keyword("NOT");
+        if self.eat_keyword("IN") {
+            self.expect(Token::LParen)?;
+            let mut list = vec![self.parse_expr()?];
+            while self.eat(Token::Comma) {
+                list.push(self.parse_expr()?);
+            }
+            let end = self.expect(Token::RParen)?;
+            return Ok(Spanned {
+                span: Span::merge(left.span, end),
+                inner: ExprKind::InList {
+                    expr: Box::new(left),
+                    list,
+                    negated,
+                },
+            });
+        }
+        if self.eat_keyword("BETWEEN") {
+            let low = self.parse_concat()?;
+            self.expect_keyword("AND")?;
+            let high = self.parse_concat()?;
+            return Ok(Spanned {
+                span: Span::merge(left.span, high.span),
+                inner: ExprKind::Between {
+                    expr: Box::new(left),
+                    low: Box::new(low),
+                    high: Box::new(high),
+                    negated,
+                },
+            });
+        }
+        if self.eat_keyword("LIKE") {
+            let pattern = self.parse_concat()?;
+            return Ok(Spanned {
+                span: Span::merge(left.span, pattern.span),
+                inner: ExprKind::Like {
+                    expr: Box::new(left),
+                    pattern: Box::new(pattern),
+                    negated,
+                },
+            });
+        }
+        if negated {
+            let next = self.peek();
+            return Err(self.expected("`IN`, `BETWEEN`, or `LIKE`".to_string(), next));
+        }
+        self.cursor = cursor;
+
+        Ok(left)
+    }
+
+    fn parse_concat(&mut self) -> CodeResult<Expr> {
+        let mut left = self.parse_additive()?;
+        while self.eat(Token::Concat) {
+            let right = self.parse_additive()?;
+            left = binary(BinaryOp::Concat, left, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_additive(&mut self) -> CodeResult<Expr> {
+        let mut left = self.parse_multiplicative()?;
+        loop {
+            let op = match self.peek().map(|t| t.inner) {
+                Some(Token::Plus) => BinaryOp::Add,
+                Some(Token::Minus) => BinaryOp::Sub,
+                _ => return Ok(left),
+            };
+            self.cursor += 1;
+            let right = self.parse_multiplicative()?;
+            left = binary(op, left, right);
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> CodeResult<Expr> {
+        let mut left = self.parse_unary()?;
+        loop {
+            let op = match self.peek().map(|t| t.inner) {
+                Some(Token::Star) => BinaryOp::Mul,
+                Some(Token::Slash) => BinaryOp::Div,
+                Some(Token::Percent) => BinaryOp::Mod,
+                _ => return Ok(left),
+            };
+            self.cursor += 1;
+            let right = self.parse_unary()?;
+            left = binary(op, left, right);
+        }
+    }
+
+    fn parse_unary(&mut self) -> CodeResult<Expr> {
+        if self.eat(Token::Minus) {
+            let start = self.prev_span();
+            let expr = self.parse_unary()?;
+            return Ok(Spanned {
+                span: Span::merge(start, expr.span),
+                inner: ExprKind::Unary {
+                    op: UnaryOp::Neg,
+                    expr: Box::new(expr),
+                },
+            });
+        }
+        if self.eat(Token::Plus) {
+            return self.parse_unary();
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> CodeResult<Expr> {
+        let token = match self.next() {
+            Some(t) => t,
+            None => return Err(self.expected("expression".to_string(), None)),
+        };
+        let span = token.span;
+        let literal = |value: CellValue| Spanned {
+            span,
+            inner: ExprKind::Literal(value),
+        };
+
+        match token.inner {
+            Token::NumericLiteral => {
+                let number = self
+                    .token_str(token)
+                    .parse()
+                    .map_err(|_| ErrorMsg::BadNumber.with_span(span))?;
+                Ok(literal(CellValue::Number(number)))
+            }
+            Token::StringLiteral => {
+                let s = self.token_str(token);
+                Ok(literal(CellValue::Text(unquote(s, '\''))))
+            }
+            Token::LParen => {
+                let expr = self.parse_expr()?;
+                let end = self.expect(Token::RParen)?;
+                Ok(Spanned {
+                    span: Span::merge(span, end),
+                    inner: expr.inner,
+                })
+            }
+            Token::QuotedIdent => self.parse_column(token),
+            Token::Ident => {
+                let word = self.token_str(token).to_ascii_uppercase();
+                match word.as_str() {
+                    "NULL" => Ok(literal(CellValue::Blank)),
+                    "TRUE" => Ok(literal(CellValue::Logical(true))),
+                    "FALSE" => Ok(literal(CellValue::Logical(false))),
+                    "CASE" => self.parse_case(span),
+                    _ if self.eat(Token::LParen) => self.parse_function(token),
+                    _ if self.is_reserved(token) => Err(self.unexpected(token)),
+                    _ => self.parse_column(token),
+                }
+            }
+            _ => Err(self.expected("expression".to_string(), Some(token))),
+        }
+    }
+
+    /// Parses a column name, which may be qualified by a table alias.
+    fn parse_column(&mut self, token: Spanned<Token>) -> CodeResult<Expr> {
+        let first = self.ident_name(token);
+        if !self.eat(Token::Period) {
+            return Ok(Spanned {
+                span: token.span,
+                inner: ExprKind::Column {
+                    table: None,
+                    name: first,
+                },
+            });
+        }
+        match self.next() {
+            Some(t) if matches!(t.inner, Token::Ident | Token::QuotedIdent) => Ok(Spanned {
+                span: Span::merge(token.span, t.span),
+                inner: ExprKind::Column {
+                    table: Some(first),
+                    name: self.ident_name(t),
+                },
+            }),
+            other => Err(self.expected("column name".to_string(), other)),
+        }
+    }
+
+    /// Parses the arguments of a function call, after the opening paren.
+    fn parse_function(&mut self, name_token: Spanned<Token>) -> CodeResult<Expr> {
+        let name = Spanned {
+            span: name_token.span,
+            inner: self.token_str(name_token).to_ascii_uppercase(),
+        };
+        let args = if self.eat(Token::Star) {
+            FunctionArgs::Star
+        } else {
+            let distinct = self.eat_keyword("DISTINCT");
+            let mut args = vec![];
+            if self.peek().is_some_and(|t| t.inner != Token::RParen) {
+                args.push(self.parse_expr()?);
+                while self.eat(Token::Comma) {
+                    args.push(self.parse_expr()?);
+                }
+            }
+            FunctionArgs::List { distinct, args }
+        };
+        let end = self.expect(Token::RParen)?;
+        Ok(Spanned {
+            span: Span::merge(name.span, end),
+            inner: ExprKind::Function { name, args },
+        })
+    }
+
+    /// Parses a `CASE` expression, after the `CASE` keyword.
+    fn parse_case(&mut self, start: Span) -> CodeResult<Expr> {
+        let operand = if self.peek_keyword("WHEN") {
+            None
+        } else {
+            Some(Box::new(self.parse_expr()?))
+        };
+        let mut branches = vec![];
+        while self.eat_keyword("WHEN") {
+            let condition = self.parse_expr()?;
+            self.expect_keyword("THEN")?;
+            let result = self.parse_expr()?;
+            branches.push((condition, result));
+        }
+        if branches.is_empty() {
+            let next = self.peek();
+            return Err(self.expected("`WHEN`".to_string(), next));
+        }
+        let else_result = if self.eat_keyword("ELSE") {
+            Some(Box::new(self.parse_expr()?))
+        } else {
+            None
+        };
+        let end = self.expect_keyword("END")?;
+        Ok(Spanned {
+            span: Span::merge(start, end),
+            inner: ExprKind::Case {
+                operand,
+                branches,
+                else_result,
+            },
+        })
+    }
+
+    fn is_reserved(&self, token: Spanned<Token>) -> bool {
+        let s = self.token_str(token);
+        RESERVED_KEYWORDS
+            .iter()
+            .any(|keyword| keyword.eq_ignore_ascii_case(s))
+    }
+
+    /// Returns the name of an identifier, removing quotes if necessary.
+    fn ident_name(&self, token: Spanned<Token>) -> String {
+        let s = self.token_str(token);
+        match token.inner {
+            Token::QuotedIdent if s.starts_with('`') => s[1..s.len() - 1].to_string(),
+            Token::QuotedIdent => unquote(s, '"'),
+            _ => s.to_string(),
+        }
+    }
+}
+
+fn binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+    Spanned {
+        span: Span::merge(left.span, right.span),
+        inner: ExprKind::Binary {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        },
+    }
+}
+
+/// Removes the surrounding quotes from a string, unescaping doubled quotes.
+fn unquote(s: &str, quote: char) -> String {
+    let doubled = format!("{quote}{quote}");
+    s[1..s.len() - 1].replace(&doubled, &quote.to_string())
+}
+
+/// Parses a cell range such as `Sheet1!A1:D500` into absolute references.
+fn parse_range(s: &str) -> Option<(CellRef, CellRef)> {
+    let (sheet, cells) = match s.rsplit_once('!') {
+        Some((sheet, cells)) => {
+            let sheet = sheet.trim();
+            let sheet = match sheet.chars().next() {
+                Some(quote @ ('\'' | '"')) => unquote(sheet, quote),
+                _ => sheet.to_string(),
+            };
+            (Some(sheet), cells)
+        }
+        None => (None, s),
+    };
+    let (start, end) = cells.split_once(':').unwrap_or((cells, cells));
+    let origin = Pos { x: 0, y: 0 };
+    let start = formulas::CellRef::parse_a1(start.trim(), origin)?;
+    let end = formulas::CellRef::parse_a1(end.trim(), origin)?;
+    Some((
+        CellRef::absolute(sheet.clone(), start.resolve_from(origin)),
+        CellRef::absolute(sheet, end.resolve_from(origin)),
+    ))
+}
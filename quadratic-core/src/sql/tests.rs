@@ -0,0 +1,229 @@
+use super::*;
+use crate::formulas::tests::grid_with_rows;
+use crate::formulas::Ctx;
+use crate::grid::Grid;
+use crate::{CodeResult, Error, ErrorMsg, SheetPos, Value};
+
+/// Grid with a table of sales in `A1:C7` of the first sheet.
+fn sales_grid() -> Grid {
+    grid_with_rows(&[
+        &["region", "product", "sales"],
+        &["West", "Apples", "10"],
+        &["East", "Apples", "20"],
+        &["West", "Pears", "5"],
+        &["North", "Pears", ""],
+        &["East", "Plums", "7.5"],
+        &["West", "Plums", "1"],
+    ])
+}
+
+fn try_query(grid: &Grid, s: &str) -> CodeResult<Vec<Vec<String>>> {
+    let pos = SheetPos {
+        x: 10,
+        y: 0,
+        sheet_id: grid.sheets()[0].id,
+    };
+    let mut ctx = Ctx::new(grid, pos);
+    match parse_sql(s)?.eval(&mut ctx)? {
+        Value::Array(array) => Ok(array
+            .rows()
+            .map(|row| row.iter().map(|value| value.to_string()).collect())
+            .collect()),
+        Value::Single(value) => Ok(vec![vec![value.to_string()]]),
+    }
+}
+#[track_caller]
+fn query(grid: &Grid, s: &str) -> Vec<Vec<String>> {
+    try_query(grid, s).expect("error evaluating query")
+}
+#[track_caller]
+fn query_err(grid: &Grid, s: &str) -> Error {
+    try_query(grid, s).expect_err("expected error")
+}
+
+macro_rules! table {
+    ($([$($value:expr),* $(,)?]),* $(,)?) => {
+        vec![$(vec![$($value.to_string()),*]),*]
+    };
+}
+
+#[test]
+fn test_sql_select() {
+    let g = sales_grid();
+
+    assert_eq!(
+        table![["region", "sales"], ["West", 10], ["West", 5], ["West", 1]],
+        query(&g, "SELECT region, sales FROM A1:C7 WHERE region = 'West'"),
+    );
+    assert_eq!(
+        table![["product", "region"], ["Plums", "East"]],
+        query(
+            &g,
+            "select product, region from 'Sheet 1'!A1:C7 where sales > 7 and sales < 10"
+        ),
+    );
+    assert_eq!(
+        table![["region", "product", "sales"], ["North", "Pears", ""]],
+        query(&g, "SELECT * FROM A1:C7 WHERE sales IS NULL;"),
+    );
+    assert_eq!(
+        table![["double", "sales * 2"], [20, 20], [40, 40]],
+        query(
+            &g,
+            "SELECT sales * 2 AS double, sales * 2 FROM A1:C7 WHERE product = 'Apples'"
+        ),
+    );
+    assert_eq!(
+        table![["1 + 2", "x"], [3, "a b"]],
+        query(&g, "SELECT 1 + 2, 'a' || ' ' || 'b' x"),
+    );
+}
+
+#[test]
+fn test_sql_group_by() {
+    let g = sales_grid();
+
+    assert_eq!(
+        table![
+            ["region", "SUM(sales)"],
+            ["West", 16],
+            ["East", 27.5],
+            ["North", ""]
+        ],
+        query(
+            &g,
+            "SELECT region, SUM(sales) FROM \"Sheet 1\"!A1:D500 GROUP BY region"
+        ),
+    );
+    assert_eq!(
+        table![["region", "n", "total"], ["East", 2, 27.5], ["West", 3, 16]],
+        query(
+            &g,
+            "SELECT region, COUNT(*) AS n, SUM(sales) total
+             FROM A1:C7
+             GROUP BY region
+             HAVING COUNT(sales) > 1
+             ORDER BY total DESC",
+        ),
+    );
+    assert_eq!(
+        table![
+            ["COUNT(*)", "COUNT(sales)", "COUNT(DISTINCT region)", "MIN(sales)", "MAX(product)", "AVG(sales)"],
+            [6, 5, 3, 1, "Plums", 8.7],
+        ],
+        query(
+            &g,
+            "SELECT COUNT(*), COUNT(sales), COUNT(DISTINCT region), MIN(sales), MAX(product), AVG(sales) FROM A1:C7",
+        ),
+    );
+}
+
+#[test]
+fn test_sql_order_limit_distinct() {
+    let g = sales_grid();
+
+    assert_eq!(
+        table![["product"], ["Apples"], ["Pears"], ["Plums"]],
+        query(&g, "SELECT DISTINCT product FROM A1:C7 ORDER BY 1"),
+    );
+    assert_eq!(
+        table![["region", "sales"], ["West", 10], ["East", 7.5]],
+        query(
+            &g,
+            "SELECT region, sales FROM A1:C7 ORDER BY sales DESC LIMIT 2 OFFSET 1"
+        ),
+    );
+    // NULLs sort first
+    assert_eq!(
+        table![["product"], ["Pears"], ["Plums"]],
+        query(
+            &g,
+            "SELECT product FROM A1:C7 ORDER BY sales, product LIMIT 2"
+        ),
+    );
+}
+
+#[test]
+fn test_sql_expressions() {
+    let g = sales_grid();
+
+    assert_eq!(
+        table![["product"], ["Pears"], ["Plums"], ["Plums"]],
+        query(
+            &g,
+            "SELECT product FROM A1:C7 WHERE product LIKE 'p%' AND sales IS NOT NULL"
+        ),
+    );
+    assert_eq!(
+        table![["region"], ["West"], ["West"], ["West"]],
+        query(
+            &g,
+            "SELECT region FROM A1:C7 WHERE region NOT IN ('East', 'North')"
+        ),
+    );
+    assert_eq!(
+        table![["sales"], [10], [5], [7.5]],
+        query(&g, "SELECT sales FROM A1:C7 WHERE sales BETWEEN 5 AND 10"),
+    );
+    assert_eq!(
+        table![["size"], ["big"], ["big"], ["small"], ["none"], ["small"], ["small"]],
+        query(
+            &g,
+            "SELECT CASE WHEN sales >= 10 THEN 'big' WHEN sales < 10 THEN 'small' ELSE 'none' END AS size FROM A1:C7",
+        ),
+    );
+    assert_eq!(
+        table![
+            ["UPPER(t.region)", "LENGTH(product)", "ROUND(sales / 3, 2)"],
+            ["WEST", 6, 3.33]
+        ],
+        query(
+            &g,
+            "SELECT UPPER(t.region), LENGTH(product), ROUND(sales / 3, 2) FROM A1:C7 AS t LIMIT 1",
+        ),
+    );
+    // Digits are rounded to an integer and bounded like the formula `ROUND`.
+    assert_eq!(
+        table![["a", "b", "c"], [3.33, 10, 0]],
+        query(
+            &g,
+            "SELECT ROUND(sales / 3, 1.6) AS a, ROUND(sales, 1e15) AS b, ROUND(sales, -1e15) AS c FROM A1:C7 LIMIT 1",
+        ),
+    );
+}
+
+#[test]
+fn test_sql_errors() {
+    let g = sales_grid();
+
+    let err = query_err(&g, "SELECT price FROM A1:C7");
+    assert_eq!(ErrorMsg::UnknownColumn("price".into()), err.msg);
+    assert_eq!(Some(crate::Span { start: 7, end: 12 }), err.span);
+
+    assert_eq!(
+        ErrorMsg::Unexpected("aggregate function in WHERE clause".into()),
+        query_err(&g, "SELECT * FROM A1:C7 WHERE SUM(sales) > 1").msg,
+    );
+    assert_eq!(
+        ErrorMsg::BadFunctionName,
+        query_err(&g, "SELECT FOO(sales) FROM A1:C7").msg,
+    );
+    assert!(matches!(
+        query_err(&g, "SELECT region FROM").msg,
+        ErrorMsg::Expected { .. },
+    ));
+    assert!(matches!(
+        query_err(&g, "SELECT 'abc").msg,
+        ErrorMsg::Expected { .. },
+    ));
+    assert_eq!(
+        ErrorMsg::BadCellReference,
+        query_err(&g, "SELECT * FROM Missing!A1:B2").msg,
+    );
+
+    // the query cell is inside the range it reads
+    assert_eq!(
+        ErrorMsg::CircularReference,
+        query_err(&g, "SELECT * FROM A0:K2").msg,
+    );
+}
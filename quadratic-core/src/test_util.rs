@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::{
     controller::GridController,
-    grid::{Bold, FillColor, SheetId},
+    grid::{get_cell_borders_in_rect, Bold, FillColor, SheetId},
     CellValue, Pos, Rect,
 };
 
@@ -132,6 +132,23 @@ pub fn assert_cell_format_fill_color(
     );
 }
 
+/// Returns the code of the code cell at a position
+pub fn code_string(grid_controller: &GridController, sheet_id: SheetId, pos: Pos) -> String {
+    grid_controller
+        .sheet(sheet_id)
+        .get_code_cell(pos)
+        .unwrap()
+        .code_string
+        .clone()
+}
+
+/// Returns whether the cell at a position has any borders
+pub fn has_border(grid_controller: &GridController, sheet_id: SheetId, pos: Pos) -> bool {
+    get_cell_borders_in_rect(grid_controller.sheet(sheet_id), Rect::single_pos(pos))
+        .iter()
+        .any(|(_, _, borders)| borders.is_some())
+}
+
 /// Util to print a simple grid to assist in TDD
 pub fn print_table(grid_controller: &GridController, sheet_id: SheetId, range: Rect) {
     let sheet = grid_controller.grid().sheet_from_id(sheet_id);
@@ -170,6 +170,59 @@ impl Array {
         Self::new_row_major(self.size, self.rows().rev().flatten().cloned().collect()).unwrap()
     }
 
+    /// Returns a new array containing the rows (for `Axis::Y`) or columns (for
+    /// `Axis::X`) at `indices`, in that order. Returns an error if `indices` is
+    /// empty or any index is out of bounds.
+    pub fn select(&self, axis: Axis, indices: &[u32]) -> Result<Array, ErrorMsg> {
+        if indices.iter().any(|&i| i >= self.size[axis].get()) {
+            return Err(ErrorMsg::IndexOutOfBounds);
+        }
+        let mut new_size = self.size;
+        new_size[axis] = NonZeroU32::new(indices.len() as u32).ok_or(ErrorMsg::EmptyArray)?;
+        let values = new_size
+            .iter()
+            .map(|(x, y)| match axis {
+                Axis::X => self.get(indices[x as usize], y).cloned(),
+                Axis::Y => self.get(x, indices[y as usize]).cloned(),
+            })
+            .try_collect()?;
+        Self::new_row_major(new_size, values).map_err(|e| e.msg)
+    }
+    /// Returns an iterator over the values in the row (for `Axis::Y`) or
+    /// column (for `Axis::X`) at `index`.
+    pub fn line(&self, axis: Axis, index: u32) -> impl '_ + Iterator<Item = &CellValue> {
+        let len = self.size[axis.other_axis()].get();
+        (0..len).filter_map(move |i| match axis {
+            Axis::X => self.get(index, i).ok(),
+            Axis::Y => self.get(i, index).ok(),
+        })
+    }
+    /// Stacks arrays vertically (for `Axis::Y`) or horizontally (for
+    /// `Axis::X`). Arrays that are narrower (or shorter) than the widest (or
+    /// tallest) array are padded with blanks.
+    pub fn concat(axis: Axis, arrays: &[Array]) -> Result<Array, ErrorMsg> {
+        if axis == Axis::X {
+            let transposed = arrays.iter().map(|a| a.transpose()).collect_vec();
+            return Ok(Self::concat(Axis::Y, &transposed)?.transpose());
+        }
+        let width = arrays.iter().map(|a| a.width()).max().unwrap_or(0);
+        let height = arrays.iter().map(|a| a.height() as u64).sum::<u64>();
+        if width as u64 * height > crate::limits::CELL_RANGE_LIMIT as u64 {
+            return Err(ErrorMsg::ArrayTooBig);
+        }
+        let new_size = ArraySize::new_or_err(width, height as u32)?;
+        let values = arrays
+            .iter()
+            .flat_map(|a| a.rows())
+            .flat_map(|row| {
+                row.iter()
+                    .cloned()
+                    .pad_using(width as usize, |_| CellValue::Blank)
+            })
+            .collect();
+        Self::new_row_major(new_size, values).map_err(|e| e.msg)
+    }
+
     /// Returns the width of an array.
     pub fn width(&self) -> u32 {
         self.size.w.get()
@@ -82,8 +82,12 @@ impl CellValue {
             CellValue::Number(n) => n.to_string(),
             CellValue::Logical(true) => "TRUE".to_string(),
             CellValue::Logical(false) => "FALSE".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => format!("DATEVALUE({:?})", i.to_string()),
+            // There is no syntax for durations of calendar years or months.
+            CellValue::Duration(d) if d.years == 0 && d.months == 0 => {
+                format!("TIME(0, 0, {})", d.seconds)
+            }
+            CellValue::Duration(d) => format!("{:?}", d.to_string()),
             CellValue::Error(_) => "[error]".to_string(),
         }
     }
@@ -188,8 +192,8 @@ impl CellValue {
             }
             CellValue::Logical(true) => "true".to_string(),
             CellValue::Logical(false) => "false".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => i.to_string(),
+            CellValue::Duration(d) => d.to_string(),
             CellValue::Error(_) => "[error]".to_string(),
         }
     }
@@ -201,8 +205,8 @@ impl CellValue {
             CellValue::Number(n) => n.to_string(),
             CellValue::Logical(true) => "true".to_string(),
             CellValue::Logical(false) => "false".to_string(),
-            CellValue::Instant(_) => todo!("repr of Instant"),
-            CellValue::Duration(_) => todo!("repr of Duration"),
+            CellValue::Instant(i) => i.to_string(),
+            CellValue::Duration(d) => d.to_string(),
             CellValue::Error(_) => "[error]".to_string(),
         }
     }
@@ -444,7 +448,7 @@ mod test {
 
     use crate::{
         grid::{NumericFormat, NumericFormatKind},
-        CellValue,
+        CellValue, Duration, Instant,
     };
 
     #[test]
@@ -651,4 +655,29 @@ mod test {
             "-123,123,123.123456"
         );
     }
+
+    #[test]
+    fn test_cell_value_time_repr() {
+        let instant = Instant::parse("2024-01-15 13:30:00").unwrap();
+        assert_eq!(
+            CellValue::Instant(instant).repr(),
+            "DATEVALUE(\"2024-01-15 13:30:00\")"
+        );
+        assert_eq!(
+            CellValue::Instant(instant).to_display(None, None, None),
+            "2024-01-15 13:30:00"
+        );
+        let duration = Duration::from_seconds(5400.0);
+        assert_eq!(CellValue::Duration(duration).repr(), "TIME(0, 0, 5400)");
+        assert_eq!(
+            CellValue::Duration(duration).to_edit(),
+            "1 hour, 30 minutes"
+        );
+        let duration = Duration {
+            years: 1,
+            months: 2,
+            seconds: 0.0,
+        };
+        assert_eq!(CellValue::Duration(duration).repr(), "\"1 year, 2 months\"");
+    }
 }
@@ -1,6 +1,6 @@
 use bigdecimal::{BigDecimal, ToPrimitive, Zero};
 
-use super::{CellValue, IsBlank, Value};
+use super::{CellValue, Duration, Instant, IsBlank, Value};
 use crate::{CodeResult, CodeResultExt, ErrorMsg, Span, Spanned, Unspan};
 
 const CURRENCY_PREFIXES: &[char] = &['$', '¥', '£', '€'];
@@ -62,6 +62,16 @@ impl From<bool> for CellValue {
         CellValue::Logical(value)
     }
 }
+impl From<Instant> for CellValue {
+    fn from(value: Instant) -> Self {
+        CellValue::Instant(value)
+    }
+}
+impl From<Duration> for CellValue {
+    fn from(value: Duration) -> Self {
+        CellValue::Duration(value)
+    }
+}
 impl<T> From<CodeResult<T>> for CellValue
 where
     CellValue: From<T>,
@@ -115,8 +125,7 @@ impl<'a> TryFrom<&'a CellValue> for f64 {
                     got: Some(value.type_name().into()),
                 })
             }
-            // todo: this may be wrong
-            CellValue::Number(n) => Ok(n.to_f64().unwrap()),
+            CellValue::Number(n) => decimal_to_f64(n).ok_or(ErrorMsg::NotANumber),
             CellValue::Logical(true) => Ok(1.0),
             CellValue::Logical(false) => Ok(0.0),
             CellValue::Instant(_) | CellValue::Duration(_) => Err(ErrorMsg::Expected {
@@ -127,6 +136,37 @@ impl<'a> TryFrom<&'a CellValue> for f64 {
         }
     }
 }
+/// Exact powers of ten that can be represented by an `f64`.
+const POWERS_OF_TEN: [f64; 23] = [
+    1e0, 1e1, 1e2, 1e3, 1e4, 1e5, 1e6, 1e7, 1e8, 1e9, 1e10, 1e11, 1e12, 1e13, 1e14, 1e15, 1e16,
+    1e17, 1e18, 1e19, 1e20, 1e21, 1e22,
+];
+
+/// Converts a decimal to the nearest `f64`.
+///
+/// `BigDecimal::to_f64()` multiplies the digits by an inexact power of ten,
+/// so `2.675` does not become the nearest `f64`. Dividing the digits by an
+/// exact power of ten is correctly rounded, so do that when the digits and
+/// power of ten are small enough, and otherwise parse the decimal
+/// representation.
+fn decimal_to_f64(n: &BigDecimal) -> Option<f64> {
+    let approx = n.to_f64()?;
+    if n.fractional_digit_count() <= 0 && approx.abs() < (1_u64 << 53) as f64 {
+        // small integers are exact
+        return Some(approx);
+    }
+    if let Some(&power) = usize::try_from(n.fractional_digit_count())
+        .ok()
+        .and_then(|scale| POWERS_OF_TEN.get(scale))
+    {
+        let digits = (approx * power).round();
+        if digits.abs() < (1_u64 << 50) as f64 {
+            return Some(digits / power);
+        }
+    }
+    n.to_string().parse().ok()
+}
+
 impl<'a> TryFrom<&'a CellValue> for i64 {
     type Error = ErrorMsg;
 
@@ -160,6 +200,26 @@ impl<'a> TryFrom<&'a CellValue> for bool {
     }
 }
 
+impl<'a> TryFrom<&'a CellValue> for Instant {
+    type Error = ErrorMsg;
+
+    fn try_from(value: &'a CellValue) -> Result<Self, Self::Error> {
+        // TODO: remove string conversions once we have a stricter type system
+        match value {
+            CellValue::Instant(i) => Ok(*i),
+            CellValue::Text(s) => Instant::parse(s).ok_or_else(|| ErrorMsg::Expected {
+                expected: "time instant".into(),
+                got: Some(value.type_name().into()),
+            }),
+            CellValue::Error(e) => Err(e.msg.clone()),
+            _ => Err(ErrorMsg::Expected {
+                expected: "time instant".into(),
+                got: Some(value.type_name().into()),
+            }),
+        }
+    }
+}
+
 impl TryFrom<CellValue> for String {
     type Error = ErrorMsg;
 
@@ -185,6 +245,7 @@ macro_rules! impl_try_from_cell_value_for {
 impl_try_from_cell_value_for!(f64);
 impl_try_from_cell_value_for!(i64);
 impl_try_from_cell_value_for!(bool);
+impl_try_from_cell_value_for!(Instant);
 
 impl<'a> TryFrom<&'a Value> for &'a CellValue {
     type Error = ErrorMsg;
@@ -223,6 +284,7 @@ impl_try_from_value_for!(String);
 impl_try_from_value_for!(f64);
 impl_try_from_value_for!(i64);
 impl_try_from_value_for!(bool);
+impl_try_from_value_for!(Instant);
 
 /// Coercion from `Value` or `CellValue` into a particular Rust type.
 pub trait CoerceInto: Sized + Unspan
@@ -304,4 +366,21 @@ mod test {
 
         assert_eq!(CellValue::from("10%"), CellValue::Text("10%".into()));
     }
+
+    #[test]
+    fn test_convert_from_number_to_f64() {
+        for s in [
+            "2.675",
+            "0.1",
+            "-1.005",
+            "123456.789",
+            "1e5",
+            "1e-30",
+            "1.7e308",
+            "0",
+        ] {
+            let n = CellValue::Number(s.parse().unwrap());
+            assert_eq!(f64::try_from(&n), Ok(s.parse::<f64>().unwrap()));
+        }
+    }
 }
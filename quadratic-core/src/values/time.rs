@@ -1,7 +1,28 @@
 use std::fmt;
 
+use chrono::{Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
 use serde::{Deserialize, Serialize};
 
+/// Formats accepted by [`Instant::parse()`] that include a time of day.
+const DATE_TIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M",
+    "%m/%d/%Y %H:%M:%S%.f",
+    "%m/%d/%Y %H:%M",
+];
+/// Formats accepted by [`Instant::parse()`] that only include a date.
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%m/%d/%Y",
+    "%B %d, %Y",
+    "%b %d, %Y",
+    "%d %B %Y",
+    "%d %b %Y",
+];
+
 #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 #[derive(Serialize, Deserialize, Debug, Copy, Clone)]
 #[cfg_attr(feature = "js", derive(ts_rs::TS))]
@@ -12,7 +33,85 @@ pub struct Instant {
 
 impl fmt::Display for Instant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{s} seconds", s = self.seconds)
+        match self.to_naive() {
+            Some(dt) if dt.time() == NaiveTime::MIN => write!(f, "{}", dt.format("%Y-%m-%d")),
+            Some(dt) => write!(f, "{}", dt.format("%Y-%m-%d %H:%M:%S%.f")),
+            None => write!(f, "{s} seconds", s = self.seconds),
+        }
+    }
+}
+
+impl Instant {
+    /// Constructs an instant from a date and time in UTC.
+    pub fn from_naive(date_time: NaiveDateTime) -> Self {
+        let seconds = date_time.timestamp() as f64
+            + date_time.timestamp_subsec_nanos() as f64 / 1_000_000_000.0;
+        Instant { seconds }
+    }
+    /// Constructs an instant at midnight UTC on a date.
+    pub fn from_date(date: NaiveDate) -> Self {
+        Self::from_naive(date.and_time(NaiveTime::MIN))
+    }
+    /// Returns the date and time in UTC, rounded to the nearest millisecond,
+    /// or `None` if it is out of range.
+    pub fn to_naive(self) -> Option<NaiveDateTime> {
+        let millis = (self.seconds * 1000.0).round();
+        if !millis.is_finite() || millis.abs() >= i64::MAX as f64 {
+            return None;
+        }
+        NaiveDateTime::from_timestamp_millis(millis as i64)
+    }
+    /// Returns the date in UTC, or `None` if it is out of range.
+    pub fn to_date(self) -> Option<NaiveDate> {
+        self.to_naive().map(|dt| dt.date())
+    }
+
+    /// Parses a date, optionally with a time of day, such as `2024-01-15`,
+    /// `2024-01-15 13:30:00`, or `1/15/2024`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        DATE_TIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+            .or_else(|| {
+                DATE_FORMATS.iter().find_map(|format| {
+                    let date = NaiveDate::parse_from_str(s, format).ok()?;
+                    Some(date.and_time(NaiveTime::MIN))
+                })
+            })
+            .map(Self::from_naive)
+    }
+
+    /// Returns the number of seconds since midnight.
+    pub fn seconds_since_midnight(self) -> Option<f64> {
+        let time = self.to_naive()?.time();
+        Some(time.num_seconds_from_midnight() as f64 + time.nanosecond() as f64 / 1_000_000_000.0)
+    }
+
+    /// Adds a duration, adding the calendar years and months first. The day of
+    /// the month is clamped to the end of the month if necessary. Returns
+    /// `None` if the result is out of range.
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        let months = duration.years as i64 * 12 + duration.months as i64;
+        let mut date_time = self.to_naive()?;
+        if months != 0 {
+            let abs_months = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+            date_time = if months > 0 {
+                date_time.checked_add_months(abs_months)?
+            } else {
+                date_time.checked_sub_months(abs_months)?
+            };
+        }
+        let seconds = Self::from_naive(date_time).seconds + duration.seconds;
+        Some(Instant { seconds }).filter(|ret| ret.to_naive().is_some())
+    }
+    /// Subtracts a duration. See [`Instant::checked_add()`].
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        self.checked_add(-duration)
+    }
+    /// Returns the duration from `earlier` to `self`, in seconds.
+    pub fn duration_since(self, earlier: Instant) -> Duration {
+        Duration::from_seconds(self.seconds - earlier.seconds)
     }
 }
 
@@ -45,13 +144,77 @@ pub struct Duration {
 
 impl fmt::Display for Duration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{y} years, {m} months, {s} seconds",
-            y = self.years,
-            m = self.months,
-            s = self.seconds,
-        )
+        let sign = if self.seconds < 0.0 { -1.0 } else { 1.0 };
+        let mut seconds = (self.seconds.abs() * 1000.0).round() / 1000.0;
+        let mut split_off = |unit_seconds: f64| {
+            let count = (seconds / unit_seconds).floor();
+            seconds -= count * unit_seconds;
+            count * sign
+        };
+        let days = split_off(86400.0);
+        let hours = split_off(3600.0);
+        let minutes = split_off(60.0);
+        let seconds = (seconds * 1000.0).round() / 1000.0 * sign;
+
+        let parts = [
+            (self.years as f64, "year"),
+            (self.months as f64, "month"),
+            (days, "day"),
+            (hours, "hour"),
+            (minutes, "minute"),
+            (seconds, "second"),
+        ]
+        .into_iter()
+        .filter(|&(count, _)| count != 0.0)
+        .map(|(count, unit)| match count.abs() == 1.0 {
+            true => format!("{count} {unit}"),
+            false => format!("{count} {unit}s"),
+        })
+        .collect::<Vec<_>>();
+
+        if parts.is_empty() {
+            write!(f, "0 seconds")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+impl Duration {
+    /// Duration of zero length.
+    pub const ZERO: Self = Duration {
+        years: 0,
+        months: 0,
+        seconds: 0.0,
+    };
+
+    /// Constructs a duration from a number of seconds.
+    pub fn from_seconds(seconds: f64) -> Self {
+        Duration {
+            seconds,
+            ..Self::ZERO
+        }
+    }
+    /// Adds two durations component-wise. Returns `None` if the number of
+    /// years or months overflows.
+    pub fn checked_add(self, other: Duration) -> Option<Self> {
+        Some(Duration {
+            years: self.years.checked_add(other.years)?,
+            months: self.months.checked_add(other.months)?,
+            seconds: self.seconds + other.seconds,
+        })
+    }
+}
+
+impl std::ops::Neg for Duration {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Duration {
+            years: self.years.wrapping_neg(),
+            months: self.months.wrapping_neg(),
+            seconds: -self.seconds,
+        }
     }
 }
 
@@ -124,6 +124,23 @@ impl GridController {
         )?)
     }
 
+    /// Moves a region of cells so that its top-left cell is at `dest`.
+    ///
+    /// Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "moveCells")]
+    pub fn js_move_cells(
+        &mut self,
+        sheet_id: String,
+        source: &Rect,
+        dest: &Pos,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(
+            &self.move_cells(sheet_id, *source, *dest, cursor),
+        )?)
+    }
+
     /// Gets the code_string of a code cell
     #[wasm_bindgen(js_name = "getCodeCell")]
     pub fn js_get_code_string(&self, sheet_id: String, pos: &Pos) -> Option<CodeCell> {
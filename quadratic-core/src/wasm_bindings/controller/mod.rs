@@ -13,6 +13,7 @@ pub mod export;
 pub mod formatting;
 pub mod import;
 pub mod render;
+pub mod rows_columns;
 pub mod sheet_offsets;
 pub mod sheets;
 pub mod summarize;
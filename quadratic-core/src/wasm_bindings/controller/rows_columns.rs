@@ -0,0 +1,64 @@
+use super::*;
+
+#[wasm_bindgen]
+impl GridController {
+    /// Inserts empty columns. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "insertColumns")]
+    pub fn js_insert_columns(
+        &mut self,
+        sheet_id: String,
+        x: i32,
+        count: u32,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(
+            &self.insert_columns(sheet_id, x as i64, count, cursor),
+        )?)
+    }
+
+    /// Deletes columns. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "deleteColumns")]
+    pub fn js_delete_columns(
+        &mut self,
+        sheet_id: String,
+        x: i32,
+        count: u32,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(
+            &self.delete_columns(sheet_id, x as i64, count, cursor),
+        )?)
+    }
+
+    /// Inserts empty rows. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "insertRows")]
+    pub fn js_insert_rows(
+        &mut self,
+        sheet_id: String,
+        y: i32,
+        count: u32,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(
+            &self.insert_rows(sheet_id, y as i64, count, cursor),
+        )?)
+    }
+
+    /// Deletes rows. Returns a [`TransactionSummary`].
+    #[wasm_bindgen(js_name = "deleteRows")]
+    pub fn js_delete_rows(
+        &mut self,
+        sheet_id: String,
+        y: i32,
+        count: u32,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let sheet_id = SheetId::from_str(&sheet_id).unwrap();
+        Ok(serde_wasm_bindgen::to_value(
+            &self.delete_rows(sheet_id, y as i64, count, cursor),
+        )?)
+    }
+}
@@ -19,6 +19,11 @@ extern "C" {
     pub fn runPython(code_string: String) -> JsValue;
 }
 
+#[wasm_bindgen(module = "/../quadratic-client/src/web-workers/rustWorker.ts")]
+extern "C" {
+    pub fn runJavascript(code_string: String) -> JsValue;
+}
+
 #[wasm_bindgen(module = "/../quadratic-client/src/web-workers/rustWorker.ts")]
 extern "C" {
     pub fn getCellsPython(code_string: String) -> JsValue;